@@ -0,0 +1,138 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use clap::Args;
+use serde::Serialize;
+
+use nexus_core::{
+    nvm::{internals::InternalView, k_trace_with, Trace},
+    stwo::{verify, BaseComponent, Machine},
+};
+
+use super::common::InputArgs;
+
+#[derive(Debug, Args)]
+pub struct BenchArgs {
+    #[command(flatten)]
+    pub inputs: InputArgs,
+
+    /// Number of times to run the guest.
+    #[arg(short = 'n', long, default_value = "1")]
+    pub iterations: usize,
+
+    /// Where to write the JSON report, printed to stdout if omitted.
+    #[arg(short, long, name = "output")]
+    pub output: Option<PathBuf>,
+}
+
+/// Measurements of a single execute-prove-verify run.
+#[derive(Debug, Serialize)]
+pub struct BenchIteration {
+    pub cycles: usize,
+    pub program_len: usize,
+    pub tracked_ram_size: usize,
+    pub log_size: u32,
+    pub trace_time_ms: u128,
+    /// Total of the proving phases below.
+    pub prove_time_ms: u128,
+    /// Filling the traces of the chips.
+    pub build_traces_time_ms: u128,
+    /// Committing to the traces, twiddle precomputation included.
+    pub commit_time_ms: u128,
+    /// Proving the committed traces.
+    pub prove_committed_time_ms: u128,
+    pub verify_time_ms: u128,
+    pub proof_size: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BenchReport {
+    pub elf: PathBuf,
+    pub iterations: Vec<BenchIteration>,
+    /// Peak resident set size of the process in kilobytes, null if the platform doesn't report it.
+    pub peak_memory_kb: Option<u64>,
+}
+
+pub fn handle_command(args: BenchArgs) -> anyhow::Result<()> {
+    anyhow::ensure!(args.iterations > 0, "number of iterations must be positive");
+    let inputs = args.inputs.load()?;
+    let builder = inputs.builder();
+
+    let mut iterations = Vec::with_capacity(args.iterations);
+    for _ in 0..args.iterations {
+        let (trace_time, traced) = timed(|| k_trace_with(&builder, 1));
+        let (view, trace) = traced?;
+        let (build_traces_time, traces) =
+            timed(|| Machine::<BaseComponent>::build_traces(&[], &trace, &view, false));
+        let traces = traces?;
+        // The commitment borrows the twiddles, so they're precomputed outside of the timed closure
+        // and their time is added to the commit phase.
+        let (twiddles_time, twiddles) = timed(|| traces.precompute_twiddles());
+        let (commit_time, commitment) =
+            timed(|| Machine::<BaseComponent>::commit(traces, &twiddles));
+        let commit_time = twiddles_time + commit_time;
+        let (prove_committed_time, proof) =
+            timed(|| Machine::<BaseComponent>::prove_committed(commitment));
+        let proof = proof?;
+
+        let log_size = proof.log_size;
+        let proof_size = proof.size_estimate();
+        let (verify_time, result) = timed(|| verify(proof, &view));
+        result?;
+
+        iterations.push(BenchIteration {
+            cycles: trace.get_num_steps(),
+            program_len: view.get_program_memory().program.len(),
+            tracked_ram_size: view.view_tracked_ram_size(),
+            log_size,
+            trace_time_ms: trace_time.as_millis(),
+            prove_time_ms: (build_traces_time + commit_time + prove_committed_time).as_millis(),
+            build_traces_time_ms: build_traces_time.as_millis(),
+            commit_time_ms: commit_time.as_millis(),
+            prove_committed_time_ms: prove_committed_time.as_millis(),
+            verify_time_ms: verify_time.as_millis(),
+            proof_size,
+        });
+    }
+
+    let peak_memory_kb = peak_memory_kb();
+    if peak_memory_kb.is_none() {
+        eprintln!(
+            "peak memory unavailable: it's read from /proc/self/status, which only Linux has"
+        );
+    }
+    let report = BenchReport {
+        elf: args.inputs.elf,
+        iterations,
+        peak_memory_kb,
+    };
+    let json = serde_json::to_string_pretty(&report).context("failed to serialize report")?;
+    match args.output {
+        Some(path) => {
+            fs::write(&path, json).with_context(|| format!("failed to write {}", path.display()))
+        }
+        None => {
+            println!("{json}");
+            Ok(())
+        }
+    }
+}
+
+fn timed<T>(f: impl FnOnce() -> T) -> (Duration, T) {
+    let start = Instant::now();
+    let result = f();
+    (start.elapsed(), result)
+}
+
+/// Reads the peak resident set size from procfs, only available on Linux.
+fn peak_memory_kb() -> Option<u64> {
+    let status = fs::read_to_string("/proc/self/status").ok()?;
+    status
+        .lines()
+        .find_map(|line| line.strip_prefix("VmHWM:"))
+        .and_then(|value| value.trim().trim_end_matches("kB").trim().parse().ok())
+}
@@ -0,0 +1,153 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use clap::Args;
+use serde::{Deserialize, Serialize};
+
+use nexus_core::{
+    nvm::{
+        internals::{EmulatorBuilder, EmulatorKind, InternalView, LinearMemoryLayout},
+        ElfFile, View,
+    },
+    stwo::Proof,
+};
+
+#[derive(Debug, Args)]
+pub struct InputArgs {
+    /// Path to the guest ELF binary.
+    #[arg(name = "elf")]
+    pub elf: PathBuf,
+
+    /// File with raw bytes of the public input.
+    #[arg(long = "public-input", name = "public-input")]
+    pub public_input: Option<PathBuf>,
+
+    /// File with raw bytes of the private input.
+    #[arg(long = "private-input", name = "private-input")]
+    pub private_input: Option<PathBuf>,
+
+    /// File with raw bytes of the associated data bound into the proof.
+    #[arg(long = "ad", name = "ad")]
+    pub ad: Option<PathBuf>,
+
+    /// `.toml` or `.json` file with the segment sizes of the memory layout, derived from a first
+    /// execution if not given.
+    #[arg(long = "layout", name = "layout")]
+    pub layout: Option<PathBuf>,
+
+    /// Maximum number of cycles the guest is allowed to execute.
+    #[arg(long = "fuel", name = "fuel")]
+    pub fuel: Option<u64>,
+}
+
+/// Loaded contents of [`InputArgs`].
+pub struct Inputs {
+    pub elf: ElfFile,
+    pub public_input: Vec<u8>,
+    pub private_input: Vec<u8>,
+    pub ad: Vec<u8>,
+    pub layout: Option<LinearMemoryLayout>,
+    pub fuel: Option<u64>,
+}
+
+impl InputArgs {
+    pub fn load(&self) -> anyhow::Result<Inputs> {
+        let elf = ElfFile::from_path(&self.elf)
+            .with_context(|| format!("failed to load ELF from {}", self.elf.display()))?;
+
+        let layout = match &self.layout {
+            Some(path) => {
+                let layout = LinearMemoryLayout::from_config_file(path)
+                    .with_context(|| format!("failed to load layout from {}", path.display()))?;
+                layout
+                    .validate_elf(&elf)
+                    .with_context(|| format!("layout {} doesn't fit the ELF", path.display()))?;
+                Some(layout)
+            }
+            None => None,
+        };
+
+        Ok(Inputs {
+            elf,
+            public_input: read_optional(self.public_input.as_deref())?,
+            private_input: read_optional(self.private_input.as_deref())?,
+            ad: read_optional(self.ad.as_deref())?,
+            layout,
+            fuel: self.fuel,
+        })
+    }
+}
+
+impl Inputs {
+    /// Builder of the emulators executing the guest with these inputs, layout and fuel.
+    pub fn builder(&self) -> EmulatorBuilder {
+        let mut builder = EmulatorBuilder::new(self.elf.clone())
+            .public_input(&self.public_input)
+            .private_input(&self.private_input)
+            .ad(&self.ad);
+        if let Some(layout) = self.layout {
+            builder = builder.layout(layout);
+        }
+        if let Some(fuel) = self.fuel {
+            builder = builder.max_cycles(fuel);
+        }
+        builder
+    }
+
+    /// The emulator executing the guest in one pass: the linear one if a layout is given, as the
+    /// Harvard emulator has no layout.
+    pub fn emulator_kind(&self) -> EmulatorKind {
+        match self.layout {
+            Some(_) => EmulatorKind::Linear,
+            None => EmulatorKind::Harvard,
+        }
+    }
+}
+
+fn read_optional(path: Option<&Path>) -> anyhow::Result<Vec<u8>> {
+    match path {
+        Some(path) => fs::read(path).with_context(|| format!("failed to read {}", path.display())),
+        None => Ok(Vec::new()),
+    }
+}
+
+/// Proof of a guest execution together with the public view it was proven against.
+#[derive(Serialize, Deserialize)]
+pub struct Receipt {
+    pub proof: Proof,
+    pub view: View,
+}
+
+impl Receipt {
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        let bytes = postcard::to_stdvec(self).context("failed to serialize receipt")?;
+        fs::write(path, bytes).with_context(|| format!("failed to write {}", path.display()))
+    }
+
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let bytes = fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+        postcard::from_bytes(&bytes).context("failed to deserialize receipt")
+    }
+}
+
+/// Prints the debug logs, exit code and public output of a finished execution.
+pub fn print_outcome(view: &View) {
+    for log in view.view_debug_logs().unwrap_or_default() {
+        print!("{}", String::from_utf8_lossy(&log));
+    }
+
+    println!("exit code: {}", view.output_exit_code().unwrap_or_default());
+
+    let output: Vec<u8> = view.get_public_output().iter().map(|e| e.value).collect();
+    println!("public output: {}", hex(&output));
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    if bytes.is_empty() {
+        return String::from("<empty>");
+    }
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
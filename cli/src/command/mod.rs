@@ -2,12 +2,35 @@ use clap::Subcommand;
 
 use super::ENV;
 
+pub mod common;
+
+pub mod bench;
 pub mod host;
+pub mod profile;
+pub mod prove;
+pub mod report;
+pub mod run;
+pub mod trace;
+pub mod verify;
 
 #[derive(Debug, Subcommand)]
 pub enum Command {
     /// Create a new host/guest Nexus package at <path>.
     Host(host::HostArgs),
+    /// Execute a guest ELF without tracing.
+    Run(run::RunArgs),
+    /// Execute a guest ELF with both passes and report the resulting trace.
+    Trace(trace::TraceArgs),
+    /// Execute and prove a guest ELF, writing a receipt.
+    Prove(prove::ProveArgs),
+    /// Verify a receipt.
+    Verify(verify::VerifyArgs),
+    /// Repeatedly execute, prove and verify a guest ELF, reporting measurements as JSON.
+    Bench(bench::BenchArgs),
+    /// Execute a guest ELF and write an HTML report of where its cycles are spent.
+    Report(report::ReportArgs),
+    /// Execute a guest ELF and export its profile for speedscope or flamegraph tools.
+    Profile(profile::ProfileArgs),
 }
 
 pub fn handle_command(cmd: Command) -> anyhow::Result<()> {
@@ -15,5 +38,12 @@ pub fn handle_command(cmd: Command) -> anyhow::Result<()> {
 
     match cmd {
         Command::Host(args) => host::handle_command(args),
+        Command::Run(args) => run::handle_command(args),
+        Command::Trace(args) => trace::handle_command(args),
+        Command::Prove(args) => prove::handle_command(args),
+        Command::Verify(args) => verify::handle_command(args),
+        Command::Bench(args) => bench::handle_command(args),
+        Command::Report(args) => report::handle_command(args),
+        Command::Profile(args) => profile::handle_command(args),
     }
 }
@@ -0,0 +1,135 @@
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use clap::{Args, ValueEnum};
+use serde_json::json;
+
+use nexus_core::nvm::internals::{EmulatorOptions, ProfileReport};
+
+use super::common::InputArgs;
+
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ProfileFormat {
+    /// speedscope JSON file format, see https://www.speedscope.app.
+    Speedscope,
+    /// Folded stacks, as consumed by flamegraph.pl and inferno.
+    Folded,
+}
+
+#[derive(Debug, Args)]
+pub struct ProfileArgs {
+    #[command(flatten)]
+    pub inputs: InputArgs,
+
+    /// Output format of the profile.
+    #[arg(long, value_enum, default_value = "speedscope")]
+    pub format: ProfileFormat,
+
+    /// Where to write the profile, defaults to `nexus-profile.json` or `nexus-profile.folded`.
+    #[arg(short, long, name = "output")]
+    pub output: Option<PathBuf>,
+}
+
+pub fn handle_command(args: ProfileArgs) -> anyhow::Result<()> {
+    let inputs = args.inputs.load()?;
+    if inputs.elf.symbols().is_empty() {
+        tracing::warn!("ELF has no symbols, all cycles are attributed to <unknown>");
+    }
+
+    let mut emulator = inputs.builder().build(inputs.emulator_kind())?;
+    emulator.get_executor_mut().set_options(&EmulatorOptions {
+        symbol_profile: true,
+        ..Default::default()
+    })?;
+    emulator.run()?;
+    let report = emulator
+        .get_executor()
+        .profile_report()
+        .expect("the profiler is enabled");
+
+    let markers: Vec<(String, usize)> = emulator
+        .get_executor()
+        .cycle_tracker
+        .iter()
+        .map(|(name, (cycles, _))| (name.clone(), *cycles))
+        .collect();
+
+    let (contents, default_output) = match args.format {
+        ProfileFormat::Speedscope => (
+            speedscope(&args.inputs.elf, &report, &markers)?,
+            "nexus-profile.json",
+        ),
+        ProfileFormat::Folded => (report.folded(), "nexus-profile.folded"),
+    };
+    let output = args.output.unwrap_or_else(|| PathBuf::from(default_output));
+    fs::write(&output, contents)
+        .with_context(|| format!("failed to write {}", output.display()))?;
+
+    println!("profile written to {}", output.display());
+    Ok(())
+}
+
+fn speedscope(
+    elf: &Path,
+    report: &ProfileReport,
+    markers: &[(String, usize)],
+) -> anyhow::Result<String> {
+    let mut frames: Vec<String> = Vec::new();
+    let mut frame_index: HashMap<String, usize> = HashMap::new();
+    let mut intern = |name: &str| -> usize {
+        *frame_index.entry(name.to_string()).or_insert_with(|| {
+            frames.push(name.to_string());
+            frames.len() - 1
+        })
+    };
+
+    let (samples, weights): (Vec<Vec<usize>>, Vec<u64>) = report
+        .stacks()
+        .map(|(stack, cycles)| (stack.iter().map(|name| intern(name)).collect(), cycles))
+        .unzip();
+    let total = report.total_cycles();
+
+    let mut profiles = vec![json!({
+        "type": "sampled",
+        "name": "cycles per function",
+        "unit": "none",
+        "startValue": 0,
+        "endValue": total,
+        "samples": samples,
+        "weights": weights,
+    })];
+
+    // Functions annotated with `#[nexus_rt::profile]` in the guest, if any.
+    if !markers.is_empty() {
+        let mut markers = markers.to_vec();
+        markers.sort();
+        let (samples, weights): (Vec<Vec<usize>>, Vec<usize>) = markers
+            .iter()
+            .map(|(name, cycles)| (vec![intern(name)], *cycles))
+            .unzip();
+        profiles.push(json!({
+            "type": "sampled",
+            "name": "cycle tracker markers",
+            "unit": "none",
+            "startValue": 0,
+            "endValue": weights.iter().sum::<usize>(),
+            "samples": samples,
+            "weights": weights,
+        }));
+    }
+
+    let frames: Vec<_> = frames.iter().map(|name| json!({ "name": name })).collect();
+    let file = json!({
+        "$schema": "https://www.speedscope.app/file-format-schema.json",
+        "shared": { "frames": frames },
+        "profiles": profiles,
+        "name": elf.display().to_string(),
+        "activeProfileIndex": 0,
+        "exporter": "cargo-nexus",
+    });
+    serde_json::to_string(&file).context("failed to serialize profile")
+}
@@ -0,0 +1,43 @@
+use std::path::PathBuf;
+
+use clap::Args;
+
+use nexus_core::{
+    nvm::{internals::InternalView, k_trace_with},
+    stwo::{digest::program_digest, prove},
+};
+
+use super::common::{print_outcome, InputArgs, Receipt};
+
+#[derive(Debug, Args)]
+pub struct ProveArgs {
+    #[command(flatten)]
+    pub inputs: InputArgs,
+
+    /// Where to write the receipt.
+    #[arg(short, long, name = "receipt", default_value = "nexus-proof")]
+    pub receipt: PathBuf,
+}
+
+pub fn handle_command(args: ProveArgs) -> anyhow::Result<()> {
+    let inputs = args.inputs.load()?;
+
+    let (view, trace) = k_trace_with(&inputs.builder(), 1)?;
+    print_outcome(&view);
+
+    let proof = prove(&trace, &view)?;
+    let stats = proof.stats();
+    println!(
+        "proof size: {} bytes ({} commitment trees, {} FRI layers, {} queries)",
+        stats.serialized_size, stats.num_commitment_trees, stats.num_fri_layers, stats.num_queries
+    );
+
+    println!(
+        "program digest: {}",
+        program_digest(view.get_program_memory())
+    );
+
+    Receipt { proof, view }.save(&args.receipt)?;
+    println!("receipt written to {}", args.receipt.display());
+    Ok(())
+}
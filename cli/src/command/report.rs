@@ -0,0 +1,219 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Write as _,
+    fs,
+    path::PathBuf,
+};
+
+use anyhow::Context;
+use clap::Args;
+use nexus_core::nvm::{k_trace_with, ElfFile, Trace, UniformTrace};
+
+use super::common::InputArgs;
+
+/// Granularity of the memory heatmap in bytes.
+const HEATMAP_BUCKET_SIZE: u32 = 1024;
+
+/// Maximum number of rows shown in each table of the report.
+const MAX_ROWS: usize = 50;
+
+#[derive(Debug, Args)]
+pub struct ReportArgs {
+    #[command(flatten)]
+    pub inputs: InputArgs,
+
+    /// Where to write the HTML report.
+    #[arg(short, long, name = "output", default_value = "nexus-report.html")]
+    pub output: PathBuf,
+}
+
+pub fn handle_command(args: ReportArgs) -> anyhow::Result<()> {
+    let inputs = args.inputs.load()?;
+    let (_view, trace) = k_trace_with(&inputs.builder(), 1)?;
+
+    let stats = ExecutionStats::collect(&trace, &inputs.elf);
+    let html = stats.render(&args.inputs.elf.display().to_string());
+    fs::write(&args.output, html)
+        .with_context(|| format!("failed to write {}", args.output.display()))?;
+
+    println!("report written to {}", args.output.display());
+    Ok(())
+}
+
+/// Name of the prover chip constraining the instruction with the given mnemonic.
+fn chip_for_mnemonic(mnemonic: &str) -> &'static str {
+    match mnemonic {
+        "add" | "addi" => "AddChip",
+        "sub" => "SubChip",
+        "slt" | "slti" => "SltChip",
+        "sltu" | "sltiu" => "SltuChip",
+        "and" | "andi" | "or" | "ori" | "xor" | "xori" => "BitOpChip",
+        "sll" | "slli" => "SllChip",
+        "srl" | "srli" => "SrlChip",
+        "sra" | "srai" => "SraChip",
+        "beq" => "BeqChip",
+        "bne" => "BneChip",
+        "blt" => "BltChip",
+        "bltu" => "BltuChip",
+        "bge" => "BgeChip",
+        "bgeu" => "BgeuChip",
+        "jal" => "JalChip",
+        "jalr" => "JalrChip",
+        "lui" => "LuiChip",
+        "auipc" => "AuipcChip",
+        "lb" | "lh" | "lw" | "lbu" | "lhu" | "sb" | "sh" | "sw" => "LoadStoreChip",
+        "ecall" => "SyscallChip",
+        _ => "unsupported",
+    }
+}
+
+#[derive(Default)]
+struct ExecutionStats {
+    total_cycles: usize,
+    // basic block start pc -> (executions, cycles)
+    blocks: HashMap<u32, (usize, usize)>,
+    functions: HashMap<String, usize>,
+    // bucket start address -> (loads, stores)
+    memory: BTreeMap<u32, (usize, usize)>,
+    // bucket start address -> name of the segment it was accessed in
+    segments: HashMap<u32, &'static str>,
+    chips: HashMap<&'static str, usize>,
+}
+
+impl ExecutionStats {
+    fn collect(trace: &UniformTrace, elf: &ElfFile) -> Self {
+        let mut stats = Self::default();
+        let layout = trace.get_memory_layout();
+        let mut block_start = None;
+
+        for step in trace.get_blocks_iter().flat_map(|block| block.steps.iter()) {
+            stats.total_cycles += 1;
+
+            let start = *block_start.get_or_insert_with(|| {
+                stats.blocks.entry(step.pc).or_default().0 += 1;
+                step.pc
+            });
+            stats.blocks.entry(start).or_default().1 += 1;
+            if step.instruction.is_branch_or_jump_instruction() {
+                block_start = None;
+            }
+
+            let function = elf
+                .symbol_for_addr(step.pc)
+                .map_or("<unknown>", |symbol| symbol.name.as_str());
+            *stats.functions.entry(function.to_string()).or_default() += 1;
+
+            for record in &step.memory_records {
+                let bucket = record.get_address() / HEATMAP_BUCKET_SIZE * HEATMAP_BUCKET_SIZE;
+                if let Some(label) = layout.segment_label(record.get_address()) {
+                    stats.segments.entry(bucket).or_insert(label);
+                }
+                let entry = stats.memory.entry(bucket).or_default();
+                // Only stores carry a previous value.
+                if record.get_prev_value().is_some() {
+                    entry.1 += 1;
+                } else {
+                    entry.0 += 1;
+                }
+            }
+
+            *stats
+                .chips
+                .entry(chip_for_mnemonic(step.instruction.opcode.name()))
+                .or_default() += 1;
+        }
+        stats
+    }
+
+    fn render(&self, title: &str) -> String {
+        let mut blocks: Vec<_> = self.blocks.iter().collect();
+        blocks.sort_by(|a, b| b.1 .1.cmp(&a.1 .1).then(a.0.cmp(b.0)));
+        let mut functions: Vec<_> = self.functions.iter().collect();
+        functions.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        let mut chips: Vec<_> = self.chips.iter().collect();
+        chips.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+        let max_accesses = self
+            .memory
+            .values()
+            .map(|(loads, stores)| loads + stores)
+            .max()
+            .unwrap_or(1);
+
+        let mut html = String::new();
+        let _ = write!(
+            html,
+            "<!DOCTYPE html><html><head><meta charset=\"utf-8\"><title>Nexus report: {title}</title>\
+             <style>{STYLE}</style></head><body><h1>Execution report</h1>\
+             <p>{title}: {} cycles</p>",
+            self.total_cycles,
+            title = escape(title),
+        );
+
+        html.push_str("<h2>Hot basic blocks</h2><table><tr><th>start pc</th><th>executions</th><th>cycles</th><th>share</th></tr>");
+        for (pc, (executions, cycles)) in blocks.into_iter().take(MAX_ROWS) {
+            let _ = write!(
+                html,
+                "<tr><td>0x{pc:08x}</td><td>{executions}</td><td>{cycles}</td>{}</tr>",
+                self.share_cell(*cycles)
+            );
+        }
+        html.push_str("</table>");
+
+        html.push_str("<h2>Cycles per function</h2><table><tr><th>function</th><th>cycles</th><th>share</th></tr>");
+        for (name, cycles) in functions.into_iter().take(MAX_ROWS) {
+            let _ = write!(
+                html,
+                "<tr><td>{}</td><td>{cycles}</td>{}</tr>",
+                escape(name),
+                self.share_cell(*cycles)
+            );
+        }
+        html.push_str("</table>");
+
+        let _ = write!(
+            html,
+            "<h2>Memory heatmap</h2><p>Accesses per {HEATMAP_BUCKET_SIZE}-byte region.</p>\
+             <table><tr><th>region</th><th>segment</th><th>loads</th><th>stores</th></tr>"
+        );
+        for (bucket, (loads, stores)) in &self.memory {
+            let heat = (loads + stores) as f64 / max_accesses as f64;
+            let segment = self.segments.get(bucket).copied().unwrap_or_default();
+            let _ = write!(
+                html,
+                "<tr style=\"background: rgba(220, 60, 30, {heat:.2})\"><td>0x{bucket:08x}</td><td>{segment}</td><td>{loads}</td><td>{stores}</td></tr>"
+            );
+        }
+        html.push_str("</table>");
+
+        html.push_str(
+            "<h2>Chip utilization</h2><table><tr><th>chip</th><th>rows</th><th>share</th></tr>",
+        );
+        for (chip, rows) in chips {
+            let _ = write!(
+                html,
+                "<tr><td>{chip}</td><td>{rows}</td>{}</tr>",
+                self.share_cell(*rows)
+            );
+        }
+        html.push_str("</table></body></html>");
+        html
+    }
+
+    fn share_cell(&self, cycles: usize) -> String {
+        let share = 100.0 * cycles as f64 / self.total_cycles.max(1) as f64;
+        format!("<td><div class=\"bar\" style=\"width: {share:.1}%\"></div>{share:.1}%</td>")
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+const STYLE: &str = "body { font-family: sans-serif; margin: 2em; } \
+    table { border-collapse: collapse; margin-bottom: 2em; } \
+    th, td { border: 1px solid #ccc; padding: 2px 8px; text-align: left; font-family: monospace; } \
+    td:last-child { min-width: 200px; } \
+    .bar { background: #4a90d9; height: 0.8em; display: inline-block; margin-right: 4px; }";
@@ -0,0 +1,50 @@
+use clap::Args;
+
+use nexus_core::nvm::VMError;
+
+use super::common::{print_outcome, InputArgs};
+
+#[derive(Debug, Args)]
+pub struct RunArgs {
+    #[command(flatten)]
+    pub inputs: InputArgs,
+}
+
+pub fn handle_command(args: RunArgs) -> anyhow::Result<()> {
+    let inputs = args.inputs.load()?;
+
+    // Logs aren't captured, so the guest prints straight to stdout.
+    let mut emulator = inputs.builder().build(inputs.emulator_kind())?;
+
+    // Breakpoints and yields stop the execution, resume after them.
+    loop {
+        match emulator.execute(false) {
+            Ok(_) => unreachable!("execution only ends with an error"),
+            Err(VMError::VMExited(_)) => break,
+            Err(VMError::Breakpoint(pc)) => {
+                match emulator.get_executor().source_location(pc) {
+                    Some(location) => eprintln!("breakpoint at pc=0x{pc:08x}, {location}"),
+                    None => eprintln!("breakpoint at pc=0x{pc:08x}"),
+                }
+                eprintln!("{}", emulator.get_executor().cpu.registers);
+            }
+            Err(VMError::Yielded(_)) => {}
+            Err(VMError::OutOfFuel { cycles, .. }) => {
+                anyhow::bail!("guest ran out of fuel after {cycles} cycles");
+            }
+            Err(err) => {
+                // Guests built with debug info tell where in their source they failed.
+                return Err(match emulator.get_executor().error_location(&err) {
+                    Some(location) => {
+                        anyhow::Error::from(err).context(format!("guest failed at {location}"))
+                    }
+                    None => err.into(),
+                });
+            }
+        }
+    }
+
+    print_outcome(&emulator.finalize());
+    println!("cycles: {}", emulator.get_executor().global_clock);
+    Ok(())
+}
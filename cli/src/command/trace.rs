@@ -0,0 +1,40 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::Context;
+use clap::Args;
+
+use nexus_core::nvm::{k_trace_with, Trace};
+
+use super::common::{print_outcome, InputArgs};
+
+#[derive(Debug, Args)]
+pub struct TraceArgs {
+    #[command(flatten)]
+    pub inputs: InputArgs,
+
+    /// Number of steps per trace block.
+    #[arg(short, long, default_value = "1")]
+    pub k: usize,
+
+    /// Where to write the serialized execution trace.
+    #[arg(short, long, name = "output")]
+    pub output: Option<PathBuf>,
+}
+
+pub fn handle_command(args: TraceArgs) -> anyhow::Result<()> {
+    let inputs = args.inputs.load()?;
+    anyhow::ensure!(args.k > 0, "k must be positive");
+
+    let (view, trace) = k_trace_with(&inputs.builder(), args.k)?;
+
+    print_outcome(&view);
+    println!("steps: {}", trace.get_num_steps());
+    println!("blocks: {}", trace.blocks.len());
+    println!("memory layout: {:?}", trace.memory_layout);
+
+    if let Some(path) = args.output {
+        let bytes = postcard::to_stdvec(&trace).context("failed to serialize trace")?;
+        fs::write(&path, bytes).with_context(|| format!("failed to write {}", path.display()))?;
+    }
+    Ok(())
+}
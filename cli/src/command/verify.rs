@@ -0,0 +1,71 @@
+use std::path::PathBuf;
+
+use anyhow::Context;
+use clap::{ArgGroup, Args};
+
+use nexus_core::{
+    nvm::{
+        internals::{elf_into_program_info, InternalView, LinearMemoryLayout},
+        ElfFile,
+    },
+    stwo::{digest::program_digest, verify, Blake2sHash},
+};
+
+use super::common::{print_outcome, Receipt};
+
+#[derive(Debug, Args)]
+#[command(group(ArgGroup::new("program").required(true).args(["elf", "program-digest"])))]
+pub struct VerifyArgs {
+    /// Path to the receipt.
+    #[arg(name = "receipt", default_value = "nexus-proof")]
+    pub receipt: PathBuf,
+
+    /// Path to the guest ELF binary the receipt must prove an execution of.
+    #[arg(long = "elf", name = "elf")]
+    pub elf: Option<PathBuf>,
+
+    /// Hex-encoded digest of the program the receipt must prove an execution of, as printed by
+    /// `nexus prove`.
+    #[arg(long = "program-digest", name = "program-digest")]
+    pub program_digest: Option<String>,
+}
+
+pub fn handle_command(args: VerifyArgs) -> anyhow::Result<()> {
+    let Receipt { proof, view } = Receipt::load(&args.receipt)?;
+
+    // The view is supplied by the prover along with the proof, so the program it contains has to be
+    // checked against the one the verifier expects.
+    let expected = match (&args.elf, &args.program_digest) {
+        (Some(path), _) => {
+            let elf = ElfFile::from_path(path)
+                .with_context(|| format!("failed to load ELF from {}", path.display()))?;
+            program_digest(&elf_into_program_info(&elf, &LinearMemoryLayout::default()))
+        }
+        (None, Some(digest)) => parse_digest(digest)?,
+        (None, None) => unreachable!("clap requires --elf or --program-digest"),
+    };
+    let actual = program_digest(view.get_program_memory());
+    if actual != expected {
+        anyhow::bail!(
+            "receipt proves a different program: expected digest {expected}, got {actual}"
+        );
+    }
+
+    verify(proof, &view)?;
+    print_outcome(&view);
+    println!("receipt verified");
+    Ok(())
+}
+
+fn parse_digest(digest: &str) -> anyhow::Result<Blake2sHash> {
+    let invalid = || anyhow::anyhow!("invalid program digest {digest}, expected 64 hex digits");
+    if digest.len() != 64 || !digest.is_ascii() {
+        return Err(invalid());
+    }
+    let mut bytes = [0u8; 32];
+    for (byte, pair) in bytes.iter_mut().zip(digest.as_bytes().chunks(2)) {
+        let pair = std::str::from_utf8(pair).map_err(|_| invalid())?;
+        *byte = u8::from_str_radix(pair, 16).map_err(|_| invalid())?;
+    }
+    Ok(Blake2sHash(bytes))
+}
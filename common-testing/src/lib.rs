@@ -1,4 +1,5 @@
 pub mod emulator;
+pub mod vectors;
 
 use nexus_vm::riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode};
 
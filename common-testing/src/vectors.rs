@@ -0,0 +1,135 @@
+//! Golden test vectors for cross-implementation testing.
+//!
+//! A [`TestVector`] pins a program and its inputs together with the digest of the execution trace,
+//! the public view and the proof the reference prover produces for them. Proving is deterministic,
+//! so replaying a vector with [`TestVector::check`] must reproduce all of them bit for bit. Downstream
+//! verifier implementations can consume the same files and only check the proof against the view.
+
+use std::{fs, path::Path};
+
+use nexus_vm::{
+    elf::ElfFile,
+    emulator::View,
+    error::VMError,
+    trace::{k_trace, UniformTrace},
+};
+use nexus_vm_prover::{prove, verify, Proof, ProveError, VerifyError};
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::vcs::blake2_hash::{Blake2sHash, Blake2sHasher};
+use thiserror::Error;
+
+/// Number of instructions per trace block used for all vectors.
+const K: usize = 1;
+
+#[derive(Debug, Error)]
+pub enum TestVectorError {
+    #[error("execution failed: {0}")]
+    Execution(#[from] VMError),
+
+    #[error("proving failed: {0}")]
+    Proving(#[from] ProveError),
+
+    #[error("verification failed: {0}")]
+    Verification(#[from] VerifyError),
+
+    #[error("trace digest mismatch: expected {expected}, got {actual}")]
+    TraceDigestMismatch {
+        expected: Blake2sHash,
+        actual: Blake2sHash,
+    },
+
+    #[error("public view differs from the recorded one")]
+    ViewMismatch,
+
+    #[error("proof differs from the recorded one")]
+    ProofMismatch,
+
+    #[error("serialization failed: {0}")]
+    Serialization(#[from] postcard::Error),
+
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Canonical program, inputs and expected results of executing and proving them.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct TestVector {
+    pub name: String,
+    pub program: ElfFile,
+    pub public_input: Vec<u8>,
+    pub private_input: Vec<u8>,
+    pub ad: Vec<u8>,
+    pub trace_digest: Blake2sHash,
+    pub view: View,
+    pub proof: Proof,
+}
+
+impl TestVector {
+    /// Executes and proves the program, recording the results as the expected ones.
+    pub fn generate(
+        name: &str,
+        program: ElfFile,
+        public_input: &[u8],
+        private_input: &[u8],
+        ad: &[u8],
+    ) -> Result<Self, TestVectorError> {
+        let (view, trace) = k_trace(program.clone(), ad, public_input, private_input, K)?;
+        let proof = prove(&trace, &view)?;
+
+        Ok(Self {
+            name: name.to_string(),
+            program,
+            public_input: public_input.to_vec(),
+            private_input: private_input.to_vec(),
+            ad: ad.to_vec(),
+            trace_digest: trace_digest(&trace)?,
+            view,
+            proof,
+        })
+    }
+
+    /// Replays the vector and checks that execution and proving reproduce the recorded results,
+    /// and that the recorded proof verifies against the recorded view.
+    pub fn check(&self) -> Result<(), TestVectorError> {
+        let (view, trace) = k_trace(
+            self.program.clone(),
+            &self.ad,
+            &self.public_input,
+            &self.private_input,
+            K,
+        )?;
+
+        let actual = trace_digest(&trace)?;
+        if actual != self.trace_digest {
+            return Err(TestVectorError::TraceDigestMismatch {
+                expected: self.trace_digest,
+                actual,
+            });
+        }
+        if postcard::to_allocvec(&view)? != postcard::to_allocvec(&self.view)? {
+            return Err(TestVectorError::ViewMismatch);
+        }
+
+        let proof = prove(&trace, &view)?;
+        if postcard::to_allocvec(&proof)? != postcard::to_allocvec(&self.proof)? {
+            return Err(TestVectorError::ProofMismatch);
+        }
+
+        verify(self.proof.clone(), &self.view)?;
+        Ok(())
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), TestVectorError> {
+        fs::write(path, postcard::to_allocvec(self)?)?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self, TestVectorError> {
+        Ok(postcard::from_bytes(&fs::read(path)?)?)
+    }
+}
+
+/// Blake2s digest of the serialized execution trace.
+pub fn trace_digest(trace: &UniformTrace) -> Result<Blake2sHash, TestVectorError> {
+    Ok(Blake2sHasher::hash(&postcard::to_allocvec(trace)?))
+}
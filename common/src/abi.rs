@@ -0,0 +1,188 @@
+//! Guest ABI, shared by the emulator and the guest runtime.
+//!
+//! A guest calls into the host with `ecall`, passing the [`SyscallCode`] in a7 and the arguments in
+//! a0-a6, and receives the result in a0. The guest finds its public input and output through two words
+//! at fixed addresses, [`PUBLIC_INPUT_ADDRESS`] and [`PUBLIC_OUTPUT_ADDRESS`]; the first word of the
+//! public output is the exit code.
+//!
+//! Typed inputs and outputs are encoded with the COBS flavor of postcard on both sides, see
+//! `nexus_rt::read_public_input` and `EmulatorBuilder::set_public_input` of the VM. A guest that
+//! panics logs its panic message with [`SyscallCode::Log`] at [`LogLevel::Error`], then exits with
+//! [`EXIT_PANIC`], leaving its public output as is.
+
+use crate::constants::WORD_SIZE;
+
+/// Address of the word holding the start address of the public input.
+pub const PUBLIC_INPUT_ADDRESS: u32 = 0x80;
+/// Address of the word holding the start address of the public output.
+pub const PUBLIC_OUTPUT_ADDRESS: u32 = PUBLIC_INPUT_ADDRESS + WORD_SIZE as u32;
+
+/// Offset of the exit code in the public output.
+pub const EXIT_CODE_OFFSET: u32 = 0;
+/// Offset of the output written by the guest in the public output, following the exit code.
+pub const PUBLIC_OUTPUT_DATA_OFFSET: u32 = EXIT_CODE_OFFSET + WORD_SIZE as u32;
+
+/// Exit code of a guest returning from main.
+pub const EXIT_SUCCESS: u32 = 0;
+/// Exit code of a guest that panicked.
+pub const EXIT_PANIC: u32 = 1;
+
+/// System calls, the code is passed in a7.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum SyscallCode {
+    // Syscall code defines opcodes start from 0x200
+    Write = 0x200, // Is converted to NOP for tracing
+    Exit = 0x201,
+    // zkVM specific syscall opcodes start from 0x400
+    ReadFromPrivateInput = 0x400,
+    CycleCount = 0x401, // Is converted to NOP for tracing
+    OverwriteStackPointer = 0x402,
+    OverwriteHeapPointer = 0x403,
+    ReadFromAuxiliaryInput = 0x404,
+    QueryMemoryLayout = 0x405,
+    HeapStats = 0x406,
+    ReadClock = 0x407,
+    Log = 0x408, // Is converted to NOP for tracing
+    Alloc = 0x409,
+    Dealloc = 0x40A,
+    Yield = 0x40B,
+    Sha256Compress = 0x40C,
+    KeccakPermute = 0x40D,
+    AddMod = 0x40E,
+    MulMod = 0x40F,
+    InvMod = 0x410,
+}
+
+impl SyscallCode {
+    /// The code passed in a7.
+    pub const fn code(self) -> u32 {
+        self as u32
+    }
+
+    /// The system call of `code`, if there is one.
+    pub const fn from_code(code: u32) -> Option<Self> {
+        let syscall = match code {
+            0x200 => Self::Write,
+            0x201 => Self::Exit,
+            0x400 => Self::ReadFromPrivateInput,
+            0x401 => Self::CycleCount,
+            0x402 => Self::OverwriteStackPointer,
+            0x403 => Self::OverwriteHeapPointer,
+            0x404 => Self::ReadFromAuxiliaryInput,
+            0x405 => Self::QueryMemoryLayout,
+            0x406 => Self::HeapStats,
+            0x407 => Self::ReadClock,
+            0x408 => Self::Log,
+            0x409 => Self::Alloc,
+            0x40A => Self::Dealloc,
+            0x40B => Self::Yield,
+            0x40C => Self::Sha256Compress,
+            0x40D => Self::KeccakPermute,
+            0x40E => Self::AddMod,
+            0x40F => Self::MulMod,
+            0x410 => Self::InvMod,
+            _ => return None,
+        };
+        Some(syscall)
+    }
+}
+
+impl From<u32> for SyscallCode {
+    fn from(value: u32) -> Self {
+        Self::from_code(value).expect("Invalid syscall code")
+    }
+}
+
+impl From<SyscallCode> for u32 {
+    fn from(val: SyscallCode) -> Self {
+        val.code()
+    }
+}
+
+/// Addresses of the memory layout the guest can query with [`SyscallCode::QueryMemoryLayout`],
+/// passing the field in a0.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum MemoryLayoutField {
+    HeapStart = 0,
+    HeapEnd = 1,
+    StackBottom = 2,
+    StackTop = 3,
+    PublicInputStart = 4,
+    PublicInputEnd = 5,
+    PublicOutputStart = 6,
+    PublicOutputEnd = 7,
+    AdStart = 8,
+    AdEnd = 9,
+    ExitCode = 10,
+}
+
+impl MemoryLayoutField {
+    pub const ALL: [Self; 11] = [
+        Self::HeapStart,
+        Self::HeapEnd,
+        Self::StackBottom,
+        Self::StackTop,
+        Self::PublicInputStart,
+        Self::PublicInputEnd,
+        Self::PublicOutputStart,
+        Self::PublicOutputEnd,
+        Self::AdStart,
+        Self::AdEnd,
+        Self::ExitCode,
+    ];
+
+    pub const fn from_u32(value: u32) -> Option<Self> {
+        if (value as usize) < Self::ALL.len() {
+            Some(Self::ALL[value as usize])
+        } else {
+            None
+        }
+    }
+}
+
+/// Severity of a message of the guest, passed in a0 of [`SyscallCode::Log`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u32)]
+pub enum LogLevel {
+    Error = 0,
+    Warn = 1,
+    Info = 2,
+    Debug = 3,
+    Trace = 4,
+}
+
+impl LogLevel {
+    /// The level encoded as `value`, unknown levels are logged as errors.
+    pub const fn from_u32(value: u32) -> Self {
+        match value {
+            1 => Self::Warn,
+            2 => Self::Info,
+            3 => Self::Debug,
+            4 => Self::Trace,
+            _ => Self::Error,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_syscall_codes_round_trip() {
+        for code in (0x200..0x202).chain(0x400..0x411) {
+            let syscall = SyscallCode::from_code(code).unwrap();
+            assert_eq!(syscall.code(), code);
+            assert_eq!(u32::from(syscall), code);
+        }
+        assert_eq!(SyscallCode::from_code(0x411), None);
+
+        for (i, field) in MemoryLayoutField::ALL.into_iter().enumerate() {
+            assert_eq!(MemoryLayoutField::from_u32(i as u32), Some(field));
+            assert_eq!(field as u32, i as u32);
+        }
+        assert_eq!(MemoryLayoutField::from_u32(11), None);
+    }
+}
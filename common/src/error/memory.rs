@@ -1,6 +1,47 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
 use thiserror::Error;
 
+use crate::memory::MemAccessSize;
+
+/// A mapped memory segment, as reported by [`MemoryError::OutOfBounds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemorySegment {
+    pub start: u32,
+    /// One byte past the end of the segment.
+    pub end: u32,
+    /// Access mode of the segment, one of `RO`, `WO`, `RW` and `NA`.
+    pub mode: &'static str,
+    /// Name of the segment, e.g. `heap`, if it was labeled.
+    pub label: Option<&'static str>,
+}
+
+impl Display for MemorySegment {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        if let Some(label) = self.label {
+            write!(f, "{label} ")?;
+        }
+        write!(
+            f,
+            "{} [0x{:08X}, 0x{:08X})",
+            self.mode, self.start, self.end
+        )
+    }
+}
+
+fn nearest_segments(below: &Option<MemorySegment>, above: &Option<MemorySegment>) -> String {
+    match (below, above) {
+        (None, None) => "no memory is mapped".to_string(),
+        (Some(below), None) => format!("nearest segment below is {below}"),
+        (None, Some(above)) => format!("nearest segment above is {above}"),
+        (Some(below), Some(above)) => {
+            format!("nearest segments are {below} below and {above} above")
+        }
+    }
+}
+
 #[derive(Error, Debug, PartialEq)]
+#[non_exhaustive]
 pub enum MemoryError {
     // Cannot write unaligned memory
     #[error("Unaligned memory write: 0x{0:08X}")]
@@ -47,4 +88,16 @@ pub enum MemoryError {
     // Invalid memory segment
     #[error("Invalid memory segment")]
     InvalidMemorySegment,
+
+    // Access outside of all mapped memory segments
+    #[error(
+        "Out of bounds {size:?} access at 0x{address:08X}, {}",
+        nearest_segments(.below, .above)
+    )]
+    OutOfBounds {
+        address: u32,
+        size: MemAccessSize,
+        below: Option<MemorySegment>,
+        above: Option<MemorySegment>,
+    },
 }
@@ -1,5 +1,5 @@
 mod memory;
 mod opcode;
 
-pub use memory::MemoryError;
+pub use memory::{MemoryError, MemorySegment};
 pub use opcode::OpcodeError;
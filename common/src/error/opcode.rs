@@ -3,6 +3,7 @@ use thiserror::Error;
 use crate::riscv::Opcode;
 
 #[derive(Debug, Error, PartialEq)]
+#[non_exhaustive]
 pub enum OpcodeError {
     #[error("Cannot convert non-builtin opcode to BuiltinOpcode: {0}")]
     OpcodeNotBuiltin(Opcode),
@@ -1,5 +1,12 @@
+#![cfg_attr(target_arch = "riscv32", no_std)]
+
+pub mod abi;
 pub mod constants;
+#[cfg(not(target_arch = "riscv32"))]
 pub mod cpu;
+#[cfg(not(target_arch = "riscv32"))]
 pub mod error;
+#[cfg(not(target_arch = "riscv32"))]
 pub mod memory;
+#[cfg(not(target_arch = "riscv32"))]
 pub mod riscv;
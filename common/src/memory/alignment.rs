@@ -10,24 +10,35 @@ macro_rules! word_align {
 #[macro_export]
 macro_rules! bytes_to_words {
     ($bytes:expr) => {{
-        // Convert the associated data to word representation.
-        let mut bytes = $bytes.to_vec();
-        let padded_len = $crate::word_align!(bytes.len());
-        bytes.resize(padded_len, 0);
-        // Convert to u32 chunks.
-        bytes
-            .chunks($crate::constants::WORD_SIZE)
-            .map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
-            .collect::<Vec<u32>>()
+        // Convert the associated data to word representation, without copying the bytes first so
+        // that the conversion of whole words vectorizes.
+        let bytes: &[u8] = &$bytes;
+        let chunks = bytes.chunks_exact($crate::constants::WORD_SIZE);
+        let remainder = chunks.remainder();
+        let mut words =
+            Vec::with_capacity($crate::word_align!(bytes.len()) / $crate::constants::WORD_SIZE);
+        words.extend(
+            chunks.map(|chunk| u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]])),
+        );
+        if !remainder.is_empty() {
+            let mut last = [0u8; $crate::constants::WORD_SIZE];
+            last[..remainder.len()].copy_from_slice(remainder);
+            words.push(u32::from_le_bytes(last));
+        }
+        words
     }};
 }
 
 #[macro_export]
 macro_rules! words_to_bytes {
     ($words:expr) => {{
-        let mut bytes: Vec<u8> = Vec::with_capacity($words.len() * $crate::constants::WORD_SIZE);
-        for word in $words {
-            bytes.extend_from_slice(&word.to_le_bytes());
+        let words: &[u32] = &$words;
+        let mut bytes: Vec<u8> = vec![0; words.len() * $crate::constants::WORD_SIZE];
+        for (chunk, word) in bytes
+            .chunks_exact_mut($crate::constants::WORD_SIZE)
+            .zip(words)
+        {
+            chunk.copy_from_slice(&word.to_le_bytes());
         }
         bytes
     }};
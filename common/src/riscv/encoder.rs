@@ -104,21 +104,18 @@ fn encode_j_type(instruction: &Instruction) -> u32 {
 }
 
 /// Encodes an instruction into its binary representation to little-endian format.
+///
+/// Custom instructions are encoded according to their instruction type, like builtin ones.
 pub fn encode_instruction(instruction: &Instruction) -> u32 {
-    if instruction.opcode.is_builtin() {
-        match instruction.ins_type {
-            InstructionType::RType => encode_r_type(instruction).to_le(),
-            InstructionType::IType => encode_i_type(instruction).to_le(),
-            InstructionType::ITypeShamt => encode_i_shamt_type(instruction).to_le(),
-            InstructionType::SType => encode_s_type(instruction).to_le(),
-            InstructionType::BType => encode_b_type(instruction).to_le(),
-            InstructionType::UType => encode_u_type(instruction).to_le(),
-            InstructionType::JType => encode_j_type(instruction).to_le(),
-            InstructionType::Unimpl => 0,
-        }
-    } else {
-        // Don't support for now, panic
-        todo!("Need to support not precompile instructions")
+    match instruction.ins_type {
+        InstructionType::RType => encode_r_type(instruction).to_le(),
+        InstructionType::IType => encode_i_type(instruction).to_le(),
+        InstructionType::ITypeShamt => encode_i_shamt_type(instruction).to_le(),
+        InstructionType::SType => encode_s_type(instruction).to_le(),
+        InstructionType::BType => encode_b_type(instruction).to_le(),
+        InstructionType::UType => encode_u_type(instruction).to_le(),
+        InstructionType::JType => encode_j_type(instruction).to_le(),
+        InstructionType::Unimpl => 0,
     }
 }
 
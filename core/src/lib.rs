@@ -4,21 +4,29 @@
 /// RISC-V processing
 pub mod nvm {
     pub use nexus_vm::{
-        elf::{ElfError, ElfFile},
+        elf::{ElfError, ElfFile, SourceLocation, Symbol},
         emulator::View,
         error::VMError,
-        trace::{bb_trace, k_trace, BBTrace, UniformTrace},
+        trace::{bb_trace, k_trace, k_trace_with, BBTrace, Trace, UniformTrace},
+        WORD_SIZE,
     };
     pub mod internals {
         pub use nexus_vm::emulator::{
             convert_instruction, elf_into_program_info, io_entries_into_vec, map_into_io_entries,
-            slice_into_io_entries, LinearEmulator, LinearMemoryLayout, MemoryInitializationEntry,
-            ProgramInfo, PublicOutputEntry,
+            slice_into_io_entries, ElfProgramEntries, Emulator, EmulatorBuilder, EmulatorKind,
+            EmulatorOptions, HarvardEmulator, InternalView, LinearEmulator, LinearMemoryLayout,
+            MemoryInitializationEntry, ProfileReport, ProgramInfo, PublicOutputEntry,
         };
     }
 }
 
 /// Stwo proving
 pub mod stwo {
-    pub use nexus_vm_prover::{prove, verify, Proof, ProvingError, VerificationError};
+    pub use nexus_vm_prover::{
+        digest,
+        machine::{BaseComponent, Machine},
+        prove, prove_with_final_memory, prove_with_public_registers, verify, verify_with_expected,
+        verify_with_final_memory, Blake2sHash, Proof, ProofStats, ProveError, ProvingError,
+        PublicRegisters, VerificationError, VerifyError,
+    };
 }
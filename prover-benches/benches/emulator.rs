@@ -0,0 +1,124 @@
+use std::time::Duration;
+
+use nexus_common::constants::WORD_SIZE;
+use nexus_vm::{
+    emulator::{Emulator, HarvardEmulator},
+    error::VMError,
+    riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+};
+
+use criterion::{black_box, criterion_group, criterion_main, BatchSize, Criterion, Throughput};
+
+/// Number of iterations of the loop executing an opcode mix.
+const ITERATIONS: u32 = 2000;
+
+criterion_group! {
+    name = emulator;
+    config = Criterion::default().warm_up_time(Duration::from_millis(3000));
+    targets = bench_emulator,
+}
+
+criterion_main!(emulator);
+
+fn bench_emulator(c: &mut Criterion) {
+    let mut group = c.benchmark_group("Emulator");
+    group.sample_size(20);
+
+    for (name, mix) in opcode_mixes() {
+        let blocks = loop_program(&mix, ITERATIONS);
+        let num_instructions = 1 + ITERATIONS as u64 * (mix.len() as u64 + 2);
+
+        group.throughput(Throughput::Elements(num_instructions));
+        group.bench_function(name, |b| {
+            b.iter_batched(
+                || HarvardEmulator::from_basic_blocks(&blocks),
+                |mut emulator| {
+                    let result = emulator.execute(black_box(false));
+                    assert!(matches!(result, Err(VMError::VMOutOfInstructions)));
+                    emulator
+                },
+                BatchSize::SmallInput,
+            )
+        });
+    }
+
+    group.finish();
+}
+
+/// Instruction mixes exercising different parts of the emulator, each is executed in a loop.
+fn opcode_mixes() -> Vec<(&'static str, Vec<Instruction>)> {
+    let ir = |opcode, op_a, op_b, op_c| Instruction::new_ir(Opcode::from(opcode), op_a, op_b, op_c);
+
+    vec![
+        (
+            "ALU",
+            vec![
+                ir(BuiltinOpcode::ADD, 2, 2, 3),
+                ir(BuiltinOpcode::XOR, 3, 3, 2),
+                ir(BuiltinOpcode::SLL, 4, 2, 3),
+                ir(BuiltinOpcode::SLTU, 5, 4, 2),
+                ir(BuiltinOpcode::ADDI, 3, 3, 7),
+                ir(BuiltinOpcode::SRAI, 6, 4, 3),
+            ],
+        ),
+        (
+            "MulDiv",
+            vec![
+                ir(BuiltinOpcode::ADDI, 2, 2, 13),
+                ir(BuiltinOpcode::MUL, 3, 2, 2),
+                ir(BuiltinOpcode::MULHU, 4, 3, 2),
+                ir(BuiltinOpcode::DIVU, 5, 3, 2),
+                ir(BuiltinOpcode::REMU, 6, 3, 5),
+            ],
+        ),
+        (
+            "LoadStore",
+            vec![
+                ir(BuiltinOpcode::ADDI, 2, 2, 1),
+                ir(BuiltinOpcode::SW, 0, 2, 0x400),
+                ir(BuiltinOpcode::SB, 0, 2, 0x405),
+                ir(BuiltinOpcode::LW, 3, 0, 0x400),
+                ir(BuiltinOpcode::LBU, 4, 0, 0x405),
+                ir(BuiltinOpcode::SH, 0, 3, 0x40A),
+                ir(BuiltinOpcode::LH, 5, 0, 0x40A),
+            ],
+        ),
+        (
+            "Branch",
+            vec![
+                ir(BuiltinOpcode::ADDI, 2, 2, 1),
+                ir(BuiltinOpcode::BEQ, 0, 2, 8),
+                ir(BuiltinOpcode::ADDI, 3, 3, 1),
+                ir(BuiltinOpcode::BLTU, 2, 0, 8),
+                ir(BuiltinOpcode::ADDI, 4, 4, 1),
+                ir(BuiltinOpcode::BGE, 0, 2, 8),
+                ir(BuiltinOpcode::ADDI, 5, 5, 1),
+            ],
+        ),
+    ]
+}
+
+/// A program executing `mix` `iterations` times, counting down in `x1`.
+fn loop_program(mix: &[Instruction], iterations: u32) -> Vec<BasicBlock> {
+    assert!(iterations < 1 << 11, "the counter is set with addi");
+
+    let back = -(WORD_SIZE as i32 * (mix.len() as i32 + 1)) as u32;
+    let body = mix
+        .iter()
+        .cloned()
+        .chain([
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, -1i32 as u32),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::BNE), 1, 0, back),
+        ])
+        .collect();
+
+    vec![
+        BasicBlock::new(vec![Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::ADDI),
+            1,
+            0,
+            iterations,
+        )]),
+        BasicBlock::new(body),
+    ]
+}
@@ -7,7 +7,7 @@ use nexus_vm::{
 };
 use nexus_vm_prover::trace::PreprocessedTraces;
 
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{black_box, criterion_group, criterion_main, Criterion, Throughput};
 
 const K: usize = 1;
 
@@ -43,6 +43,7 @@ fn bench_prove(c: &mut Criterion) {
 
         let mut group = c.benchmark_group(format!("Prove-LogSize-{log_size}"));
         group.sample_size(20);
+        group.throughput(Throughput::Elements(1 << log_size));
 
         group.bench_function("ComputeProof", |b| {
             b.iter(|| nexus_vm_prover::prove(black_box(&program_trace), black_box(&view)).unwrap())
@@ -6,6 +6,7 @@ use nexus_vm::{
     trace::{k_trace_direct, UniformTrace},
 };
 use nexus_vm_prover::{
+    chips::{AddChip, CpuChip, DecodingCheckChip, RangeCheckChip, RegisterMemCheckChip},
     components::AllLookupElements,
     machine::BaseComponent,
     trace::{
@@ -15,7 +16,10 @@ use nexus_vm_prover::{
     traits::{generate_interaction_trace, MachineChip},
 };
 
-use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use criterion::{
+    black_box, criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, Criterion,
+    Throughput,
+};
 use stwo_prover::core::channel::Blake2sChannel;
 
 const K: usize = 1;
@@ -42,7 +46,7 @@ const _: () = {
 criterion_group! {
     name = trace_gen;
     config = Criterion::default().warm_up_time(Duration::from_millis(3000));
-    targets = bench_trace_gen,
+    targets = bench_trace_gen, bench_chip_fill,
 }
 
 criterion_main!(trace_gen);
@@ -116,6 +120,75 @@ fn bench_trace_gen(c: &mut Criterion) {
     }
 }
 
+/// Main trace fill rate of individual chips, in rows per second.
+fn bench_chip_fill(c: &mut Criterion) {
+    for &log_size in LOG_SIZES {
+        let blocks = program_trace(log_size);
+        let (view, execution_trace) = k_trace_direct(&blocks, K).expect("error generating trace");
+
+        let mut group = c.benchmark_group(format!("ChipFill-LogSize-{log_size}"));
+        group.sample_size(20);
+        group.throughput(Throughput::Elements(1 << log_size));
+
+        bench_chip::<CpuChip>(&mut group, "CpuChip", log_size, &execution_trace, &view);
+        bench_chip::<DecodingCheckChip>(
+            &mut group,
+            "DecodingCheckChip",
+            log_size,
+            &execution_trace,
+            &view,
+        );
+        bench_chip::<AddChip>(&mut group, "AddChip", log_size, &execution_trace, &view);
+        bench_chip::<RegisterMemCheckChip>(
+            &mut group,
+            "RegisterMemCheckChip",
+            log_size,
+            &execution_trace,
+            &view,
+        );
+        bench_chip::<RangeCheckChip>(
+            &mut group,
+            "RangeCheckChip",
+            log_size,
+            &execution_trace,
+            &view,
+        );
+        group.finish();
+    }
+}
+
+fn bench_chip<C: MachineChip>(
+    group: &mut BenchmarkGroup<WallTime>,
+    name: &str,
+    log_size: u32,
+    execution_trace: &UniformTrace,
+    view: &View,
+) {
+    let mut program_traces = ProgramTracesBuilder::new(
+        log_size,
+        view.get_program_memory(),
+        view.get_initial_memory(),
+        view.get_exit_code(),
+        view.get_public_output(),
+    );
+
+    group.bench_function(name, |b| {
+        b.iter(|| {
+            let mut prover_traces = TracesBuilder::new(black_box(log_size));
+            let mut prover_side_note = SideNote::new(&mut program_traces, view);
+            let program_steps = iter_program_steps(execution_trace, prover_traces.num_rows());
+            for (row_idx, program_step) in program_steps.enumerate() {
+                C::fill_main_trace(
+                    black_box(&mut prover_traces),
+                    black_box(row_idx),
+                    black_box(&program_step),
+                    black_box(&mut prover_side_note),
+                )
+            }
+        })
+    });
+}
+
 fn fill_main_trace(
     prover_traces: &mut TracesBuilder,
     execution_trace: &UniformTrace,
@@ -10,6 +10,7 @@ const ALL_VARIANTS_IDENT: &str = "ALL_VARIANTS";
 
 const SIZE_FN_IDENT: &str = "size";
 const OFFSET_FN_IDENT: &str = "offset";
+const NAME_FN_IDENT: &str = "name";
 
 pub fn generate_impls(input: TokenStream) -> syn::Result<TokenStream> {
     let input: syn::ItemEnum = syn::parse2(input)?;
@@ -58,6 +59,20 @@ pub fn generate_impls(input: TokenStream) -> syn::Result<TokenStream> {
         }
     };
 
+    let name_impl = {
+        let ident_iter = ident_iter.clone();
+        let name_iter = variants.iter().map(|v| v.0.to_string());
+        let name_fn_ident = quote::format_ident!("{NAME_FN_IDENT}");
+        quote! {
+            #[doc = "Returns the name of the variant."]
+            pub const fn #name_fn_ident(self) -> &'static str {
+                match self {
+                    #( Self::#ident_iter => #name_iter, )*
+                }
+            }
+        }
+    };
+
     let ident_iter = _ident_iter;
     let mut offset = 0usize;
     let offset_iter = variants.iter().map(|v| {
@@ -85,6 +100,8 @@ pub fn generate_impls(input: TokenStream) -> syn::Result<TokenStream> {
 
             #offset_impl
 
+            #name_impl
+
             #all_variants_impl
 
             #string_id_impl
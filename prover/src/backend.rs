@@ -0,0 +1,62 @@
+//! Backends the prover can commit to traces and prove constraints with.
+//!
+//! Traces are always generated as SIMD columns, the SIMD backend only relies on portable SIMD and runs
+//! on any target. The remaining proving stages are generic over [`ProverBackend`], so that they can run
+//! on the plain [`CpuBackend`] where the SIMD backend is slow or unavailable, e.g. on wasm. The proof
+//! doesn't depend on the backend.
+
+use stwo_prover::{
+    constraint_framework::{FrameworkComponent, FrameworkEval},
+    core::{
+        air::ComponentProver,
+        backend::{simd::SimdBackend, BackendForChannel, Column, CpuBackend},
+        fields::m31::BaseField,
+        poly::{circle::CircleEvaluation, BitReversedOrder},
+        vcs::blake2_merkle::Blake2sMerkleChannel,
+        ColumnVec,
+    },
+};
+
+/// Backend of the commitment and proving stages, see [`crate::machine::Machine::prove_with_backend`].
+pub trait ProverBackend: BackendForChannel<Blake2sMerkleChannel> + Sized {
+    /// Moves trace columns generated on the SIMD backend to this backend.
+    fn from_simd_evals(
+        evals: ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    ) -> ColumnVec<CircleEvaluation<Self, BaseField, BitReversedOrder>>;
+
+    /// Returns `component` as a prover on this backend.
+    fn component_prover<E: FrameworkEval + Sync + 'static>(
+        component: FrameworkComponent<E>,
+    ) -> Box<dyn ComponentProver<Self>>;
+}
+
+impl ProverBackend for SimdBackend {
+    fn from_simd_evals(
+        evals: ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    ) -> ColumnVec<CircleEvaluation<Self, BaseField, BitReversedOrder>> {
+        evals
+    }
+
+    fn component_prover<E: FrameworkEval + Sync + 'static>(
+        component: FrameworkComponent<E>,
+    ) -> Box<dyn ComponentProver<Self>> {
+        Box::new(component)
+    }
+}
+
+impl ProverBackend for CpuBackend {
+    fn from_simd_evals(
+        evals: ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    ) -> ColumnVec<CircleEvaluation<Self, BaseField, BitReversedOrder>> {
+        evals
+            .into_iter()
+            .map(|eval| CircleEvaluation::new(eval.domain, eval.values.to_cpu()))
+            .collect()
+    }
+
+    fn component_prover<E: FrameworkEval + Sync + 'static>(
+        component: FrameworkComponent<E>,
+    ) -> Box<dyn ComponentProver<Self>> {
+        Box::new(component)
+    }
+}
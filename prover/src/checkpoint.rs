@@ -0,0 +1,101 @@
+//! Crash-safe proving checkpoints.
+//!
+//! [`Machine::prove_with_checkpoints`] writes the output of each completed proving phase to a directory,
+//! and [`Machine::resume`] continues a proof lost to a crash from the last one:
+//!
+//! - `traces.bin`: the filled traces and side notes, once [`Machine::build_traces`] completes.
+//! - `proof.bin`: the finished proof.
+//!
+//! The committed trees aren't written, stwo's commitment scheme can't be serialized. They are
+//! recomputed from the traces on resumption, and since proving is deterministic, the resumed proof is
+//! the same as an uninterrupted one. Every file is written next to its final path and renamed, so a
+//! crash while writing leaves the previous phase intact.
+//!
+//! [`Machine::prove_with_checkpoints`]: crate::machine::Machine::prove_with_checkpoints
+//! [`Machine::resume`]: crate::machine::Machine::resume
+//! [`Machine::build_traces`]: crate::machine::Machine::build_traces
+
+use std::{borrow::Cow, fs, io::ErrorKind, path::Path};
+
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use stwo_prover::core::vcs::blake2_hash::Blake2sHash;
+
+use crate::{
+    error::ProveError,
+    machine::{Proof, PublicRegisters},
+    trace::{program_trace::ProgramTraces, sidenote::SideNote, FinalizedTraces},
+};
+
+const TRACES_FILE: &str = "traces.bin";
+const PROOF_FILE: &str = "proof.bin";
+
+/// Output of [`crate::machine::Machine::build_traces`], borrowed when written and owned when read.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct TracesCheckpoint<'a> {
+    pub(crate) log_size: u32,
+    // Identifies the chips of the machine the traces were filled by
+    pub(crate) log_constraint_degree: u32,
+    pub(crate) preprocessed_columns: Vec<Cow<'a, str>>,
+    pub(crate) associated_data: Cow<'a, [u8]>,
+    pub(crate) finalized_trace: Cow<'a, FinalizedTraces>,
+    pub(crate) finalized_program_trace: Cow<'a, ProgramTraces>,
+    pub(crate) side_note: Cow<'a, SideNote>,
+    // Names of the extension components
+    pub(crate) extensions: Vec<Cow<'a, str>>,
+    pub(crate) public_registers: Option<PublicRegisters>,
+    pub(crate) final_memory_digest: Option<Blake2sHash>,
+}
+
+/// Removes the checkpoints of a previous proof in `dir`, creating it if needed.
+pub(crate) fn clear(dir: &Path) -> Result<(), ProveError> {
+    fs::create_dir_all(dir).map_err(ProveError::Checkpoint)?;
+    for file in [TRACES_FILE, PROOF_FILE] {
+        let path = dir.join(file);
+        if path.exists() {
+            fs::remove_file(path).map_err(ProveError::Checkpoint)?;
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn write_traces(dir: &Path, traces: &TracesCheckpoint<'_>) -> Result<(), ProveError> {
+    write(dir, TRACES_FILE, traces)
+}
+
+pub(crate) fn read_traces(dir: &Path) -> Result<TracesCheckpoint<'static>, ProveError> {
+    read(dir, TRACES_FILE)?.ok_or_else(|| {
+        ProveError::Checkpoint(std::io::Error::new(
+            ErrorKind::NotFound,
+            format!("no proving checkpoint in {}", dir.display()),
+        ))
+    })
+}
+
+pub(crate) fn write_proof(dir: &Path, proof: &Proof) -> Result<(), ProveError> {
+    write(dir, PROOF_FILE, proof)
+}
+
+pub(crate) fn read_proof(dir: &Path) -> Result<Option<Proof>, ProveError> {
+    read(dir, PROOF_FILE)
+}
+
+fn write<T: Serialize>(dir: &Path, file: &str, value: &T) -> Result<(), ProveError> {
+    let bytes = postcard::to_stdvec(value)
+        .map_err(|e| ProveError::InvalidCheckpoint(format!("{file}: {e}")))?;
+    let path = dir.join(file);
+    let partial = dir.join(format!("{file}.partial"));
+    fs::write(&partial, bytes)
+        .and_then(|_| fs::rename(&partial, &path))
+        .map_err(ProveError::Checkpoint)
+}
+
+fn read<T: DeserializeOwned>(dir: &Path, file: &str) -> Result<Option<T>, ProveError> {
+    let bytes = match fs::read(dir.join(file)) {
+        Ok(bytes) => bytes,
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(ProveError::Checkpoint(e)),
+    };
+    postcard::from_bytes(&bytes)
+        .map(Some)
+        .map_err(|e| ProveError::InvalidCheckpoint(format!("{file}: {e}")))
+}
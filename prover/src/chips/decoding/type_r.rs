@@ -6,7 +6,8 @@ use crate::{
 };
 
 use crate::column::Column::{
-    self, ImmC, InstrVal, IsAdd, IsSub, OpA, OpA0, OpA1_4, OpB, OpB0, OpB1_4, OpC, OpC0_3, OpC4,
+    self, ImmC, InstrVal, IsAdd, IsDiv, IsDivu, IsMul, IsMulh, IsMulhsu, IsMulhu, IsRem, IsRemu,
+    IsSub, OpA, OpA0, OpA1_4, OpB, OpB0, OpB1_4, OpC, OpC0_3, OpC4,
 };
 use crate::trace::eval::trace_eval;
 use nexus_vm::riscv::InstructionType::RType;
@@ -294,5 +295,34 @@ impl MachineChip for TypeRChip {
                     + E::F::from(BaseField::from(0b0000000)) * BaseField::from(1 << 1)
                     - instr_val[3].clone()),
         );
+
+        // M extension instructions have no immediate variants, so the constraints don't depend on imm_c.
+        // (is_op) ・ (op_a1_4 + funct3・2^4 + op_b0・2^7 - instr_val_2) = 0
+        // (is_op) ・ (op_c4 + b0000001・2 - instr_val_4) = 0
+        for (op, funct3) in [
+            (IsMul, 0b000),
+            (IsMulh, 0b001),
+            (IsMulhsu, 0b010),
+            (IsMulhu, 0b011),
+            (IsDiv, 0b100),
+            (IsDivu, 0b101),
+            (IsRem, 0b110),
+            (IsRemu, 0b111),
+        ] {
+            let [is_op] = trace_eval.column_eval(op);
+            eval.add_constraint(
+                is_op.clone()
+                    * (op_a1_4.clone()
+                        + E::F::from(BaseField::from(funct3)) * BaseField::from(1 << 4)
+                        + op_b0.clone() * BaseField::from(1 << 7)
+                        - instr_val[1].clone()),
+            );
+            eval.add_constraint(
+                is_op
+                    * (op_c4.clone()
+                        + E::F::from(BaseField::from(0b0000001)) * BaseField::from(1 << 1)
+                        - instr_val[3].clone()),
+            );
+        }
     }
 }
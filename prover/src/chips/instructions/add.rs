@@ -1,6 +1,6 @@
 use stwo_prover::{constraint_framework::EvalAtRow, core::fields::FieldExpOps};
 
-use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
+use nexus_vm::riscv::BuiltinOpcode;
 
 use crate::{
     column::Column::{self, *},
@@ -8,7 +8,7 @@ use crate::{
     trace::{
         eval::{trace_eval, TraceEval},
         sidenote::SideNote,
-        BoolWord, ProgramStep, TracesBuilder, Word,
+        BoolWordN, ProgramStep, TracesBuilder, Word, WordN,
     },
     traits::{ExecuteChip, MachineChip},
 };
@@ -21,16 +21,16 @@ pub struct ExecutionResult {
     sum_bytes: Word,
 }
 
-pub fn add_with_carries(a: Word, b: Word) -> (Word, BoolWord) {
-    let mut sum_bytes = [0u8; WORD_SIZE];
-    let mut carry_bits = [false; WORD_SIZE];
+pub fn add_with_carries<const N: usize>(a: WordN<N>, b: WordN<N>) -> (WordN<N>, BoolWordN<N>) {
+    let mut sum_bytes = [0u8; N];
+    let mut carry_bits = [false; N];
 
     // Compute the sum and carry of each limb.
     let (sum, c0) = a[0].overflowing_add(b[0]);
     carry_bits[0] = c0;
     sum_bytes[0] = sum;
     // Process the remaining bytes
-    for i in 1..WORD_SIZE {
+    for i in 1..N {
         // Add the bytes and the previous carry
         let (sum, c1) = a[i].overflowing_add(carry_bits[i - 1] as u8);
         let (sum, c2) = sum.overflowing_add(b[i]);
@@ -236,4 +236,16 @@ mod test {
         }
         assert_chip::<Chips>(traces, Some(program_trace.finalize()));
     }
+
+    #[test]
+    fn test_add_with_carries_is_word_size_generic() {
+        let (sum, carries) = add_with_carries(0xFFFF_FFFFu32.to_le_bytes(), 1u32.to_le_bytes());
+        assert_eq!(u32::from_le_bytes(sum), 0);
+        assert_eq!(carries, [true; 4]);
+
+        let (sum, carries) =
+            add_with_carries(0x0000_00FF_FFFF_FFFFu64.to_le_bytes(), 1u64.to_le_bytes());
+        assert_eq!(u64::from_le_bytes(sum), 0x0000_0100_0000_0000);
+        assert_eq!(carries, [true, true, true, true, true, false, false, false]);
+    }
 }
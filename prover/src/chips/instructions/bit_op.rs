@@ -2,7 +2,7 @@ use std::array;
 
 use num_traits::Zero;
 use stwo_prover::{
-    constraint_framework::{logup::LogupTraceGenerator, EvalAtRow, Relation, RelationEntry},
+    constraint_framework::{EvalAtRow, Relation, RelationEntry},
     core::{
         backend::simd::m31::{PackedBaseField, LOG_N_LANES},
         fields::m31::BaseField,
@@ -18,6 +18,7 @@ use crate::{
     components::AllLookupElements,
     trace::{
         eval::{trace_eval, TraceEval},
+        logup::LogupTraceBuilder,
         program_trace::ProgramTraces,
         sidenote::SideNote,
         FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder, Word,
@@ -323,7 +324,7 @@ impl MachineChip for BitOpChip {
     ///
     /// data[vec_row] contains sixteen rows. A single write_frac() adds sixteen rows.
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         _preprocessed_trace: &PreprocessedTraces,
         _program_traces: &ProgramTraces,
@@ -0,0 +1,457 @@
+use num_traits::{One, Zero};
+use stwo_prover::{
+    constraint_framework::EvalAtRow,
+    core::fields::{m31::BaseField, FieldExpOps},
+};
+
+use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
+
+use super::mul::{
+    add_mul_add_constraints, fill_carries, mul_add_with_carries, sign_extend, sign_extend_eval,
+    PRODUCT_SIZE,
+};
+use crate::{
+    column::Column::{self, *},
+    components::AllLookupElements,
+    trace::{
+        eval::{trace_eval, TraceEval},
+        sidenote::SideNote,
+        ProgramStep, TracesBuilder, Word,
+    },
+    traits::{ExecuteChip, MachineChip},
+};
+
+// Support DIV and DIVU opcodes.
+pub struct DivChip;
+
+pub struct ExecutionResult {
+    pub quotient: Word,
+    pub remainder: Word,
+}
+
+/// Computes the quotient and the remainder of b / c, as specified by RISC-V for division by zero and overflow.
+pub(crate) fn div_rem(b: u32, c: u32, signed: bool) -> (u32, u32) {
+    if c == 0 {
+        return (u32::MAX, b);
+    }
+    if signed {
+        let (b, c) = (b as i32, c as i32);
+        // -2^31 / -1 overflows, the quotient wraps around to -2^31
+        (b.wrapping_div(c) as u32, b.wrapping_rem(c) as u32)
+    } else {
+        (b / c, b % c)
+    }
+}
+
+impl ExecuteChip for DivChip {
+    type ExecutionResult = ExecutionResult;
+    fn execute(program_step: &ProgramStep) -> Self::ExecutionResult {
+        let value_b = program_step.get_value_b();
+        let (value_c, _) = program_step.get_value_c();
+        let signed = matches!(
+            program_step.step.instruction.opcode.builtin(),
+            Some(BuiltinOpcode::DIV) | Some(BuiltinOpcode::REM)
+        );
+
+        let (quotient, remainder) = div_rem(
+            u32::from_le_bytes(value_b),
+            u32::from_le_bytes(value_c),
+            signed,
+        );
+        ExecutionResult {
+            quotient: quotient.to_le_bytes(),
+            remainder: remainder.to_le_bytes(),
+        }
+    }
+}
+
+/// Fills the columns shared by DIV, DIVU, REM and REMU, and returns the quotient and the remainder.
+///
+/// The columns witness b = qt・c + rem, with |rem| < |c| and rem having the sign of b unless it is zero. Unsigned
+/// operations leave all sign bits zero.
+pub(crate) fn fill_div_rem(
+    traces: &mut TracesBuilder,
+    row_idx: usize,
+    vm_step: &ProgramStep,
+) -> ExecutionResult {
+    let signed = matches!(
+        vm_step.step.instruction.opcode.builtin(),
+        Some(BuiltinOpcode::DIV) | Some(BuiltinOpcode::REM)
+    );
+    let value_b = vm_step.get_value_b();
+    let (value_c, _) = vm_step.get_value_c();
+    let ExecutionResult {
+        quotient,
+        remainder,
+    } = DivChip::execute(vm_step);
+
+    let sgn = |word: Word| signed && (word[WORD_SIZE - 1] >> 7) == 1;
+    let (sgn_b, sgn_c, sgn_qt, sgn_rem) =
+        (sgn(value_b), sgn(value_c), sgn(quotient), sgn(remainder));
+    let div_by_zero = value_c == [0; WORD_SIZE];
+    let overflow = signed
+        && u32::from_le_bytes(value_b) == i32::MIN as u32
+        && u32::from_le_bytes(value_c) == u32::MAX;
+
+    // qt・c + rem = b modulo 2^64, where b isn't sign-extended on overflow
+    let (product, carries) = mul_add_with_carries(
+        sign_extend(quotient, sgn_qt),
+        sign_extend(value_c, sgn_c),
+        sign_extend(remainder, sgn_rem),
+    );
+    assert_eq!(product, sign_extend(value_b, sgn_b && !overflow));
+    fill_carries(traces, row_idx, carries);
+
+    // rem_diff = |c| - |rem| - 1, the absolute values of negative words are their complements plus one
+    let complement = |word: u32, sgn: bool| if sgn { !word } else { word };
+    let c_complement = complement(u32::from_le_bytes(value_c), sgn_c);
+    let rem_complement = complement(u32::from_le_bytes(remainder), sgn_rem);
+    if !div_by_zero {
+        let rem_diff =
+            (c_complement as u64 + sgn_c as u64 - rem_complement as u64 - sgn_rem as u64 - 1)
+                as u32;
+        // Carry from the lower 16 bits of rem_diff + rem' + sgn_rem + 1 into the upper ones, at most two.
+        let carry = ((rem_diff & 0xFFFF) + (rem_complement & 0xFFFF) + sgn_rem as u32 + 1
+            - (c_complement & 0xFFFF)
+            - sgn_c as u32)
+            >> 16;
+        traces.fill_columns(row_idx, rem_diff, RemDiff);
+        traces.fill_columns(row_idx, [carry > 0, carry > 1], CarryFlag);
+    }
+
+    let c_sum = value_c.iter().map(|&limb| limb as u32).sum::<u32>();
+    let div_by_zero_aux = if div_by_zero {
+        BaseField::zero()
+    } else {
+        BaseField::from(c_sum).inverse()
+    };
+    traces.fill_columns(row_idx, div_by_zero, DivByZero);
+    traces.fill_columns(row_idx, div_by_zero_aux, DivByZeroAux);
+    traces.fill_columns(row_idx, overflow, DivOverflow);
+
+    // Fill Helper{1,2,3,4} with the words without their sign bits
+    for (mut word, col) in [
+        (remainder, Helper1),
+        (value_b, Helper2),
+        (value_c, Helper3),
+        (quotient, Helper4),
+    ] {
+        word[WORD_SIZE - 1] &= 0x7f;
+        traces.fill_columns(row_idx, word, col);
+    }
+    traces.fill_columns(row_idx, sgn_b, SgnB);
+    traces.fill_columns(row_idx, sgn_c, SgnC);
+    traces.fill_columns(row_idx, sgn_qt, SgnQt);
+    traces.fill_columns(row_idx, sgn_rem, SgnRem);
+    traces.fill_columns(row_idx, sgn_b && !sgn_rem, SgnRemAux);
+    traces.fill_columns(row_idx, quotient, Qt);
+    traces.fill_columns(row_idx, remainder, Rem);
+
+    ExecutionResult {
+        quotient,
+        remainder,
+    }
+}
+
+/// Adds the constraints shared by DIV, DIVU, REM and REMU. `is_signed` and `is_unsigned` are the flags of the signed
+/// and the unsigned operation.
+pub(crate) fn add_div_rem_constraints<E: EvalAtRow>(
+    eval: &mut E,
+    trace_eval: &TraceEval<E>,
+    is_signed: E::F,
+    is_unsigned: E::F,
+) {
+    let is_div_rem = is_signed.clone() + is_unsigned.clone();
+    let one = E::F::one();
+    // modulus for 8-bit limbs
+    let modulus = E::F::from(BaseField::from(1 << 8));
+    // modulus for 7-bit
+    let modulus_7 = E::F::from(BaseField::from(1 << 7));
+
+    let value_b = trace_eval!(trace_eval, ValueB);
+    let value_c = trace_eval!(trace_eval, ValueC);
+    let qt = trace_eval!(trace_eval, Qt);
+    let rem = trace_eval!(trace_eval, Rem);
+    let rem_diff = trace_eval!(trace_eval, RemDiff);
+    let carry_flag = trace_eval!(trace_eval, CarryFlag);
+    let [sgn_b] = trace_eval!(trace_eval, SgnB);
+    let [sgn_c] = trace_eval!(trace_eval, SgnC);
+    let [sgn_qt] = trace_eval!(trace_eval, SgnQt);
+    let [sgn_rem] = trace_eval!(trace_eval, SgnRem);
+    let [sgn_rem_aux] = trace_eval!(trace_eval, SgnRemAux);
+    let [div_by_zero] = trace_eval!(trace_eval, DivByZero);
+    let [div_by_zero_aux] = trace_eval!(trace_eval, DivByZeroAux);
+    let [overflow] = trace_eval!(trace_eval, DivOverflow);
+
+    // Sign bits are the most significant bits for signed operations, and zero for unsigned ones.
+    // is_signed ・ (h[3] + sgn・2^7 - val[3]) = 0 for (rem, h1), (b, h2), (c, h3), (qt, h4)
+    for (val, sgn, helper) in [
+        (&rem, &sgn_rem, Helper1),
+        (&value_b, &sgn_b, Helper2),
+        (&value_c, &sgn_c, Helper3),
+        (&qt, &sgn_qt, Helper4),
+    ] {
+        let helper_val = trace_eval.column_eval::<WORD_SIZE>(helper);
+        eval.add_constraint(
+            is_signed.clone()
+                * (modulus_7.clone() * sgn.clone() + helper_val[3].clone() - val[3].clone()),
+        );
+    }
+    // is_unsigned ・ (sgn_b + sgn_c + sgn_qt + sgn_rem) = 0
+    eval.add_constraint(
+        is_unsigned.clone() * (sgn_b.clone() + sgn_c.clone() + sgn_qt.clone() + sgn_rem.clone()),
+    );
+
+    // Division by zero: div_by_zero is set if and only if all limbs of c are zero, then qt is all ones.
+    // is_div_rem ・ div_by_zero ・ (c_val[0] + c_val[1] + c_val[2] + c_val[3]) = 0
+    // is_div_rem ・ ((c_val[0] + c_val[1] + c_val[2] + c_val[3])・div_by_zero_aux + div_by_zero - 1) = 0
+    // is_div_rem ・ div_by_zero ・ (qt[i] - 255) = 0
+    let c_sum = value_c
+        .iter()
+        .fold(E::F::zero(), |acc, limb| acc + limb.clone());
+    eval.add_constraint(is_div_rem.clone() * div_by_zero.clone() * c_sum.clone());
+    eval.add_constraint(
+        is_div_rem.clone() * (c_sum * div_by_zero_aux + div_by_zero.clone() - one.clone()),
+    );
+    for limb in qt.iter() {
+        eval.add_constraint(
+            is_div_rem.clone()
+                * div_by_zero.clone()
+                * (limb.clone() - E::F::from(BaseField::from(0xFF))),
+        );
+    }
+
+    // Overflow: -2^31 / -1 has the quotient -2^31 and the remainder 0, so qt・c + rem is 2^31 instead of b.
+    // is_unsigned ・ overflow = 0
+    // is_signed ・ overflow ・ (b_val - 2^31) = 0, limb by limb
+    // is_signed ・ overflow ・ (c_val - (2^32 - 1)) = 0, limb by limb
+    eval.add_constraint(is_unsigned.clone() * overflow.clone());
+    for i in 0..WORD_SIZE {
+        let b_limb = if i == WORD_SIZE - 1 { 0x80 } else { 0 };
+        eval.add_constraint(
+            is_signed.clone()
+                * overflow.clone()
+                * (value_b[i].clone() - E::F::from(BaseField::from(b_limb))),
+        );
+        eval.add_constraint(
+            is_signed.clone()
+                * overflow.clone()
+                * (value_c[i].clone() - E::F::from(BaseField::from(0xFF))),
+        );
+    }
+
+    // qt・c + rem = b modulo 2^64 on sign-extended values. The upper half of b is zero on overflow.
+    let x = sign_extend_eval::<E>(&qt, &sgn_qt);
+    let y = sign_extend_eval::<E>(&value_c, &sgn_c);
+    let z = sign_extend_eval::<E>(&rem, &sgn_rem);
+    let p = sign_extend_eval::<E>(&value_b, &(sgn_b.clone() - overflow));
+    add_mul_add_constraints::<E, PRODUCT_SIZE>(
+        eval,
+        trace_eval,
+        is_div_rem.clone(),
+        &x,
+        &y,
+        &z,
+        &p,
+    );
+
+    // The remainder is negative only if b is, and zero if b is negative and the remainder isn't.
+    // is_signed ・ sgn_rem ・ (1 - sgn_b) = 0
+    // is_signed ・ (sgn_rem_aux - sgn_b・(1 - sgn_rem)) = 0
+    // is_signed ・ sgn_rem_aux ・ (rem[0] + rem[1] + rem[2] + rem[3]) = 0
+    eval.add_constraint(is_signed.clone() * sgn_rem.clone() * (one.clone() - sgn_b.clone()));
+    eval.add_constraint(
+        is_signed.clone() * (sgn_rem_aux.clone() - sgn_b.clone() * (one.clone() - sgn_rem.clone())),
+    );
+    let rem_sum = rem
+        .iter()
+        .fold(E::F::zero(), |acc, limb| acc + limb.clone());
+    eval.add_constraint(is_signed.clone() * sgn_rem_aux * rem_sum);
+
+    // |rem| < |c| unless dividing by zero: rem_diff + |rem| + 1 = |c| with no carry out of 32 bits, where
+    // |x| = x' + sgn_x and x' is x with every limb complemented when sgn_x is set. Both sides are below 2^32 + 2.
+    // (is_div_rem - div_by_zero) ・ (rem_diff_lo + rem'_lo + sgn_rem + 1 - c'_lo - sgn_c - (carry[0] + carry[1])・2^16) = 0
+    // (is_div_rem - div_by_zero) ・ (rem_diff_hi + rem'_hi + carry[0] + carry[1] - c'_hi) = 0
+    let complement = |word: &[E::F; WORD_SIZE], sgn: &E::F| -> [E::F; WORD_SIZE] {
+        std::array::from_fn(|i| {
+            word[i].clone()
+                + sgn.clone()
+                    * (E::F::from(BaseField::from(0xFF)) - word[i].clone() * BaseField::from(2))
+        })
+    };
+    let rem_c = complement(&rem, &sgn_rem);
+    let c_c = complement(&value_c, &sgn_c);
+    let half = |word: &[E::F; WORD_SIZE], i: usize| {
+        word[i].clone() + word[i + 1].clone() * modulus.clone()
+    };
+    let carry = carry_flag[0].clone() + carry_flag[1].clone();
+    let is_rem_bounded = is_div_rem - div_by_zero;
+    eval.add_constraint(
+        is_rem_bounded.clone()
+            * (half(&rem_diff, 0) + half(&rem_c, 0) + sgn_rem + one
+                - half(&c_c, 0)
+                - sgn_c
+                - carry.clone() * BaseField::from(1 << 16)),
+    );
+    eval.add_constraint(
+        is_rem_bounded * (half(&rem_diff, 2) + half(&rem_c, 2) + carry - half(&c_c, 2)),
+    );
+}
+
+impl MachineChip for DivChip {
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        _side_note: &mut SideNote,
+    ) {
+        let vm_step = match vm_step {
+            Some(vm_step) => vm_step,
+            None => return, // padding
+        };
+        if !matches!(
+            vm_step.step.instruction.opcode.builtin(),
+            Some(BuiltinOpcode::DIV) | Some(BuiltinOpcode::DIVU)
+        ) {
+            return;
+        }
+
+        let ExecutionResult { quotient, .. } = fill_div_rem(traces, row_idx, vm_step);
+
+        assert_eq!(
+            quotient,
+            vm_step
+                .get_result()
+                .expect("DIV/DIVU instruction must have a result")
+        );
+        traces.fill_columns(row_idx, quotient, ValueA);
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        _lookup_elements: &AllLookupElements,
+    ) {
+        let [is_div] = trace_eval!(trace_eval, IsDiv);
+        let [is_divu] = trace_eval!(trace_eval, IsDivu);
+        let value_a = trace_eval!(trace_eval, ValueA);
+        let qt = trace_eval!(trace_eval, Qt);
+
+        // (is_div + is_divu) ・ (a_val - qt) = 0, limb by limb
+        for i in 0..WORD_SIZE {
+            eval.add_constraint(
+                (is_div.clone() + is_divu.clone()) * (value_a[i].clone() - qt[i].clone()),
+            );
+        }
+        add_div_rem_constraints(eval, trace_eval, is_div, is_divu);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        chips::{
+            AddChip, CpuChip, DecodingCheckChip, ProgramMemCheckChip, RangeCheckChip,
+            RegisterMemCheckChip, RemChip, SllChip, SubChip, TimestampChip,
+        },
+        test_utils::assert_chip,
+        trace::{
+            preprocessed::PreprocessedBuilder, program::iter_program_steps,
+            program_trace::ProgramTracesBuilder,
+        },
+    };
+
+    use super::*;
+    use nexus_vm::{
+        emulator::InternalView,
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    const LOG_SIZE: u32 = PreprocessedBuilder::MIN_LOG_SIZE;
+
+    fn setup_basic_block_ir() -> Vec<BasicBlock> {
+        // x1 = 7, x2 = -3 (0xFFFFFFFD), x3 = 0x80000000, x4 = -1, x5 = 0x10000, x6 = -0x20000
+        let mut instructions = vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 7),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 3),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 2, 0, 10),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 11, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 3, 11, 31),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 4, 0, 11),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 5, 11, 16),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 6, 4, 17),
+        ];
+        // Operands covering signs, division by zero and overflow
+        for opcode in [
+            BuiltinOpcode::DIV,
+            BuiltinOpcode::DIVU,
+            BuiltinOpcode::REM,
+            BuiltinOpcode::REMU,
+        ] {
+            for (op_b, op_c) in [
+                (1, 1),
+                (1, 2),
+                (2, 1),
+                (2, 2),
+                (1, 0),
+                (2, 0),
+                (0, 1),
+                (3, 4),
+                (3, 2),
+                (4, 3),
+                (1, 3),
+                (6, 5),
+                (5, 6),
+                (6, 2),
+            ] {
+                instructions.push(Instruction::new_ir(Opcode::from(opcode), 7, op_b, op_c));
+            }
+        }
+        vec![BasicBlock::new(instructions)]
+    }
+
+    #[test]
+    fn test_k_trace_constrained_div_rem_instructions() {
+        type Chips = (
+            CpuChip,
+            DecodingCheckChip,
+            AddChip,
+            SubChip,
+            SllChip,
+            DivChip,
+            RemChip,
+            RegisterMemCheckChip,
+            ProgramMemCheckChip,
+            TimestampChip,
+            RangeCheckChip,
+        );
+        let basic_block = setup_basic_block_ir();
+        let k = 1;
+
+        // Get traces from VM K-Trace interface
+        let (view, vm_traces) = k_trace_direct(&basic_block, k).expect("Failed to create trace");
+        let program_info = view.get_program_memory();
+
+        // Trace circuit
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_steps = iter_program_steps(&vm_traces, traces.num_rows());
+        let program_traces = ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, program_info);
+        let mut side_note = SideNote::new(&program_traces, &view);
+
+        for (row_idx, program_step) in program_steps.enumerate() {
+            Chips::fill_main_trace(&mut traces, row_idx, &program_step, &mut side_note);
+        }
+        assert_chip::<Chips>(traces, Some(program_traces.finalize()));
+    }
+
+    #[test]
+    fn test_div_rem() {
+        assert_eq!(div_rem(7, 0, true), (u32::MAX, 7));
+        assert_eq!(div_rem(7, 0, false), (u32::MAX, 7));
+        assert_eq!(div_rem(0x8000_0000, u32::MAX, true), (0x8000_0000, 0));
+        assert_eq!(div_rem(0x8000_0000, u32::MAX, false), (0, 0x8000_0000));
+        assert_eq!(div_rem(-7i32 as u32, 2, true), (-3i32 as u32, -1i32 as u32));
+        assert_eq!(div_rem(7, -2i32 as u32, true), (-3i32 as u32, 1));
+    }
+}
@@ -1,7 +1,12 @@
-use nexus_vm::{memory::MemAccessSize, riscv::BuiltinOpcode, WORD_SIZE};
+use std::collections::BTreeSet;
+
+use nexus_vm::{
+    emulator::MemoryInitializationEntry, memory::MemAccessSize, riscv::BuiltinOpcode, trace::Trace,
+    WORD_SIZE,
+};
 use num_traits::{One, Zero};
 use stwo_prover::{
-    constraint_framework::{logup::LogupTraceGenerator, EvalAtRow, Relation, RelationEntry},
+    constraint_framework::{EvalAtRow, Relation, RelationEntry},
     core::{
         backend::simd::m31::{PackedBaseField, LOG_N_LANES},
         fields::m31::{self, BaseField},
@@ -20,8 +25,10 @@ use crate::{
         PreprocessedColumn, ProgramColumn,
     },
     components::AllLookupElements,
+    error::ProveError,
     trace::{
         eval::{preprocessed_trace_eval, program_trace_eval, trace_eval},
+        logup::LogupTraceBuilder,
         program_trace::ProgramTraces,
         sidenote::SideNote,
         FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder, Word,
@@ -62,7 +69,8 @@ impl VirtualColumnForSum for Ram3_4Accessed {
 // Support SB, SH, SW, LB, LH and LW opcodes
 pub struct LoadStoreChip;
 
-const LOOKUP_TUPLE_SIZE: usize = 2 * WORD_SIZE + 1;
+// The last element of a tuple is whether the address is read-only data, see `RamReadOnly`.
+const LOOKUP_TUPLE_SIZE: usize = 2 * WORD_SIZE + 2;
 stwo_prover::relation!(LoadStoreLookupElements, LOOKUP_TUPLE_SIZE);
 
 impl MachineChip for LoadStoreChip {
@@ -86,7 +94,7 @@ impl MachineChip for LoadStoreChip {
     }
 
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         preprocessed_trace: &PreprocessedTraces,
         program_traces: &ProgramTraces,
@@ -102,6 +110,8 @@ impl MachineChip for LoadStoreChip {
         // - `address{0,1,2,3}` is the accessed memory address in four-limbs each containing one byte
         // - `value` is the one-byte value written to or read from the memory.
         // - `counter{0,1,2,3}` is the timestamp of the memory access. In four-limbs each containing one byte.
+        // - `read_only` is one if the address is read-only data. It's taken from the public initial memory
+        //   for the initial and final values, so every access to read-only data must carry it as well.
 
         // Add initial values to logup sum
         Self::add_initial_values(
@@ -155,7 +165,12 @@ impl MachineChip for LoadStoreChip {
         );
 
         // Subtract final values from logup sum
-        Self::subtract_final_values(original_traces, lookup_element, logup_trace_gen);
+        Self::subtract_final_values(
+            original_traces,
+            program_traces,
+            lookup_element,
+            logup_trace_gen,
+        );
     }
 
     fn add_constraints<E: stwo_prover::constraint_framework::EvalAtRow>(
@@ -179,6 +194,14 @@ impl MachineChip for LoadStoreChip {
                     * (ram_init_final_addr[i].clone() - public_ram_addr[i].clone()),
             );
         }
+        // Rows of public initial memory and output take part in the memory check, otherwise the
+        // prover could leave the public bytes out and initialize their addresses to zero elsewhere.
+        // (initial_memory_flag + public_output_flag) ・(1 - ram_init_final_flag) = 0
+        let [ram_init_final_flag] = trace_eval!(trace_eval, Column::RamInitFinalFlag);
+        eval.add_constraint(
+            (initial_memory_flag.clone() + public_output_flag.clone())
+                * (E::F::one() - ram_init_final_flag.clone()),
+        );
         // public_output_flag ・(ram_final_value - public_output_value) = 0
         let [ram_final_value] = trace_eval!(trace_eval, Column::RamFinalValue);
         let [public_output_value] =
@@ -187,6 +210,36 @@ impl MachineChip for LoadStoreChip {
             public_output_flag.clone() * (ram_final_value.clone() - public_output_value.clone()),
         );
 
+        // Constraints for RAM vs the claimed final memory, see `Proof::final_memory_digest`
+        let [final_memory_mode] =
+            program_trace_eval!(trace_eval, ProgramColumn::PublicFinalMemoryMode);
+        let [final_memory_flag] =
+            program_trace_eval!(trace_eval, ProgramColumn::PublicFinalMemoryFlag);
+        let final_memory_addr =
+            program_trace_eval!(trace_eval, ProgramColumn::PublicFinalMemoryAddr);
+        let [final_memory_value] =
+            program_trace_eval!(trace_eval, ProgramColumn::PublicFinalMemoryValue);
+        // final_memory_flag ・(ram_init_final_addr_i - final_memory_addr_i) = 0 for i = 1, 2, 3, 4
+        for i in 0..WORD_SIZE {
+            eval.add_constraint(
+                final_memory_flag.clone()
+                    * (ram_init_final_addr[i].clone() - final_memory_addr[i].clone()),
+            );
+        }
+        // final_memory_flag ・(ram_final_value - final_memory_value) = 0
+        eval.add_constraint(
+            final_memory_flag.clone() * (ram_final_value.clone() - final_memory_value),
+        );
+        // final_memory_flag ・(1 - ram_init_final_flag) = 0
+        eval.add_constraint(
+            final_memory_flag.clone() * (E::F::one() - ram_init_final_flag.clone()),
+        );
+        // Every address under the memory check is exposed, otherwise the prover could hide bytes.
+        // final_memory_mode ・ram_init_final_flag ・(1 - final_memory_flag) = 0
+        eval.add_constraint(
+            final_memory_mode * ram_init_final_flag * (E::F::one() - final_memory_flag),
+        );
+
         // Computing ram1_ts_prev_aux = clk - 1 - ram1_ts_prev
         // Helper1 used for borrow handling
         let clk = preprocessed_trace_eval!(trace_eval, PreprocessedColumn::Clk);
@@ -394,6 +447,11 @@ impl MachineChip for LoadStoreChip {
                     + carry_flag[1].clone() * BaseField::from(1 << 16)),
         );
 
+        // Read-only data can't be stored to
+        // is_store * ram_read_only = 0
+        let [ram_read_only] = trace_eval!(trace_eval, Column::RamReadOnly);
+        eval.add_constraint(is_store.clone() * ram_read_only);
+
         let [ram1_val_prev] = trace_eval!(trace_eval, Ram1ValPrev);
         let [ram2_val_prev] = trace_eval!(trace_eval, Ram2ValPrev);
         let [ram1_val_cur] = trace_eval!(trace_eval, Ram1ValCur);
@@ -552,6 +610,55 @@ impl MachineChip for LoadStoreChip {
 }
 
 impl LoadStoreChip {
+    /// Finds the first load or store the memory check rejects: a store to read-only data, or an
+    /// access covering read-only and writable bytes at once.
+    pub(crate) fn find_read_only_violation(
+        trace: &impl Trace,
+        init_memory: &[MemoryInitializationEntry],
+    ) -> Option<ProveError> {
+        let read_only: BTreeSet<u32> = init_memory
+            .iter()
+            .filter(|entry| entry.read_only)
+            .map(|entry| entry.address)
+            .collect();
+        if read_only.is_empty() {
+            return None;
+        }
+
+        let steps = trace.get_blocks_iter().flat_map(|block| block.steps.iter());
+        steps
+            .filter(|step| {
+                matches!(
+                    step.instruction.opcode.builtin(),
+                    Some(
+                        BuiltinOpcode::SB
+                            | BuiltinOpcode::SH
+                            | BuiltinOpcode::SW
+                            | BuiltinOpcode::LB
+                            | BuiltinOpcode::LH
+                            | BuiltinOpcode::LBU
+                            | BuiltinOpcode::LHU
+                            | BuiltinOpcode::LW
+                    )
+                )
+            })
+            .find_map(|step| {
+                step.memory_records.iter().find_map(|record| {
+                    let (pc, address) = (step.pc, record.get_address());
+                    let is_read_only = read_only.contains(&address);
+                    if (1..record.get_size() as u32)
+                        .any(|i| read_only.contains(&address.wrapping_add(i)) != is_read_only)
+                    {
+                        Some(ProveError::ReadOnlyBoundary { pc, address })
+                    } else if is_read_only && record.get_prev_value().is_some() {
+                        Some(ProveError::ReadOnlyStore { pc, address })
+                    } else {
+                        None
+                    }
+                })
+            })
+    }
+
     fn fill_main_trace_step(
         traces: &mut TracesBuilder,
         row_idx: usize,
@@ -616,6 +723,22 @@ impl LoadStoreChip {
 
             let size = memory_record.get_size() as usize;
 
+            // Rejected with `ProveError::ReadOnlyStore` or `ReadOnlyBoundary` before the trace is filled.
+            let read_only = side_note.rw_mem_check.read_only.contains(&byte_address);
+            assert!(
+                (1..size as u32).all(|i| side_note
+                    .rw_mem_check
+                    .read_only
+                    .contains(&(byte_address + i))
+                    == read_only),
+                "memory access at 0x{byte_address:x} crosses the boundary of read-only data"
+            );
+            assert!(
+                is_load || !read_only,
+                "store to read-only data at 0x{byte_address:x}"
+            );
+            traces.fill_columns(row_idx, read_only, Column::RamReadOnly);
+
             if !is_load {
                 assert!(
                     (memory_record.get_prev_value().unwrap() as usize) < { 1usize } << (size * 8),
@@ -754,7 +877,7 @@ impl LoadStoreChip {
         original_traces: &FinalizedTraces,
         program_traces: &ProgramTraces,
         lookup_element: &LoadStoreLookupElements,
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
     ) {
         let [ram_init_final_flag] = original_traces.get_base_column(Column::RamInitFinalFlag);
         let ram_init_final_addr =
@@ -763,6 +886,7 @@ impl LoadStoreChip {
             program_traces.get_base_column(ProgramColumn::PublicInitialMemoryFlag);
         let [initial_memory_value] =
             program_traces.get_base_column(ProgramColumn::PublicInitialMemoryValue);
+        let [read_only_flag] = program_traces.get_base_column(ProgramColumn::PublicReadOnlyFlag);
         let mut logup_col_gen = logup_trace_gen.new_col();
         // Add (address, value, 0)
         for vec_row in 0..(1 << (original_traces.log_size() - LOG_N_LANES)) {
@@ -773,7 +897,8 @@ impl LoadStoreChip {
             tuple.push(initial_memory_flag.data[vec_row] * initial_memory_value.data[vec_row]); // Is this too much degree?
                                                                                                 // The counter is zero
             tuple.extend_from_slice(&[PackedBaseField::zero(); WORD_SIZE]);
-            assert_eq!(tuple.len(), 2 * WORD_SIZE + 1);
+            tuple.push(read_only_flag.data[vec_row]);
+            assert_eq!(tuple.len(), LOOKUP_TUPLE_SIZE);
             let denom = lookup_element.combine(&tuple);
             let numerator = ram_init_final_flag.data[vec_row];
             logup_col_gen.write_frac(vec_row, numerator.into(), denom);
@@ -792,6 +917,7 @@ impl LoadStoreChip {
             program_trace_eval!(trace_eval, ProgramColumn::PublicInitialMemoryFlag);
         let [initial_memory_value] =
             program_trace_eval!(trace_eval, ProgramColumn::PublicInitialMemoryValue);
+        let [read_only_flag] = program_trace_eval!(trace_eval, ProgramColumn::PublicReadOnlyFlag);
         let mut tuple = vec![];
         for address_byte in ram_init_final_addr.iter() {
             tuple.push(address_byte.clone());
@@ -801,7 +927,8 @@ impl LoadStoreChip {
         for _ in 0..WORD_SIZE {
             tuple.extend_from_slice(&[E::F::zero()]);
         }
-        assert_eq!(tuple.len(), 2 * WORD_SIZE + 1);
+        tuple.push(read_only_flag);
+        assert_eq!(tuple.len(), LOOKUP_TUPLE_SIZE);
         let numerator = ram_init_final_flag;
 
         eval.add_to_relation(RelationEntry::new(
@@ -815,7 +942,7 @@ impl LoadStoreChip {
         original_traces: &FinalizedTraces,
         preprocessed_traces: &PreprocessedTraces,
         lookup_elements: &LoadStoreLookupElements,
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         val_prev: Column,
         ts_prev: Column,
         val_cur: Column,
@@ -868,7 +995,7 @@ impl LoadStoreChip {
     fn subtract_access<Accessed: VirtualColumn<1>>(
         original_traces: &FinalizedTraces,
         lookup_elements: &LoadStoreLookupElements,
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         val_prev: Column,
         ts_prev: Column,
         address_offset: u8,
@@ -876,6 +1003,7 @@ impl LoadStoreChip {
         let [val_prev] = original_traces.get_base_column(val_prev);
         let ts_prev = original_traces.get_base_column::<WORD_SIZE>(ts_prev);
         let base_address = original_traces.get_base_column::<WORD_SIZE>(Column::RamBaseAddr);
+        let [ram_read_only] = original_traces.get_base_column(Column::RamReadOnly);
         // Subtract previous tuple
         let mut logup_col_gen = logup_trace_gen.new_col();
         for vec_row in 0..(1 << (original_traces.log_size() - LOG_N_LANES)) {
@@ -892,7 +1020,8 @@ impl LoadStoreChip {
             for ts_prev_byte in ts_prev.into_iter() {
                 tuple.push(ts_prev_byte.data[vec_row]);
             }
-            assert_eq!(tuple.len(), 2 * WORD_SIZE + 1);
+            tuple.push(ram_read_only.data[vec_row]);
+            assert_eq!(tuple.len(), LOOKUP_TUPLE_SIZE);
             let [accessed] = Accessed::read_from_finalized_traces(original_traces, vec_row);
             logup_col_gen.write_frac(
                 vec_row,
@@ -925,7 +1054,9 @@ impl LoadStoreChip {
         for ts_prev_byte in ts_prev.into_iter() {
             tuple.push(ts_prev_byte);
         }
-        assert_eq!(tuple.len(), 2 * WORD_SIZE + 1);
+        let [ram_read_only] = trace_eval!(trace_eval, Column::RamReadOnly);
+        tuple.push(ram_read_only);
+        assert_eq!(tuple.len(), LOOKUP_TUPLE_SIZE);
 
         eval.add_to_relation(RelationEntry::new(
             lookup_elements,
@@ -938,12 +1069,13 @@ impl LoadStoreChip {
         original_traces: &FinalizedTraces,
         preprocessed_traces: &PreprocessedTraces,
         lookup_elements: &LoadStoreLookupElements,
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         val_cur: Column,
         address_offset: u8,
     ) {
         let [val_cur] = original_traces.get_base_column(val_cur);
         let base_address = original_traces.get_base_column::<WORD_SIZE>(Column::RamBaseAddr);
+        let [ram_read_only] = original_traces.get_base_column(Column::RamReadOnly);
         // Add current tuple
         let clk =
             preprocessed_traces.get_preprocessed_base_column::<WORD_SIZE>(PreprocessedColumn::Clk);
@@ -962,7 +1094,8 @@ impl LoadStoreChip {
             for clk_byte in clk.into_iter() {
                 tuple.push(clk_byte.data[vec_row]);
             }
-            assert_eq!(tuple.len(), 2 * WORD_SIZE + 1);
+            tuple.push(ram_read_only.data[vec_row]);
+            assert_eq!(tuple.len(), LOOKUP_TUPLE_SIZE);
             let [accessed] = Accessed::read_from_finalized_traces(original_traces, vec_row);
             logup_col_gen.write_frac(
                 vec_row,
@@ -994,7 +1127,9 @@ impl LoadStoreChip {
         for clk_byte in clk.into_iter() {
             tuple.push(clk_byte);
         }
-        assert_eq!(tuple.len(), 2 * WORD_SIZE + 1);
+        let [ram_read_only] = trace_eval!(trace_eval, Column::RamReadOnly);
+        tuple.push(ram_read_only);
+        assert_eq!(tuple.len(), LOOKUP_TUPLE_SIZE);
 
         eval.add_to_relation(RelationEntry::new(lookup_elements, accessed.into(), &tuple));
     }
@@ -1009,8 +1144,9 @@ impl LoadStoreChip {
     /// The public output related columns do not appear here because they are constrained to use `RamFinalValue`.
     fn subtract_final_values(
         original_traces: &FinalizedTraces,
+        program_traces: &ProgramTraces,
         lookup_elements: &LoadStoreLookupElements,
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
     ) {
         let [ram_init_final_flag] = original_traces.get_base_column(Column::RamInitFinalFlag);
         let ram_init_final_addr =
@@ -1018,6 +1154,7 @@ impl LoadStoreChip {
         let [ram_final_value] = original_traces.get_base_column(Column::RamFinalValue);
         let ram_final_counter =
             original_traces.get_base_column::<WORD_SIZE>(Column::RamFinalCounter);
+        let [read_only_flag] = program_traces.get_base_column(ProgramColumn::PublicReadOnlyFlag);
         let mut logup_col_gen = logup_trace_gen.new_col();
         for vec_row in 0..(1 << (original_traces.log_size() - LOG_N_LANES)) {
             let mut tuple = vec![];
@@ -1028,7 +1165,8 @@ impl LoadStoreChip {
             for counter_byte in ram_final_counter.iter() {
                 tuple.push(counter_byte.data[vec_row]);
             }
-            assert_eq!(tuple.len(), 2 * WORD_SIZE + 1);
+            tuple.push(read_only_flag.data[vec_row]);
+            assert_eq!(tuple.len(), LOOKUP_TUPLE_SIZE);
             let denom = lookup_elements.combine(&tuple);
             let numerator = ram_init_final_flag.data[vec_row];
             logup_col_gen.write_frac(vec_row, (-numerator).into(), denom);
@@ -1045,6 +1183,7 @@ impl LoadStoreChip {
         let ram_init_final_addr = trace_eval!(trace_eval, Column::RamInitFinalAddr);
         let [ram_final_value] = trace_eval!(trace_eval, Column::RamFinalValue);
         let ram_final_counter = trace_eval!(trace_eval, Column::RamFinalCounter);
+        let [read_only_flag] = program_trace_eval!(trace_eval, ProgramColumn::PublicReadOnlyFlag);
         let mut tuple = vec![];
         for address_byte in ram_init_final_addr.iter() {
             tuple.push(address_byte.clone());
@@ -1053,7 +1192,8 @@ impl LoadStoreChip {
         for counter_byte in ram_final_counter.iter() {
             tuple.push(counter_byte.clone());
         }
-        assert_eq!(tuple.len(), 2 * WORD_SIZE + 1);
+        tuple.push(read_only_flag);
+        assert_eq!(tuple.len(), LOOKUP_TUPLE_SIZE);
         let numerator = ram_init_final_flag;
 
         eval.add_to_relation(RelationEntry::new(
@@ -1082,7 +1222,10 @@ mod test {
     };
 
     use super::*;
+    use stwo_prover::core::fields::qm31::SecureField;
+
     use nexus_vm::{
+        emulator::{MemoryInitializationEntry, ProgramInfo},
         riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
         trace::k_trace_direct,
     };
@@ -1211,4 +1354,143 @@ mod test {
         assert_chip::<Chips>(traces, Some(program_trace.finalize()));
         Machine::<Chips>::prove(&vm_traces, &view).unwrap();
     }
+
+    fn initial_memory_traces(checked: bool) -> (TracesBuilder, ProgramTraces) {
+        let init_memory = [MemoryInitializationEntry {
+            address: 0x1000,
+            value: 42,
+            read_only: false,
+        }];
+        let program_trace =
+            ProgramTracesBuilder::new(LOG_SIZE, &ProgramInfo::dummy(), &init_memory, &[], &[])
+                .finalize();
+
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        traces.fill_columns(0, 0x1000u32, Column::RamInitFinalAddr);
+        traces.fill_columns(0, checked, Column::RamInitFinalFlag);
+        traces.fill_columns(0, 42u8, Column::RamFinalValue);
+        (traces, program_trace)
+    }
+
+    #[test]
+    fn test_initial_memory_in_memory_check() {
+        let (traces, program_trace) = initial_memory_traces(true);
+        assert_chip::<LoadStoreChip>(traces, Some(program_trace));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_initial_memory_left_out_of_memory_check() {
+        let (traces, program_trace) = initial_memory_traces(false);
+        assert_chip::<LoadStoreChip>(traces, Some(program_trace));
+    }
+
+    /// Traces of a SB storing 7 at 0x1000, which is initialized to 42.
+    fn store_traces(read_only: bool, ram_read_only: bool) -> (TracesBuilder, ProgramTraces) {
+        let init_memory = [MemoryInitializationEntry {
+            address: 0x1000,
+            value: 42,
+            read_only,
+        }];
+        let program_trace =
+            ProgramTracesBuilder::new(LOG_SIZE, &ProgramInfo::dummy(), &init_memory, &[], &[])
+                .finalize();
+
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        traces.fill_columns(0, 0x1000u32, Column::RamInitFinalAddr);
+        traces.fill_columns(0, true, Column::RamInitFinalFlag);
+        traces.fill_columns(0, 7u8, Column::RamFinalValue);
+        traces.fill_columns(0, 1u32, Column::RamFinalCounter);
+
+        traces.fill_columns(0, true, IsSb);
+        traces.fill_columns(0, 0x1000u32, Column::ValueA);
+        traces.fill_columns(0, 0x1000u32, Column::RamBaseAddr);
+        traces.fill_columns(0, 42u8, Ram1ValPrev);
+        traces.fill_columns(0, 7u8, Ram1ValCur);
+        let (ts_prev_aux, helper) =
+            decr_subtract_with_borrow(1u32.to_le_bytes(), 0u32.to_le_bytes());
+        traces.fill_columns(0, ts_prev_aux, Ram1TsPrevAux);
+        traces.fill_columns(0, helper, Helper1);
+        traces.fill_columns(0, ram_read_only, Column::RamReadOnly);
+        (traces, program_trace)
+    }
+
+    #[test]
+    fn test_store_to_writable_memory() {
+        let (traces, program_trace) = store_traces(false, false);
+        let (_, claimed_sum) = assert_chip::<LoadStoreChip>(traces, Some(program_trace));
+        assert_eq!(claimed_sum, SecureField::zero());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_store_to_read_only_memory() {
+        let (traces, program_trace) = store_traces(true, true);
+        assert_chip::<LoadStoreChip>(traces, Some(program_trace));
+    }
+
+    #[test]
+    fn test_find_read_only_violation() {
+        // x1 = 0x80000, x2 = 7
+        let setup = [
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 1, 1, 19),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 7),
+        ];
+        let trace_of = |access: Instruction| {
+            let mut instructions = setup.to_vec();
+            instructions.push(access);
+            k_trace_direct(&vec![BasicBlock::new(instructions)], 1)
+                .expect("error generating trace")
+                .1
+        };
+        let read_only = |addresses: std::ops::Range<u32>| -> Vec<MemoryInitializationEntry> {
+            addresses
+                .map(|address| MemoryInitializationEntry {
+                    address,
+                    value: 0,
+                    read_only: true,
+                })
+                .collect()
+        };
+        let pc = |trace: &nexus_vm::trace::UniformTrace| trace.blocks[3].steps[0].pc;
+
+        // *x1 = x2
+        let store = trace_of(Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::SB),
+            1,
+            2,
+            0,
+        ));
+        assert!(LoadStoreChip::find_read_only_violation(&store, &[]).is_none());
+        assert!(matches!(
+            LoadStoreChip::find_read_only_violation(&store, &read_only(0x80000..0x80001)),
+            Some(ProveError::ReadOnlyStore { pc: store_pc, address: 0x80000 })
+                if store_pc == pc(&store)
+        ));
+
+        // x3 = *x1
+        let load = trace_of(Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::LW),
+            3,
+            1,
+            0,
+        ));
+        assert!(
+            LoadStoreChip::find_read_only_violation(&load, &read_only(0x80000..0x80004)).is_none()
+        );
+        assert!(matches!(
+            LoadStoreChip::find_read_only_violation(&load, &read_only(0x80000..0x80002)),
+            Some(ProveError::ReadOnlyBoundary { pc: load_pc, address: 0x80000 })
+                if load_pc == pc(&load)
+        ));
+    }
+
+    #[test]
+    fn test_store_to_read_only_memory_as_writable() {
+        // The access doesn't match the initial value of the address, so the memory check fails.
+        let (traces, program_trace) = store_traces(true, false);
+        let (_, claimed_sum) = assert_chip::<LoadStoreChip>(traces, Some(program_trace));
+        assert_ne!(claimed_sum, SecureField::zero());
+    }
 }
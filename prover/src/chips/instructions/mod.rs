@@ -7,10 +7,14 @@ pub(crate) mod bit_op;
 pub(crate) mod blt;
 pub(crate) mod bltu;
 pub(crate) mod bne;
+pub(crate) mod div;
 pub(crate) mod jal;
 pub(crate) mod jalr;
 pub(crate) mod load_store;
 pub(crate) mod lui;
+pub(crate) mod mul;
+pub(crate) mod mulh;
+pub(crate) mod rem;
 pub(crate) mod sll;
 pub(crate) mod slt;
 pub(crate) mod sltu;
@@ -19,10 +23,13 @@ pub(crate) mod srl;
 pub(crate) mod sub;
 pub(crate) mod syscall;
 
+#[cfg(test)]
+mod proptests;
+
 pub use self::{
     add::add_with_carries, add::AddChip, auipc::AuipcChip, beq::BeqChip, bge::BgeChip,
-    bgeu::BgeuChip, bit_op::BitOpChip, blt::BltChip, bltu::BltuChip, bne::BneChip, jal::JalChip,
-    jalr::JalrChip, load_store::LoadStoreChip, lui::LuiChip, sll::SllChip, slt::SltChip,
-    sltu::SltuChip, sra::SraChip, srl::SrlChip, sub::subtract_with_borrow, sub::SubChip,
-    syscall::SyscallChip,
+    bgeu::BgeuChip, bit_op::BitOpChip, blt::BltChip, bltu::BltuChip, bne::BneChip, div::DivChip,
+    jal::JalChip, jalr::JalrChip, load_store::LoadStoreChip, lui::LuiChip, mul::MulChip,
+    mulh::MulhChip, rem::RemChip, sll::SllChip, slt::SltChip, sltu::SltuChip, sra::SraChip,
+    srl::SrlChip, sub::subtract_with_borrow, sub::SubChip, syscall::SyscallChip,
 };
@@ -0,0 +1,290 @@
+use num_traits::Zero;
+use stwo_prover::{constraint_framework::EvalAtRow, core::fields::m31::BaseField};
+
+use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
+
+use crate::{
+    column::Column::{self, *},
+    components::AllLookupElements,
+    trace::{
+        eval::{trace_eval, TraceEval},
+        sidenote::SideNote,
+        ProgramStep, TracesBuilder, Word,
+    },
+    traits::{ExecuteChip, MachineChip},
+};
+
+/// Number of 8-bit limbs of a 64-bit product.
+pub(crate) const PRODUCT_SIZE: usize = 2 * WORD_SIZE;
+
+/// A 64-bit value in 8-bit limbs.
+pub(crate) type DoubleWord = [u8; PRODUCT_SIZE];
+
+/// Sign-extends `word` to 64 bits, `sgn` is the sign bit of the word when it's read as a signed value.
+pub(crate) fn sign_extend(word: Word, sgn: bool) -> DoubleWord {
+    let mut ret = [if sgn { 0xFF } else { 0 }; PRODUCT_SIZE];
+    ret[..WORD_SIZE].copy_from_slice(&word);
+    ret
+}
+
+/// Computes x・y + z modulo 2^64 in 8-bit limbs, together with the carry out of every limb.
+///
+/// Every carry is below 2^11, since a limb adds up at most eight products of bytes.
+pub(crate) fn mul_add_with_carries(
+    x: DoubleWord,
+    y: DoubleWord,
+    z: DoubleWord,
+) -> (DoubleWord, [u32; PRODUCT_SIZE]) {
+    let mut limbs = [0u8; PRODUCT_SIZE];
+    let mut carries = [0u32; PRODUCT_SIZE];
+    let mut carry = 0u32;
+    for k in 0..PRODUCT_SIZE {
+        let mut sum = z[k] as u32 + carry;
+        for i in 0..=k {
+            sum += x[i] as u32 * y[k - i] as u32;
+        }
+        limbs[k] = sum as u8;
+        carry = sum >> 8;
+        carries[k] = carry;
+    }
+    (limbs, carries)
+}
+
+/// Fills MulCarry1, MulCarry2 and MulCarryMsb with the carries of a 64-bit product.
+pub(crate) fn fill_carries(
+    traces: &mut TracesBuilder,
+    row_idx: usize,
+    carries: [u32; PRODUCT_SIZE],
+) {
+    let low_bytes = carries.map(|carry| carry as u8);
+    let msb = carries.map(|carry| (carry >> 8) as u8);
+    let (carry_1, carry_2) = low_bytes.split_at(WORD_SIZE);
+    traces.fill_columns_bytes(row_idx, carry_1, MulCarry1);
+    traces.fill_columns_bytes(row_idx, carry_2, MulCarry2);
+    traces.fill_columns(row_idx, msb, MulCarryMsb);
+}
+
+/// Sign-extends the limbs of a word to 64 bits in constraints, `sgn` is the sign bit, or zero for unsigned words.
+pub(crate) fn sign_extend_eval<E: EvalAtRow>(
+    word: &[E::F; WORD_SIZE],
+    sgn: &E::F,
+) -> [E::F; PRODUCT_SIZE] {
+    std::array::from_fn(|i| {
+        if i < WORD_SIZE {
+            word[i].clone()
+        } else {
+            sgn.clone() * BaseField::from(0xFF)
+        }
+    })
+}
+
+/// Adds the constraints of x・y + z = p modulo 2^(8・N) limb by limb, for N <= 8.
+///
+/// The carry out of limb k is MulCarry{1,2}[k] + MulCarryMsb[k]・2^8, where the range checks bound it below 2^11.
+/// Limbs add up at most eight products of bytes, so both sides are far below the modulus of M31.
+pub(crate) fn add_mul_add_constraints<E: EvalAtRow, const N: usize>(
+    eval: &mut E,
+    trace_eval: &TraceEval<E>,
+    selector: E::F,
+    x: &[E::F; PRODUCT_SIZE],
+    y: &[E::F; PRODUCT_SIZE],
+    z: &[E::F; PRODUCT_SIZE],
+    p: &[E::F; N],
+) {
+    let carry_1 = trace_eval!(trace_eval, MulCarry1);
+    let carry_2 = trace_eval!(trace_eval, MulCarry2);
+    let carry_msb = trace_eval!(trace_eval, MulCarryMsb);
+    let carries: [E::F; PRODUCT_SIZE] = std::array::from_fn(|k| {
+        let low_byte = if k < WORD_SIZE {
+            carry_1[k].clone()
+        } else {
+            carry_2[k - WORD_SIZE].clone()
+        };
+        low_byte + carry_msb[k].clone() * BaseField::from(1 << 8)
+    });
+
+    // selector・(Σ_{i+j=k} x_i・y_j + z_k + carry_{k-1} - p_k - carry_k・2^8) = 0
+    for k in 0..N {
+        let mut sum = z[k].clone();
+        for i in 0..=k {
+            sum = sum + x[i].clone() * y[k - i].clone();
+        }
+        if k > 0 {
+            sum = sum + carries[k - 1].clone();
+        }
+        eval.add_constraint(
+            selector.clone() * (sum - p[k].clone() - carries[k].clone() * BaseField::from(1 << 8)),
+        );
+    }
+}
+
+// Support MUL opcode.
+pub struct MulChip;
+
+pub struct ExecutionResult {
+    pub product: DoubleWord,
+    pub carries: [u32; PRODUCT_SIZE],
+}
+
+impl ExecuteChip for MulChip {
+    type ExecutionResult = ExecutionResult;
+    fn execute(program_step: &ProgramStep) -> Self::ExecutionResult {
+        let value_b = program_step.get_value_b();
+        let (value_c, _) = program_step.get_value_c();
+
+        // The low half of the product doesn't depend on the signs of the operands.
+        let (product, carries) = mul_add_with_carries(
+            sign_extend(value_b, false),
+            sign_extend(value_c, false),
+            [0; PRODUCT_SIZE],
+        );
+        ExecutionResult { product, carries }
+    }
+}
+
+impl MachineChip for MulChip {
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        _side_note: &mut SideNote,
+    ) {
+        let vm_step = match vm_step {
+            Some(vm_step) => vm_step,
+            None => return, // padding
+        };
+        if !matches!(
+            vm_step.step.instruction.opcode.builtin(),
+            Some(BuiltinOpcode::MUL)
+        ) {
+            return;
+        }
+
+        let ExecutionResult { product, carries } = Self::execute(vm_step);
+        let result: Word = product[..WORD_SIZE].try_into().unwrap();
+
+        assert_eq!(
+            result,
+            vm_step
+                .get_result()
+                .expect("MUL instruction must have a result")
+        );
+        traces.fill_columns(row_idx, result, ValueA);
+        fill_carries(traces, row_idx, carries);
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        _lookup_elements: &AllLookupElements,
+    ) {
+        let [is_mul] = trace_eval!(trace_eval, IsMul);
+        let value_a = trace_eval!(trace_eval, ValueA);
+        let value_b = trace_eval!(trace_eval, ValueB);
+        let value_c = trace_eval!(trace_eval, ValueC);
+
+        // The first four limbs of b・c are the same for the zero-extended operands.
+        let zero = E::F::zero();
+        let b = sign_extend_eval::<E>(&value_b, &zero);
+        let c = sign_extend_eval::<E>(&value_c, &zero);
+        let z = std::array::from_fn(|_| zero.clone());
+        add_mul_add_constraints(eval, trace_eval, is_mul, &b, &c, &z, &value_a);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        chips::{
+            AddChip, CpuChip, DecodingCheckChip, ProgramMemCheckChip, RangeCheckChip,
+            RegisterMemCheckChip, SubChip, TimestampChip,
+        },
+        test_utils::assert_chip,
+        trace::{
+            preprocessed::PreprocessedBuilder, program::iter_program_steps,
+            program_trace::ProgramTracesBuilder,
+        },
+    };
+
+    use super::*;
+    use nexus_vm::{
+        emulator::InternalView,
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    const LOG_SIZE: u32 = PreprocessedBuilder::MIN_LOG_SIZE;
+
+    fn setup_basic_block_ir() -> Vec<BasicBlock> {
+        let basic_block = BasicBlock::new(vec![
+            // x1 = 7, x2 = -3 (0xFFFFFFFD), x3 = 0x7FF
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 7),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 3),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 2, 0, 10),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 0x7FF),
+            // x4 = 7 * 7 = 49
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 4, 1, 1),
+            // x5 = 7 * -3 = -21
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 5, 1, 2),
+            // x6 = -3 * -3 = 9
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 6, 2, 2),
+            // x7 = 0x7FF * 0x7FF = 0x3FF001
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 7, 3, 3),
+            // x8 = 0x3FF001 * 0x3FF001, overflowing 32 bits
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 8, 7, 7),
+            // x9 = 0
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 9, 8, 0),
+        ]);
+        vec![basic_block]
+    }
+
+    #[test]
+    fn test_k_trace_constrained_mul_instructions() {
+        type Chips = (
+            CpuChip,
+            DecodingCheckChip,
+            AddChip,
+            SubChip,
+            MulChip,
+            RegisterMemCheckChip,
+            ProgramMemCheckChip,
+            TimestampChip,
+            RangeCheckChip,
+        );
+        let basic_block = setup_basic_block_ir();
+        let k = 1;
+
+        // Get traces from VM K-Trace interface
+        let (view, vm_traces) = k_trace_direct(&basic_block, k).expect("Failed to create trace");
+        let program_info = view.get_program_memory();
+
+        // Trace circuit
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_steps = iter_program_steps(&vm_traces, traces.num_rows());
+        let program_traces = ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, program_info);
+        let mut side_note = SideNote::new(&program_traces, &view);
+
+        for (row_idx, program_step) in program_steps.enumerate() {
+            Chips::fill_main_trace(&mut traces, row_idx, &program_step, &mut side_note);
+        }
+        assert_chip::<Chips>(traces, Some(program_traces.finalize()));
+    }
+
+    #[test]
+    fn test_mul_add_with_carries() {
+        for (x, y, z) in [
+            (0u64, 0u64, 0u64),
+            (u64::MAX, u64::MAX, u64::MAX),
+            (0xFFFF_FFFF, 0xFFFF_FFFF, 0xFFFF_FFFF),
+            (0xFFFF_FFFF_8000_0000, 0x1234_5678, 0xDEAD_BEEF),
+        ] {
+            let (product, carries) =
+                mul_add_with_carries(x.to_le_bytes(), y.to_le_bytes(), z.to_le_bytes());
+            assert_eq!(
+                u64::from_le_bytes(product),
+                x.wrapping_mul(y).wrapping_add(z)
+            );
+            assert!(carries.iter().all(|&carry| carry < 1 << 11));
+        }
+    }
+}
@@ -0,0 +1,256 @@
+use num_traits::Zero;
+use stwo_prover::{constraint_framework::EvalAtRow, core::fields::m31::BaseField};
+
+use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
+
+use super::mul::{
+    add_mul_add_constraints, fill_carries, mul_add_with_carries, sign_extend, sign_extend_eval,
+    DoubleWord, PRODUCT_SIZE,
+};
+use crate::{
+    column::Column::{self, *},
+    components::AllLookupElements,
+    trace::{
+        eval::{trace_eval, TraceEval},
+        sidenote::SideNote,
+        ProgramStep, TracesBuilder, Word,
+    },
+    traits::{ExecuteChip, MachineChip},
+};
+
+// Support MULH, MULHSU and MULHU opcodes.
+pub struct MulhChip;
+
+pub struct ExecutionResult {
+    pub sgn_b: bool,
+    pub sgn_c: bool,
+    pub product: DoubleWord,
+    pub carries: [u32; PRODUCT_SIZE],
+}
+
+impl ExecuteChip for MulhChip {
+    type ExecutionResult = ExecutionResult;
+    fn execute(program_step: &ProgramStep) -> Self::ExecutionResult {
+        let value_b = program_step.get_value_b();
+        let (value_c, _) = program_step.get_value_c();
+
+        // Unsigned operands have a zero sign bit, so that they are zero-extended.
+        let (signed_b, signed_c) = match program_step.step.instruction.opcode.builtin() {
+            Some(BuiltinOpcode::MULH) => (true, true),
+            Some(BuiltinOpcode::MULHSU) => (true, false),
+            Some(BuiltinOpcode::MULHU) => (false, false),
+            _ => panic!("Unsupported opcode for MulhChip"),
+        };
+        let sgn_b = signed_b && program_step.get_sgn_b();
+        let sgn_c = signed_c && program_step.get_sgn_c();
+
+        let (product, carries) = mul_add_with_carries(
+            sign_extend(value_b, sgn_b),
+            sign_extend(value_c, sgn_c),
+            [0; PRODUCT_SIZE],
+        );
+        ExecutionResult {
+            sgn_b,
+            sgn_c,
+            product,
+            carries,
+        }
+    }
+}
+
+impl MachineChip for MulhChip {
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        _side_note: &mut SideNote,
+    ) {
+        let vm_step = match vm_step {
+            Some(vm_step) => vm_step,
+            None => return, // padding
+        };
+        if !matches!(
+            vm_step.step.instruction.opcode.builtin(),
+            Some(BuiltinOpcode::MULH) | Some(BuiltinOpcode::MULHSU) | Some(BuiltinOpcode::MULHU)
+        ) {
+            return;
+        }
+
+        let ExecutionResult {
+            sgn_b,
+            sgn_c,
+            product,
+            carries,
+        } = Self::execute(vm_step);
+        let (low, high) = product.split_at(WORD_SIZE);
+        let result: Word = high.try_into().unwrap();
+
+        assert_eq!(
+            result,
+            vm_step
+                .get_result()
+                .expect("MULH/MULHSU/MULHU instruction must have a result")
+        );
+        traces.fill_columns(row_idx, result, ValueA);
+        traces.fill_columns_bytes(row_idx, low, Helper1);
+        fill_carries(traces, row_idx, carries);
+
+        // Fill Helper2 and Helper3 with the operands without their sign bits
+        let mut helper_b = vm_step.get_value_b();
+        helper_b[WORD_SIZE - 1] &= 0x7f;
+        let (mut helper_c, _) = vm_step.get_value_c();
+        helper_c[WORD_SIZE - 1] &= 0x7f;
+        traces.fill_columns(row_idx, helper_b, Helper2);
+        traces.fill_columns(row_idx, helper_c, Helper3);
+        traces.fill_columns(row_idx, sgn_b, SgnB);
+        traces.fill_columns(row_idx, sgn_c, SgnC);
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        _lookup_elements: &AllLookupElements,
+    ) {
+        let [is_mulh] = trace_eval!(trace_eval, IsMulh);
+        let [is_mulhsu] = trace_eval!(trace_eval, IsMulhsu);
+        let [is_mulhu] = trace_eval!(trace_eval, IsMulhu);
+        let value_a = trace_eval!(trace_eval, ValueA);
+        let value_b = trace_eval!(trace_eval, ValueB);
+        let value_c = trace_eval!(trace_eval, ValueC);
+        let [sgn_b] = trace_eval!(trace_eval, SgnB);
+        let [sgn_c] = trace_eval!(trace_eval, SgnC);
+        let helper1_val = trace_eval!(trace_eval, Helper1);
+        let helper2_val = trace_eval!(trace_eval, Helper2);
+        let helper3_val = trace_eval!(trace_eval, Helper3);
+
+        // modulus for 7-bit
+        let modulus_7 = E::F::from(BaseField::from(1 << 7));
+
+        // (is_mulh + is_mulhsu) ・ (h2[3] + sgn_b・2^7 - b_val[3]) = 0
+        eval.add_constraint(
+            (is_mulh.clone() + is_mulhsu.clone())
+                * (modulus_7.clone() * sgn_b.clone() + helper2_val[3].clone() - value_b[3].clone()),
+        );
+        // is_mulhu ・ sgn_b = 0
+        eval.add_constraint(is_mulhu.clone() * sgn_b.clone());
+        // is_mulh ・ (h3[3] + sgn_c・2^7 - c_val[3]) = 0
+        eval.add_constraint(
+            is_mulh.clone()
+                * (modulus_7 * sgn_c.clone() + helper3_val[3].clone() - value_c[3].clone()),
+        );
+        // (is_mulhsu + is_mulhu) ・ sgn_c = 0
+        eval.add_constraint((is_mulhsu.clone() + is_mulhu.clone()) * sgn_c.clone());
+
+        // The product of the sign-extended operands is h1 + a_val・2^32, modulo 2^64.
+        let b = sign_extend_eval::<E>(&value_b, &sgn_b);
+        let c = sign_extend_eval::<E>(&value_c, &sgn_c);
+        let z = std::array::from_fn(|_| E::F::zero());
+        let product: [E::F; PRODUCT_SIZE] = std::array::from_fn(|i| {
+            if i < WORD_SIZE {
+                helper1_val[i].clone()
+            } else {
+                value_a[i - WORD_SIZE].clone()
+            }
+        });
+        add_mul_add_constraints(
+            eval,
+            trace_eval,
+            is_mulh + is_mulhsu + is_mulhu,
+            &b,
+            &c,
+            &z,
+            &product,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        chips::{
+            AddChip, CpuChip, DecodingCheckChip, ProgramMemCheckChip, RangeCheckChip,
+            RegisterMemCheckChip, SllChip, SubChip, TimestampChip,
+        },
+        test_utils::assert_chip,
+        trace::{
+            preprocessed::PreprocessedBuilder, program::iter_program_steps,
+            program_trace::ProgramTracesBuilder,
+        },
+    };
+
+    use super::*;
+    use nexus_vm::{
+        emulator::InternalView,
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    const LOG_SIZE: u32 = PreprocessedBuilder::MIN_LOG_SIZE;
+
+    fn setup_basic_block_ir() -> Vec<BasicBlock> {
+        let mut instructions = vec![
+            // x1 = 7, x2 = -3 (0xFFFFFFFD), x3 = 0x7FF, x4 = 0x3FF800, x5 = 0x80000000
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 7),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 3),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 2, 0, 10),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 0x7FF),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 4, 3, 3),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 4, 4, 10),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 11, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 5, 11, 31),
+        ];
+        // Every combination of positive and negative operands
+        for opcode in [
+            BuiltinOpcode::MULH,
+            BuiltinOpcode::MULHSU,
+            BuiltinOpcode::MULHU,
+        ] {
+            for (op_b, op_c) in [
+                (1, 1),
+                (1, 2),
+                (2, 1),
+                (2, 2),
+                (4, 4),
+                (5, 5),
+                (5, 2),
+                (2, 0),
+            ] {
+                instructions.push(Instruction::new_ir(Opcode::from(opcode), 6, op_b, op_c));
+            }
+        }
+        vec![BasicBlock::new(instructions)]
+    }
+
+    #[test]
+    fn test_k_trace_constrained_mulh_instructions() {
+        type Chips = (
+            CpuChip,
+            DecodingCheckChip,
+            AddChip,
+            SubChip,
+            SllChip,
+            MulhChip,
+            RegisterMemCheckChip,
+            ProgramMemCheckChip,
+            TimestampChip,
+            RangeCheckChip,
+        );
+        let basic_block = setup_basic_block_ir();
+        let k = 1;
+
+        // Get traces from VM K-Trace interface
+        let (view, vm_traces) = k_trace_direct(&basic_block, k).expect("Failed to create trace");
+        let program_info = view.get_program_memory();
+
+        // Trace circuit
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_steps = iter_program_steps(&vm_traces, traces.num_rows());
+        let program_traces = ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, program_info);
+        let mut side_note = SideNote::new(&program_traces, &view);
+
+        for (row_idx, program_step) in program_steps.enumerate() {
+            Chips::fill_main_trace(&mut traces, row_idx, &program_step, &mut side_note);
+        }
+        assert_chip::<Chips>(traces, Some(program_traces.finalize()));
+    }
+}
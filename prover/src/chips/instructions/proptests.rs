@@ -0,0 +1,230 @@
+//! Property-based tests of instruction chips.
+//!
+//! Random instruction sequences of a single opcode class are executed by the emulator and the
+//! resulting trace is filled by all base chips. The constraints must hold for the honest trace,
+//! and must be violated once the result of any instruction is forged.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use proptest::{prelude::*, sample::Index};
+use stwo_prover::core::fields::m31::BaseField;
+
+use nexus_vm::{
+    emulator::InternalView,
+    riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+    trace::k_trace_direct,
+};
+
+use crate::{
+    column::Column,
+    machine::BaseComponent,
+    test_utils::assert_chip,
+    trace::{
+        program::iter_program_steps, program_trace::ProgramTracesBuilder, sidenote::SideNote,
+        PreprocessedTraces, TracesBuilder,
+    },
+    traits::MachineChip,
+};
+
+const LOG_SIZE: u32 = PreprocessedTraces::MIN_LOG_SIZE;
+
+/// Maximum number of random instructions following the register initialization.
+const MAX_INSTRUCTIONS: usize = 64;
+
+fn register() -> impl Strategy<Value = u8> {
+    0u8..32
+}
+
+fn pick(opcodes: &'static [BuiltinOpcode]) -> impl Strategy<Value = Opcode> {
+    proptest::sample::select(opcodes).prop_map(Opcode::from)
+}
+
+/// Upper 20 bits of a register value, biased towards the edges of the signed and unsigned range.
+fn upper_immediate() -> impl Strategy<Value = u32> {
+    prop_oneof![
+        Just(0),
+        Just(0x7FFFF),
+        Just(0x80000),
+        Just(0xFFFFF),
+        0u32..=0xFFFFF,
+    ]
+}
+
+fn lower_immediate() -> impl Strategy<Value = u32> {
+    prop_oneof![Just(0), Just(0x7FF), Just(0x800), Just(0xFFF), 0u32..=0xFFF]
+}
+
+/// Loads a random value into every register but x0.
+fn register_init() -> impl Strategy<Value = Vec<Instruction>> {
+    proptest::collection::vec((upper_immediate(), lower_immediate()), 31).prop_map(|values| {
+        values
+            .into_iter()
+            .zip(1u8..)
+            .flat_map(|((hi, lo), reg)| {
+                [
+                    Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), reg, 0, hi),
+                    Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), reg, reg, lo),
+                ]
+            })
+            .collect()
+    })
+}
+
+fn alu_register() -> impl Strategy<Value = Instruction> {
+    const OPCODES: &[BuiltinOpcode] = &[
+        BuiltinOpcode::ADD,
+        BuiltinOpcode::SUB,
+        BuiltinOpcode::SLT,
+        BuiltinOpcode::SLTU,
+        BuiltinOpcode::AND,
+        BuiltinOpcode::OR,
+        BuiltinOpcode::XOR,
+    ];
+    (pick(OPCODES), register(), register(), register())
+        .prop_map(|(op, rd, rs1, rs2)| Instruction::new_ir(op, rd, rs1, rs2 as u32))
+}
+
+fn alu_immediate() -> impl Strategy<Value = Instruction> {
+    const OPCODES: &[BuiltinOpcode] = &[
+        BuiltinOpcode::ADDI,
+        BuiltinOpcode::SLTI,
+        BuiltinOpcode::SLTIU,
+        BuiltinOpcode::ANDI,
+        BuiltinOpcode::ORI,
+        BuiltinOpcode::XORI,
+    ];
+    (pick(OPCODES), register(), register(), lower_immediate())
+        .prop_map(|(op, rd, rs1, imm)| Instruction::new_ir(op, rd, rs1, imm))
+}
+
+fn shift() -> impl Strategy<Value = Instruction> {
+    const OPCODES: &[BuiltinOpcode] = &[
+        BuiltinOpcode::SLL,
+        BuiltinOpcode::SRL,
+        BuiltinOpcode::SRA,
+        BuiltinOpcode::SLLI,
+        BuiltinOpcode::SRLI,
+        BuiltinOpcode::SRAI,
+    ];
+    // Both the shift amount and the register index fit into 5 bits.
+    (pick(OPCODES), register(), register(), 0u32..32)
+        .prop_map(|(op, rd, rs1, c)| Instruction::new_ir(op, rd, rs1, c))
+}
+
+fn mul_div() -> impl Strategy<Value = Instruction> {
+    const OPCODES: &[BuiltinOpcode] = &[
+        BuiltinOpcode::MUL,
+        BuiltinOpcode::MULH,
+        BuiltinOpcode::MULHSU,
+        BuiltinOpcode::MULHU,
+        BuiltinOpcode::DIV,
+        BuiltinOpcode::DIVU,
+        BuiltinOpcode::REM,
+        BuiltinOpcode::REMU,
+    ];
+    // Reading x0 covers division by zero.
+    (pick(OPCODES), register(), register(), register())
+        .prop_map(|(op, rd, rs1, rs2)| Instruction::new_ir(op, rd, rs1, rs2 as u32))
+}
+
+fn upper() -> impl Strategy<Value = Instruction> {
+    const OPCODES: &[BuiltinOpcode] = &[BuiltinOpcode::LUI, BuiltinOpcode::AUIPC];
+    (pick(OPCODES), register(), upper_immediate())
+        .prop_map(|(op, rd, imm)| Instruction::new_ir(op, rd, 0, imm))
+}
+
+/// Random program: register initialization followed by instructions drawn from `class`.
+fn random_program(
+    class: impl Strategy<Value = Instruction>,
+) -> impl Strategy<Value = (Vec<BasicBlock>, usize)> {
+    (
+        register_init(),
+        proptest::collection::vec(class, 1..=MAX_INSTRUCTIONS),
+    )
+        .prop_map(|(init, instructions)| {
+            let offset = init.len();
+            let block = BasicBlock::new(init.into_iter().chain(instructions).collect());
+            (vec![block], offset)
+        })
+}
+
+/// Fills the main trace of `program`, optionally forging the result of the instruction at `forged_row`.
+fn fill_and_assert(program: &[BasicBlock], forged_row: Option<(usize, u8)>) {
+    let (view, vm_traces) = k_trace_direct(&program.to_vec(), 1).expect("failed to create trace");
+    let program_info = view.get_program_memory();
+
+    let mut traces = TracesBuilder::new(LOG_SIZE);
+    let program_steps = iter_program_steps(&vm_traces, traces.num_rows());
+    let program_trace = ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, program_info);
+    let mut side_note = SideNote::new(&program_trace, &view);
+
+    for (row_idx, program_step) in program_steps.enumerate() {
+        BaseComponent::fill_main_trace(&mut traces, row_idx, &program_step, &mut side_note);
+    }
+    if let Some((row_idx, mask)) = forged_row {
+        let [limb, ..] = traces.column_mut::<4>(row_idx, Column::ValueA);
+        *limb = BaseField::from((limb.0 as u8 ^ mask) as u32);
+    }
+    assert_chip::<BaseComponent>(traces, Some(program_trace.finalize()));
+}
+
+/// Checks that the honest trace satisfies the constraints and a forged one doesn't.
+fn check_class(
+    (program, offset): (Vec<BasicBlock>, usize),
+    forged: Index,
+    mask: u8,
+) -> Result<(), TestCaseError> {
+    fill_and_assert(&program, None);
+
+    // Writes to x0 are discarded, forging them may go unnoticed by design.
+    let candidates: Vec<usize> = program[0].0[offset..]
+        .iter()
+        .enumerate()
+        .filter(|(_, instruction)| instruction.op_a as u8 != 0)
+        .map(|(idx, _)| offset + idx)
+        .collect();
+    if candidates.is_empty() {
+        return Ok(());
+    }
+    let row_idx = *forged.get(&candidates);
+
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        fill_and_assert(&program, Some((row_idx, mask)))
+    }));
+    prop_assert!(
+        result.is_err(),
+        "forged result of {} at row {row_idx} satisfied the constraints",
+        program[0].0[row_idx]
+    );
+    Ok(())
+}
+
+proptest! {
+    // Every case fills and checks the full trace twice, keep the number of cases low.
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn alu_register_constraints(program in random_program(alu_register()), forged: Index, mask in 1u8..) {
+        check_class(program, forged, mask)?;
+    }
+
+    #[test]
+    fn alu_immediate_constraints(program in random_program(alu_immediate()), forged: Index, mask in 1u8..) {
+        check_class(program, forged, mask)?;
+    }
+
+    #[test]
+    fn shift_constraints(program in random_program(shift()), forged: Index, mask in 1u8..) {
+        check_class(program, forged, mask)?;
+    }
+
+    #[test]
+    fn upper_immediate_constraints(program in random_program(upper()), forged: Index, mask in 1u8..) {
+        check_class(program, forged, mask)?;
+    }
+
+    #[test]
+    fn mul_div_constraints(program in random_program(mul_div()), forged: Index, mask in 1u8..) {
+        check_class(program, forged, mask)?;
+    }
+}
@@ -0,0 +1,67 @@
+use stwo_prover::constraint_framework::EvalAtRow;
+
+use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
+
+use super::div::{add_div_rem_constraints, fill_div_rem, ExecutionResult};
+use crate::{
+    column::Column::{self, *},
+    components::AllLookupElements,
+    trace::{
+        eval::{trace_eval, TraceEval},
+        sidenote::SideNote,
+        ProgramStep, TracesBuilder,
+    },
+    traits::MachineChip,
+};
+
+// Support REM and REMU opcodes. The columns are shared with DivChip, see `fill_div_rem`.
+pub struct RemChip;
+
+impl MachineChip for RemChip {
+    fn fill_main_trace(
+        traces: &mut TracesBuilder,
+        row_idx: usize,
+        vm_step: &Option<ProgramStep>,
+        _side_note: &mut SideNote,
+    ) {
+        let vm_step = match vm_step {
+            Some(vm_step) => vm_step,
+            None => return, // padding
+        };
+        if !matches!(
+            vm_step.step.instruction.opcode.builtin(),
+            Some(BuiltinOpcode::REM) | Some(BuiltinOpcode::REMU)
+        ) {
+            return;
+        }
+
+        let ExecutionResult { remainder, .. } = fill_div_rem(traces, row_idx, vm_step);
+
+        assert_eq!(
+            remainder,
+            vm_step
+                .get_result()
+                .expect("REM/REMU instruction must have a result")
+        );
+        traces.fill_columns(row_idx, remainder, ValueA);
+    }
+
+    fn add_constraints<E: EvalAtRow>(
+        eval: &mut E,
+        trace_eval: &TraceEval<E>,
+        _lookup_elements: &AllLookupElements,
+    ) {
+        let [is_rem] = trace_eval!(trace_eval, IsRem);
+        let [is_remu] = trace_eval!(trace_eval, IsRemu);
+        let value_a = trace_eval!(trace_eval, ValueA);
+        let rem = trace_eval!(trace_eval, Rem);
+
+        // (is_rem + is_remu) ・ (a_val - rem) = 0, limb by limb
+        for i in 0..WORD_SIZE {
+            eval.add_constraint(
+                (is_rem.clone() + is_remu.clone()) * (value_a[i].clone() - rem[i].clone()),
+            );
+        }
+        add_div_rem_constraints(eval, trace_eval, is_rem, is_remu);
+    }
+}
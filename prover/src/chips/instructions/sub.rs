@@ -1,6 +1,6 @@
 use stwo_prover::{constraint_framework::EvalAtRow, core::fields::FieldExpOps};
 
-use nexus_vm::{riscv::BuiltinOpcode, WORD_SIZE};
+use nexus_vm::riscv::BuiltinOpcode;
 
 use crate::{
     column::Column::{self, *},
@@ -8,7 +8,7 @@ use crate::{
     trace::{
         eval::{trace_eval, TraceEval},
         sidenote::SideNote,
-        BoolWord, ProgramStep, TracesBuilder, Word,
+        BoolWordN, ProgramStep, TracesBuilder, Word, WordN,
     },
     traits::{ExecuteChip, MachineChip},
 };
@@ -21,9 +21,9 @@ pub struct ExecutionResult {
     pub diff_bytes: Word,
 }
 
-pub fn subtract_with_borrow(x: Word, y: Word) -> (Word, BoolWord) {
-    let mut diff_bytes = [0u8; WORD_SIZE];
-    let mut borrow_bits: BoolWord = [false; WORD_SIZE];
+pub fn subtract_with_borrow<const N: usize>(x: WordN<N>, y: WordN<N>) -> (WordN<N>, BoolWordN<N>) {
+    let mut diff_bytes = [0u8; N];
+    let mut borrow_bits: BoolWordN<N> = [false; N];
 
     // Step 2. Compute the difference and borrow of each limb.
     let (diff, b0) = x[0].overflowing_sub(y[0]);
@@ -31,7 +31,7 @@ pub fn subtract_with_borrow(x: Word, y: Word) -> (Word, BoolWord) {
     diff_bytes[0] = diff;
 
     // Process the remaining difference bytes
-    for i in 1..WORD_SIZE {
+    for i in 1..N {
         // Subtract the bytes and the previous borrow
         let (diff, b1) = x[i].overflowing_sub(borrow_bits[i - 1] as u8);
         let (diff, b2) = diff.overflowing_sub(y[i]);
@@ -226,4 +226,16 @@ mod test {
         }
         assert_chip::<Chips>(traces, Some(program_traces.finalize()));
     }
+
+    #[test]
+    fn test_subtract_with_borrow_is_word_size_generic() {
+        let (diff, borrows) = subtract_with_borrow(0u32.to_le_bytes(), 1u32.to_le_bytes());
+        assert_eq!(u32::from_le_bytes(diff), u32::MAX);
+        assert_eq!(borrows, [true; 4]);
+
+        let (diff, borrows) =
+            subtract_with_borrow(0x0000_0100_0000_0000u64.to_le_bytes(), 1u64.to_le_bytes());
+        assert_eq!(u64::from_le_bytes(diff), 0x0000_00FF_FFFF_FFFF);
+        assert_eq!(borrows, [true, true, true, true, true, false, false, false]);
+    }
 }
@@ -1,13 +1,16 @@
 use num_traits::One;
 use stwo_prover::{constraint_framework::EvalAtRow, core::fields::m31::BaseField};
 
-use nexus_vm::{riscv::BuiltinOpcode, SyscallCode};
+use nexus_vm::{riscv::BuiltinOpcode, trace::Trace, SyscallCode};
 
 use crate::{
-    column::Column::{self},
+    column::{
+        Column::{self},
+        PreprocessedColumn,
+    },
     components::AllLookupElements,
     trace::{
-        eval::{trace_eval, TraceEval},
+        eval::{preprocessed_trace_eval, trace_eval, TraceEval},
         sidenote::SideNote,
         ProgramStep, TracesBuilder,
     },
@@ -19,6 +22,42 @@ use crate::virtual_column::VirtualColumn;
 
 pub struct SyscallChip;
 
+/// Syscalls that an execution can't use and still satisfy the constraints.
+///
+/// The hash states and the 256-bit operands of the precompiles are accessed in a single step,
+/// while the memory checking of the main trace allows one access per row. The results of the
+/// memory layout, heap statistics and allocator syscalls come from host-side state that isn't
+/// committed, so their flags are constrained to zero rather than trusting ValueA.
+const UNPROVABLE_SYSCALLS: [SyscallCode; 9] = [
+    SyscallCode::Sha256Compress,
+    SyscallCode::KeccakPermute,
+    SyscallCode::AddMod,
+    SyscallCode::MulMod,
+    SyscallCode::InvMod,
+    SyscallCode::QueryMemoryLayout,
+    SyscallCode::HeapStats,
+    SyscallCode::Alloc,
+    SyscallCode::Dealloc,
+];
+
+impl SyscallChip {
+    /// The first syscall of `trace` that can't be proven, with its pc.
+    pub(crate) fn find_unprovable(trace: &impl Trace) -> Option<(SyscallCode, u32)> {
+        trace.get_blocks_iter().find_map(|block| {
+            block.steps.iter().find_map(|step| {
+                let program_step = ProgramStep {
+                    step: step.clone(),
+                    regs: block.regs,
+                };
+                let syscall = SyscallCode::from_code(program_step.get_syscall_code()?)?;
+                UNPROVABLE_SYSCALLS
+                    .contains(&syscall)
+                    .then_some((syscall, step.pc))
+            })
+        })
+    }
+}
+
 impl MachineChip for SyscallChip {
     fn fill_main_trace(
         traces: &mut TracesBuilder,
@@ -42,9 +81,11 @@ impl MachineChip for SyscallChip {
             .expect("ECALL must have syscall number at Register::X17");
 
         let result = vm_step.step.result;
-        match (syscall_number, result) {
-            (0x200, None) => traces.fill_columns(row_idx, true, Column::IsSysDebug),
-            (0x201, result) => {
+        match (SyscallCode::from_code(syscall_number), result) {
+            (Some(SyscallCode::Write), None) => {
+                traces.fill_columns(row_idx, true, Column::IsSysDebug)
+            }
+            (Some(SyscallCode::Exit), result) => {
                 // the result may be present or not depending on a pass, has no effect
                 let _ = result;
 
@@ -52,19 +93,49 @@ impl MachineChip for SyscallChip {
                 // PcNext should be the current Pc
                 traces.fill_columns(row_idx, vm_step.step.pc, Column::PcNext);
             }
-            (0x400, Some(result)) => {
+            (Some(SyscallCode::ReadFromPrivateInput), Some(result)) => {
                 traces.fill_columns(row_idx, true, Column::IsSysPrivInput);
                 traces.fill_columns(row_idx, result, Column::ValueA);
             }
-            (0x401, None) => traces.fill_columns(row_idx, true, Column::IsSysCycleCount),
-            (0x402, Some(result)) => {
+            (Some(SyscallCode::CycleCount), None) => {
+                traces.fill_columns(row_idx, true, Column::IsSysCycleCount)
+            }
+            (Some(SyscallCode::OverwriteStackPointer), Some(result)) => {
                 traces.fill_columns(row_idx, true, Column::IsSysStackReset);
                 traces.fill_columns(row_idx, result, Column::ValueA);
             }
-            (0x403, Some(result)) => {
+            (Some(SyscallCode::OverwriteHeapPointer), Some(result)) => {
                 traces.fill_columns(row_idx, true, Column::IsSysHeapReset);
                 traces.fill_columns(row_idx, result, Column::ValueA);
             }
+            (Some(SyscallCode::ReadClock), Some(result)) => {
+                traces.fill_columns(row_idx, true, Column::IsSysReadClock);
+                traces.fill_columns(row_idx, result, Column::ValueA);
+            }
+            (Some(SyscallCode::Log), None) => traces.fill_columns(row_idx, true, Column::IsSysLog),
+            (Some(SyscallCode::Yield), None) => {
+                traces.fill_columns(row_idx, true, Column::IsSysYield)
+            }
+            (
+                Some(
+                    SyscallCode::Sha256Compress
+                    | SyscallCode::KeccakPermute
+                    | SyscallCode::AddMod
+                    | SyscallCode::MulMod
+                    | SyscallCode::InvMod
+                    | SyscallCode::QueryMemoryLayout
+                    | SyscallCode::HeapStats
+                    | SyscallCode::Alloc
+                    | SyscallCode::Dealloc,
+                ),
+                _,
+            ) => {
+                // Rejected with `ProveError::UnsupportedSyscall` before the trace is filled.
+                panic!(
+                    "Syscall 0x{:x} can't be proven, on row {}",
+                    syscall_number, row_idx
+                );
+            }
             _ => {
                 panic!(
                     "Unknown syscall number: 0x{:x} and result: {:?}, on row {}",
@@ -86,6 +157,13 @@ impl MachineChip for SyscallChip {
         let [is_sys_cycle_count] = trace_eval!(trace_eval, Column::IsSysCycleCount);
         let [is_sys_stack_reset] = trace_eval!(trace_eval, Column::IsSysStackReset);
         let [is_sys_heap_reset] = trace_eval!(trace_eval, Column::IsSysHeapReset);
+        let [is_sys_memory_layout] = trace_eval!(trace_eval, Column::IsSysMemoryLayout);
+        let [is_sys_heap_stats] = trace_eval!(trace_eval, Column::IsSysHeapStats);
+        let [is_sys_read_clock] = trace_eval!(trace_eval, Column::IsSysReadClock);
+        let [is_sys_log] = trace_eval!(trace_eval, Column::IsSysLog);
+        let [is_sys_alloc] = trace_eval!(trace_eval, Column::IsSysAlloc);
+        let [is_sys_dealloc] = trace_eval!(trace_eval, Column::IsSysDealloc);
+        let [is_sys_yield] = trace_eval!(trace_eval, Column::IsSysYield);
         let value_b = trace_eval!(trace_eval, Column::ValueB);
 
         // is_type_sys・				(b_val_3) = 0
@@ -102,6 +180,20 @@ impl MachineChip for SyscallChip {
         // is_type_sys・is_sys_stack_reset・	(b_val_2 - 0x04) = 0  // b_val=0x402
         // is_type_sys・is_sys_heap_reset・	(b_val_1 - 0x03) = 0  // b_val=0x403
         // is_type_sys・is_sys_heap_reset・	(b_val_2 - 0x04) = 0  // b_val=0x403
+        // is_type_sys・is_sys_memory_layout・	(b_val_1 - 0x05) = 0  // b_val=0x405
+        // is_type_sys・is_sys_memory_layout・	(b_val_2 - 0x04) = 0  // b_val=0x405
+        // is_type_sys・is_sys_heap_stats・	(b_val_1 - 0x06) = 0  // b_val=0x406
+        // is_type_sys・is_sys_heap_stats・	(b_val_2 - 0x04) = 0  // b_val=0x406
+        // is_type_sys・is_sys_read_clock・	(b_val_1 - 0x07) = 0  // b_val=0x407
+        // is_type_sys・is_sys_read_clock・	(b_val_2 - 0x04) = 0  // b_val=0x407
+        // is_type_sys・is_sys_log・		(b_val_1 - 0x08) = 0  // b_val=0x408
+        // is_type_sys・is_sys_log・		(b_val_2 - 0x04) = 0  // b_val=0x408
+        // is_type_sys・is_sys_alloc・		(b_val_1 - 0x09) = 0  // b_val=0x409
+        // is_type_sys・is_sys_alloc・		(b_val_2 - 0x04) = 0  // b_val=0x409
+        // is_type_sys・is_sys_dealloc・	(b_val_1 - 0x0A) = 0  // b_val=0x40A
+        // is_type_sys・is_sys_dealloc・	(b_val_2 - 0x04) = 0  // b_val=0x40A
+        // is_type_sys・is_sys_yield・		(b_val_1 - 0x0B) = 0  // b_val=0x40B
+        // is_type_sys・is_sys_yield・		(b_val_2 - 0x04) = 0  // b_val=0x40B
 
         let syscall_table = [
             (SyscallCode::Write as u32, &is_sys_debug),
@@ -113,6 +205,13 @@ impl MachineChip for SyscallChip {
                 &is_sys_stack_reset,
             ),
             (SyscallCode::OverwriteHeapPointer as u32, &is_sys_heap_reset),
+            (SyscallCode::QueryMemoryLayout as u32, &is_sys_memory_layout),
+            (SyscallCode::HeapStats as u32, &is_sys_heap_stats),
+            (SyscallCode::ReadClock as u32, &is_sys_read_clock),
+            (SyscallCode::Log as u32, &is_sys_log),
+            (SyscallCode::Alloc as u32, &is_sys_alloc),
+            (SyscallCode::Dealloc as u32, &is_sys_dealloc),
+            (SyscallCode::Yield as u32, &is_sys_yield),
         ];
 
         eval.add_constraint(is_type_sys.clone() * value_b[2].clone());
@@ -132,7 +231,7 @@ impl MachineChip for SyscallChip {
         }
 
         // Enforce that one flag is set
-        // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_priv_input + is_sys_cycle_count + is_sys_stack_reset + is_sys_heap_reset - 1) = 0
+        // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_priv_input + is_sys_cycle_count + is_sys_stack_reset + is_sys_heap_reset + is_sys_memory_layout + is_sys_heap_stats + is_sys_read_clock + is_sys_log + is_sys_alloc + is_sys_dealloc + is_sys_yield - 1) = 0
         eval.add_constraint(
             is_type_sys.clone()
                 * (is_sys_debug.clone()
@@ -141,23 +240,40 @@ impl MachineChip for SyscallChip {
                     + is_sys_cycle_count.clone()
                     + is_sys_stack_reset.clone()
                     + is_sys_heap_reset.clone()
+                    + is_sys_memory_layout.clone()
+                    + is_sys_heap_stats.clone()
+                    + is_sys_read_clock.clone()
+                    + is_sys_log.clone()
+                    + is_sys_alloc.clone()
+                    + is_sys_dealloc.clone()
+                    + is_sys_yield.clone()
                     - E::F::one()),
         );
 
         // Enforcing values for op_a
-        // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_cycle_count)・(op_a) = 0
-        // is_type_sys・(is_sys_priv_input + is_sys_heap_reset)・(10 - op_a) = 0
+        // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_cycle_count + is_sys_log + is_sys_yield)・(op_a) = 0
+        // is_type_sys・(is_sys_priv_input + is_sys_heap_reset + is_sys_memory_layout + is_sys_heap_stats + is_sys_read_clock + is_sys_alloc + is_sys_dealloc)・(10 - op_a) = 0
         // is_type_sys・(is_sys_stack_reset)・(2 - op_a) = 0
         let [op_a] = trace_eval!(trace_eval, Column::OpA);
 
         eval.add_constraint(
             is_type_sys.clone()
-                * (is_sys_debug.clone() + is_sys_halt.clone() + is_sys_cycle_count.clone())
+                * (is_sys_debug.clone()
+                    + is_sys_halt.clone()
+                    + is_sys_cycle_count.clone()
+                    + is_sys_log.clone()
+                    + is_sys_yield.clone())
                 * op_a.clone(),
         );
         eval.add_constraint(
             is_type_sys.clone()
-                * (is_sys_priv_input.clone() + is_sys_heap_reset.clone())
+                * (is_sys_priv_input.clone()
+                    + is_sys_heap_reset.clone()
+                    + is_sys_memory_layout.clone()
+                    + is_sys_heap_stats.clone()
+                    + is_sys_read_clock.clone()
+                    + is_sys_alloc.clone()
+                    + is_sys_dealloc.clone())
                 * (E::F::from(BaseField::from(10)) - op_a.clone()),
         );
         eval.add_constraint(
@@ -167,16 +283,41 @@ impl MachineChip for SyscallChip {
         );
 
         // Enforcing ranges for a_val
-        // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_cycle_count)・(a_val_1 + a_val_2 * 256) = 0
-        // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_cycle_count)・(a_val_3 + a_val_3 * 256) = 0
+        // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_cycle_count + is_sys_log + is_sys_yield)・(a_val_1 + a_val_2 * 256) = 0
+        // is_type_sys・(is_sys_debug + is_sys_halt + is_sys_cycle_count + is_sys_log + is_sys_yield)・(a_val_3 + a_val_3 * 256) = 0
         let value_a = trace_eval!(trace_eval, Column::ValueA);
         for a in value_a.chunks(2) {
             eval.add_constraint(
                 is_type_sys.clone()
-                    * (is_sys_debug.clone() + is_sys_halt.clone() + is_sys_cycle_count.clone())
+                    * (is_sys_debug.clone()
+                        + is_sys_halt.clone()
+                        + is_sys_cycle_count.clone()
+                        + is_sys_log.clone()
+                        + is_sys_yield.clone())
                     * (a[0].clone() + a[1].clone() * E::F::from(BaseField::from(256))),
             );
         }
+
+        // The clock read is the timestamp of the row
+        // is_type_sys・is_sys_read_clock・(a_val_i - clk_i) = 0
+        let clk = preprocessed_trace_eval!(trace_eval, PreprocessedColumn::Clk);
+        for (a, c) in value_a.into_iter().zip(clk) {
+            eval.add_constraint(is_type_sys.clone() * is_sys_read_clock.clone() * (a - c));
+        }
+
+        // The results of these syscalls can't be checked, see `UNPROVABLE_SYSCALLS`
+        // is_type_sys・is_sys_memory_layout = 0
+        // is_type_sys・is_sys_heap_stats = 0
+        // is_type_sys・is_sys_alloc = 0
+        // is_type_sys・is_sys_dealloc = 0
+        for is_sys in [
+            is_sys_memory_layout,
+            is_sys_heap_stats,
+            is_sys_alloc,
+            is_sys_dealloc,
+        ] {
+            eval.add_constraint(is_type_sys.clone() * is_sys);
+        }
     }
 }
 
@@ -189,7 +330,9 @@ mod test {
         },
         test_utils::assert_chip,
         trace::{
-            program::iter_program_steps, program_trace::ProgramTracesBuilder, PreprocessedTraces,
+            program::iter_program_steps,
+            program_trace::{ProgramTraces, ProgramTracesBuilder},
+            PreprocessedTraces,
         },
     };
 
@@ -218,6 +361,15 @@ mod test {
             // Heap reset syscall (0x403)
             Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, SyscallCode::OverwriteHeapPointer as u32),
             Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+            // Read clock syscall (0x407)
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, SyscallCode::ReadClock as u32),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+            // Log syscall (0x408)
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, SyscallCode::Log as u32),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+            // Yield syscall (0x40B)
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, SyscallCode::Yield as u32),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
             // End with Halt syscall (0x201)
             Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, SyscallCode::Exit as u32),
             Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
@@ -230,17 +382,20 @@ mod test {
         vec![basic_block]
     }
 
-    #[test]
-    fn test_k_trace_constrained_syscall_instructions() {
-        type Chips = (
-            CpuChip,
-            DecodingCheckChip,
-            SyscallChip,
-            AddChip,
-            RegisterMemCheckChip,
-            ProgramMemCheckChip,
-            RangeCheckChip,
-        );
+    type Chips = (
+        CpuChip,
+        DecodingCheckChip,
+        SyscallChip,
+        AddChip,
+        RegisterMemCheckChip,
+        ProgramMemCheckChip,
+        RangeCheckChip,
+    );
+
+    /// Row of the read clock ECALL in [`setup_basic_block_ir`].
+    const READ_CLOCK_ROW: usize = 9;
+
+    fn syscall_traces() -> (TracesBuilder, ProgramTraces) {
         let basic_block = setup_basic_block_ir();
         let k = 1;
 
@@ -258,6 +413,29 @@ mod test {
         for (row_idx, program_step) in program_steps.enumerate() {
             Chips::fill_main_trace(&mut traces, row_idx, &program_step, &mut side_note);
         }
-        assert_chip::<Chips>(traces, Some(program_traces.finalize()));
+        (traces, program_traces.finalize())
+    }
+
+    #[test]
+    fn test_k_trace_constrained_syscall_instructions() {
+        let (traces, program_traces) = syscall_traces();
+        assert_chip::<Chips>(traces, Some(program_traces));
+    }
+
+    #[test]
+    fn test_read_clock_matches_clk() {
+        let (traces, program_traces) = syscall_traces();
+        let [is_sys_read_clock] = traces.column(READ_CLOCK_ROW, Column::IsSysReadClock);
+        assert_eq!(is_sys_read_clock, BaseField::one());
+        assert_chip::<SyscallChip>(traces, Some(program_traces));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_read_clock_forged_value_a() {
+        let (mut traces, program_traces) = syscall_traces();
+        *traces.column_mut::<{ Column::ValueA.size() }>(READ_CLOCK_ROW, Column::ValueA)[0] +=
+            BaseField::one();
+        assert_chip::<SyscallChip>(traces, Some(program_traces));
     }
 }
@@ -3,7 +3,7 @@ use num_traits::{One, Zero};
 
 use nexus_vm::WORD_SIZE;
 use stwo_prover::{
-    constraint_framework::{logup::LogupTraceGenerator, EvalAtRow, Relation, RelationEntry},
+    constraint_framework::{EvalAtRow, Relation, RelationEntry},
     core::{
         backend::simd::m31::{PackedBaseField, LOG_N_LANES},
         fields::m31::BaseField,
@@ -15,6 +15,7 @@ use crate::{
     components::AllLookupElements,
     trace::{
         eval::{preprocessed_trace_eval, program_trace_eval, trace_eval, TraceEval},
+        logup::LogupTraceBuilder,
         program_trace::ProgramTraces,
         sidenote::SideNote,
         utils::FromBaseFields,
@@ -114,7 +115,7 @@ impl MachineChip for ProgramMemCheckChip {
     /// * 1 / lookup_element.combine(tuple) is subtracted for each instruction
     /// where tuples contain (the address, the whole word of the instruction, final counter value).
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         _preprocessed_trace: &PreprocessedTraces,
         program_trace: &ProgramTraces,
@@ -223,7 +224,7 @@ impl ProgramMemCheckChip {
     ///
     /// The initial content of the memory is located on rows where PrgMemoryFlag is 1.
     fn add_initial_digest(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         program_traces: &ProgramTraces,
         lookup_element: &ProgramCheckLookupElements,
@@ -304,7 +305,7 @@ impl ProgramMemCheckChip {
     /// Most columns are the same as the initial program memory content.
     /// The final counter is located on the FinalPrgMemoryCtr column.
     fn subtract_final_digest(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         program_traces: &ProgramTraces,
         lookup_element: &ProgramCheckLookupElements,
@@ -386,7 +387,7 @@ impl ProgramMemCheckChip {
     ///
     /// The numerator is zero on the padding rows, so that the row doesn't contribute to the logup sum.
     fn subtract_access(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         lookup_element: &ProgramCheckLookupElements,
     ) {
@@ -464,7 +465,7 @@ impl ProgramMemCheckChip {
     ///
     /// The numerator is zero when the row is padding, so that the row doesn't contribute to the logup sum.
     fn add_access(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         lookup_element: &ProgramCheckLookupElements,
     ) {
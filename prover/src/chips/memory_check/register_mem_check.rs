@@ -1,7 +1,7 @@
 use nexus_vm::WORD_SIZE;
 use num_traits::Zero;
 use stwo_prover::{
-    constraint_framework::{logup::LogupTraceGenerator, EvalAtRow, Relation, RelationEntry},
+    constraint_framework::{EvalAtRow, Relation, RelationEntry},
     core::{backend::simd::m31::LOG_N_LANES, fields::m31::BaseField},
 };
 
@@ -17,6 +17,7 @@ use crate::{
     components::AllLookupElements,
     trace::{
         eval::{trace_eval, TraceEval},
+        logup::LogupTraceBuilder,
         program_trace::ProgramTraces,
         regs::AccessResult,
         sidenote::SideNote,
@@ -223,7 +224,7 @@ impl MachineChip for RegisterMemCheckChip {
     }
 
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         preprocessed_trace: &PreprocessedTraces,
         _program_trace: &ProgramTraces,
@@ -292,7 +293,7 @@ impl MachineChip for RegisterMemCheckChip {
 
 impl RegisterMemCheckChip {
     fn subtract_prev_reg<AccessFlag: VirtualColumn<1>>(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         lookup_element: &RegisterCheckLookupElements,
         reg_address: Column,
@@ -343,7 +344,7 @@ impl RegisterMemCheckChip {
     }
 
     fn add_cur_reg<AccessFlag: VirtualColumn<1>>(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         preprocessed_trace: &PreprocessedTraces,
         lookup_element: &RegisterCheckLookupElements,
@@ -19,7 +19,7 @@ use crate::{
         eval::{preprocessed_trace_eval, trace_eval, TraceEval},
         sidenote::SideNote,
         utils::FromBaseFields,
-        BoolWord, ProgramStep, TracesBuilder, Word,
+        BoolWordN, ProgramStep, TracesBuilder, WordN,
     },
     traits::MachineChip,
 };
@@ -101,10 +101,14 @@ impl MachineChip for TimestampChip {
 /// Performs x - 1 - y, returning the result and the borrow bits
 ///
 /// Note that for - 1 - y, for every limb, just one borrow bit suffices
-pub fn decr_subtract_with_borrow(x: Word, y: Word) -> (Word, BoolWord) {
-    let (diff, borrow1) = subtract_with_borrow(x, 1u32.to_le_bytes());
+pub fn decr_subtract_with_borrow<const N: usize>(
+    x: WordN<N>,
+    y: WordN<N>,
+) -> (WordN<N>, BoolWordN<N>) {
+    let one = array::from_fn(|i| (i == 0) as u8);
+    let (diff, borrow1) = subtract_with_borrow(x, one);
     let (diff, borrow2) = subtract_with_borrow(diff, y);
-    for i in 0..WORD_SIZE {
+    for i in 0..N {
         assert!(!borrow1[i] || !borrow2[i]);
     }
     let borrow = array::from_fn(|i| borrow1[i] | borrow2[i]);
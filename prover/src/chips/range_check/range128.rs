@@ -3,7 +3,7 @@
 // The target of the 0..127 rangecheck depends on the opcode.
 
 use stwo_prover::{
-    constraint_framework::{logup::LogupTraceGenerator, Relation, RelationEntry},
+    constraint_framework::{Relation, RelationEntry},
     core::backend::simd::m31::PackedBaseField,
 };
 
@@ -17,13 +17,15 @@ use stwo_prover::core::{
 use crate::{
     components::AllLookupElements,
     trace::{
-        program_trace::ProgramTraces, sidenote::SideNote, FinalizedTraces, PreprocessedTraces,
-        ProgramStep, TracesBuilder,
+        logup::LogupTraceBuilder, program_trace::ProgramTraces, sidenote::SideNote,
+        FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder,
     },
     traits::MachineChip,
 };
 
-use crate::column::Column::{self, Helper2, Helper3, IsBge, IsBlt, IsSlt};
+use crate::column::Column::{
+    self, Helper1, Helper2, Helper3, Helper4, IsBge, IsBlt, IsDiv, IsMulh, IsMulhsu, IsRem, IsSlt,
+};
 
 /// A Chip for range-checking values for 0..=127
 ///
@@ -51,11 +53,27 @@ impl MachineChip for Range128Chip {
         let [is_slt] = traces.column(row_idx, IsSlt);
         let [is_bge] = traces.column(row_idx, IsBge);
         let [is_blt] = traces.column(row_idx, IsBlt);
-        let last_limb_checked = [Helper2, Helper3];
-        for col in last_limb_checked.into_iter() {
+        let [is_mulh] = traces.column(row_idx, IsMulh);
+        let [is_mulhsu] = traces.column(row_idx, IsMulhsu);
+        let [is_div] = traces.column(row_idx, IsDiv);
+        let [is_rem] = traces.column(row_idx, IsRem);
+        // The last limbs of Helper{1,2,3,4} hold the words checked for sign bits, without the sign bit
+        let last_limb_checked = [
+            (
+                Helper2,
+                is_slt + is_bge + is_blt + is_mulh + is_mulhsu + is_div + is_rem,
+            ),
+            (
+                Helper3,
+                is_slt + is_bge + is_blt + is_mulh + is_div + is_rem,
+            ),
+            (Helper1, is_div + is_rem),
+            (Helper4, is_div + is_rem),
+        ];
+        for (col, selector) in last_limb_checked.into_iter() {
             let word: [_; WORD_SIZE] = traces.column(row_idx, col);
             let last_limb = word[3];
-            fill_main_col(last_limb, is_slt + is_bge + is_blt, side_note);
+            fill_main_col(last_limb, selector, side_note);
         }
         let [is_jalr] = traces.column(row_idx, Column::IsJalr);
         let [qt_aux] = traces.column(row_idx, Column::QtAux);
@@ -73,7 +91,7 @@ impl MachineChip for Range128Chip {
     ///
     /// data[vec_row] contains sixteen rows. A single write_frac() adds sixteen numbers.
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         _preprocessed_traces: &PreprocessedTraces,
         _program_traces: &ProgramTraces,
@@ -85,11 +103,26 @@ impl MachineChip for Range128Chip {
         let [is_slt]: [_; 1] = original_traces.get_base_column(IsSlt);
         let [is_bge]: [_; 1] = original_traces.get_base_column(IsBge);
         let [is_blt]: [_; 1] = original_traces.get_base_column(IsBlt);
-        for col in [Helper2, Helper3].into_iter() {
+        let [is_mulh]: [_; 1] = original_traces.get_base_column(IsMulh);
+        let [is_mulhsu]: [_; 1] = original_traces.get_base_column(IsMulhsu);
+        let [is_div]: [_; 1] = original_traces.get_base_column(IsDiv);
+        let [is_rem]: [_; 1] = original_traces.get_base_column(IsRem);
+        for (col, selectors) in [
+            (
+                Helper2,
+                vec![is_slt, is_bge, is_blt, is_mulh, is_mulhsu, is_div, is_rem],
+            ),
+            (
+                Helper3,
+                vec![is_slt, is_bge, is_blt, is_mulh, is_div, is_rem],
+            ),
+            (Helper1, vec![is_div, is_rem]),
+            (Helper4, vec![is_div, is_rem]),
+        ] {
             let helper: [_; WORD_SIZE] = original_traces.get_base_column(col);
             check_col(
                 helper[3],
-                &[is_slt, is_bge, is_blt],
+                &selectors,
                 original_traces.log_size(),
                 logup_trace_gen,
                 lookup_element,
@@ -136,14 +169,25 @@ impl MachineChip for Range128Chip {
         let [is_slt] = trace_eval.column_eval(IsSlt);
         let [is_bge] = trace_eval.column_eval(IsBge);
         let [is_blt] = trace_eval.column_eval(IsBlt);
-
-        let numerator = is_slt.clone() + is_bge.clone() + is_blt.clone();
-        for col in [Helper2, Helper3].into_iter() {
+        let [is_mulh] = trace_eval.column_eval(IsMulh);
+        let [is_mulhsu] = trace_eval.column_eval(IsMulhsu);
+        let [is_div] = trace_eval.column_eval(IsDiv);
+        let [is_rem] = trace_eval.column_eval(IsRem);
+
+        let is_signed_div_rem = is_div + is_rem;
+        let numerator_c = is_slt + is_bge + is_blt + is_mulh + is_signed_div_rem.clone();
+        let numerator_b = numerator_c.clone() + is_mulhsu;
+        for (col, numerator) in [
+            (Helper2, numerator_b),
+            (Helper3, numerator_c),
+            (Helper1, is_signed_div_rem.clone()),
+            (Helper4, is_signed_div_rem),
+        ] {
             let value = trace_eval.column_eval::<WORD_SIZE>(col);
 
             eval.add_to_relation(RelationEntry::new(
                 lookup_elements,
-                numerator.clone().into(),
+                numerator.into(),
                 &[value[3].clone()],
             ));
         }
@@ -193,7 +237,7 @@ fn check_col(
     base_column: &BaseColumn,
     selectors: &[&BaseColumn],
     log_size: u32,
-    logup_trace_gen: &mut LogupTraceGenerator,
+    logup_trace_gen: &mut LogupTraceBuilder,
     lookup_element: &Range128LookupElements,
 ) {
     let mut logup_col_gen = logup_trace_gen.new_col();
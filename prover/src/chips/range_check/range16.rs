@@ -1,7 +1,7 @@
 // This file contains range-checking values for 0..=15.
 
 use nexus_vm::riscv::{BuiltinOpcode, InstructionType};
-use stwo_prover::constraint_framework::{logup::LogupTraceGenerator, Relation, RelationEntry};
+use stwo_prover::constraint_framework::{Relation, RelationEntry};
 
 use num_traits::Zero;
 use stwo_prover::core::{
@@ -13,8 +13,8 @@ use crate::{
     column::Column::{self, OpA1_4, OpB0_3, OpB1_4, OpC0_3, OpC12_15, OpC16_19, OpC1_4, OpC4_7},
     components::AllLookupElements,
     trace::{
-        eval::TraceEval, program_trace::ProgramTraces, sidenote::SideNote, FinalizedTraces,
-        PreprocessedTraces, ProgramStep, TracesBuilder,
+        eval::TraceEval, logup::LogupTraceBuilder, program_trace::ProgramTraces,
+        sidenote::SideNote, FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder,
     },
     traits::MachineChip,
     virtual_column::{
@@ -115,7 +115,7 @@ impl MachineChip for Range16Chip {
     ///
     /// data[vec_row] contains sixteen rows. A single write_frac() adds sixteen numbers.
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         _preprocessed_traces: &PreprocessedTraces,
         _program_traces: &ProgramTraces,
@@ -220,13 +220,13 @@ fn add_constraints_for_type<
 fn fill_interaction_for_type<VC: VirtualColumn<1>>(
     original_traces: &FinalizedTraces,
     lookup_element: &Range16LookupElements,
-    logup_trace_gen: &mut LogupTraceGenerator,
+    logup_trace_gen: &mut LogupTraceBuilder,
     cols: &[Column],
 ) {
     for col in cols.iter() {
         let [value_basecolumn]: [&BaseColumn; 1] = original_traces.get_base_column(*col);
         let log_size = original_traces.log_size();
-        let logup_trace_gen: &mut LogupTraceGenerator = logup_trace_gen;
+        let logup_trace_gen: &mut LogupTraceBuilder = logup_trace_gen;
         // TODO: we can deal with two limbs at a time.
         let mut logup_col_gen = logup_trace_gen.new_col();
         // vec_row is row_idx divided by 16. Because SIMD.
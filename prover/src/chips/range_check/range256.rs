@@ -1,6 +1,6 @@
 // This file contains range-checking values for 0..=255.
 
-use stwo_prover::constraint_framework::{logup::LogupTraceGenerator, Relation, RelationEntry};
+use stwo_prover::constraint_framework::{Relation, RelationEntry};
 
 use nexus_vm::WORD_SIZE;
 use num_traits::{One, Zero};
@@ -12,16 +12,16 @@ use stwo_prover::core::{
 use crate::{
     column::Column::{
         self, CReg1TsPrev, CReg2TsPrev, CReg3TsPrev, FinalPrgMemoryCtr, Helper1, InstrVal,
-        OpC16_23, OpC24_31, Pc, PcNextAux, PrevCtr, ProgCtrCur, ProgCtrPrev, Qt, Ram1TsPrev,
-        Ram1TsPrevAux, Ram1ValCur, Ram1ValPrev, Ram2TsPrev, Ram2TsPrevAux, Ram2ValCur, Ram2ValPrev,
-        Ram3TsPrev, Ram3TsPrevAux, Ram3ValCur, Ram3ValPrev, Ram4TsPrev, Ram4TsPrevAux, Ram4ValCur,
-        Ram4ValPrev, RamBaseAddr, RamFinalCounter, RamFinalValue, RamInitFinalAddr, Reg1TsPrev,
-        Reg2TsPrev, Reg3TsPrev, Rem, RemDiff, ValueA, ValueB, ValueC,
+        MulCarry1, MulCarry2, OpC16_23, OpC24_31, Pc, PcNextAux, PrevCtr, ProgCtrCur, ProgCtrPrev,
+        Qt, Ram1TsPrev, Ram1TsPrevAux, Ram1ValCur, Ram1ValPrev, Ram2TsPrev, Ram2TsPrevAux,
+        Ram2ValCur, Ram2ValPrev, Ram3TsPrev, Ram3TsPrevAux, Ram3ValCur, Ram3ValPrev, Ram4TsPrev,
+        Ram4TsPrevAux, Ram4ValCur, Ram4ValPrev, RamBaseAddr, RamFinalCounter, RamFinalValue,
+        RamInitFinalAddr, Reg1TsPrev, Reg2TsPrev, Reg3TsPrev, Rem, RemDiff, ValueA, ValueB, ValueC,
     },
     components::AllLookupElements,
     trace::{
-        eval::TraceEval, program_trace::ProgramTraces, sidenote::SideNote, FinalizedTraces,
-        PreprocessedTraces, ProgramStep, TracesBuilder,
+        eval::TraceEval, logup::LogupTraceBuilder, program_trace::ProgramTraces,
+        sidenote::SideNote, FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder,
     },
     traits::MachineChip,
     virtual_column::{self, VirtualColumn},
@@ -36,7 +36,7 @@ const LOOKUP_TUPLE_SIZE: usize = 1;
 stwo_prover::relation!(Range256LookupElements, LOOKUP_TUPLE_SIZE);
 
 impl Range256Chip {
-    const CHECKED_WORDS: [Column; 31] = [
+    const CHECKED_WORDS: [Column; 33] = [
         Pc,
         PcNextAux,
         InstrVal,
@@ -66,6 +66,8 @@ impl Range256Chip {
         Rem,
         Qt,
         RemDiff,
+        MulCarry1,
+        MulCarry2,
         RamInitFinalAddr,
         RamFinalCounter,
     ];
@@ -127,7 +129,7 @@ impl MachineChip for Range256Chip {
     ///
     /// data[vec_row] contains sixteen rows. A single write_frac() adds sixteen numbers.
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         _preprocessed_traces: &PreprocessedTraces,
         _program_traces: &ProgramTraces,
@@ -231,7 +233,7 @@ fn fill_main_cols<const N: usize>(value_col: [BaseField; N], side_note: &mut Sid
 fn check_bytes<const N: usize>(
     basecolumn: [&BaseColumn; N],
     log_size: u32,
-    logup_trace_gen: &mut LogupTraceGenerator,
+    logup_trace_gen: &mut LogupTraceBuilder,
     lookup_element: &Range256LookupElements,
 ) {
     // TODO: we can deal with two limbs at a time.
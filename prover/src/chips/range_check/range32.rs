@@ -1,6 +1,6 @@
 // This file contains range-checking values for 0..=31.
 
-use stwo_prover::constraint_framework::{logup::LogupTraceGenerator, Relation, RelationEntry};
+use stwo_prover::constraint_framework::{Relation, RelationEntry};
 
 use num_traits::One;
 use stwo_prover::core::{
@@ -12,8 +12,8 @@ use crate::{
     column::Column::{self, OpA, OpB, Reg1Address, Reg2Address, Reg3Address},
     components::AllLookupElements,
     trace::{
-        eval::TraceEval, program_trace::ProgramTraces, sidenote::SideNote, FinalizedTraces,
-        PreprocessedTraces, ProgramStep, TracesBuilder,
+        eval::TraceEval, logup::LogupTraceBuilder, program_trace::ProgramTraces,
+        sidenote::SideNote, FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder,
     },
     traits::MachineChip,
 };
@@ -52,7 +52,7 @@ impl MachineChip for Range32Chip {
     ///
     /// data[vec_row] contains sixteen rows. A single write_frac() adds sixteen numbers.
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         _preprocessed_traces: &PreprocessedTraces,
         _program_traces: &ProgramTraces,
@@ -4,7 +4,7 @@ use nexus_vm::{
     riscv::{BuiltinOpcode, InstructionType},
     WORD_SIZE,
 };
-use stwo_prover::constraint_framework::{logup::LogupTraceGenerator, Relation, RelationEntry};
+use stwo_prover::constraint_framework::{Relation, RelationEntry};
 
 use num_traits::Zero;
 use stwo_prover::core::{
@@ -16,12 +16,12 @@ use crate::{
     column::Column::{self, OpC1_3, OpC5_7, OpC8_10},
     components::AllLookupElements,
     trace::{
-        eval::TraceEval, program_trace::ProgramTraces, sidenote::SideNote, FinalizedTraces,
-        PreprocessedTraces, ProgramStep, TracesBuilder,
+        eval::TraceEval, logup::LogupTraceBuilder, program_trace::ProgramTraces,
+        sidenote::SideNote, FinalizedTraces, PreprocessedTraces, ProgramStep, TracesBuilder,
     },
     traits::MachineChip,
     virtual_column::{
-        IsTypeB, IsTypeINoShift, IsTypeJ, IsTypeS, VirtualColumn, VirtualColumnForSum,
+        IsMulDiv, IsTypeB, IsTypeINoShift, IsTypeJ, IsTypeS, VirtualColumn, VirtualColumnForSum,
     },
 };
 
@@ -80,6 +80,15 @@ impl MachineChip for Range8Chip {
             fill_main_elm(helper1_0, side_note);
         }
 
+        // Add multiplicities for the upper bits of the product carries in case of the M extension
+        let [is_mul_div] = IsMulDiv::read_from_traces_builder(traces, row_idx);
+        if !is_mul_div.is_zero() {
+            let carry_msb: [_; 8] = traces.column(row_idx, Column::MulCarryMsb);
+            for limb in carry_msb {
+                fill_main_elm(limb, side_note);
+            }
+        }
+
         fill_main_for_type::<IsTypeINoShift>(
             traces,
             row_idx,
@@ -118,7 +127,7 @@ impl MachineChip for Range8Chip {
     ///
     /// data[vec_row] contains sixteen rows. A single write_frac() adds sixteen numbers.
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         _preprocessed_traces: &PreprocessedTraces,
         _program_traces: &ProgramTraces,
@@ -164,6 +173,19 @@ impl MachineChip for Range8Chip {
             logup_col_gen.write_frac(vec_row, is_type.into(), denom);
         }
         logup_col_gen.finalize_col();
+
+        // Fill the interaction trace for MulCarryMsb in case of the M extension
+        let carry_msb: [&BaseColumn; 8] = original_traces.get_base_column(Column::MulCarryMsb);
+        for limb in carry_msb {
+            let mut logup_col_gen = logup_trace_gen.new_col();
+            for vec_row in 0..(1 << (log_size - LOG_N_LANES)) {
+                let checked_tuple = vec![limb.data[vec_row]];
+                let denom = lookup_element.combine(&checked_tuple);
+                let [is_mul_div] = IsMulDiv::read_from_finalized_traces(original_traces, vec_row);
+                logup_col_gen.write_frac(vec_row, is_mul_div.into(), denom);
+            }
+            logup_col_gen.finalize_col();
+        }
     }
 
     fn add_constraints<E: stwo_prover::constraint_framework::EvalAtRow>(
@@ -192,6 +214,17 @@ impl MachineChip for Range8Chip {
             numerator.into(),
             &[value],
         ));
+
+        // Add checked multiplicities for MulCarryMsb in case of the M extension
+        let [is_mul_div] = IsMulDiv::eval(trace_eval);
+        let carry_msb = trace_eval.column_eval::<8>(Column::MulCarryMsb);
+        for limb in carry_msb {
+            eval.add_to_relation(RelationEntry::new(
+                lookup_elements,
+                is_mul_div.clone().into(),
+                &[limb],
+            ));
+        }
     }
 }
 
@@ -220,13 +253,13 @@ fn add_constraints_for_type<
 fn fill_interaction_for_type<VC: VirtualColumn<1>>(
     original_traces: &FinalizedTraces,
     lookup_element: &Range8LookupElements,
-    logup_trace_gen: &mut LogupTraceGenerator,
+    logup_trace_gen: &mut LogupTraceBuilder,
     cols: &[Column],
 ) {
     for col in cols.iter() {
         let [value_basecolumn]: [&BaseColumn; 1] = original_traces.get_base_column(*col);
         let log_size = original_traces.log_size();
-        let logup_trace_gen: &mut LogupTraceGenerator = logup_trace_gen;
+        let logup_trace_gen: &mut LogupTraceBuilder = logup_trace_gen;
         // TODO: we can deal with two limbs at a time.
         let mut logup_col_gen = logup_trace_gen.new_col();
         // vec_row is row_idx divided by 16. Because SIMD.
@@ -17,6 +17,119 @@ impl Column {
     pub(crate) const fn reads_next_row_mask(&self) -> bool {
         matches!(self, Self::Pc | Self::IsPadding)
     }
+
+    /// Returns the layout and the owning chip of the column.
+    pub const fn info(self) -> ColumnInfo {
+        ColumnInfo {
+            name: self.name(),
+            offset: self.offset(),
+            width: self.size(),
+            owner: self.owner(),
+        }
+    }
+
+    /// Returns the name of the chip filling the column, or `None` if several chips fill it.
+    pub const fn owner(self) -> Option<&'static str> {
+        use Column::*;
+        match self {
+            Pc
+            | PcNext
+            | OpA
+            | OpB
+            | OpC
+            | ImmC
+            | InstrVal
+            | ValueB
+            | ValueC
+            | IsAdd
+            | IsOr
+            | IsAnd
+            | IsXor
+            | IsSub
+            | IsSltu
+            | IsSlt
+            | IsBne
+            | IsBeq
+            | IsBltu
+            | IsBlt
+            | IsBgeu
+            | IsBge
+            | IsJal
+            | IsSb
+            | IsSh
+            | IsSw
+            | IsLb
+            | IsLh
+            | IsLbu
+            | IsLhu
+            | IsLw
+            | IsLui
+            | IsAuipc
+            | IsJalr
+            | IsSll
+            | IsSrl
+            | IsSra
+            | IsMul
+            | IsMulh
+            | IsMulhsu
+            | IsMulhu
+            | IsDiv
+            | IsDivu
+            | IsRem
+            | IsRemu
+            | IsEcall
+            | IsEbreak
+            | IsPadding
+            | ValueAEffectiveFlag
+            | ValueAEffectiveFlagAux
+            | ValueAEffectiveFlagAuxInv
+            | PcCarry => Some("CpuChip"),
+            IsSysDebug | IsSysHalt | IsSysPrivInput | IsSysCycleCount | IsSysStackReset
+            | IsSysHeapReset | IsSysMemoryLayout | IsSysHeapStats | IsSysReadClock | IsSysLog
+            | IsSysAlloc | IsSysDealloc | IsSysYield => Some("SyscallChip"),
+            PcNextAux | RemAux | QtAux => Some("JalrChip"),
+            Exp | SraDegreeAux => Some("SraChip"),
+            ValueA4_7 | ValueB4_7 | ValueC4_7 => Some("BitOpChip"),
+            ValueAEffective | Reg1Address | Reg2Address | Reg3Address | Reg1ValPrev
+            | Reg2ValPrev | Reg3ValPrev | Reg1TsPrev | Reg2TsPrev | Reg3TsPrev => {
+                Some("RegisterMemCheckChip")
+            }
+            ProgCtrPrev | ProgCtrCur | ProgCtrCarry | FinalPrgMemoryCtr => {
+                Some("ProgramMemCheckChip")
+            }
+            CReg1TsPrev | CReg2TsPrev | CReg3TsPrev | CH1Minus | CH2Minus | CH3Minus => {
+                Some("TimestampChip")
+            }
+            RamBaseAddr | Ram1ValCur | Ram2ValCur | Ram3ValCur | Ram4ValCur | Ram1ValPrev
+            | Ram2ValPrev | Ram3ValPrev | Ram4ValPrev | Ram1TsPrev | Ram2TsPrev | Ram3TsPrev
+            | Ram4TsPrev | Ram1TsPrevAux | Ram2TsPrevAux | Ram3TsPrevAux | Ram4TsPrevAux
+            | RamReadOnly => Some("LoadStoreChip"),
+            OpC0_3 | OpC1_3 | OpC1_4 | OpC4_7 | OpC5_7 | OpC8_10 | OpC11 | OpC12 | OpC20
+            | OpA1_4 | OpB0_3 | OpB1_4 | OpC0 | OpC4 | OpA0 | OpB0 | OpB4 | OpC12_15 | OpC16_23
+            | OpC16_19 | OpC24_31 => Some("DecodingCheckChip"),
+            // Operands, carries and helpers shared between instruction chips, and columns no chip
+            // fills.
+            ValueA | CarryFlag | BorrowFlag | PrevCtr | Helper1 | Helper2 | Helper3 | Helper4
+            | SgnA | SgnB | SgnC | Neq | Neq12 | Neq34 | LtFlag | Rem | Qt | ShiftBit1
+            | ShiftBit2 | ShiftBit3 | ShiftBit4 | ShiftBit5 | Exp1_3 | RemDiff | Neq12Aux
+            | Neq34Aux | Neq12AuxInv | Neq34AuxInv | MulCarry1 | MulCarry2 | MulCarryMsb
+            | SgnQt | SgnRem | SgnRemAux | DivByZero | DivByZeroAux | DivOverflow
+            | RamInitFinalAddr | RamInitFinalFlag | RamFinalValue | RamFinalCounter => None,
+        }
+    }
+}
+
+/// Layout and ownership of a main trace column, see [`Column::info`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnInfo {
+    /// Name of the column variant.
+    pub name: &'static str,
+    /// Index of the first base field cell of the column in the main trace.
+    pub offset: usize,
+    /// Number of base field cells, e.g. one per limb of a word.
+    pub width: usize,
+    /// Name of the chip filling the column, `None` if several chips fill it.
+    pub owner: Option<&'static str>,
 }
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, ColumnsEnum)]
@@ -154,6 +267,30 @@ pub enum Column {
     /// Boolean flag on whether the row is a SRA.
     #[size = 1]
     IsSra,
+    /// Boolean flag on whether the row is a MUL.
+    #[size = 1]
+    IsMul,
+    /// Boolean flag on whether the row is a MULH.
+    #[size = 1]
+    IsMulh,
+    /// Boolean flag on whether the row is a MULHSU.
+    #[size = 1]
+    IsMulhsu,
+    /// Boolean flag on whether the row is a MULHU.
+    #[size = 1]
+    IsMulhu,
+    /// Boolean flag on whether the row is a DIV.
+    #[size = 1]
+    IsDiv,
+    /// Boolean flag on whether the row is a DIVU.
+    #[size = 1]
+    IsDivu,
+    /// Boolean flag on whether the row is a REM.
+    #[size = 1]
+    IsRem,
+    /// Boolean flag on whether the row is a REMU.
+    #[size = 1]
+    IsRemu,
     /// Boolean flag on whether the row is an ECALL.
     #[size = 1]
     IsEcall,
@@ -178,6 +315,27 @@ pub enum Column {
     /// Boolean flag on whether the row is an ECALL_HEAP_RESET (OverwriteHeapPointer).
     #[size = 1]
     IsSysHeapReset,
+    /// Boolean flag on whether the row is an ECALL_MEMORY_LAYOUT (QueryMemoryLayout).
+    #[size = 1]
+    IsSysMemoryLayout,
+    /// Boolean flag on whether the row is an ECALL_HEAP_STATS (HeapStats).
+    #[size = 1]
+    IsSysHeapStats,
+    /// Boolean flag on whether the row is an ECALL_READ_CLOCK (ReadClock).
+    #[size = 1]
+    IsSysReadClock,
+    /// Boolean flag on whether the row is an ECALL_LOG (Log).
+    #[size = 1]
+    IsSysLog,
+    /// Boolean flag on whether the row is an ECALL_ALLOC (Alloc).
+    #[size = 1]
+    IsSysAlloc,
+    /// Boolean flag on whether the row is an ECALL_DEALLOC (Dealloc).
+    #[size = 1]
+    IsSysDealloc,
+    /// Boolean flag on whether the row is an ECALL_YIELD (Yield).
+    #[size = 1]
+    IsSysYield,
     /// Boolean flag on whether the row is a padding.
     #[size = 1]
     IsPadding,
@@ -267,6 +425,33 @@ pub enum Column {
     /// Auxiliary column for SRA chip, equals sgn_b・(exp1_3-1)・exp in the doc, to keep the constraint degree low.
     #[size = 1]
     SraDegreeAux,
+    /// Low bytes of the carries of the first four limbs of a 64-bit product, for the M extension chips.
+    #[size = 4]
+    MulCarry1,
+    /// Low bytes of the carries of the last four limbs of a 64-bit product, for the M extension chips.
+    #[size = 4]
+    MulCarry2,
+    /// Upper bits of the carries of the eight limbs of a 64-bit product, each in 0..=7.
+    #[size = 8]
+    MulCarryMsb,
+    /// Signed bit of the quotient of DIV and REM.
+    #[size = 1]
+    SgnQt,
+    /// Signed bit of the remainder of DIV and REM.
+    #[size = 1]
+    SgnRem,
+    /// Auxiliary column for DIV and REM, equals sgn_b・(1-sgn_rem), to keep the constraint degree low.
+    #[size = 1]
+    SgnRemAux,
+    /// Division by zero flag of DIV{U} and REM{U}.
+    #[size = 1]
+    DivByZero,
+    /// Inverse of the sum of the divisor limbs, when it's non-zero.
+    #[size = 1]
+    DivByZeroAux,
+    /// Signed division overflow flag, set when dividing -2^31 by -1.
+    #[size = 1]
+    DivOverflow,
 
     /// 1 indicates OpA is non-zero, 0 indicates OpA is zero
     #[size = 1]
@@ -387,6 +572,9 @@ pub enum Column {
     /// Auxiliary columns for comparing Ram4TsPrev and Clk
     #[size = 4]
     Ram4TsPrevAux,
+    /// 1 if the bytes accessed at RamBaseAddr are read-only data, which can't be stored to
+    #[size = 1]
+    RamReadOnly,
 
     /// Auxiliary variable for decoding instruction: bits[0..=3] of OpC argument
     #[size = 1]
@@ -488,6 +676,7 @@ pub enum Column {
 //     pub const ALL_VARIANTS: &[Column] = /* ... */;
 //     pub const fn size(self) -> usize { /* ... */ }
 //     pub const fn offset(self) -> usize { /* ... */ }
+//     pub const fn name(self) -> &'static str { /* ... */ }
 // }
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, ColumnsEnum)]
@@ -511,6 +700,9 @@ pub enum ProgramColumn {
     /// A flag indicating (PublicRamAddr, PublicInitialMemoryValue) is a byte in the publicly known initial memory
     #[size = 1]
     PublicInitialMemoryFlag,
+    /// A flag indicating the byte of the publicly known initial memory at PublicRamAddr is read-only data
+    #[size = 1]
+    PublicReadOnlyFlag,
     /// The one-byte content of public output at PublicRamAddr
     #[size = 1]
     PublicOutputValue,
@@ -520,6 +712,18 @@ pub enum ProgramColumn {
     /// The address of initial memory or public output; actually a selective copy of RamInitFinalAddr that the verifier knows
     #[size = 4]
     PublicRamAddr,
+    /// 1 on every row if the proof claims the final RAM, 0 otherwise
+    #[size = 1]
+    PublicFinalMemoryMode,
+    /// A flag indicating the row's (PublicFinalMemoryAddr, PublicFinalMemoryValue) is a byte of the claimed final RAM
+    #[size = 1]
+    PublicFinalMemoryFlag,
+    /// The address of a byte of the claimed final RAM; a copy of RamInitFinalAddr that the verifier knows
+    #[size = 4]
+    PublicFinalMemoryAddr,
+    /// The one-byte content of the claimed final RAM at PublicFinalMemoryAddr
+    #[size = 1]
+    PublicFinalMemoryValue,
 }
 
 // proc macro derived:
@@ -529,6 +733,7 @@ pub enum ProgramColumn {
 //     pub const ALL_VARIANTS: &[Column] = /* ... */;
 //     pub const fn size(self) -> usize { /* ... */ }
 //     pub const fn offset(self) -> usize { /* ... */ }
+//     pub const fn name(self) -> &'static str { /* ... */ }
 // }
 
 #[derive(Debug, Copy, Clone, Hash, PartialEq, Eq, ColumnsEnum)]
@@ -562,4 +767,25 @@ pub enum PreprocessedColumn {
 //     pub const STRING_IDS: &[&str] = /* ... */
 //     pub const fn size(self) -> usize { /* ... */ }
 //     pub const fn offset(self) -> usize { /* ... */ }
+//     pub const fn name(self) -> &'static str { /* ... */ }
 // }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn column_info_covers_layout() {
+        let mut offset = 0;
+        for col in Column::ALL_VARIANTS {
+            let info = col.info();
+            assert_eq!(info.name, format!("{col:?}"));
+            assert_eq!(info.offset, offset);
+            offset += info.width;
+        }
+        assert_eq!(offset, Column::COLUMNS_NUM);
+
+        assert_eq!(Column::IsSysLog.owner(), Some("SyscallChip"));
+        assert_eq!(Column::Helper1.owner(), None);
+    }
+}
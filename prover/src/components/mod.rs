@@ -10,6 +10,7 @@ mod lookups;
 pub use lookups::AllLookupElements;
 pub(crate) use lookups::RegisteredLookupBound;
 
+/// Default log2 of the constraint degree bound of chips, see [`MachineChip::log_constraint_degree`].
 pub(super) const LOG_CONSTRAINT_DEGREE: u32 = 2;
 
 pub type MachineComponent<C> = FrameworkComponent<MachineEval<C>>;
@@ -17,14 +18,25 @@ pub type MachineComponent<C> = FrameworkComponent<MachineEval<C>>;
 pub struct MachineEval<C> {
     log_n_rows: u32,
     lookup_elements: AllLookupElements,
+    batch_index: usize,
     _phantom_data: PhantomData<C>,
 }
 
 impl<C> MachineEval<C> {
     pub(crate) fn new(log_n_rows: u32, lookup_elements: AllLookupElements) -> Self {
+        Self::new_in_batch(log_n_rows, lookup_elements, 0)
+    }
+
+    /// Evaluator of the `batch_index`-th execution of a batch proof, see [`TraceEval::for_component`].
+    pub(crate) fn new_in_batch(
+        log_n_rows: u32,
+        lookup_elements: AllLookupElements,
+        batch_index: usize,
+    ) -> Self {
         Self {
             log_n_rows,
             lookup_elements,
+            batch_index,
             _phantom_data: PhantomData,
         }
     }
@@ -36,11 +48,12 @@ impl<C: MachineChip> FrameworkEval for MachineEval<C> {
     }
 
     fn max_constraint_log_degree_bound(&self) -> u32 {
-        self.log_n_rows + LOG_CONSTRAINT_DEGREE
+        self.log_n_rows + C::log_constraint_degree()
     }
 
     fn evaluate<E: EvalAtRow>(&self, mut eval: E) -> E {
-        let trace_eval = TraceEval::new(&mut eval);
+        let trace_eval =
+            TraceEval::for_component(&mut eval, &C::preprocessed_columns(), self.batch_index);
         C::add_constraints(&mut eval, &trace_eval, &self.lookup_elements);
 
         if !self.lookup_elements.is_empty() {
@@ -54,6 +67,7 @@ pub(crate) fn machine_component_info<C: MachineChip>() -> InfoEvaluator {
     let eval = MachineEval::<C> {
         log_n_rows: 1,
         lookup_elements: AllLookupElements::dummy(),
+        batch_index: 0,
         _phantom_data: PhantomData,
     };
     eval.evaluate(InfoEvaluator::empty())
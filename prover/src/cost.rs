@@ -0,0 +1,157 @@
+//! Estimates of the cost of verifying a proof, e.g. by a smart contract.
+//!
+//! The estimate follows the shape of the proof: Merkle decommitments of every committed tree and
+//! FRI layer at each query, DEEP quotients and FRI folds at each query, and the commitments, sampled
+//! values and decommitments sent to the verifier. It is an upper bound of what a verifier has to do,
+//! decommitment paths shared between queries are counted once per query. Evaluating the constraints
+//! at the out-of-domain point is not included, it doesn't depend on the PCS parameters.
+//!
+//! Proofs are currently always generated with [`PcsConfig::default`], other configurations are meant
+//! for comparing parameters before they're supported, see [`presets`].
+
+use stwo_prover::core::{
+    fields::secure_column::SECURE_EXTENSION_DEGREE,
+    fri::FriConfig,
+    pcs::{PcsConfig, TreeVec},
+};
+
+/// Size of a Blake2s digest, in bytes.
+const HASH_SIZE: usize = 32;
+/// Size of a base field element, in bytes.
+const BASE_FIELD_SIZE: usize = 4;
+/// Size of a secure field element, in bytes.
+const SECURE_FIELD_SIZE: usize = BASE_FIELD_SIZE * SECURE_EXTENSION_DEGREE;
+/// Size of the proof of work nonce, in bytes.
+const NONCE_SIZE: usize = 8;
+
+/// Estimated cost of verifying a proof.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VerifierCost {
+    /// Number of Blake2s hashes of Merkle nodes, each hashing two child digests and the values of
+    /// the columns at the node.
+    pub hash_invocations: usize,
+    /// Number of secure field operations for DEEP quotients and FRI folding.
+    pub field_ops: usize,
+    /// Size of the proof data sent to the verifier, in bytes.
+    pub calldata_size: usize,
+}
+
+impl VerifierCost {
+    /// Estimates the cost of verifying a proof committing to columns of `log_sizes`, with
+    /// `n_sampled_values` values sampled at the out-of-domain point, and a composition polynomial
+    /// of degree bound `composition_log_size`, under `config`.
+    pub fn estimate(
+        log_sizes: &TreeVec<Vec<u32>>,
+        n_sampled_values: usize,
+        composition_log_size: u32,
+        config: &PcsConfig,
+    ) -> Self {
+        let log_blowup_factor = config.fri_config.log_blowup_factor;
+        let log_last_layer_degree_bound = config.fri_config.log_last_layer_degree_bound;
+        let n_queries = config.fri_config.n_queries;
+        let mut cost = Self {
+            calldata_size: NONCE_SIZE + n_sampled_values * SECURE_FIELD_SIZE,
+            ..Self::default()
+        };
+
+        // Trace trees, followed by the composition polynomial split into its coordinates.
+        let composition = vec![composition_log_size; SECURE_EXTENSION_DEGREE];
+        for columns in log_sizes.iter().chain([&composition]) {
+            let Some(&max_log_size) = columns.iter().max() else {
+                continue;
+            };
+            cost.add_tree(
+                max_log_size + log_blowup_factor,
+                columns.len() * BASE_FIELD_SIZE,
+                n_queries,
+            );
+        }
+
+        // Each query computes the quotients of all sampled values at a pair of conjugate points.
+        cost.field_ops += n_queries * 2 * 2 * (n_sampled_values + SECURE_EXTENSION_DEGREE);
+
+        // FRI commits to the quotients, then folds them in half per layer until the degree bound
+        // of the last layer.
+        cost.add_tree(
+            composition_log_size + log_blowup_factor,
+            SECURE_FIELD_SIZE,
+            n_queries,
+        );
+        for log_degree in (log_last_layer_degree_bound + 1..composition_log_size).rev() {
+            cost.add_tree(log_degree + log_blowup_factor, SECURE_FIELD_SIZE, n_queries);
+            cost.field_ops += n_queries * 4;
+        }
+        cost.field_ops += n_queries * 4;
+        cost.calldata_size += (1 << log_last_layer_degree_bound) * SECURE_FIELD_SIZE;
+
+        cost
+    }
+
+    /// Adds a Merkle tree of depth `log_size`, opened at a pair of leaves of `leaf_size` bytes per
+    /// query.
+    fn add_tree(&mut self, log_size: u32, leaf_size: usize, n_queries: usize) {
+        let depth = log_size as usize;
+        self.hash_invocations += n_queries * (depth + 1);
+        self.calldata_size +=
+            HASH_SIZE + n_queries * (2 * leaf_size + depth.saturating_sub(1) * HASH_SIZE);
+    }
+}
+
+/// Configurations with the conjectured security of `config`, trading fewer queries for a larger
+/// blowup factor, from a blowup of 2 up to 16.
+///
+/// A larger blowup makes proving slower and lowers the largest supported trace, fewer queries make
+/// verification cheaper.
+pub fn presets(config: &PcsConfig) -> Vec<PcsConfig> {
+    let fri_config = &config.fri_config;
+    let query_bits = fri_config.log_blowup_factor as usize * fri_config.n_queries;
+
+    (1..=4)
+        .map(|log_blowup_factor| PcsConfig {
+            pow_bits: config.pow_bits,
+            fri_config: FriConfig::new(
+                fri_config.log_last_layer_degree_bound,
+                log_blowup_factor,
+                query_bits.div_ceil(log_blowup_factor as usize),
+            ),
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn log_sizes() -> TreeVec<Vec<u32>> {
+        TreeVec::new(vec![vec![16; 20], vec![16; 200], vec![16; 40]])
+    }
+
+    #[test]
+    fn cost_grows_with_queries_and_trace() {
+        let config = PcsConfig::default();
+        let cost = VerifierCost::estimate(&log_sizes(), 300, 17, &config);
+        assert!(cost.hash_invocations > 0 && cost.field_ops > 0 && cost.calldata_size > 0);
+
+        let mut more_queries = config;
+        more_queries.fri_config.n_queries += 10;
+        let expensive = VerifierCost::estimate(&log_sizes(), 300, 17, &more_queries);
+        assert!(expensive.hash_invocations > cost.hash_invocations);
+        assert!(expensive.field_ops > cost.field_ops);
+        assert!(expensive.calldata_size > cost.calldata_size);
+
+        let larger = VerifierCost::estimate(&log_sizes().map(|_| vec![20; 200]), 300, 21, &config);
+        assert!(larger.hash_invocations > cost.hash_invocations);
+    }
+
+    #[test]
+    fn presets_keep_security() {
+        let config = PcsConfig::default();
+        let bits = |config: &PcsConfig| {
+            config.fri_config.log_blowup_factor as usize * config.fri_config.n_queries
+        };
+        for preset in presets(&config) {
+            assert!(bits(&preset) >= bits(&config));
+            assert_eq!(preset.pow_bits, config.pow_bits);
+        }
+    }
+}
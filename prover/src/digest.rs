@@ -0,0 +1,117 @@
+//! Digests of the public values of an execution.
+//!
+//! They let a verifier pin the program and the output of a proven execution without keeping them
+//! around, see [`crate::verify_with_expected`].
+//!
+//! Proofs of [`crate::prove_with_final_memory`] claim the [`final_memory_digest`] of the RAM at the
+//! end of execution, bound to the committed RAM by the memory check. The next execution can be
+//! chained to it by comparing the claim to the [`initial_memory_digest`] of its initial memory.
+
+use std::collections::BTreeMap;
+
+use nexus_vm::emulator::{
+    InternalView, MemoryInitializationEntry, ProgramInfo, PublicOutputEntry, View,
+};
+use stwo_prover::core::vcs::blake2_hash::{Blake2sHash, Blake2sHasher};
+
+/// Blake2s digest of the initial program counter and the program memory.
+pub fn program_digest(program_info: &ProgramInfo) -> Blake2sHash {
+    let mut hasher = Blake2sHasher::new();
+    hasher.update(&program_info.initial_pc.to_le_bytes());
+    for entry in &program_info.program {
+        hasher.update(&entry.pc.to_le_bytes());
+        hasher.update(&entry.instruction_word.to_le_bytes());
+    }
+    hasher.finalize()
+}
+
+/// Blake2s digest of the bytes of the public output, excluding the exit code.
+pub fn output_digest(output_memory: &[PublicOutputEntry]) -> Blake2sHash {
+    let bytes: Vec<u8> = output_memory.iter().map(|entry| entry.value).collect();
+    Blake2sHasher::hash(&bytes)
+}
+
+/// Blake2s digest of the final read-write memory, see [`crate::Proof::final_memory_digest`].
+///
+/// Only non-zero bytes are hashed, in the order of their addresses, since untouched memory reads
+/// as zero. The digest is the same as the [`initial_memory_digest`] of the same memory content.
+pub fn final_memory_digest(final_memory: &[PublicOutputEntry]) -> Blake2sHash {
+    memory_digest(
+        final_memory
+            .iter()
+            .map(|entry| (entry.address, entry.value)),
+    )
+}
+
+/// Blake2s digest of the initial memory, to compare with the [`final_memory_digest`] of the
+/// execution it continues from.
+pub fn initial_memory_digest(init_memory: &[MemoryInitializationEntry]) -> Blake2sHash {
+    memory_digest(init_memory.iter().map(|entry| (entry.address, entry.value)))
+}
+
+fn memory_digest(bytes: impl Iterator<Item = (u32, u8)>) -> Blake2sHash {
+    let bytes: BTreeMap<u32, u8> = bytes.filter(|&(_, value)| value != 0).collect();
+
+    let mut hasher = Blake2sHasher::new();
+    for (address, value) in bytes {
+        hasher.update(&address.to_le_bytes());
+        hasher.update(&[value]);
+    }
+    hasher.finalize()
+}
+
+/// Blake2s digest of the public I/O of the execution of `view`: the public input, the exit code and
+/// the public output.
+///
+/// A view without memory layout, e.g. of a trace of basic blocks, has no public input and counts as
+/// exiting with 0.
+pub fn public_io_digest(view: &View) -> Blake2sHash {
+    let input = view.view_public_input().unwrap_or_default();
+    let output: Vec<u8> = view
+        .get_public_output()
+        .iter()
+        .map(|entry| entry.value)
+        .collect();
+
+    let mut hasher = Blake2sHasher::new();
+    hasher.update(&(input.len() as u32).to_le_bytes());
+    hasher.update(&input);
+    hasher.update(&view.output_exit_code().unwrap_or_default().to_le_bytes());
+    hasher.update(&output);
+    hasher.finalize()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{verify_with_expected, VerifyError};
+    use nexus_vm::{
+        emulator::InternalView,
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+
+    #[test]
+    fn verify_with_expected_digests() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let proof = crate::prove(&program_trace, &view).unwrap();
+
+        let exit_code = view.output_exit_code().unwrap_or_default();
+        let program = program_digest(view.get_program_memory());
+        let output = output_digest(view.get_public_output());
+        verify_with_expected(proof.clone(), &view, exit_code, program, output).unwrap();
+
+        let err =
+            verify_with_expected(proof.clone(), &view, exit_code + 1, program, output).unwrap_err();
+        assert!(matches!(err, VerifyError::PublicValueMismatch(_)));
+
+        let other_output = Blake2sHasher::hash(b"other output");
+        let err = verify_with_expected(proof, &view, exit_code, program, other_output).unwrap_err();
+        assert!(matches!(err, VerifyError::OutputDigestMismatch { .. }));
+    }
+}
@@ -0,0 +1,121 @@
+//! Versioned encoding of proofs, for persisting them or sending them over the network.
+//!
+//! A [`NexusProof`] binds a [`Proof`] to the digests of the proven program and its public I/O, so that
+//! a receiver can tell which execution a proof is of before verifying it. The binary encoding is
+//! postcard, starting with the format version.
+
+use nexus_vm::emulator::{InternalView, View};
+use serde::{Deserialize, Serialize};
+use stwo_prover::core::vcs::blake2_hash::Blake2sHash;
+
+use crate::{digest, error::VerifyError, machine::Proof};
+
+/// Version of the encoding of [`NexusProof`], bumped on every change to the layout of the proof.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Proof of an execution together with the digests of its public values.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NexusProof {
+    /// Must be the first field, it is decoded on its own to reject proofs of other versions.
+    pub version: u32,
+    /// See [`digest::program_digest`].
+    pub program_digest: Blake2sHash,
+    /// See [`digest::public_io_digest`].
+    pub public_io_digest: Blake2sHash,
+    pub proof: Proof,
+}
+
+impl NexusProof {
+    /// Wraps `proof` of the execution of `view`.
+    pub fn new(proof: Proof, view: &View) -> Self {
+        Self {
+            version: FORMAT_VERSION,
+            program_digest: digest::program_digest(view.get_program_memory()),
+            public_io_digest: digest::public_io_digest(view),
+            proof,
+        }
+    }
+
+    /// Compact binary encoding of the proof.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        postcard::to_stdvec(self).expect("failed to serialize proof")
+    }
+
+    /// Decodes a proof encoded by [`NexusProof::to_bytes`] of the current [`FORMAT_VERSION`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, VerifyError> {
+        let (version, _) = postcard::take_from_bytes::<u32>(bytes)
+            .map_err(|err| VerifyError::MalformedProof(err.to_string()))?;
+        check_version(version)?;
+        postcard::from_bytes(bytes).map_err(|err| VerifyError::MalformedProof(err.to_string()))
+    }
+
+    /// Verifies the proof against the execution of `view`, rejecting proofs of another program or
+    /// public I/O before checking the proof itself.
+    pub fn verify(self, view: &View) -> Result<(), VerifyError> {
+        check_version(self.version)?;
+
+        let actual = digest::program_digest(view.get_program_memory());
+        if actual != self.program_digest {
+            return Err(VerifyError::ProgramDigestMismatch {
+                expected: self.program_digest,
+                actual,
+            });
+        }
+        let actual = digest::public_io_digest(view);
+        if actual != self.public_io_digest {
+            return Err(VerifyError::PublicValueMismatch(format!(
+                "expected public I/O digest {}, got {actual}",
+                self.public_io_digest
+            )));
+        }
+        crate::verify(self.proof, view)
+    }
+}
+
+fn check_version(version: u32) -> Result<(), VerifyError> {
+    if version != FORMAT_VERSION {
+        return Err(VerifyError::ParameterMismatch(format!(
+            "proof format version {version} is not supported, expected {FORMAT_VERSION}"
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use nexus_vm::{
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+    use stwo_prover::core::vcs::blake2_hash::Blake2sHasher;
+
+    #[test]
+    fn encoding_round_trip() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let proof = NexusProof::new(crate::prove(&program_trace, &view).unwrap(), &view);
+
+        let mut bytes = proof.to_bytes();
+        let decoded = NexusProof::from_bytes(&bytes).unwrap();
+        assert_eq!(decoded.to_bytes(), bytes);
+        decoded.clone().verify(&view).unwrap();
+
+        let mut tampered = decoded;
+        tampered.public_io_digest = Blake2sHasher::hash(b"other public I/O");
+        let err = tampered.verify(&view).unwrap_err();
+        assert!(matches!(err, VerifyError::PublicValueMismatch(_)));
+
+        // The version is encoded as a varint, a single byte for small versions.
+        bytes[0] = (FORMAT_VERSION + 1) as u8;
+        let err = NexusProof::from_bytes(&bytes).unwrap_err();
+        assert!(matches!(err, VerifyError::ParameterMismatch(_)));
+
+        let err = NexusProof::from_bytes(&bytes[..0]).unwrap_err();
+        assert!(matches!(err, VerifyError::MalformedProof(_)));
+    }
+}
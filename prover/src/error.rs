@@ -0,0 +1,145 @@
+use nexus_vm::{error::TraceFileError, SyscallCode};
+use stwo_prover::core::{
+    prover::{ProvingError, VerificationError},
+    vcs::blake2_hash::Blake2sHash,
+};
+use thiserror::Error;
+
+use crate::column::Column;
+
+/// Errors related to proving a zkVM execution.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ProveError {
+    // The trace of the execution doesn't fit in the largest domain the prover supports
+    #[error(
+        "Trace of {num_steps} steps needs log size {log_size}, the maximum is {max_log_size}: \
+         run the program on a smaller input, or prove on a 64-bit host if it runs on a 32-bit one"
+    )]
+    TraceTooLarge {
+        num_steps: usize,
+        log_size: u32,
+        max_log_size: u32,
+    },
+
+    // The serialized trace couldn't be read
+    #[error("Failed to read the trace file")]
+    TraceFile(#[from] TraceFileError),
+
+    // The serialized trace is of an execution of a different program than the one requested
+    #[error("Program digest mismatch: expected {expected}, got {actual}")]
+    ProgramDigestMismatch {
+        expected: Blake2sHash,
+        actual: Blake2sHash,
+    },
+
+    // The execution uses a syscall whose effects the constraints don't cover
+    #[error("Syscall {syscall:?} at pc=0x{pc:08X} can't be proven")]
+    UnsupportedSyscall { syscall: SyscallCode, pc: u32 },
+
+    // The execution stores to read-only data, which the memory check rejects
+    #[error("Store to read-only data at 0x{address:08X}, pc=0x{pc:08X}")]
+    ReadOnlyStore { pc: u32, address: u32 },
+
+    // The execution accesses read-only and writable bytes at once, which the memory check rejects
+    #[error(
+        "Memory access at 0x{address:08X} crosses the boundary of read-only data, pc=0x{pc:08X}"
+    )]
+    ReadOnlyBoundary { pc: u32, address: u32 },
+
+    // A batch proof was requested for no executions
+    #[error("A batch proof needs at least one execution")]
+    EmptyBatch,
+
+    // The final memory doesn't fit in the program trace
+    #[error(transparent)]
+    FinalMemory(#[from] FinalMemoryError),
+
+    // A proving checkpoint couldn't be written or read
+    #[error("Failed to access the proving checkpoint")]
+    Checkpoint(#[source] std::io::Error),
+
+    // A proving checkpoint is corrupted or was written by a different machine
+    #[error("Invalid proving checkpoint: {0}")]
+    InvalidCheckpoint(String),
+
+    // Proving the trace failed in the underlying prover
+    #[error(transparent)]
+    Stwo(#[from] ProvingError),
+}
+
+/// First row of the main trace that differs from the execution it was filled from, see
+/// [`crate::trace::cross_check_trace`].
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+#[error(
+    "Trace row {row} (pc=0x{pc:08X}) diverges from the execution: {column:?} is 0x{actual:08X}, \
+     expected 0x{expected:08X}"
+)]
+pub struct TraceMismatch {
+    pub row: usize,
+    pub pc: u32,
+    pub column: Column,
+    pub expected: u32,
+    pub actual: u32,
+}
+
+/// Final memory that can't be filled into the program trace, see
+/// [`crate::trace::program_trace::ProgramTracesBuilder::fill_final_memory`].
+#[derive(Debug, Clone, Error, PartialEq, Eq)]
+pub enum FinalMemoryError {
+    #[error("Final memory of {len} bytes doesn't fit in {num_rows} rows")]
+    TooLarge { len: usize, num_rows: usize },
+    #[error("Final memory isn't sorted by address at 0x{address:08X}")]
+    Unsorted { address: u32 },
+}
+
+/// Errors related to verification of a zkVM proof.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum VerifyError {
+    // The proof could not be decoded or its structure is invalid
+    #[error("Malformed proof: {0}")]
+    MalformedProof(String),
+
+    // The proof was generated with parameters the verifier doesn't accept
+    #[error("Proof parameter mismatch: {0}")]
+    ParameterMismatch(String),
+
+    // Opening of the committed traces failed: Merkle decommitment, FRI, OODS or proof-of-work check
+    #[error("Commitment verification failed")]
+    CommitmentVerification(#[source] VerificationError),
+
+    // Public values bound by the proof differ from the ones supplied to the verifier
+    #[error("Public value mismatch: {0}")]
+    PublicValueMismatch(String),
+
+    // Commitment to the program and its public I/O differs from the one in the proof
+    #[error("Program digest mismatch: expected {expected}, got {actual}")]
+    ProgramDigestMismatch {
+        expected: Blake2sHash,
+        actual: Blake2sHash,
+    },
+
+    // Digest of the public output differs from the one expected by the verifier
+    #[error("Output digest mismatch: expected {expected}, got {actual}")]
+    OutputDigestMismatch {
+        expected: Blake2sHash,
+        actual: Blake2sHash,
+    },
+
+    // Digest of the final memory supplied to the verifier differs from the one claimed by the proof
+    #[error("Final memory digest mismatch: expected {expected}, got {actual}")]
+    FinalMemoryDigestMismatch {
+        expected: Blake2sHash,
+        actual: Blake2sHash,
+    },
+}
+
+impl From<VerificationError> for VerifyError {
+    fn from(err: VerificationError) -> Self {
+        match err {
+            VerificationError::InvalidStructure(reason) => Self::MalformedProof(reason),
+            err => Self::CommitmentVerification(err),
+        }
+    }
+}
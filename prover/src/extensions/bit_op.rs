@@ -116,6 +116,7 @@ impl BuiltInExtension for BitOpMultiplicity {
     type Eval = BitOpMultiplicityEval;
 
     fn generate_preprocessed_trace(
+        &self,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
         let base_cols = Self::preprocessed_base_columns();
         let domain = CanonicCoset::new(BitOpMultiplicityEval::LOG_SIZE).circle_domain();
@@ -125,7 +126,7 @@ impl BuiltInExtension for BitOpMultiplicity {
             .collect()
     }
 
-    fn preprocessed_trace_sizes() -> Vec<u32> {
+    fn preprocessed_trace_sizes(&self) -> Vec<u32> {
         // preprocessed column for each of [and, or, xor] with 2 input lookups
         std::iter::repeat(BitOpMultiplicityEval::LOG_SIZE)
             .take(5)
@@ -147,6 +148,7 @@ impl BuiltInExtension for BitOpMultiplicity {
     }
 
     fn generate_interaction_trace(
+        &self,
         side_note: &SideNote,
         lookup_elements: &AllLookupElements,
     ) -> (
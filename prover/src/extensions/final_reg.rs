@@ -24,6 +24,7 @@ use stwo_prover::{
 use crate::{
     chips::memory_check::register_mem_check::RegisterCheckLookupElements,
     components::AllLookupElements,
+    machine::PublicRegisters,
     trace::{sidenote::SideNote, utils::IntoBaseFields},
 };
 
@@ -45,26 +46,62 @@ impl RegisterIdx {
     }
 }
 
+/// Public register values of a given stage, `start` or `end`.
+#[derive(Debug, Clone)]
+pub struct PublicRegisterValue {
+    stage: &'static str,
+    byte: usize,
+}
+
+impl PublicRegisterValue {
+    pub const fn new(stage: &'static str, byte: usize) -> Self {
+        Self { stage, byte }
+    }
+
+    pub fn id(&self) -> PreProcessedColumnId {
+        PreProcessedColumnId {
+            id: format!(
+                "preprocessed_{}_register_value_{}_{}",
+                self.stage,
+                self.byte,
+                FinalRegEval::LOG_SIZE
+            ),
+        }
+    }
+}
+
 /// A component for the final register memory state
+///
+/// If constructed with [`PublicRegisters`], the register file at the start and the end of execution is
+/// committed to in the preprocessed trace: the initial memory state is taken from it and the final one
+/// is constrained to match it.
 #[derive(Debug, Clone)]
 pub struct FinalReg {
-    _private: (),
+    public: Option<PublicRegisters>,
 }
 
 impl FinalReg {
     pub(super) const fn new() -> Self {
-        Self { _private: () }
+        Self { public: None }
+    }
+
+    pub(super) const fn with_public_registers(public: PublicRegisters) -> Self {
+        Self {
+            public: Some(public),
+        }
     }
 }
 
 pub(crate) struct FinalRegEval {
     lookup_elements: RegisterCheckLookupElements,
+    public: bool,
 }
 
 impl Default for FinalRegEval {
     fn default() -> Self {
         Self {
             lookup_elements: RegisterCheckLookupElements::dummy(),
+            public: false,
         }
     }
 }
@@ -95,6 +132,17 @@ impl FrameworkEval for FinalRegEval {
         // Add initial register memory state
         let mut tuple: [E::F; Self::TUPLE_SIZE] = std::array::from_fn(|_| E::F::zero());
         tuple[0] = reg_idx.clone();
+        if self.public {
+            for i in 0..WORD_SIZE {
+                tuple[1 + WORD_SIZE + i] =
+                    eval.get_preprocessed_column(PublicRegisterValue::new("start", i).id());
+            }
+            for (i, value) in final_value.iter().enumerate() {
+                let public_value =
+                    eval.get_preprocessed_column(PublicRegisterValue::new("end", i).id());
+                eval.add_constraint(value.clone() - public_value);
+            }
+        }
         let numerator = E::F::one();
 
         eval.add_to_relation(RelationEntry::new(
@@ -129,6 +177,7 @@ impl FrameworkEvalExt for FinalRegEval {
         let register_check_lookup_elements: &RegisterCheckLookupElements = lookup_elements.as_ref();
         Self {
             lookup_elements: register_check_lookup_elements.clone(),
+            public: false,
         }
     }
 }
@@ -137,8 +186,9 @@ impl BuiltInExtension for FinalReg {
     type Eval = FinalRegEval;
 
     fn generate_preprocessed_trace(
+        &self,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
-        let base_cols = Self::preprocessed_base_columns();
+        let base_cols = self.preprocessed_base_columns();
         let domain = CanonicCoset::new(FinalRegEval::LOG_SIZE).circle_domain();
         base_cols
             .into_iter()
@@ -146,8 +196,15 @@ impl BuiltInExtension for FinalReg {
             .collect()
     }
 
-    fn preprocessed_trace_sizes() -> Vec<u32> {
-        vec![FinalRegEval::LOG_SIZE]
+    fn preprocessed_trace_sizes(&self) -> Vec<u32> {
+        vec![FinalRegEval::LOG_SIZE; self.preprocessed_base_columns().len()]
+    }
+
+    fn eval(&self, lookup_elements: &AllLookupElements) -> FinalRegEval {
+        FinalRegEval {
+            public: self.public.is_some(),
+            ..FinalRegEval::new(lookup_elements)
+        }
     }
 
     /// The first four columns represent the final values, the following four columns represent the final timestamps.
@@ -165,6 +222,7 @@ impl BuiltInExtension for FinalReg {
     }
 
     fn generate_interaction_trace(
+        &self,
         side_note: &SideNote,
         lookup_elements: &AllLookupElements,
     ) -> (
@@ -174,7 +232,8 @@ impl BuiltInExtension for FinalReg {
         let lookup_element: &RegisterCheckLookupElements = lookup_elements.as_ref();
 
         let mut logup_trace_gen = LogupTraceGenerator::new(FinalRegEval::LOG_SIZE);
-        let row_idx = &Self::preprocessed_base_columns()[0];
+        let preprocessed_cols = self.preprocessed_base_columns();
+        let row_idx = &preprocessed_cols[0];
         let base_cols = Self::base_columns(side_note);
 
         // Adding the initial register memory state
@@ -184,6 +243,12 @@ impl BuiltInExtension for FinalReg {
             let mut tuple: [PackedM31; FinalRegEval::TUPLE_SIZE] =
                 [BaseField::zero().into(); FinalRegEval::TUPLE_SIZE]; // reg_idx, cur_timestamp, cur_value
             tuple[0] = row_idx; // Use row_idx as register index
+            if self.public.is_some() {
+                // The start values follow the register index in the preprocessed trace.
+                for i in 0..WORD_SIZE {
+                    tuple[1 + WORD_SIZE + i] = preprocessed_cols[1 + i].data[vec_row];
+                }
+            }
             let denom = lookup_element.combine(tuple.as_slice());
             let numerator = PackedBaseField::broadcast(BaseField::one());
             logup_col_gen.write_frac(vec_row, numerator.into(), denom);
@@ -210,9 +275,22 @@ impl BuiltInExtension for FinalReg {
 }
 
 impl FinalReg {
-    fn preprocessed_base_columns() -> Vec<BaseColumn> {
+    /// The register index, followed by the bytes of public start and end values if enabled.
+    ///
+    /// The order must match the one in which [`FinalRegEval`] requests the columns.
+    fn preprocessed_base_columns(&self) -> Vec<BaseColumn> {
         let reg_idx = BaseColumn::from_iter((0..32).map(BaseField::from));
-        vec![reg_idx]
+        let mut cols = vec![reg_idx];
+        if let Some(public) = &self.public {
+            for values in [&public.start, &public.end] {
+                for i in 0..WORD_SIZE {
+                    cols.push(BaseColumn::from_iter(
+                        values.iter().map(|value| value.into_base_fields()[i]),
+                    ));
+                }
+            }
+        }
+        cols
     }
     fn base_columns(side_note: &SideNote) -> Vec<BaseColumn> {
         let mut base_cols: Vec<BaseColumn> = vec![];
@@ -28,7 +28,7 @@ use stwo_prover::{
     },
 };
 
-use crate::{components::AllLookupElements, trace::sidenote::SideNote};
+use crate::{backend::ProverBackend, components::AllLookupElements, trace::sidenote::SideNote};
 
 mod bit_op;
 mod final_reg;
@@ -52,6 +52,7 @@ trait BuiltInExtension {
     type Eval: FrameworkEvalExt;
 
     fn generate_preprocessed_trace(
+        &self,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>;
 
     fn generate_original_trace(
@@ -59,6 +60,7 @@ trait BuiltInExtension {
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>;
 
     fn generate_interaction_trace(
+        &self,
         side_note: &SideNote,
         lookup_elements: &AllLookupElements,
     ) -> (
@@ -66,15 +68,21 @@ trait BuiltInExtension {
         SecureField,
     );
 
-    fn to_component_prover(
+    /// Returns the evaluator of the component, components with parameters affecting constraints
+    /// should override it.
+    fn eval(&self, lookup_elements: &AllLookupElements) -> Self::Eval {
+        Self::Eval::new(lookup_elements)
+    }
+
+    fn to_component_prover<B: ProverBackend>(
         &self,
         tree_span_provider: &mut TraceLocationAllocator,
         lookup_elements: &AllLookupElements,
         claimed_sum: SecureField,
-    ) -> Box<dyn ComponentProver<SimdBackend>> {
-        Box::new(FrameworkComponent::new(
+    ) -> Box<dyn ComponentProver<B>> {
+        B::component_prover(FrameworkComponent::new(
             tree_span_provider,
-            Self::Eval::new(lookup_elements),
+            self.eval(lookup_elements),
             claimed_sum,
         ))
     }
@@ -87,7 +95,7 @@ trait BuiltInExtension {
     ) -> Box<dyn Component> {
         Box::new(FrameworkComponent::new(
             tree_span_provider,
-            Self::Eval::new(lookup_elements),
+            self.eval(lookup_elements),
             claimed_sum,
         ))
     }
@@ -101,7 +109,7 @@ trait BuiltInExtension {
     }
 
     /// Returns the log_sizes of each preprocessed columns
-    fn preprocessed_trace_sizes() -> Vec<u32>;
+    fn preprocessed_trace_sizes(&self) -> Vec<u32>;
 }
 
 extension_dispatch! {
@@ -120,6 +128,11 @@ impl ExtensionComponent {
     pub(super) const fn final_reg() -> Self {
         Self::FinalReg(FinalReg::new())
     }
+    pub(super) const fn final_reg_with_public_registers(
+        public_registers: crate::machine::PublicRegisters,
+    ) -> Self {
+        Self::FinalReg(FinalReg::with_public_registers(public_registers))
+    }
     pub(super) const fn multiplicity8() -> Self {
         Self::Multiplicity8(Multiplicity8::new())
     }
@@ -168,7 +181,7 @@ macro_rules! extension_dispatch {
                 &self,
             ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
                 match self {
-                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::generate_preprocessed_trace(), )*
+                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::generate_preprocessed_trace(inner), )*
                 }
             }
 
@@ -190,16 +203,16 @@ macro_rules! extension_dispatch {
                 SecureField,
             ) {
                 match self {
-                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::generate_interaction_trace(side_note, lookup_elements), )*
+                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::generate_interaction_trace(inner, side_note, lookup_elements), )*
                 }
             }
 
-            pub(crate) fn to_component_prover(
+            pub(crate) fn to_component_prover<B: ProverBackend>(
                 &self,
                 tree_span_provider: &mut TraceLocationAllocator,
                 lookup_elements: &AllLookupElements,
                 claimed_sum: SecureField,
-            ) -> Box<dyn ComponentProver<SimdBackend>> {
+            ) -> Box<dyn ComponentProver<B>> {
                 match self {
                     $( $_enum::$name(inner) => <$name as BuiltInExtension>::to_component_prover(inner, tree_span_provider, lookup_elements, claimed_sum), )*
                 }
@@ -224,7 +237,22 @@ macro_rules! extension_dispatch {
 
             pub(crate) fn preprocessed_trace_sizes(&self) -> Vec<u32> {
                 match self {
-                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::preprocessed_trace_sizes(), )*
+                    $( $_enum::$name(inner) => <$name as BuiltInExtension>::preprocessed_trace_sizes(inner), )*
+                }
+            }
+
+            /// Name of the variant, e.g. to record the extensions of a proof in a checkpoint.
+            pub(crate) fn name(&self) -> &'static str {
+                match self {
+                    $( $_enum::$name(_) => stringify!($name), )*
+                }
+            }
+
+            /// Extension of the variant named `name`, the inverse of [`Self::name`] up to public registers.
+            pub(crate) fn from_name(name: &str) -> Option<Self> {
+                match name {
+                    $( stringify!($name) => Some($_enum::$name($name::new())), )*
+                    _ => None,
                 }
             }
         }
@@ -128,6 +128,7 @@ where
     type Eval = MultiplicityEval<LEN, L>;
 
     fn generate_preprocessed_trace(
+        &self,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
         let base_cols = Self::preprocessed_base_columns();
         let domain = CanonicCoset::new(Self::Eval::LOG_SIZE).circle_domain();
@@ -137,7 +138,7 @@ where
             .collect()
     }
 
-    fn preprocessed_trace_sizes() -> Vec<u32> {
+    fn preprocessed_trace_sizes(&self) -> Vec<u32> {
         vec![Self::Eval::LOG_SIZE]
     }
 
@@ -156,6 +157,7 @@ where
     }
 
     fn generate_interaction_trace(
+        &self,
         side_note: &SideNote,
         lookup_elements: &AllLookupElements,
     ) -> (
@@ -109,6 +109,7 @@ impl BuiltInExtension for Multiplicity8 {
     type Eval = MultiplicityEval8;
 
     fn generate_preprocessed_trace(
+        &self,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
         let base_cols = Self::preprocessed_base_columns();
         let domain = CanonicCoset::new(Self::Eval::LOG_SIZE).circle_domain();
@@ -118,7 +119,7 @@ impl BuiltInExtension for Multiplicity8 {
             .collect()
     }
 
-    fn preprocessed_trace_sizes() -> Vec<u32> {
+    fn preprocessed_trace_sizes(&self) -> Vec<u32> {
         vec![Self::Eval::LOG_SIZE]
     }
 
@@ -137,6 +138,7 @@ impl BuiltInExtension for Multiplicity8 {
     }
 
     fn generate_interaction_trace(
+        &self,
         side_note: &SideNote,
         lookup_elements: &AllLookupElements,
     ) -> (
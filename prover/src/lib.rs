@@ -1,32 +1,93 @@
+pub mod backend;
 pub mod chips;
 pub mod components;
 pub mod extensions;
 pub mod trace;
 
+#[cfg(not(target_family = "wasm"))]
+pub(crate) mod checkpoint;
 pub mod column;
+pub mod cost;
+pub mod digest;
+pub mod encoding;
+pub mod error;
+pub mod thread_pool;
 pub mod traits;
 pub mod virtual_column;
 
 pub mod machine;
 
-#[cfg(test)]
-mod test_utils;
+#[cfg(any(test, feature = "testing"))]
+pub mod test_utils;
 
 use nexus_vm::emulator::InternalView;
 pub(crate) use nexus_vm::WORD_SIZE;
 
-pub use machine::Proof;
+pub use cost::VerifierCost;
+pub use encoding::NexusProof;
+pub use error::{FinalMemoryError, ProveError, TraceMismatch, VerifyError};
+pub use machine::{BatchProof, BatchStatement, ComponentInfo, Proof, ProofStats, PublicRegisters};
 
 pub use stwo_prover::core::prover::{ProvingError, VerificationError};
+pub use stwo_prover::core::vcs::blake2_hash::Blake2sHash;
 
 pub fn prove(
     trace: &impl nexus_vm::trace::Trace,
     view: &nexus_vm::emulator::View,
-) -> Result<Proof, ProvingError> {
+) -> Result<Proof, ProveError> {
     machine::Machine::<machine::BaseComponent>::prove(trace, view)
 }
 
-pub fn verify(proof: Proof, view: &nexus_vm::emulator::View) -> Result<(), VerificationError> {
+/// Proves like [`prove`], exposing the register file at the start and the end of execution
+/// in [`Proof::public_registers`].
+pub fn prove_with_public_registers(
+    trace: &impl nexus_vm::trace::Trace,
+    view: &nexus_vm::emulator::View,
+) -> Result<Proof, ProveError> {
+    machine::Machine::<machine::BaseComponent>::prove_with_public_registers(&[], trace, view)
+}
+
+/// Proves like [`prove`], claiming the digest of the RAM at the end of execution in
+/// [`Proof::final_memory_digest`]. The final memory is returned for [`verify_with_final_memory`].
+pub fn prove_with_final_memory(
+    trace: &impl nexus_vm::trace::Trace,
+    view: &nexus_vm::emulator::View,
+) -> Result<(Proof, Vec<nexus_vm::emulator::PublicOutputEntry>), ProveError> {
+    machine::Machine::<machine::BaseComponent>::prove_with_final_memory(&[], trace, view)
+}
+
+/// Proves several independent executions in a single proof, see
+/// [`machine::Machine::prove_batch`].
+pub fn prove_batch<T: nexus_vm::trace::Trace>(
+    executions: &[(&T, &nexus_vm::emulator::View)],
+) -> Result<BatchProof, ProveError> {
+    machine::Machine::<machine::BaseComponent>::prove_batch(&[], executions)
+}
+
+/// Verifies a proof produced by [`prove_batch`] of the executions of `views`, in the same order.
+pub fn verify_batch(
+    proof: BatchProof,
+    views: &[&nexus_vm::emulator::View],
+) -> Result<(), VerifyError> {
+    let associated_data: Vec<Vec<u8>> = views
+        .iter()
+        .map(|view| view.view_associated_data().unwrap_or_default())
+        .collect();
+    let statements: Vec<BatchStatement<'_>> = views
+        .iter()
+        .zip(&associated_data)
+        .map(|(view, ad)| BatchStatement {
+            program_info: view.get_program_memory(),
+            ad,
+            init_memory: view.get_initial_memory(),
+            exit_code: view.get_exit_code(),
+            output_memory: view.get_public_output(),
+        })
+        .collect();
+    machine::Machine::<machine::BaseComponent>::verify_batch(&[], proof, &statements)
+}
+
+pub fn verify(proof: Proof, view: &nexus_vm::emulator::View) -> Result<(), VerifyError> {
     machine::Machine::<machine::BaseComponent>::verify(
         proof,
         view.get_program_memory(),
@@ -36,3 +97,65 @@ pub fn verify(proof: Proof, view: &nexus_vm::emulator::View) -> Result<(), Verif
         view.get_public_output(),
     )
 }
+
+/// Verifies a proof produced by [`prove_with_final_memory`] like [`verify`], checking its
+/// [`Proof::final_memory_digest`] against `final_memory`.
+pub fn verify_with_final_memory(
+    proof: Proof,
+    view: &nexus_vm::emulator::View,
+    final_memory: &[nexus_vm::emulator::PublicOutputEntry],
+) -> Result<(), VerifyError> {
+    let ad = view.view_associated_data().unwrap_or_default();
+    let statement = BatchStatement {
+        program_info: view.get_program_memory(),
+        ad: &ad,
+        init_memory: view.get_initial_memory(),
+        exit_code: view.get_exit_code(),
+        output_memory: view.get_public_output(),
+    };
+    machine::Machine::<machine::BaseComponent>::verify_with_final_memory(
+        &[],
+        proof,
+        &statement,
+        final_memory,
+    )
+}
+
+/// Verifies `proof` like [`verify`], then checks the public values of `view` against the ones
+/// the caller expects: the exit code, and the [`digest::program_digest`] and
+/// [`digest::output_digest`].
+///
+/// A view without exit code, e.g. of a trace of basic blocks, counts as exiting with 0.
+pub fn verify_with_expected(
+    proof: Proof,
+    view: &nexus_vm::emulator::View,
+    exit_code: u32,
+    program_digest: Blake2sHash,
+    output_digest: Blake2sHash,
+) -> Result<(), VerifyError> {
+    verify(proof, view)?;
+
+    let actual_exit_code = view.output_exit_code().unwrap_or_default();
+    if actual_exit_code != exit_code {
+        return Err(VerifyError::PublicValueMismatch(format!(
+            "expected exit code {exit_code}, got {actual_exit_code}"
+        )));
+    }
+
+    let actual = digest::program_digest(view.get_program_memory());
+    if actual != program_digest {
+        return Err(VerifyError::ProgramDigestMismatch {
+            expected: program_digest,
+            actual,
+        });
+    }
+
+    let actual = digest::output_digest(view.get_public_output());
+    if actual != output_digest {
+        return Err(VerifyError::OutputDigestMismatch {
+            expected: output_digest,
+            actual,
+        });
+    }
+    Ok(())
+}
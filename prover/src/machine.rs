@@ -1,4 +1,6 @@
 use std::marker::PhantomData;
+#[cfg(not(target_family = "wasm"))]
+use std::{borrow::Cow, path::Path};
 
 use num_traits::Zero;
 use stwo_prover::{
@@ -7,40 +9,63 @@ use stwo_prover::{
         air::{Component, ComponentProver},
         backend::simd::SimdBackend,
         channel::{Blake2sChannel, Channel},
-        fields::qm31::SecureField,
+        circle::M31_CIRCLE_LOG_ORDER,
+        fields::{m31::BaseField, qm31::SecureField},
         pcs::{CommitmentSchemeProver, CommitmentSchemeVerifier, PcsConfig, TreeVec},
-        poly::circle::{CanonicCoset, PolyOps},
-        prover::{prove, verify, ProvingError, StarkProof, VerificationError},
-        vcs::blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher},
+        poly::{
+            circle::{CanonicCoset, PolyOps},
+            twiddles::TwiddleTree,
+        },
+        prover::{prove, verify, StarkProof},
+        vcs::{
+            blake2_hash::Blake2sHash,
+            blake2_merkle::{Blake2sMerkleChannel, Blake2sMerkleHasher},
+        },
     },
 };
 
 use super::trace::eval::{INTERACTION_TRACE_IDX, ORIGINAL_TRACE_IDX, PREPROCESSED_TRACE_IDX};
 use super::trace::{
-    program::iter_program_steps, program_trace::ProgramTracesBuilder, sidenote::SideNote,
-    PreprocessedTraces, TracesBuilder,
+    cross_check_trace,
+    program::iter_program_steps,
+    program_trace::{ProgramTraces, ProgramTracesBuilder},
+    sidenote::SideNote,
+    FinalizedTraces, PreprocessedTraces, TracesBuilder,
 };
+use nexus_common::{constants::NUM_REGISTERS, cpu::Registers};
+#[cfg(not(target_family = "wasm"))]
+use nexus_vm::trace::read_trace_file;
 use nexus_vm::{
     emulator::{InternalView, MemoryInitializationEntry, ProgramInfo, PublicOutputEntry, View},
+    riscv::Register,
     trace::Trace,
 };
 
-use super::components::{MachineComponent, MachineEval, LOG_CONSTRAINT_DEGREE};
+use super::components::{MachineComponent, MachineEval};
 use super::traits::MachineChip;
 use crate::{
+    backend::ProverBackend,
     chips::{
         AddChip, AuipcChip, BeqChip, BgeChip, BgeuChip, BitOpChip, BltChip, BltuChip, BneChip,
-        CpuChip, DecodingCheckChip, JalChip, JalrChip, LoadStoreChip, LuiChip, ProgramMemCheckChip,
-        RangeCheckChip, RegisterMemCheckChip, SllChip, SltChip, SltuChip, SraChip, SrlChip,
-        SubChip, SyscallChip, TimestampChip,
+        CpuChip, DecodingCheckChip, DivChip, JalChip, JalrChip, LoadStoreChip, LuiChip, MulChip,
+        MulhChip, ProgramMemCheckChip, RangeCheckChip, RegisterMemCheckChip, RemChip, SllChip,
+        SltChip, SltuChip, SraChip, SrlChip, SubChip, SyscallChip, TimestampChip,
     },
-    column::{PreprocessedColumn, ProgramColumn},
+    column::{Column, PreprocessedColumn, ProgramColumn},
     components::{self, AllLookupElements},
+    cost::VerifierCost,
+    digest::final_memory_digest,
+    error::{ProveError, TraceMismatch, VerifyError},
     extensions::ExtensionComponent,
     traits::generate_interaction_trace,
 };
+#[cfg(not(target_family = "wasm"))]
+use crate::{
+    checkpoint::{self, TracesCheckpoint},
+    digest::program_digest,
+};
 use serde::{Deserialize, Serialize};
-/// Base component tuple for constraining virtual machine execution based on RV32I ISA.
+/// Base component tuple for constraining virtual machine execution based on RV32IM ISA.
 pub type BaseComponent = (
     CpuChip,
     DecodingCheckChip,
@@ -62,6 +87,10 @@ pub type BaseComponent = (
     SllChip,
     SrlChip,
     SraChip,
+    MulChip,
+    MulhChip,
+    DivChip,
+    RemChip,
     LoadStoreChip,
     SyscallChip,
     ProgramMemCheckChip,
@@ -82,11 +111,26 @@ const BASE_EXTENSIONS: &[ExtensionComponent] = &[
     ExtensionComponent::multiplicity256(),
 ];
 
+/// Register file at the start and at the end of the proven execution.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PublicRegisters {
+    pub start: [u32; NUM_REGISTERS],
+    pub end: [u32; NUM_REGISTERS],
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct Proof {
     pub stark_proof: StarkProof<Blake2sMerkleHasher>,
     pub claimed_sum: Vec<SecureField>,
     pub log_size: u32,
+    /// Register file exposed as public values, only present in proofs produced by
+    /// [`Machine::prove_with_public_registers`].
+    #[serde(default)]
+    pub public_registers: Option<PublicRegisters>,
+    /// [`crate::digest::final_memory_digest`] of the RAM at the end of execution, only present in
+    /// proofs produced by [`Machine::prove_with_final_memory`].
+    #[serde(default)]
+    pub final_memory_digest: Option<Blake2sHash>,
 }
 
 impl Proof {
@@ -96,26 +140,167 @@ impl Proof {
             stark_proof,
             claimed_sum,
             log_size,
+            public_registers,
+            final_memory_digest,
         } = self;
         stark_proof.size_estimate()
             + claimed_sum.iter().map(std::mem::size_of_val).sum::<usize>()
             + std::mem::size_of_val(log_size)
+            + std::mem::size_of_val(public_registers)
+            + std::mem::size_of_val(final_memory_digest)
+    }
+
+    /// Reports the size and shape of the proof, e.g. for size regression tests.
+    pub fn stats(&self) -> ProofStats {
+        let serialized_size = postcard::to_stdvec(self)
+            .expect("proof serialization cannot fail")
+            .len();
+        let fri_proof = &self.stark_proof.fri_proof;
+
+        ProofStats {
+            serialized_size,
+            num_commitment_trees: self.stark_proof.commitments.len(),
+            // The first layer is followed by the inner layers, FRI ends with the last layer
+            // polynomial.
+            num_fri_layers: 1 + fri_proof.inner_layers.len(),
+            num_queries: PcsConfig::default().fri_config.n_queries,
+        }
+    }
+}
+
+/// Size and shape of a [`Proof`], see [`Proof::stats`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProofStats {
+    /// Size of the proof serialized with postcard, in bytes.
+    pub serialized_size: usize,
+    /// Number of Merkle trees committed to: preprocessed, main and interaction traces, and the
+    /// composition polynomial.
+    pub num_commitment_trees: usize,
+    /// Number of FRI layers with decommitments.
+    pub num_fri_layers: usize,
+    /// Number of FRI queries, the proof is always generated with the default configuration.
+    pub num_queries: usize,
+}
+
+/// Layout of the main component of a machine as the verifier sees it, see [`Machine::component_info`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ComponentInfo {
+    /// Row offsets each column is sampled at, relative to the row constrained, by commitment tree:
+    /// preprocessed, main and interaction trace. Preprocessed columns are only sampled at the row itself.
+    pub mask_offsets: Vec<Vec<Vec<isize>>>,
+    /// Log2 of the constraint degree bound, see [`MachineChip::log_constraint_degree`].
+    pub log_constraint_degree: u32,
+}
+
+impl ComponentInfo {
+    /// Number of columns in each commitment tree.
+    pub fn n_columns(&self) -> Vec<usize> {
+        self.mask_offsets.iter().map(Vec::len).collect()
+    }
+
+    /// Number of values sampled at the out-of-domain point.
+    pub fn n_sampled_values(&self) -> usize {
+        self.mask_offsets.iter().flatten().map(Vec::len).sum()
+    }
+}
+
+/// Proof of several independent executions, see [`Machine::prove_batch`].
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchProof {
+    pub stark_proof: StarkProof<Blake2sMerkleHasher>,
+    /// Claimed logup sums of each execution, the sums of every execution add up to zero.
+    pub claimed_sums: Vec<Vec<SecureField>>,
+    /// Log size of the traces of each execution.
+    pub log_sizes: Vec<u32>,
+}
+
+/// Public inputs of one execution of a batch, see [`Machine::verify_batch`].
+#[derive(Clone, Copy, Debug)]
+pub struct BatchStatement<'a> {
+    pub program_info: &'a ProgramInfo,
+    pub ad: &'a [u8],
+    pub init_memory: &'a [MemoryInitializationEntry],
+    pub exit_code: &'a [PublicOutputEntry],
+    pub output_memory: &'a [PublicOutputEntry],
+}
+
+/// Filled traces of an execution, the result of [`Machine::build_traces`].
+pub struct MachineTraces {
+    log_size: u32,
+    log_constraint_degree: u32,
+    config: PcsConfig,
+    associated_data: Vec<u8>,
+    preprocessed_trace: PreprocessedTraces,
+    finalized_trace: FinalizedTraces,
+    finalized_program_trace: ProgramTraces,
+    side_note: SideNote,
+    extensions: Vec<ExtensionComponent>,
+    public_registers: Option<PublicRegisters>,
+    final_memory_digest: Option<Blake2sHash>,
+}
+
+impl MachineTraces {
+    pub fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    /// Final value of every RAM byte under the memory check, sorted by address: the final memory
+    /// that [`Proof::final_memory_digest`] is verified against.
+    pub fn final_memory(&self) -> Vec<PublicOutputEntry> {
+        self.side_note.rw_mem_check.final_memory()
+    }
+
+    /// Precomputes twiddles for committing to the traces, see [`Machine::commit`].
+    pub fn precompute_twiddles(&self) -> TwiddleTree<SimdBackend> {
+        self.precompute_twiddles_for()
     }
+
+    /// Same as [`Self::precompute_twiddles`], for committing on backend `B`.
+    pub fn precompute_twiddles_for<B: ProverBackend>(&self) -> TwiddleTree<B> {
+        B::precompute_twiddles(
+            CanonicCoset::new(
+                self.log_size
+                    + self.log_constraint_degree
+                    + self.config.fri_config.log_blowup_factor,
+            )
+            .circle_domain()
+            .half_coset,
+        )
+    }
+}
+
+/// Traces committed to by [`Machine::commit`], ready to be proven with [`Machine::prove_committed`].
+pub struct MachineCommitment<'a, B: ProverBackend = SimdBackend> {
+    log_size: u32,
+    commitment_scheme: CommitmentSchemeProver<'a, B, Blake2sMerkleChannel>,
+    prover_channel: Blake2sChannel,
+    lookup_elements: AllLookupElements,
+    claimed_sum: Vec<SecureField>,
+    extensions: Vec<ExtensionComponent>,
+    public_registers: Option<PublicRegisters>,
+    final_memory_digest: Option<Blake2sHash>,
 }
 
 /// Main (empty) struct implementing proving functionality of zkVM.
 ///
-/// The generic parameter determines which chips are enabled. The default is [`BaseComponent`] for RV32I ISA.
+/// The generic parameter determines which chips are enabled. The default is [`BaseComponent`] for RV32IM ISA.
 /// This functionality mainly exists for testing and removing a component **does not** remove columns it uses in the AIR.
 ///
 /// Note that the order of chips affects correctness, e.g. if columns used by a component require additional lookups,
 /// then it should be positioned in the front.
+///
+/// Proving runs in three stages, [`Machine::build_traces`], [`Machine::commit`] and [`Machine::prove_committed`],
+/// which can be called separately to schedule them independently, e.g. on different thread pools.
+///
+/// Proving is deterministic: the prover has no internal randomness and its channel is only seeded with the associated
+/// data of the view, so the same trace and view always produce the same serialized proof, regardless of the platform
+/// or the number of threads. Tests may rely on comparing proofs byte-for-byte.
 pub struct Machine<C = BaseComponent> {
     _phantom_data: PhantomData<C>,
 }
 
-impl<C: MachineChip + Sync> Machine<C> {
-    pub fn prove(trace: &impl Trace, view: &View) -> Result<Proof, ProvingError> {
+impl<C: MachineChip + Sync + 'static> Machine<C> {
+    pub fn prove(trace: &impl Trace, view: &View) -> Result<Proof, ProveError> {
         Self::prove_with_extensions(&[], trace, view)
     }
 
@@ -123,48 +308,261 @@ impl<C: MachineChip + Sync> Machine<C> {
         extensions: &[ExtensionComponent],
         trace: &impl Trace,
         view: &View,
-    ) -> Result<Proof, ProvingError> {
-        let num_steps = trace.get_num_steps();
-        let program_len = view.get_program_memory().program.len();
-        let tracked_ram_size = view.view_tracked_ram_size();
+    ) -> Result<Proof, ProveError> {
+        Self::prove_impl::<SimdBackend>(extensions, trace, view, false)
+    }
 
-        let log_size = Self::max_log_size(&[num_steps, program_len, tracked_ram_size])
-            .max(PreprocessedTraces::MIN_LOG_SIZE);
+    /// Same as [`Self::prove_with_extensions`], committing and proving on backend `B`, e.g. [`CpuBackend`] on
+    /// targets where the SIMD backend is slow. Traces are generated on the SIMD backend regardless, see
+    /// [`crate::backend`].
+    ///
+    /// [`CpuBackend`]: stwo_prover::core::backend::CpuBackend
+    pub fn prove_with_backend<B: ProverBackend>(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+    ) -> Result<Proof, ProveError> {
+        Self::prove_impl::<B>(extensions, trace, view, false)
+    }
 
-        let extensions_iter = BASE_EXTENSIONS.iter().chain(extensions);
+    /// Same as [`Self::prove_with_extensions`], additionally exposes the register file at the start and
+    /// the end of execution in [`Proof::public_registers`].
+    pub fn prove_with_public_registers(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+    ) -> Result<Proof, ProveError> {
+        Self::prove_impl::<SimdBackend>(extensions, trace, view, true)
+    }
 
-        let config = PcsConfig::default();
-        // Precompute twiddles.
-        let twiddles = SimdBackend::precompute_twiddles(
-            CanonicCoset::new(
-                log_size + LOG_CONSTRAINT_DEGREE + config.fri_config.log_blowup_factor,
-            )
-            .circle_domain()
-            .half_coset,
-        );
+    /// Same as [`Self::prove_with_extensions`], additionally claims the digest of the RAM at the end of
+    /// execution in [`Proof::final_memory_digest`].
+    ///
+    /// Returns the final memory along with the proof, the verifier needs it to check the claim, see
+    /// [`Self::verify_with_final_memory`].
+    pub fn prove_with_final_memory(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+    ) -> Result<(Proof, Vec<PublicOutputEntry>), ProveError> {
+        let traces = Self::build_traces_with_final_memory(extensions, trace, view, false, true)?;
+        let final_memory = traces.final_memory();
+        let twiddles = traces.precompute_twiddles();
+        let proof = Self::prove_committed(Self::commit(traces, &twiddles))?;
+        Ok((proof, final_memory))
+    }
 
-        // Setup protocol.
-        let prover_channel = &mut Blake2sChannel::default();
-        for byte in view.view_associated_data().unwrap_or_default() {
-            prover_channel.mix_u64(byte.into());
+    fn prove_impl<B: ProverBackend>(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+        public_registers: bool,
+    ) -> Result<Proof, ProveError> {
+        let traces = Self::build_traces(extensions, trace, view, public_registers)?;
+        let twiddles = traces.precompute_twiddles_for::<B>();
+        let commitment = Self::commit(traces, &twiddles);
+        Self::prove_committed(commitment)
+    }
+}
+
+/// Proving from and to files, there is no file system on wasm.
+#[cfg(not(target_family = "wasm"))]
+impl<C: MachineChip + Sync + 'static> Machine<C> {
+    /// Proves an execution serialized with [`nexus_vm::trace::write_trace_file`], so that the trace can be
+    /// generated on a different machine or ahead of time.
+    ///
+    /// Fails if the trace isn't an execution of `program_info`.
+    pub fn prove_from_trace_file<P: AsRef<std::path::Path> + ?Sized>(
+        path: &P,
+        program_info: &ProgramInfo,
+    ) -> Result<Proof, ProveError> {
+        let (view, trace) = read_trace_file(path)?;
+
+        let expected = program_digest(program_info);
+        let actual = program_digest(view.get_program_memory());
+        if expected != actual {
+            return Err(ProveError::ProgramDigestMismatch { expected, actual });
         }
+        Self::prove(&trace, &view)
+    }
 
-        let mut commitment_scheme =
-            CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(config, &twiddles);
+    /// Same as [`Self::prove_with_extensions`], writing the traces and the proof to `checkpoint_dir` once
+    /// they're complete, so that a proof lost to a crash can be continued with [`Self::resume`].
+    ///
+    /// Checkpoints of a previous proof in `checkpoint_dir` are removed.
+    pub fn prove_with_checkpoints<P: AsRef<Path> + ?Sized>(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+        checkpoint_dir: &P,
+    ) -> Result<Proof, ProveError> {
+        let checkpoint_dir = checkpoint_dir.as_ref();
+        checkpoint::clear(checkpoint_dir)?;
+        let traces = Self::build_traces(extensions, trace, view, false)?;
+        checkpoint::write_traces(checkpoint_dir, &Self::traces_checkpoint(&traces))?;
+        Self::prove_checkpointed(traces, checkpoint_dir)
+    }
 
-        // Fill columns of the preprocessed trace.
-        let preprocessed_trace = PreprocessedTraces::new(log_size);
+    /// Continues a proof started by [`Self::prove_with_checkpoints`] from the last phase completed in
+    /// `checkpoint_dir`, the proof is returned right away if it was finished.
+    ///
+    /// The committed trees aren't checkpointed, they are recomputed from the traces. Proving is deterministic,
+    /// so the resumed proof is the same as an uninterrupted one.
+    pub fn resume<P: AsRef<Path> + ?Sized>(checkpoint_dir: &P) -> Result<Proof, ProveError> {
+        let checkpoint_dir = checkpoint_dir.as_ref();
+        if let Some(proof) = checkpoint::read_proof(checkpoint_dir)? {
+            return Ok(proof);
+        }
+        let traces = Self::traces_from_checkpoint(checkpoint::read_traces(checkpoint_dir)?)?;
+        Self::prove_checkpointed(traces, checkpoint_dir)
+    }
 
-        // Fill columns of the original trace.
-        let mut prover_traces = TracesBuilder::new(log_size);
-        let program_traces = ProgramTracesBuilder::new(
+    fn prove_checkpointed(
+        traces: MachineTraces,
+        checkpoint_dir: &Path,
+    ) -> Result<Proof, ProveError> {
+        let twiddles = traces.precompute_twiddles();
+        let proof = Self::prove_committed(Self::commit(traces, &twiddles))?;
+        checkpoint::write_proof(checkpoint_dir, &proof)?;
+        Ok(proof)
+    }
+
+    fn traces_checkpoint(traces: &MachineTraces) -> TracesCheckpoint<'_> {
+        TracesCheckpoint {
+            log_size: traces.log_size,
+            log_constraint_degree: traces.log_constraint_degree,
+            preprocessed_columns: Self::preprocessed_column_ids(),
+            associated_data: Cow::Borrowed(&traces.associated_data),
+            finalized_trace: Cow::Borrowed(&traces.finalized_trace),
+            finalized_program_trace: Cow::Borrowed(&traces.finalized_program_trace),
+            side_note: Cow::Borrowed(&traces.side_note),
+            extensions: traces
+                .extensions
+                .iter()
+                .map(|ext| Cow::Borrowed(ext.name()))
+                .collect(),
+            public_registers: traces.public_registers,
+            final_memory_digest: traces.final_memory_digest,
+        }
+    }
+
+    fn traces_from_checkpoint(
+        checkpoint: TracesCheckpoint<'_>,
+    ) -> Result<MachineTraces, ProveError> {
+        let TracesCheckpoint {
+            log_size,
+            log_constraint_degree,
+            preprocessed_columns,
+            associated_data,
+            finalized_trace,
+            finalized_program_trace,
+            side_note,
+            extensions,
+            public_registers,
+            final_memory_digest,
+        } = checkpoint;
+
+        let config = PcsConfig::default();
+        if log_constraint_degree != C::log_constraint_degree()
+            || preprocessed_columns != Self::preprocessed_column_ids()
+        {
+            return Err(ProveError::InvalidCheckpoint(
+                "the traces were filled by a different machine".to_string(),
+            ));
+        }
+        if log_size > Self::max_supported_log_size(&config)
+            || finalized_trace.log_size() != log_size
+            || finalized_program_trace.log_size() != log_size
+        {
+            return Err(ProveError::InvalidCheckpoint(format!(
+                "inconsistent log size {log_size}"
+            )));
+        }
+        let extensions = extensions
+            .iter()
+            .map(|name| {
+                let ext = ExtensionComponent::from_name(name).ok_or_else(|| {
+                    ProveError::InvalidCheckpoint(format!("unknown extension {name}"))
+                })?;
+                Ok(match (ext, public_registers) {
+                    (ExtensionComponent::FinalReg(_), Some(public_registers)) => {
+                        ExtensionComponent::final_reg_with_public_registers(public_registers)
+                    }
+                    (ext, _) => ext,
+                })
+            })
+            .collect::<Result<_, ProveError>>()?;
+
+        Ok(MachineTraces {
+            log_size,
+            log_constraint_degree,
+            config,
+            associated_data: associated_data.into_owned(),
+            preprocessed_trace: PreprocessedTraces::with_custom_columns(
+                log_size,
+                &C::preprocessed_columns(),
+            ),
+            finalized_trace: finalized_trace.into_owned(),
+            finalized_program_trace: finalized_program_trace.into_owned(),
+            side_note: side_note.into_owned(),
+            extensions,
+            public_registers,
+            final_memory_digest,
+        })
+    }
+
+    fn preprocessed_column_ids() -> Vec<Cow<'static, str>> {
+        C::preprocessed_columns()
+            .iter()
+            .map(|column| Cow::Borrowed(column.id))
+            .collect()
+    }
+}
+
+impl<C: MachineChip + Sync + 'static> Machine<C> {
+    /// Fills the main trace of the execution and compares it row by row to a replay of `trace`,
+    /// see [`cross_check_trace`]. Meant for debugging chips, when proving fails on a trace that
+    /// passes [`nexus_vm::validate::validate_trace`].
+    pub fn cross_check(trace: &impl Trace, view: &View) -> Result<(), TraceMismatch> {
+        let log_size = Self::trace_log_size(trace, view);
+        let program_traces = Self::program_traces(view, log_size);
+        let (traces, _) = Self::fill_main_trace(trace, view, log_size, &program_traces, None);
+        cross_check_trace(&traces, trace)
+    }
+
+    fn trace_log_size(trace: &impl Trace, view: &View) -> u32 {
+        let num_steps = trace.get_num_steps();
+        let program_len = view.get_program_memory().program.len();
+        let tracked_ram_size = view.view_tracked_ram_size();
+
+        Self::max_log_size(&[num_steps, program_len, tracked_ram_size])
+            .max(PreprocessedTraces::MIN_LOG_SIZE)
+    }
+
+    fn program_traces(view: &View, log_size: u32) -> ProgramTracesBuilder {
+        ProgramTracesBuilder::new(
             log_size,
             view.get_program_memory(),
             view.get_initial_memory(),
             view.get_exit_code(),
             view.get_public_output(),
-        );
-        let mut prover_side_note = SideNote::new(&program_traces, view);
+        )
+    }
+
+    /// Fills the main trace of `trace`, with the register memory check starting from
+    /// `start_registers` if given.
+    fn fill_main_trace(
+        trace: &impl Trace,
+        view: &View,
+        log_size: u32,
+        program_traces: &ProgramTracesBuilder,
+        start_registers: Option<[u32; NUM_REGISTERS]>,
+    ) -> (TracesBuilder, SideNote) {
+        let mut prover_traces = TracesBuilder::new(log_size);
+        let mut prover_side_note = SideNote::new(program_traces, view);
+        if let Some(start_registers) = start_registers {
+            prover_side_note.register_mem_check.last_access_value = start_registers;
+        }
         let program_steps = iter_program_steps(trace, prover_traces.num_rows());
         for (row_idx, program_step) in program_steps.enumerate() {
             C::fill_main_trace(
@@ -174,35 +572,175 @@ impl<C: MachineChip + Sync> Machine<C> {
                 &mut prover_side_note,
             );
         }
+        (prover_traces, prover_side_note)
+    }
+
+    /// First proving stage: fills the traces of the execution.
+    ///
+    /// If `public_registers` is set, the resulting proof exposes the register file, see
+    /// [`Self::prove_with_public_registers`].
+    pub fn build_traces(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+        public_registers: bool,
+    ) -> Result<MachineTraces, ProveError> {
+        Self::build_traces_with_final_memory(extensions, trace, view, public_registers, false)
+    }
+
+    /// Same as [`Self::build_traces`], if `final_memory` is set the resulting proof additionally
+    /// claims the digest of the final RAM, see [`Self::prove_with_final_memory`].
+    pub fn build_traces_with_final_memory(
+        extensions: &[ExtensionComponent],
+        trace: &impl Trace,
+        view: &View,
+        public_registers: bool,
+        final_memory: bool,
+    ) -> Result<MachineTraces, ProveError> {
+        let num_steps = trace.get_num_steps();
+        let program_len = view.get_program_memory().program.len();
+        let tracked_ram_size = view.view_tracked_ram_size();
+        let log_size = Self::trace_log_size(trace, view);
+
+        tracing::debug!(
+            target: "nexus_prover::trace",
+            num_steps,
+            program_len,
+            tracked_ram_size,
+            log_size,
+            "proving execution trace"
+        );
+
+        let config = PcsConfig::default();
+        let max_log_size = Self::max_supported_log_size(&config);
+        if log_size > max_log_size {
+            return Err(ProveError::TraceTooLarge {
+                num_steps,
+                log_size,
+                max_log_size,
+            });
+        }
+        if let Some((syscall, pc)) = SyscallChip::find_unprovable(trace) {
+            return Err(ProveError::UnsupportedSyscall { syscall, pc });
+        }
+        if let Some(error) =
+            LoadStoreChip::find_read_only_violation(trace, view.get_initial_memory())
+        {
+            return Err(error);
+        }
+
+        // Fill columns of the preprocessed trace.
+        let preprocessed_trace =
+            PreprocessedTraces::with_custom_columns(log_size, &C::preprocessed_columns());
+
+        // Fill columns of the original trace.
+        let mut program_traces = Self::program_traces(view, log_size);
+        let start_registers: [u32; NUM_REGISTERS] = trace
+            .get_blocks_iter()
+            .next()
+            .map(|block| std::array::from_fn(|i| block.regs.read(Register::from(i as u8))))
+            .unwrap_or_default();
+        let (prover_traces, prover_side_note) = Self::fill_main_trace(
+            trace,
+            view,
+            log_size,
+            &program_traces,
+            public_registers.then_some(start_registers),
+        );
+
+        let final_memory_digest = if final_memory {
+            let final_memory = prover_side_note.rw_mem_check.final_memory();
+            program_traces.fill_final_memory(&final_memory)?;
+            Some(final_memory_digest(&final_memory))
+        } else {
+            None
+        };
 
         let finalized_trace = prover_traces.finalize();
         let finalized_program_trace = program_traces.finalize();
+        tracing::trace!(target: "nexus_prover::trace", "main and program traces filled");
+
+        let public_registers = public_registers.then(|| PublicRegisters {
+            start: start_registers,
+            end: prover_side_note.register_mem_check.last_access_value,
+        });
+
+        Ok(MachineTraces {
+            log_size,
+            log_constraint_degree: C::log_constraint_degree(),
+            config,
+            associated_data: view.view_associated_data().unwrap_or_default(),
+            preprocessed_trace,
+            finalized_trace,
+            finalized_program_trace,
+            side_note: prover_side_note,
+            extensions: Self::all_extensions(extensions, public_registers),
+            public_registers,
+            final_memory_digest,
+        })
+    }
+
+    /// Second proving stage: commits to the preprocessed, main and interaction traces.
+    ///
+    /// `twiddles` must be computed with [`MachineTraces::precompute_twiddles`], or
+    /// [`MachineTraces::precompute_twiddles_for`] to commit on another backend.
+    pub fn commit<B: ProverBackend>(
+        traces: MachineTraces,
+        twiddles: &TwiddleTree<B>,
+    ) -> MachineCommitment<'_, B> {
+        let MachineTraces {
+            log_size,
+            log_constraint_degree: _,
+            config,
+            associated_data,
+            preprocessed_trace,
+            finalized_trace,
+            finalized_program_trace,
+            side_note: prover_side_note,
+            extensions,
+            public_registers,
+            final_memory_digest,
+        } = traces;
+        let extensions_iter = extensions.iter();
+
+        // Setup protocol.
+        let mut prover_channel = Blake2sChannel::default();
+        for byte in associated_data {
+            prover_channel.mix_u64(byte.into());
+        }
+
+        let mut commitment_scheme =
+            CommitmentSchemeProver::<B, Blake2sMerkleChannel>::new(config, twiddles);
 
         let mut tree_builder = commitment_scheme.tree_builder();
-        let _preprocessed_trace_location = tree_builder.extend_evals(
+        let _preprocessed_trace_location = tree_builder.extend_evals(B::from_simd_evals(
             preprocessed_trace
                 .clone()
                 .into_circle_evaluation()
                 .into_iter()
-                .chain(finalized_program_trace.clone().into_circle_evaluation()),
-        );
+                .chain(finalized_program_trace.clone().into_circle_evaluation())
+                .collect(),
+        ));
         // Handle extensions for the preprocessed trace
         for ext in extensions_iter.clone() {
-            tree_builder.extend_evals(ext.generate_preprocessed_trace());
+            tree_builder.extend_evals(B::from_simd_evals(ext.generate_preprocessed_trace()));
         }
-        tree_builder.commit(prover_channel);
+        tree_builder.commit(&mut prover_channel);
 
         let mut tree_builder = commitment_scheme.tree_builder();
-        let _main_trace_location =
-            tree_builder.extend_evals(finalized_trace.clone().into_circle_evaluation());
+        let _main_trace_location = tree_builder.extend_evals(B::from_simd_evals(
+            finalized_trace.clone().into_circle_evaluation(),
+        ));
         // Handle extensions for the main trace
         for ext in extensions_iter.clone() {
-            tree_builder.extend_evals(ext.generate_original_trace(&prover_side_note));
+            tree_builder.extend_evals(B::from_simd_evals(
+                ext.generate_original_trace(&prover_side_note),
+            ));
         }
-        tree_builder.commit(prover_channel);
+        tree_builder.commit(&mut prover_channel);
 
         let mut lookup_elements = AllLookupElements::default();
-        C::draw_lookup_elements(&mut lookup_elements, prover_channel);
+        C::draw_lookup_elements(&mut lookup_elements, &mut prover_channel);
 
         let (interaction_trace, claimed_sum) = generate_interaction_trace::<C>(
             &finalized_trace,
@@ -210,37 +748,68 @@ impl<C: MachineChip + Sync> Machine<C> {
             &finalized_program_trace,
             &lookup_elements,
         );
+        tracing::trace!(target: "nexus_prover::trace", "interaction trace generated");
 
         let mut tree_builder = commitment_scheme.tree_builder();
-        let _interaction_trace_location = tree_builder.extend_evals(interaction_trace);
+        let _interaction_trace_location =
+            tree_builder.extend_evals(B::from_simd_evals(interaction_trace));
         // Handle extensions for the interaction trace
         let mut all_claimed_sum = vec![claimed_sum];
-        for ext in extensions_iter.clone() {
+        for ext in extensions_iter {
             let (interaction_trace, claimed_sum) =
                 ext.generate_interaction_trace(&prover_side_note, &lookup_elements);
             all_claimed_sum.push(claimed_sum);
-            tree_builder.extend_evals(interaction_trace);
+            tree_builder.extend_evals(B::from_simd_evals(interaction_trace));
+        }
+        tree_builder.commit(&mut prover_channel);
+
+        MachineCommitment {
+            log_size,
+            commitment_scheme,
+            prover_channel,
+            lookup_elements,
+            claimed_sum: all_claimed_sum,
+            extensions,
+            public_registers,
+            final_memory_digest,
         }
-        tree_builder.commit(prover_channel);
+    }
+
+    /// Last proving stage: proves the committed traces satisfy the constraints.
+    pub fn prove_committed<B: ProverBackend>(
+        commitment: MachineCommitment<'_, B>,
+    ) -> Result<Proof, ProveError> {
+        let MachineCommitment {
+            log_size,
+            commitment_scheme,
+            mut prover_channel,
+            lookup_elements,
+            claimed_sum: all_claimed_sum,
+            extensions,
+            public_registers,
+            final_memory_digest,
+        } = commitment;
 
         let tree_span_provider = &mut TraceLocationAllocator::default();
-        let main_component = MachineComponent::new(
+        let main_component = B::component_prover(MachineComponent::new(
             tree_span_provider,
             MachineEval::<C>::new(log_size, lookup_elements.clone()),
-            claimed_sum,
-        );
-        let ext_components: Vec<Box<dyn ComponentProver<SimdBackend>>> = extensions_iter
+            all_claimed_sum[0],
+        ));
+        let ext_components: Vec<Box<dyn ComponentProver<B>>> = extensions
+            .iter()
             .zip(all_claimed_sum.get(1..).unwrap_or_default())
             .map(|(ext, claimed_sum)| {
                 ext.to_component_prover(tree_span_provider, &lookup_elements, *claimed_sum)
             })
             .collect();
-        let mut components_ref: Vec<&dyn ComponentProver<SimdBackend>> =
-            ext_components.iter().map(|c| &**c).collect();
-        components_ref.insert(0, &main_component);
-        let proof = prove::<SimdBackend, Blake2sMerkleChannel>(
+        let components_ref: Vec<&dyn ComponentProver<B>> = std::iter::once(&main_component)
+            .chain(&ext_components)
+            .map(|c| &**c)
+            .collect();
+        let proof = prove::<B, Blake2sMerkleChannel>(
             &components_ref,
-            prover_channel,
+            &mut prover_channel,
             commitment_scheme,
         )?;
 
@@ -248,87 +817,490 @@ impl<C: MachineChip + Sync> Machine<C> {
             stark_proof: proof,
             claimed_sum: all_claimed_sum,
             log_size,
+            public_registers,
+            final_memory_digest,
         })
     }
 
-    pub fn verify(
-        proof: Proof,
-        program_info: &ProgramInfo,
-        ad: &[u8],
-        init_memory: &[MemoryInitializationEntry],
-        exit_code: &[PublicOutputEntry],
-        output_memory: &[PublicOutputEntry],
-    ) -> Result<(), VerificationError> {
-        Self::verify_with_extensions(
-            &[],
-            proof,
-            program_info,
-            ad,
-            init_memory,
-            exit_code,
-            output_memory,
-        )
-    }
-
-    pub fn verify_with_extensions(
+    /// Proves several independent executions, e.g. of different programs, in a single proof, so
+    /// that the fixed cost of verification is paid once per batch.
+    ///
+    /// Every execution keeps its own components and logup sums, the executions only share the
+    /// commitment trees, the lookup elements and the constant preprocessed columns of extensions.
+    /// Batch proofs don't expose public registers.
+    pub fn prove_batch<T: Trace>(
         extensions: &[ExtensionComponent],
-        proof: Proof,
-        program_info: &ProgramInfo,
-        ad: &[u8],
-        init_memory: &[MemoryInitializationEntry],
-        exit_code: &[PublicOutputEntry],
-        output_memory: &[PublicOutputEntry],
-    ) -> Result<(), VerificationError> {
-        let Proof {
-            stark_proof: proof,
-            claimed_sum,
-            log_size,
-        } = proof;
-
-        if claimed_sum.len() != extensions.len() + BASE_EXTENSIONS.len() + 1 {
-            return Err(VerificationError::InvalidStructure(
-                "claimed sum len mismatch".to_string(),
-            ));
-        }
-        if claimed_sum.iter().sum::<SecureField>() != SecureField::zero() {
-            return Err(VerificationError::InvalidStructure(
-                "claimed logup sum is not zero".to_string(),
-            ));
+        executions: &[(&T, &View)],
+    ) -> Result<BatchProof, ProveError> {
+        if executions.is_empty() {
+            return Err(ProveError::EmptyBatch);
         }
-        let extensions_iter = BASE_EXTENSIONS.iter().chain(extensions);
+        let traces = executions
+            .iter()
+            .map(|&(trace, view)| Self::build_traces(extensions, trace, view, false))
+            .collect::<Result<Vec<_>, _>>()?;
+        let log_sizes: Vec<u32> = traces.iter().map(MachineTraces::log_size).collect();
 
         let config = PcsConfig::default();
-        let verifier_channel = &mut Blake2sChannel::default();
-        for &byte in ad {
-            verifier_channel.mix_u64(byte.into());
+        let twiddles = Self::batch_twiddles(&log_sizes, &config);
+        let mut prover_channel = Blake2sChannel::default();
+        Self::mix_batch_associated_data(
+            &mut prover_channel,
+            traces
+                .iter()
+                .map(|traces| traces.associated_data.as_slice()),
+        );
+        let mut commitment_scheme =
+            CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(config, &twiddles);
+
+        // Columns of each execution follow the ones of the previous execution in every tree.
+        let mut tree_builder = commitment_scheme.tree_builder();
+        for (idx, traces) in traces.iter().enumerate() {
+            tree_builder.extend_evals(
+                traces
+                    .preprocessed_trace
+                    .clone()
+                    .into_circle_evaluation()
+                    .into_iter()
+                    .chain(
+                        traces
+                            .finalized_program_trace
+                            .clone()
+                            .into_circle_evaluation(),
+                    ),
+            );
+            if idx == 0 {
+                for ext in &traces.extensions {
+                    tree_builder.extend_evals(ext.generate_preprocessed_trace());
+                }
+            }
         }
+        tree_builder.commit(&mut prover_channel);
 
-        let commitment_scheme = &mut CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+        let mut tree_builder = commitment_scheme.tree_builder();
+        for traces in &traces {
+            tree_builder.extend_evals(traces.finalized_trace.clone().into_circle_evaluation());
+            for ext in &traces.extensions {
+                tree_builder.extend_evals(ext.generate_original_trace(&traces.side_note));
+            }
+        }
+        tree_builder.commit(&mut prover_channel);
 
-        // simulate the prover and compute expected commitment to preprocessed trace
-        {
-            let config = PcsConfig::default();
-            let verifier_channel = &mut verifier_channel.clone();
-            let twiddles = SimdBackend::precompute_twiddles(
-                CanonicCoset::new(
-                    log_size + LOG_CONSTRAINT_DEGREE + config.fri_config.log_blowup_factor,
-                )
-                .circle_domain()
+        let mut lookup_elements = AllLookupElements::default();
+        C::draw_lookup_elements(&mut lookup_elements, &mut prover_channel);
+
+        let mut tree_builder = commitment_scheme.tree_builder();
+        let mut claimed_sums = Vec::with_capacity(traces.len());
+        for traces in &traces {
+            let (interaction_trace, claimed_sum) = generate_interaction_trace::<C>(
+                &traces.finalized_trace,
+                &traces.preprocessed_trace,
+                &traces.finalized_program_trace,
+                &lookup_elements,
+            );
+            tree_builder.extend_evals(interaction_trace);
+            let mut execution_claimed_sum = vec![claimed_sum];
+            for ext in &traces.extensions {
+                let (interaction_trace, claimed_sum) =
+                    ext.generate_interaction_trace(&traces.side_note, &lookup_elements);
+                execution_claimed_sum.push(claimed_sum);
+                tree_builder.extend_evals(interaction_trace);
+            }
+            claimed_sums.push(execution_claimed_sum);
+        }
+        tree_builder.commit(&mut prover_channel);
+        tracing::trace!(target: "nexus_prover::trace", "batch traces committed");
+
+        // Components must be allocated in the order their columns were committed.
+        let tree_span_provider = &mut TraceLocationAllocator::default();
+        let mut main_components = Vec::with_capacity(traces.len());
+        let mut ext_components = Vec::with_capacity(traces.len());
+        for (idx, ((traces, claimed_sum), &log_size)) in
+            traces.iter().zip(&claimed_sums).zip(&log_sizes).enumerate()
+        {
+            main_components.push(MachineComponent::new(
+                tree_span_provider,
+                MachineEval::<C>::new_in_batch(log_size, lookup_elements.clone(), idx),
+                claimed_sum[0],
+            ));
+            let components: Vec<Box<dyn ComponentProver<SimdBackend>>> = traces
+                .extensions
+                .iter()
+                .zip(&claimed_sum[1..])
+                .map(|(ext, claimed_sum)| {
+                    ext.to_component_prover(tree_span_provider, &lookup_elements, *claimed_sum)
+                })
+                .collect();
+            ext_components.push(components);
+        }
+        let components_ref: Vec<&dyn ComponentProver<SimdBackend>> = main_components
+            .iter()
+            .zip(&ext_components)
+            .flat_map(|(main_component, ext_components)| {
+                std::iter::once(main_component as &dyn ComponentProver<SimdBackend>).chain(
+                    ext_components
+                        .iter()
+                        .map(|c| &**c as &dyn ComponentProver<SimdBackend>),
+                )
+            })
+            .collect();
+        let proof = prove::<SimdBackend, Blake2sMerkleChannel>(
+            &components_ref,
+            &mut prover_channel,
+            commitment_scheme,
+        )?;
+
+        Ok(BatchProof {
+            stark_proof: proof,
+            claimed_sums,
+            log_sizes,
+        })
+    }
+
+    /// Verifies a proof produced by [`Self::prove_batch`] against the public inputs of each
+    /// execution, in the order they were proven.
+    pub fn verify_batch(
+        extensions: &[ExtensionComponent],
+        proof: BatchProof,
+        statements: &[BatchStatement<'_>],
+    ) -> Result<(), VerifyError> {
+        let BatchProof {
+            stark_proof: proof,
+            claimed_sums,
+            log_sizes,
+        } = proof;
+
+        if statements.is_empty()
+            || claimed_sums.len() != statements.len()
+            || log_sizes.len() != statements.len()
+        {
+            return Err(VerifyError::ParameterMismatch(format!(
+                "expected {} executions, the proof has {}",
+                statements.len(),
+                log_sizes.len()
+            )));
+        }
+        let config = PcsConfig::default();
+        let max_log_size = Self::max_supported_log_size(&config);
+        for (claimed_sum, &log_size) in claimed_sums.iter().zip(&log_sizes) {
+            if claimed_sum.len() != extensions.len() + BASE_EXTENSIONS.len() + 1 {
+                return Err(VerifyError::ParameterMismatch(format!(
+                    "expected {} claimed sums per execution, got {}",
+                    extensions.len() + BASE_EXTENSIONS.len() + 1,
+                    claimed_sum.len()
+                )));
+            }
+            if !(PreprocessedTraces::MIN_LOG_SIZE..=max_log_size).contains(&log_size) {
+                return Err(VerifyError::ParameterMismatch(format!(
+                    "log size {log_size} is outside of {}..={max_log_size}",
+                    PreprocessedTraces::MIN_LOG_SIZE
+                )));
+            }
+            // Lookups of an execution can't be balanced by another execution of the batch.
+            if claimed_sum.iter().sum::<SecureField>() != SecureField::zero() {
+                return Err(VerifyError::MalformedProof(
+                    "claimed logup sum of an execution is not zero".to_string(),
+                ));
+            }
+        }
+        let all_extensions = Self::all_extensions(extensions, None);
+
+        let verifier_channel = &mut Blake2sChannel::default();
+        Self::mix_batch_associated_data(
+            verifier_channel,
+            statements.iter().map(|statement| statement.ad),
+        );
+        let commitment_scheme = &mut CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+
+        // simulate the prover and compute expected commitment to preprocessed trace
+        {
+            let verifier_channel = &mut verifier_channel.clone();
+            let twiddles = Self::batch_twiddles(&log_sizes, &config);
+            let commitment_scheme =
+                &mut CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
+                    config, &twiddles,
+                );
+
+            let mut tree_builder = commitment_scheme.tree_builder();
+            for (idx, (statement, &log_size)) in statements.iter().zip(&log_sizes).enumerate() {
+                let program_trace = ProgramTracesBuilder::new(
+                    log_size,
+                    statement.program_info,
+                    statement.init_memory,
+                    statement.exit_code,
+                    statement.output_memory,
+                )
+                .finalize();
+                tree_builder.extend_evals(
+                    PreprocessedTraces::with_custom_columns(log_size, &C::preprocessed_columns())
+                        .into_circle_evaluation()
+                        .into_iter()
+                        .chain(program_trace.into_circle_evaluation()),
+                );
+                if idx == 0 {
+                    for ext in &all_extensions {
+                        tree_builder.extend_evals(ext.generate_preprocessed_trace());
+                    }
+                }
+            }
+            tree_builder.commit(verifier_channel);
+
+            let preprocessed_expected = commitment_scheme.roots()[PREPROCESSED_TRACE_IDX];
+            let preprocessed = proof.commitments[PREPROCESSED_TRACE_IDX];
+            if preprocessed_expected != preprocessed {
+                return Err(VerifyError::ProgramDigestMismatch {
+                    expected: preprocessed_expected,
+                    actual: preprocessed,
+                });
+            }
+        }
+
+        let trace_log_sizes = Self::batch_trace_log_sizes(&log_sizes, &all_extensions);
+        for idx in [PREPROCESSED_TRACE_IDX, ORIGINAL_TRACE_IDX] {
+            commitment_scheme.commit(
+                proof.commitments[idx],
+                &trace_log_sizes[idx],
+                verifier_channel,
+            );
+        }
+
+        let mut lookup_elements = AllLookupElements::default();
+        C::draw_lookup_elements(&mut lookup_elements, verifier_channel);
+
+        let tree_span_provider = &mut TraceLocationAllocator::default();
+        let mut main_components = Vec::with_capacity(statements.len());
+        let mut ext_components = Vec::with_capacity(statements.len());
+        for (idx, (claimed_sum, &log_size)) in claimed_sums.iter().zip(&log_sizes).enumerate() {
+            main_components.push(MachineComponent::new(
+                tree_span_provider,
+                MachineEval::<C>::new_in_batch(log_size, lookup_elements.clone(), idx),
+                claimed_sum[0],
+            ));
+            let components: Vec<Box<dyn Component>> = all_extensions
+                .iter()
+                .zip(&claimed_sum[1..])
+                .map(|(ext, claimed_sum)| {
+                    ext.to_component(tree_span_provider, &lookup_elements, *claimed_sum)
+                })
+                .collect();
+            ext_components.push(components);
+        }
+        let components_ref: Vec<&dyn Component> = main_components
+            .iter()
+            .zip(&ext_components)
+            .flat_map(|(main_component, ext_components)| {
+                std::iter::once(main_component as &dyn Component)
+                    .chain(ext_components.iter().map(|c| &**c as &dyn Component))
+            })
+            .collect();
+
+        commitment_scheme.commit(
+            proof.commitments[INTERACTION_TRACE_IDX],
+            &trace_log_sizes[INTERACTION_TRACE_IDX],
+            verifier_channel,
+        );
+
+        verify(&components_ref, verifier_channel, commitment_scheme, proof)?;
+        Ok(())
+    }
+
+    /// Expected column sizes in each commitment interaction of a batch proof: the columns of each
+    /// execution follow the ones of the previous execution, except for the preprocessed columns of
+    /// extensions, which are committed once.
+    fn batch_trace_log_sizes(
+        log_sizes: &[u32],
+        all_extensions: &[ExtensionComponent],
+    ) -> TreeVec<Vec<u32>> {
+        TreeVec::concat_cols(log_sizes.iter().enumerate().map(|(idx, &log_size)| {
+            let mut sizes = Self::trace_log_sizes(log_size, all_extensions);
+            if idx > 0 {
+                sizes[PREPROCESSED_TRACE_IDX].truncate(Self::main_preprocessed_columns_num());
+            }
+            sizes
+        }))
+    }
+
+    /// Twiddles for committing to the traces of a batch with traces of `log_sizes`.
+    fn batch_twiddles(log_sizes: &[u32], config: &PcsConfig) -> TwiddleTree<SimdBackend> {
+        let log_size = log_sizes.iter().copied().max().unwrap_or_default();
+        SimdBackend::precompute_twiddles(
+            CanonicCoset::new(
+                log_size + C::log_constraint_degree() + config.fri_config.log_blowup_factor,
+            )
+            .circle_domain()
+            .half_coset,
+        )
+    }
+
+    /// Seeds the channel of a batch proof with the associated data of each execution, prefixed
+    /// by its length, so that the proof is bound to how the data is split between executions.
+    fn mix_batch_associated_data<'a>(
+        channel: &mut Blake2sChannel,
+        associated_data: impl ExactSizeIterator<Item = &'a [u8]>,
+    ) {
+        channel.mix_u64(associated_data.len() as u64);
+        for ad in associated_data {
+            channel.mix_u64(ad.len() as u64);
+            for &byte in ad {
+                channel.mix_u64(byte.into());
+            }
+        }
+    }
+
+    /// Verifies a proof of [`Machine::prove`] against the program and its public I/O.
+    ///
+    /// The components of the machine are reconstructed from the log size of the proof and the channel is replayed
+    /// from the commitments, so that the verifier doesn't need access to the execution. A program or public I/O
+    /// other than the proven one is reported as [`VerifyError::ProgramDigestMismatch`].
+    pub fn verify(
+        proof: Proof,
+        program_info: &ProgramInfo,
+        ad: &[u8],
+        init_memory: &[MemoryInitializationEntry],
+        exit_code: &[PublicOutputEntry],
+        output_memory: &[PublicOutputEntry],
+    ) -> Result<(), VerifyError> {
+        Self::verify_with_extensions(
+            &[],
+            proof,
+            program_info,
+            ad,
+            init_memory,
+            exit_code,
+            output_memory,
+        )
+    }
+
+    /// Verifies a proof of [`Machine::prove_with_extensions`], `extensions` must be the ones used by the prover.
+    pub fn verify_with_extensions(
+        extensions: &[ExtensionComponent],
+        proof: Proof,
+        program_info: &ProgramInfo,
+        ad: &[u8],
+        init_memory: &[MemoryInitializationEntry],
+        exit_code: &[PublicOutputEntry],
+        output_memory: &[PublicOutputEntry],
+    ) -> Result<(), VerifyError> {
+        let statement = BatchStatement {
+            program_info,
+            ad,
+            init_memory,
+            exit_code,
+            output_memory,
+        };
+        Self::verify_impl(extensions, proof, &statement, None)
+    }
+
+    /// Verifies a proof of [`Machine::prove_with_final_memory`] of the execution described by `statement`,
+    /// checking [`Proof::final_memory_digest`] against `final_memory`.
+    pub fn verify_with_final_memory(
+        extensions: &[ExtensionComponent],
+        proof: Proof,
+        statement: &BatchStatement<'_>,
+        final_memory: &[PublicOutputEntry],
+    ) -> Result<(), VerifyError> {
+        Self::verify_impl(extensions, proof, statement, Some(final_memory))
+    }
+
+    fn verify_impl(
+        extensions: &[ExtensionComponent],
+        proof: Proof,
+        statement: &BatchStatement<'_>,
+        final_memory: Option<&[PublicOutputEntry]>,
+    ) -> Result<(), VerifyError> {
+        let &BatchStatement {
+            program_info,
+            ad,
+            init_memory,
+            exit_code,
+            output_memory,
+        } = statement;
+        let Proof {
+            stark_proof: proof,
+            claimed_sum,
+            log_size,
+            public_registers,
+            final_memory_digest: claimed_final_memory_digest,
+        } = proof;
+
+        if claimed_sum.len() != extensions.len() + BASE_EXTENSIONS.len() + 1 {
+            return Err(VerifyError::ParameterMismatch(format!(
+                "expected {} claimed sums, got {}",
+                extensions.len() + BASE_EXTENSIONS.len() + 1,
+                claimed_sum.len()
+            )));
+        }
+        let config = PcsConfig::default();
+        let max_log_size = Self::max_supported_log_size(&config);
+        if !(PreprocessedTraces::MIN_LOG_SIZE..=max_log_size).contains(&log_size) {
+            return Err(VerifyError::ParameterMismatch(format!(
+                "log size {log_size} is outside of {}..={max_log_size}",
+                PreprocessedTraces::MIN_LOG_SIZE
+            )));
+        }
+        if claimed_sum.iter().sum::<SecureField>() != SecureField::zero() {
+            return Err(VerifyError::MalformedProof(
+                "claimed logup sum is not zero".to_string(),
+            ));
+        }
+        match (claimed_final_memory_digest, final_memory) {
+            (Some(expected), Some(final_memory)) => {
+                let actual = final_memory_digest(final_memory);
+                if actual != expected {
+                    return Err(VerifyError::FinalMemoryDigestMismatch { expected, actual });
+                }
+            }
+            (None, None) => {}
+            (Some(_), None) => return Err(VerifyError::ParameterMismatch(
+                "the proof claims a final memory digest, the final memory is needed to verify it"
+                    .to_string(),
+            )),
+            (None, Some(_)) => {
+                return Err(VerifyError::ParameterMismatch(
+                    "the proof doesn't claim a final memory".to_string(),
+                ))
+            }
+        }
+        let all_extensions = Self::all_extensions(extensions, public_registers);
+        let extensions_iter = all_extensions.iter();
+
+        let verifier_channel = &mut Blake2sChannel::default();
+        for &byte in ad {
+            verifier_channel.mix_u64(byte.into());
+        }
+
+        let commitment_scheme = &mut CommitmentSchemeVerifier::<Blake2sMerkleChannel>::new(config);
+
+        // simulate the prover and compute expected commitment to preprocessed trace
+        {
+            let config = PcsConfig::default();
+            let verifier_channel = &mut verifier_channel.clone();
+            let twiddles = SimdBackend::precompute_twiddles(
+                CanonicCoset::new(
+                    log_size + C::log_constraint_degree() + config.fri_config.log_blowup_factor,
+                )
+                .circle_domain()
                 .half_coset,
             );
             let commitment_scheme =
                 &mut CommitmentSchemeProver::<SimdBackend, Blake2sMerkleChannel>::new(
                     config, &twiddles,
                 );
-            let preprocessed_trace = PreprocessedTraces::new(log_size);
-            let program_trace = ProgramTracesBuilder::new(
+            let preprocessed_trace =
+                PreprocessedTraces::with_custom_columns(log_size, &C::preprocessed_columns());
+            let mut program_trace = ProgramTracesBuilder::new(
                 log_size,
                 program_info,
                 init_memory,
                 exit_code,
                 output_memory,
-            )
-            .finalize();
+            );
+            if let Some(final_memory) = final_memory {
+                program_trace
+                    .fill_final_memory(final_memory)
+                    .map_err(|err| VerifyError::PublicValueMismatch(err.to_string()))?;
+            }
+            let program_trace = program_trace.finalize();
 
             let mut tree_builder = commitment_scheme.tree_builder();
             let _preprocessed_trace_location = tree_builder.extend_evals(
@@ -346,32 +1318,17 @@ impl<C: MachineChip + Sync> Machine<C> {
             let preprocessed_expected = commitment_scheme.roots()[PREPROCESSED_TRACE_IDX];
             let preprocessed = proof.commitments[PREPROCESSED_TRACE_IDX];
             if preprocessed_expected != preprocessed {
-                return Err(VerificationError::InvalidStructure(format!("invalid commitment to preprocessed trace: \
-                                                                        expected {preprocessed_expected}, got {preprocessed}")));
+                return Err(VerifyError::ProgramDigestMismatch {
+                    expected: preprocessed_expected,
+                    actual: preprocessed,
+                });
             }
         }
 
-        // Retrieve the expected column sizes in each commitment interaction, from the AIR.
-
         // Info evaluation can be avoided if the prover sends lookup elements along with the proof, this requires
         // implementing  [`serde::Serialize`] for all relations and [`AllLookupElements`]. Note that the verifier
         // should still independently draw elements and match it against received ones.
-        let mut sizes = vec![components::machine_component_info::<C>()
-            .mask_offsets
-            .as_cols_ref()
-            .map_cols(|_| log_size)];
-        for ext in extensions_iter.clone() {
-            sizes.push(ext.trace_sizes());
-        }
-        let mut log_sizes = TreeVec::concat_cols(sizes.into_iter());
-        // use the fact that preprocessed columns are only allowed to have [0] mask
-        log_sizes[PREPROCESSED_TRACE_IDX] = std::iter::repeat(log_size)
-            .take(PreprocessedColumn::COLUMNS_NUM + ProgramColumn::COLUMNS_NUM)
-            .collect();
-        for ext in extensions_iter.clone() {
-            // extending log_sizes[PREPROCESSED_TRACE_IDX] with the dimension of the preprocessed columns
-            log_sizes[PREPROCESSED_TRACE_IDX].extend(ext.preprocessed_trace_sizes());
-        }
+        let log_sizes = Self::trace_log_sizes(log_size, &all_extensions);
 
         for idx in [PREPROCESSED_TRACE_IDX, ORIGINAL_TRACE_IDX] {
             commitment_scheme.commit(proof.commitments[idx], &log_sizes[idx], verifier_channel);
@@ -402,7 +1359,111 @@ impl<C: MachineChip + Sync> Machine<C> {
             verifier_channel,
         );
 
-        verify(&components_ref, verifier_channel, commitment_scheme, proof)
+        verify(&components_ref, verifier_channel, commitment_scheme, proof)?;
+        Ok(())
+    }
+
+    /// Layout of the main component, e.g. for verifiers implemented outside of this crate.
+    ///
+    /// Extension components aren't included, their layout doesn't depend on the chips of the machine.
+    pub fn component_info() -> ComponentInfo {
+        let mut mask_offsets = components::machine_component_info::<C>().mask_offsets.0;
+        mask_offsets.resize(INTERACTION_TRACE_IDX + 1, Vec::new());
+        mask_offsets[PREPROCESSED_TRACE_IDX] = vec![vec![0]; Self::main_preprocessed_columns_num()];
+        ComponentInfo {
+            mask_offsets,
+            log_constraint_degree: C::log_constraint_degree(),
+        }
+    }
+
+    /// Estimates the cost of verifying a proof of a trace of `log_size` rows, proven with
+    /// `extensions`, under `config`. See [`crate::cost`] for what the estimate covers.
+    pub fn estimate_verifier_cost(
+        log_size: u32,
+        extensions: &[ExtensionComponent],
+        config: &PcsConfig,
+    ) -> VerifierCost {
+        let log_sizes = Self::trace_log_sizes(log_size, &Self::all_extensions(extensions, None));
+
+        // Every column is sampled at least once, the main component samples some columns at
+        // neighbouring rows as well.
+        let n_columns: usize = log_sizes.iter().map(Vec::len).sum();
+        let component_info = Self::component_info();
+        let n_extra_samples =
+            component_info.n_sampled_values() - component_info.n_columns().iter().sum::<usize>();
+
+        VerifierCost::estimate(
+            &log_sizes,
+            n_columns + n_extra_samples,
+            log_size + C::log_constraint_degree(),
+            config,
+        )
+    }
+
+    /// Expected column sizes in each commitment interaction, from the AIR.
+    fn trace_log_sizes(log_size: u32, all_extensions: &[ExtensionComponent]) -> TreeVec<Vec<u32>> {
+        let mut sizes = vec![components::machine_component_info::<C>()
+            .mask_offsets
+            .as_cols_ref()
+            .map_cols(|_| log_size)];
+        for ext in all_extensions {
+            sizes.push(ext.trace_sizes());
+        }
+        let mut log_sizes = TreeVec::concat_cols(sizes.into_iter());
+        // use the fact that preprocessed columns are only allowed to have [0] mask
+        log_sizes[PREPROCESSED_TRACE_IDX] = std::iter::repeat(log_size)
+            .take(Self::main_preprocessed_columns_num())
+            .collect();
+        for ext in all_extensions {
+            // extending log_sizes[PREPROCESSED_TRACE_IDX] with the dimension of the preprocessed columns
+            log_sizes[PREPROCESSED_TRACE_IDX].extend(ext.preprocessed_trace_sizes());
+        }
+        log_sizes
+    }
+
+    /// Number of preprocessed columns of the main component: [`PreprocessedColumn`]s, columns
+    /// contributed by chips and [`ProgramColumn`]s.
+    fn main_preprocessed_columns_num() -> usize {
+        PreprocessedColumn::COLUMNS_NUM
+            + C::preprocessed_columns().len()
+            + ProgramColumn::COLUMNS_NUM
+    }
+
+    /// Base extensions followed by `extensions`, with the final register component exposing
+    /// `public_registers` if given.
+    fn all_extensions(
+        extensions: &[ExtensionComponent],
+        public_registers: Option<PublicRegisters>,
+    ) -> Vec<ExtensionComponent> {
+        BASE_EXTENSIONS
+            .iter()
+            .chain(extensions)
+            .map(|ext| match (ext, public_registers) {
+                (ExtensionComponent::FinalReg(_), Some(public_registers)) => {
+                    ExtensionComponent::final_reg_with_public_registers(public_registers)
+                }
+                _ => ext.clone(),
+            })
+            .collect()
+    }
+
+    /// Largest log_size of traces, such that the domain constraints are evaluated on still fits in the
+    /// circle group of M31.
+    ///
+    /// On 32-bit targets such as wasm, the evaluations of the main trace on the blown-up domain must
+    /// also fit in half of the address space, so that oversized traces are rejected up front instead
+    /// of running out of memory mid-proof.
+    fn max_supported_log_size(config: &PcsConfig) -> u32 {
+        let log_blowup_factor = config.fri_config.log_blowup_factor;
+        let max_log_size = M31_CIRCLE_LOG_ORDER - C::log_constraint_degree() - log_blowup_factor;
+        if cfg!(target_pointer_width = "32") {
+            let log_row_bytes = (Column::COLUMNS_NUM * size_of::<BaseField>())
+                .next_power_of_two()
+                .ilog2();
+            max_log_size.min(usize::BITS - 1 - log_row_bytes - log_blowup_factor)
+        } else {
+            max_log_size
+        }
     }
 
     /// Computes minimum allowed log_size from a slice of lengths.
@@ -418,10 +1479,16 @@ impl<C: MachineChip + Sync> Machine<C> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::trace::{eval::TraceEval, CustomPreprocessedColumn, ProgramStep};
+    use nexus_common::constants::ELF_TEXT_START;
     use nexus_vm::{
+        emulator::LinearMemoryLayout,
         riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
-        trace::k_trace_direct,
+        trace::{k_trace_direct, Block, UniformTrace},
+        SyscallCode, WORD_SIZE,
     };
+    use num_traits::One;
+    use stwo_prover::{constraint_framework::EvalAtRow, core::fields::m31::BaseField};
 
     #[test]
     fn prove_verify() {
@@ -447,4 +1514,674 @@ mod tests {
         )
         .unwrap();
     }
+
+    #[test]
+    fn verify_rejects_program_mismatch() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 0),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+
+        let mut program_info = view.get_program_memory().clone();
+        program_info.program[1].instruction_word ^= 1 << 7;
+
+        let err = Machine::<BaseComponent>::verify(
+            proof,
+            &program_info,
+            &[],
+            view.get_initial_memory(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, VerifyError::ProgramDigestMismatch { .. }));
+    }
+
+    #[test]
+    fn prove_verify_public_registers() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 3, 2, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let proof =
+            Machine::<BaseComponent>::prove_with_public_registers(&[], &program_trace, &view)
+                .unwrap();
+        let public_registers = proof.public_registers.expect("registers are public");
+        assert_eq!(public_registers.start, [0; NUM_REGISTERS]);
+        assert_eq!(public_registers.end[..4], [0, 1, 2, 3]);
+
+        let verify = |proof| {
+            Machine::<BaseComponent>::verify(
+                proof,
+                view.get_program_memory(),
+                &[],
+                view.get_initial_memory(),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+        };
+        verify(proof.clone()).unwrap();
+
+        let mut tampered = proof;
+        tampered.public_registers.as_mut().unwrap().end[3] = 4;
+        assert!(matches!(
+            verify(tampered).unwrap_err(),
+            VerifyError::ProgramDigestMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn prove_verify_final_memory() {
+        let basic_block = vec![BasicBlock::new(vec![
+            // x1 = 0x80000, x2 = 42
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 1, 1, 19),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 42),
+            // *x1 = x2
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 0),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let (proof, final_memory) =
+            Machine::<BaseComponent>::prove_with_final_memory(&[], &program_trace, &view).unwrap();
+        let stored: Vec<(u32, u8)> = final_memory
+            .iter()
+            .filter(|entry| (0x80000..0x80004).contains(&entry.address))
+            .map(|entry| (entry.address, entry.value))
+            .collect();
+        assert_eq!(
+            stored,
+            [(0x80000, 42), (0x80001, 0), (0x80002, 0), (0x80003, 0)]
+        );
+
+        // The next execution starting from the final memory chains to the claim.
+        let next_init_memory: Vec<MemoryInitializationEntry> = final_memory
+            .iter()
+            .map(|entry| MemoryInitializationEntry {
+                address: entry.address,
+                value: entry.value,
+                read_only: false,
+            })
+            .collect();
+        assert_eq!(
+            proof.final_memory_digest,
+            Some(crate::digest::initial_memory_digest(&next_init_memory))
+        );
+
+        let statement = BatchStatement {
+            program_info: view.get_program_memory(),
+            ad: &[],
+            init_memory: view.get_initial_memory(),
+            exit_code: view.get_exit_code(),
+            output_memory: view.get_public_output(),
+        };
+        let verify = |proof, final_memory: &[PublicOutputEntry]| {
+            Machine::<BaseComponent>::verify_with_final_memory(&[], proof, &statement, final_memory)
+        };
+        verify(proof.clone(), &final_memory).unwrap();
+
+        // The claim can't be verified without the final memory.
+        assert!(matches!(
+            Machine::<BaseComponent>::verify(
+                proof.clone(),
+                view.get_program_memory(),
+                &[],
+                view.get_initial_memory(),
+                view.get_exit_code(),
+                view.get_public_output(),
+            )
+            .unwrap_err(),
+            VerifyError::ParameterMismatch(_)
+        ));
+
+        let mut tampered_memory = final_memory.clone();
+        tampered_memory
+            .iter_mut()
+            .find(|entry| entry.address == 0x80000)
+            .unwrap()
+            .value = 43;
+        assert!(matches!(
+            verify(proof.clone(), &tampered_memory).unwrap_err(),
+            VerifyError::FinalMemoryDigestMismatch { .. }
+        ));
+
+        // A claim consistent with the tampered memory doesn't match the committed RAM.
+        let mut tampered = proof;
+        tampered.final_memory_digest = Some(crate::digest::final_memory_digest(&tampered_memory));
+        assert!(matches!(
+            verify(tampered, &tampered_memory).unwrap_err(),
+            VerifyError::ProgramDigestMismatch { .. }
+        ));
+    }
+
+    #[test]
+    fn verify_rejects_oversized_log_size() {
+        let basic_block = vec![BasicBlock::new(vec![Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::ADDI),
+            1,
+            0,
+            1,
+        )])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let mut proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        proof.log_size = 64;
+
+        let err = Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            view.get_initial_memory(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap_err();
+        assert!(matches!(err, VerifyError::ParameterMismatch(_)));
+    }
+
+    /// Chip contributing a preprocessed column, constrained to be boolean.
+    struct EvenRowChip;
+
+    const EVEN_ROW_COLUMN: CustomPreprocessedColumn = CustomPreprocessedColumn {
+        id: "test_is_even_row",
+        generate: is_even_row,
+    };
+
+    fn is_even_row(log_size: u32) -> Vec<BaseField> {
+        (0..1u32 << log_size)
+            .map(|row| BaseField::from(u32::from(row % 2 == 0)))
+            .collect()
+    }
+
+    impl MachineChip for EvenRowChip {
+        fn fill_main_trace(
+            _traces: &mut TracesBuilder,
+            _row_idx: usize,
+            _vm_step: &Option<ProgramStep>,
+            _side_note: &mut SideNote,
+        ) {
+        }
+
+        fn add_constraints<E: EvalAtRow>(
+            eval: &mut E,
+            trace_eval: &TraceEval<E>,
+            _lookup_elements: &AllLookupElements,
+        ) {
+            let is_even = trace_eval.custom_preprocessed_column_eval(EVEN_ROW_COLUMN.id);
+            eval.add_constraint(is_even.clone() * (is_even - E::F::one()));
+        }
+
+        fn preprocessed_columns() -> Vec<CustomPreprocessedColumn> {
+            vec![EVEN_ROW_COLUMN]
+        }
+    }
+
+    #[test]
+    fn prove_verify_custom_preprocessed_column() {
+        type Chips = (BaseComponent, EvenRowChip);
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let log_size = PreprocessedTraces::MIN_LOG_SIZE;
+        let preprocessed =
+            PreprocessedTraces::with_custom_columns(log_size, &Chips::preprocessed_columns());
+        assert!(preprocessed.get_custom_column(EVEN_ROW_COLUMN.id).is_some());
+        assert!(preprocessed.get_custom_column("unknown").is_none());
+
+        let proof = Machine::<Chips>::prove(&program_trace, &view).unwrap();
+        Machine::<Chips>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            view.get_initial_memory(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    /// Chip constraining the same column as [`EvenRowChip`] with a constraint of degree 8.
+    struct HighDegreeChip;
+
+    impl MachineChip for HighDegreeChip {
+        fn fill_main_trace(
+            _traces: &mut TracesBuilder,
+            _row_idx: usize,
+            _vm_step: &Option<ProgramStep>,
+            _side_note: &mut SideNote,
+        ) {
+        }
+
+        fn add_constraints<E: EvalAtRow>(
+            eval: &mut E,
+            trace_eval: &TraceEval<E>,
+            _lookup_elements: &AllLookupElements,
+        ) {
+            let is_even = trace_eval.custom_preprocessed_column_eval(EVEN_ROW_COLUMN.id);
+            let square = is_even.clone() * is_even.clone();
+            let fourth = square.clone() * square;
+            eval.add_constraint(fourth.clone() * fourth - is_even);
+        }
+
+        fn preprocessed_columns() -> Vec<CustomPreprocessedColumn> {
+            vec![EVEN_ROW_COLUMN]
+        }
+
+        fn log_constraint_degree() -> u32 {
+            3
+        }
+    }
+
+    #[test]
+    fn prove_verify_high_degree_chip() {
+        type Chips = (BaseComponent, HighDegreeChip);
+        assert_eq!(BaseComponent::log_constraint_degree(), 2);
+        assert_eq!(Chips::log_constraint_degree(), 3);
+
+        let basic_block = vec![BasicBlock::new(vec![Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::ADDI),
+            1,
+            0,
+            1,
+        )])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let proof = Machine::<Chips>::prove(&program_trace, &view).unwrap();
+        Machine::<Chips>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            view.get_initial_memory(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn prove_resume_from_checkpoint() {
+        let dir = std::env::temp_dir().join("nexus_prover_test_checkpoint");
+        let _ = std::fs::remove_dir_all(&dir);
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::XOR), 2, 1, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        assert!(matches!(
+            Machine::<BaseComponent>::resume(&dir),
+            Err(ProveError::Checkpoint(_))
+        ));
+        let proof =
+            Machine::<BaseComponent>::prove_with_checkpoints(&[], &program_trace, &view, &dir)
+                .unwrap();
+
+        // Crash after the traces were written.
+        std::fs::remove_file(dir.join("proof.bin")).unwrap();
+        assert!(matches!(
+            Machine::<(BaseComponent, EvenRowChip)>::resume(&dir),
+            Err(ProveError::InvalidCheckpoint(_))
+        ));
+        let resumed = Machine::<BaseComponent>::resume(&dir).unwrap();
+        assert_eq!(
+            postcard::to_stdvec(&resumed).unwrap(),
+            postcard::to_stdvec(&proof).unwrap()
+        );
+        // The finished proof is returned as is.
+        assert_eq!(
+            Machine::<BaseComponent>::resume(&dir).unwrap().log_size,
+            proof.log_size
+        );
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        Machine::<BaseComponent>::verify(
+            resumed,
+            view.get_program_memory(),
+            &[],
+            view.get_initial_memory(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn prove_verify_batch() {
+        let programs = [
+            vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+            ],
+            vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 3),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 2, 0, 1),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::XOR), 3, 2, 1),
+            ],
+        ];
+        let executions: Vec<_> = programs
+            .into_iter()
+            .map(|instructions| {
+                k_trace_direct(&vec![BasicBlock::new(instructions)], 1)
+                    .expect("error generating trace")
+            })
+            .collect();
+        let batch: Vec<_> = executions
+            .iter()
+            .map(|(view, trace)| (trace, view))
+            .collect();
+
+        let proof = Machine::<BaseComponent>::prove_batch(&[], &batch).unwrap();
+        assert_eq!(proof.claimed_sums.len(), 2);
+
+        fn statement(view: &View) -> BatchStatement<'_> {
+            BatchStatement {
+                program_info: view.get_program_memory(),
+                ad: &[],
+                init_memory: view.get_initial_memory(),
+                exit_code: view.get_exit_code(),
+                output_memory: view.get_public_output(),
+            }
+        }
+        let statements = [statement(&executions[0].0), statement(&executions[1].0)];
+        Machine::<BaseComponent>::verify_batch(&[], proof.clone(), &statements).unwrap();
+
+        let swapped = [statements[1], statements[0]];
+        assert!(matches!(
+            Machine::<BaseComponent>::verify_batch(&[], proof.clone(), &swapped).unwrap_err(),
+            VerifyError::ProgramDigestMismatch { .. }
+        ));
+        assert!(matches!(
+            Machine::<BaseComponent>::verify_batch(&[], proof, &statements[..1]).unwrap_err(),
+            VerifyError::ParameterMismatch(_)
+        ));
+        assert!(matches!(
+            Machine::<BaseComponent>::prove_batch::<UniformTrace>(&[], &[]),
+            Err(ProveError::EmptyBatch)
+        ));
+    }
+
+    #[test]
+    fn prove_from_trace_file() {
+        let path = std::env::temp_dir().join(format!("nexus-prove-{}.bin", std::process::id()));
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        nexus_vm::trace::write_trace_file(&path, &view, &program_trace).unwrap();
+
+        let mut other_program = view.get_program_memory().clone();
+        other_program.program[1].instruction_word ^= 1 << 7;
+        let err =
+            Machine::<BaseComponent>::prove_from_trace_file(&path, &other_program).unwrap_err();
+        let proof =
+            Machine::<BaseComponent>::prove_from_trace_file(&path, view.get_program_memory());
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(err, ProveError::ProgramDigestMismatch { .. }));
+        Machine::<BaseComponent>::verify(
+            proof.unwrap(),
+            view.get_program_memory(),
+            &[],
+            view.get_initial_memory(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn prove_in_stages() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let traces =
+            Machine::<BaseComponent>::build_traces(&[], &program_trace, &view, false).unwrap();
+        let twiddles = traces.precompute_twiddles();
+        let commitment = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap()
+            .install(|| Machine::<BaseComponent>::commit(traces, &twiddles));
+        let proof = Machine::<BaseComponent>::prove_committed(commitment).unwrap();
+
+        Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            view.get_initial_memory(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    #[test]
+    fn cross_check_filled_trace() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 3, 2, 4),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 4, 3, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        Machine::<BaseComponent>::cross_check(&program_trace, &view).unwrap();
+    }
+
+    #[test]
+    fn proof_stats() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+        let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+
+        let stats = proof.stats();
+        assert_eq!(
+            stats.serialized_size,
+            postcard::to_stdvec(&proof).unwrap().len()
+        );
+        assert_eq!(stats.num_commitment_trees, 4);
+        assert!(stats.num_fri_layers > 1);
+        assert_eq!(stats.num_queries, PcsConfig::default().fri_config.n_queries);
+    }
+
+    #[test]
+    fn component_info_matches_trace_layout() {
+        let info = Machine::<BaseComponent>::component_info();
+        let n_columns = info.n_columns();
+        assert_eq!(
+            n_columns[PREPROCESSED_TRACE_IDX],
+            PreprocessedColumn::COLUMNS_NUM + ProgramColumn::COLUMNS_NUM
+        );
+        assert_eq!(
+            n_columns[ORIGINAL_TRACE_IDX],
+            crate::column::Column::COLUMNS_NUM
+        );
+        assert!(n_columns[INTERACTION_TRACE_IDX] > 0);
+        assert!(info.n_sampled_values() > n_columns.iter().sum::<usize>());
+        assert_eq!(info.log_constraint_degree, 2);
+
+        let log_sizes =
+            Machine::<BaseComponent>::trace_log_sizes(PreprocessedTraces::MIN_LOG_SIZE, &[]);
+        assert_eq!(
+            n_columns,
+            log_sizes.iter().map(Vec::len).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn verifier_cost_grows_with_log_size() {
+        let config = PcsConfig::default();
+        let small = Machine::<BaseComponent>::estimate_verifier_cost(
+            PreprocessedTraces::MIN_LOG_SIZE,
+            &[],
+            &config,
+        );
+        let large = Machine::<BaseComponent>::estimate_verifier_cost(
+            PreprocessedTraces::MIN_LOG_SIZE + 4,
+            &[],
+            &config,
+        );
+        assert!(large.hash_invocations > small.hash_invocations);
+        assert!(large.calldata_size > small.calldata_size);
+    }
+
+    #[test]
+    fn prove_is_deterministic() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLTU), 3, 1, 2),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let serialized_proof = || {
+            let proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+            postcard::to_allocvec(&proof).unwrap()
+        };
+        let first = serialized_proof();
+        let second = rayon::ThreadPoolBuilder::new()
+            .num_threads(1)
+            .build()
+            .unwrap()
+            .install(serialized_proof);
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn prove_on_cpu_backend() {
+        use stwo_prover::core::backend::CpuBackend;
+
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 2, 1, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLTU), 3, 1, 2),
+        ])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let proof =
+            Machine::<BaseComponent>::prove_with_backend::<CpuBackend>(&[], &program_trace, &view)
+                .unwrap();
+        let simd_proof = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap();
+        assert_eq!(
+            postcard::to_allocvec(&proof).unwrap(),
+            postcard::to_allocvec(&simd_proof).unwrap()
+        );
+
+        Machine::<BaseComponent>::verify(
+            proof,
+            view.get_program_memory(),
+            &[],
+            view.get_initial_memory(),
+            view.get_exit_code(),
+            view.get_public_output(),
+        )
+        .unwrap();
+    }
+
+    /// A trace claiming more steps than the prover supports.
+    struct OversizedTrace(UniformTrace);
+
+    impl Trace for OversizedTrace {
+        fn get_memory_layout(&self) -> &LinearMemoryLayout {
+            self.0.get_memory_layout()
+        }
+
+        fn get_blocks_iter(&self) -> impl Iterator<Item = &Block> + '_ {
+            self.0.get_blocks_iter()
+        }
+
+        fn get_start(&self) -> usize {
+            self.0.get_start()
+        }
+
+        fn get_num_steps(&self) -> usize {
+            1 << M31_CIRCLE_LOG_ORDER
+        }
+    }
+
+    #[test]
+    fn prove_rejects_oversized_trace() {
+        let basic_block = vec![BasicBlock::new(vec![Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::ADDI),
+            1,
+            0,
+            1,
+        )])];
+        let (view, program_trace) =
+            k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+        let err =
+            Machine::<BaseComponent>::prove(&OversizedTrace(program_trace), &view).unwrap_err();
+        assert!(matches!(
+            err,
+            ProveError::TraceTooLarge {
+                num_steps,
+                log_size: M31_CIRCLE_LOG_ORDER,
+                ..
+            } if num_steps == 1 << M31_CIRCLE_LOG_ORDER
+        ));
+    }
+
+    #[test]
+    fn prove_rejects_unprovable_syscalls() {
+        for syscall in [
+            SyscallCode::Sha256Compress,
+            SyscallCode::KeccakPermute,
+            SyscallCode::AddMod,
+            SyscallCode::MulMod,
+            SyscallCode::InvMod,
+            SyscallCode::QueryMemoryLayout,
+            SyscallCode::HeapStats,
+            SyscallCode::Alloc,
+            SyscallCode::Dealloc,
+        ] {
+            // Pointers to the state and operands of the precompiles, ignored by the others.
+            let basic_block = vec![BasicBlock::new(vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 10, 0, 0x10000),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 11, 0, 0x10001),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 12, 0, 0x10002),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, syscall as u32),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+            ])];
+            let (view, program_trace) =
+                k_trace_direct(&basic_block, 1).expect("error generating trace");
+
+            let err = Machine::<BaseComponent>::prove(&program_trace, &view).unwrap_err();
+            let expected_pc = ELF_TEXT_START + 4 * WORD_SIZE as u32;
+            assert!(
+                matches!(
+                    err,
+                    ProveError::UnsupportedSyscall { syscall: s, pc } if s == syscall && pc == expected_pc
+                ),
+                "unexpected error {err:?}"
+            );
+        }
+    }
 }
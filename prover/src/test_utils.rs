@@ -1,3 +1,8 @@
+//! Utilities for testing chips, available to other crates with the `testing` feature.
+//!
+//! A typical chip test fills a [`TracesBuilder`] row by row with [`MachineChip::fill_main_trace`]
+//! and then checks the constraints with [`assert_chip`].
+
 use stwo_prover::{
     constraint_framework::{assert_constraints, EvalAtRow},
     core::{
@@ -13,8 +18,9 @@ use stwo_prover::{
     },
 };
 
+pub use crate::components::AllLookupElements;
 use crate::{
-    components::{AllLookupElements, LOG_CONSTRAINT_DEGREE},
+    components::LOG_CONSTRAINT_DEGREE,
     trace::{program_trace::ProgramTracesBuilder, FinalizedTraces, PreprocessedTraces},
     traits::generate_interaction_trace,
 };
@@ -24,15 +30,35 @@ use super::{
     traits::MachineChip,
 };
 
-pub(crate) fn test_params(
+pub fn test_params(
+    log_size: u32,
+) -> (
+    PcsConfig,
+    stwo_prover::core::poly::twiddles::TwiddleTree<SimdBackend>,
+) {
+    test_params_with_degree(log_size, LOG_CONSTRAINT_DEGREE)
+}
+
+/// Same as [`test_params`], with twiddles large enough for the constraints of `C`.
+pub fn test_params_for<C: MachineChip>(
+    log_size: u32,
+) -> (
+    PcsConfig,
+    stwo_prover::core::poly::twiddles::TwiddleTree<SimdBackend>,
+) {
+    test_params_with_degree(log_size, C::log_constraint_degree())
+}
+
+fn test_params_with_degree(
     log_size: u32,
+    log_constraint_degree: u32,
 ) -> (
     PcsConfig,
     stwo_prover::core::poly::twiddles::TwiddleTree<SimdBackend>,
 ) {
     let config = PcsConfig::default();
     let twiddles = SimdBackend::precompute_twiddles(
-        CanonicCoset::new(log_size + config.fri_config.log_blowup_factor + LOG_CONSTRAINT_DEGREE)
+        CanonicCoset::new(log_size + config.fri_config.log_blowup_factor + log_constraint_degree)
             .circle_domain()
             .half_coset,
     );
@@ -40,18 +66,18 @@ pub(crate) fn test_params(
 }
 
 /// Filled out traces, mainly for testing
-pub(crate) struct CommittedTraces<'a> {
-    pub(crate) commitment_scheme: CommitmentSchemeProver<'a, SimdBackend, Blake2sMerkleChannel>,
-    pub(crate) prover_channel: Blake2sChannel,
-    pub(crate) lookup_elements: AllLookupElements,
-    pub(crate) preprocessed_trace: PreprocessedTraces,
-    pub(crate) interaction_trace: Vec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
-    pub(crate) claimed_sum: SecureField,
-    pub(crate) program_trace: ProgramTraces,
+pub struct CommittedTraces<'a> {
+    pub commitment_scheme: CommitmentSchemeProver<'a, SimdBackend, Blake2sMerkleChannel>,
+    pub prover_channel: Blake2sChannel,
+    pub lookup_elements: AllLookupElements,
+    pub preprocessed_trace: PreprocessedTraces,
+    pub interaction_trace: Vec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>>,
+    pub claimed_sum: SecureField,
+    pub program_trace: ProgramTraces,
 }
 
 /// Testing utility for filling in traces
-pub(crate) fn commit_traces<'a, C: MachineChip>(
+pub fn commit_traces<'a, C: MachineChip>(
     config: PcsConfig,
     twiddles: &'a stwo_prover::core::poly::twiddles::TwiddleTree<SimdBackend>,
     traces: &FinalizedTraces,
@@ -64,7 +90,8 @@ pub(crate) fn commit_traces<'a, C: MachineChip>(
     let program_trace =
         program_traces.unwrap_or_else(|| ProgramTracesBuilder::dummy(traces.log_size()).finalize());
     // Preprocessed trace
-    let preprocessed_trace = PreprocessedTraces::new(traces.log_size());
+    let preprocessed_trace =
+        PreprocessedTraces::with_custom_columns(traces.log_size(), &C::preprocessed_columns());
     let mut tree_builder = commitment_scheme.tree_builder();
     let _preprocessed_trace_location = tree_builder.extend_evals(
         preprocessed_trace
@@ -101,11 +128,11 @@ pub(crate) fn commit_traces<'a, C: MachineChip>(
 }
 
 /// Assuming traces are filled, assert constraints
-pub(crate) fn assert_chip<C: MachineChip>(
+pub fn assert_chip<C: MachineChip>(
     traces: TracesBuilder,
     program_trace: Option<ProgramTraces>,
 ) -> (AllLookupElements, SecureField) {
-    let (config, twiddles) = test_params(traces.log_size());
+    let (config, twiddles) = test_params_for::<C>(traces.log_size());
 
     let finalized_trace = traces.finalize();
     let log_size = finalized_trace.log_size();
@@ -141,7 +168,7 @@ pub(crate) fn assert_chip<C: MachineChip>(
         &trace_polys,
         CanonicCoset::new(log_size),
         |mut eval| {
-            let trace_eval = TraceEval::new(&mut eval);
+            let trace_eval = TraceEval::for_component(&mut eval, &C::preprocessed_columns(), 0);
             C::add_constraints(&mut eval, &trace_eval, &lookup_elements);
 
             if !lookup_elements.is_empty() {
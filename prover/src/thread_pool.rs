@@ -0,0 +1,39 @@
+//! Thread pools for proving where the standard library can't spawn threads.
+//!
+//! The prover parallelizes trace generation and proving with rayon, on the pool it's called from. On wasm,
+//! threads are web workers started by the embedder: the prover can run on a pool of them built with
+//! [`with_spawner`], entered with [`ThreadPool::install`]. Without such a pool, rayon falls back to the
+//! calling thread there.
+
+use rayon::{ThreadBuilder, ThreadPool, ThreadPoolBuildError, ThreadPoolBuilder};
+
+/// Builds a pool of `num_threads` threads, each of which is started by `spawn`, e.g. by posting it to a
+/// web worker that calls [`ThreadBuilder::run`].
+pub fn with_spawner<S>(num_threads: usize, spawn: S) -> Result<ThreadPool, ThreadPoolBuildError>
+where
+    S: FnMut(ThreadBuilder) -> std::io::Result<()>,
+{
+    ThreadPoolBuilder::new()
+        .num_threads(num_threads)
+        .spawn_handler(spawn)
+        .build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_spawner() {
+        let mut spawned = 0;
+        let pool = with_spawner(2, |thread| {
+            spawned += 1;
+            std::thread::spawn(|| thread.run());
+            Ok(())
+        })
+        .unwrap();
+
+        assert_eq!(spawned, 2);
+        assert_eq!(pool.install(rayon::current_num_threads), 2);
+    }
+}
@@ -0,0 +1,244 @@
+//! Row-by-row audit of the constraints against the execution the main trace was filled from.
+//!
+//! Proving only tells whether all constraints hold. The audit here evaluates the constraints of the
+//! chips natively on every row, and compares the row to a replay of the execution as
+//! [`super::cross_check_trace`] does. A row the two disagree on points to a bug in the chip set:
+//! constraints holding on a row that diverges from the execution are unsound, and constraints failing
+//! on a row that matches it are incomplete.
+//!
+//! Lookups span many rows and aren't evaluated, relations are only checked when proving.
+
+use std::ops::Mul;
+
+use num_traits::Zero;
+use stwo_prover::{
+    constraint_framework::{
+        preprocessed_columns::PreProcessedColumnId, EvalAtRow, Relation, RelationEntry,
+        ORIGINAL_TRACE_IDX,
+    },
+    core::{
+        channel::Blake2sChannel,
+        fields::{m31::BaseField, qm31::SecureField, secure_column::SECURE_EXTENSION_DEGREE},
+    },
+};
+
+use nexus_vm::trace::Trace;
+
+use super::{
+    cross_check::Replay, eval::TraceEval, program_trace::ProgramTraces, PreprocessedTraces,
+    TracesBuilder,
+};
+use crate::{
+    column::{PreprocessedColumn, ProgramColumn},
+    components::AllLookupElements,
+    error::TraceMismatch,
+    traits::MachineChip,
+};
+
+/// Row on which the constraints of the chips and the execution disagree.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RowDisagreement {
+    /// The constraints hold on a row diverging from the execution.
+    Unsound(TraceMismatch),
+    /// Constraints fail on a row matching the execution, or on a padding row. Constraints are
+    /// numbered in the order [`MachineChip::add_constraints`] adds them.
+    Incomplete {
+        row: usize,
+        failed_constraints: Vec<usize>,
+    },
+}
+
+impl RowDisagreement {
+    /// Index of the row in the trace.
+    pub fn row(&self) -> usize {
+        match self {
+            Self::Unsound(mismatch) => mismatch.row,
+            Self::Incomplete { row, .. } => *row,
+        }
+    }
+}
+
+/// Evaluates the constraints of `C` on every row of `traces` and compares the row to a replay of
+/// `trace`, returning the rows they disagree on.
+///
+/// `traces` and `program_traces` must be filled from `trace`, which must be a k = 1 trace.
+pub fn audit_rows<C: MachineChip>(
+    traces: &TracesBuilder,
+    program_traces: &ProgramTraces,
+    trace: &impl Trace,
+) -> Vec<RowDisagreement> {
+    let custom_columns = C::preprocessed_columns();
+    let preprocessed_traces =
+        PreprocessedTraces::with_custom_columns(traces.log_size(), &custom_columns);
+    let preprocessed_ids: Vec<&str> = PreprocessedColumn::STRING_IDS
+        .iter()
+        .copied()
+        .chain(custom_columns.iter().map(|column| column.id))
+        .chain(ProgramColumn::STRING_IDS.iter().copied())
+        .collect();
+    // Relations aren't checked, any elements will do.
+    let mut lookup_elements = AllLookupElements::default();
+    C::draw_lookup_elements(&mut lookup_elements, &mut Blake2sChannel::default());
+
+    let mut replay = Replay::default();
+    let mut blocks = trace.get_blocks_iter();
+    let mut disagreements = Vec::new();
+    for row in 0..traces.num_rows() {
+        let preprocessed_values = preprocessed_traces
+            .row_values(row)
+            .into_iter()
+            .chain(program_traces.row_values(row));
+        let mut eval = RowEvaluator {
+            traces,
+            row,
+            next_column: 0,
+            preprocessed: preprocessed_ids
+                .iter()
+                .copied()
+                .zip(preprocessed_values)
+                .collect(),
+            num_constraints: 0,
+            failed_constraints: Vec::new(),
+        };
+        let trace_eval = TraceEval::for_component(&mut eval, &custom_columns, 0);
+        C::add_constraints(&mut eval, &trace_eval, &lookup_elements);
+
+        let semantics = match blocks.next() {
+            Some(block) => replay.check_row(traces, row, block),
+            None => Ok(()),
+        };
+        match (semantics, eval.failed_constraints.is_empty()) {
+            (Err(mismatch), true) => disagreements.push(RowDisagreement::Unsound(mismatch)),
+            (Ok(()), false) => disagreements.push(RowDisagreement::Incomplete {
+                row,
+                failed_constraints: eval.failed_constraints,
+            }),
+            _ => {}
+        }
+    }
+    disagreements
+}
+
+/// Evaluates constraints on a single row of the main trace.
+struct RowEvaluator<'a> {
+    traces: &'a TracesBuilder,
+    row: usize,
+    // Main trace column read by the next mask
+    next_column: usize,
+    preprocessed: Vec<(&'a str, BaseField)>,
+    num_constraints: usize,
+    failed_constraints: Vec<usize>,
+}
+
+impl EvalAtRow for RowEvaluator<'_> {
+    type F = BaseField;
+    type EF = SecureField;
+
+    fn next_interaction_mask<const N: usize>(
+        &mut self,
+        interaction: usize,
+        offsets: [isize; N],
+    ) -> [Self::F; N] {
+        if interaction != ORIGINAL_TRACE_IDX {
+            return [BaseField::zero(); N];
+        }
+        let column = &self.traces.cols[self.next_column];
+        self.next_column += 1;
+        offsets.map(|offset| {
+            column[(self.row as isize + offset).rem_euclid(column.len() as isize) as usize]
+        })
+    }
+
+    fn get_preprocessed_column(&mut self, column: PreProcessedColumnId) -> Self::F {
+        self.preprocessed
+            .iter()
+            .find(|(id, _)| *id == column.id)
+            .map(|(_, value)| *value)
+            .unwrap_or_else(|| panic!("unknown preprocessed column {}", column.id))
+    }
+
+    fn add_constraint<G>(&mut self, constraint: G)
+    where
+        Self::EF: Mul<G, Output = Self::EF> + From<G>,
+    {
+        if !SecureField::from(constraint).is_zero() {
+            self.failed_constraints.push(self.num_constraints);
+        }
+        self.num_constraints += 1;
+    }
+
+    fn combine_ef(values: [Self::F; SECURE_EXTENSION_DEGREE]) -> Self::EF {
+        SecureField::from_m31_array(values)
+    }
+
+    fn add_to_relation<R: Relation<Self::F, Self::EF>>(
+        &mut self,
+        _entry: RelationEntry<'_, Self::F, Self::EF, R>,
+    ) {
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        column::Column,
+        machine::BaseComponent,
+        trace::{
+            program::iter_program_steps, program_trace::ProgramTracesBuilder, sidenote::SideNote,
+        },
+    };
+    use nexus_vm::{
+        riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode},
+        trace::{k_trace_direct, UniformTrace},
+    };
+
+    const LOG_SIZE: u32 = PreprocessedTraces::MIN_LOG_SIZE;
+
+    fn program(imm: u32) -> Vec<BasicBlock> {
+        vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, imm),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 1, 7),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 3, 1, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 4, 3, 1),
+        ])]
+    }
+
+    fn fill(program: &[BasicBlock]) -> (TracesBuilder, ProgramTraces, UniformTrace) {
+        let (view, vm_traces) =
+            k_trace_direct(&program.to_vec(), 1).expect("Failed to create trace");
+        let program_info = view.get_program_memory();
+
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_traces = ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, program_info);
+        let mut side_note = SideNote::new(&program_traces, &view);
+        for (row_idx, program_step) in iter_program_steps(&vm_traces, traces.num_rows()).enumerate()
+        {
+            BaseComponent::fill_main_trace(&mut traces, row_idx, &program_step, &mut side_note);
+        }
+        (traces, program_traces.finalize(), vm_traces)
+    }
+
+    #[test]
+    fn test_audit_rows() {
+        let (mut traces, program_traces, vm_traces) = fill(&program(5));
+        assert!(audit_rows::<BaseComponent>(&traces, &program_traces, &vm_traces).is_empty());
+
+        // A trace consistent with another execution satisfies the constraints.
+        let (_, _, other_traces) = fill(&program(6));
+        let disagreements = audit_rows::<BaseComponent>(&traces, &program_traces, &other_traces);
+        let RowDisagreement::Unsound(mismatch) = &disagreements[0] else {
+            panic!("expected an unsound row, got {disagreements:?}");
+        };
+        assert_eq!((mismatch.row, mismatch.column), (0, Column::ValueA));
+        assert_eq!((mismatch.expected, mismatch.actual), (6, 5));
+
+        // Marking an executed row as padding only breaks the constraints.
+        traces.fill_columns(2, true, Column::IsPadding);
+        let disagreements = audit_rows::<BaseComponent>(&traces, &program_traces, &vm_traces);
+        assert!(disagreements
+            .iter()
+            .all(|d| matches!(d, RowDisagreement::Incomplete { .. })));
+        assert!(disagreements.iter().any(|d| d.row() == 2));
+    }
+}
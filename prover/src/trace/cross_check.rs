@@ -0,0 +1,150 @@
+//! Cross-check of the main trace against the execution it was filled from.
+//!
+//! Failing constraints only tell which component is unsatisfied, not on which row the main trace
+//! started to diverge from the execution. The check here replays the register file of the
+//! execution and compares the operand columns of every row to it, so that a bug in filling the
+//! trace is reported at the first row it affects.
+
+use nexus_common::cpu::Registers;
+use nexus_vm::{
+    cpu::RegisterFile,
+    riscv::{BuiltinOpcode, InstructionType, Register},
+    trace::{Block, Trace},
+};
+
+use super::TracesBuilder;
+use crate::{column::Column, error::TraceMismatch};
+
+/// Compares the `Pc`, `ValueA`, `ValueB` and `ValueC` columns of `traces` row by row to a replay of
+/// `trace`, and returns the first mismatch.
+///
+/// `traces` must be filled from `trace`, which must be a k = 1 trace.
+pub fn cross_check_trace(traces: &TracesBuilder, trace: &impl Trace) -> Result<(), TraceMismatch> {
+    let mut replay = Replay::default();
+    for (row, block) in trace.get_blocks_iter().enumerate() {
+        replay.check_row(traces, row, block)?;
+    }
+    Ok(())
+}
+
+/// Register file of the execution, replayed row by row.
+#[derive(Default)]
+pub(crate) struct Replay {
+    regs: Option<RegisterFile>,
+}
+
+impl Replay {
+    /// Compares the operand columns of `row` to the execution of `block`, the block of the row, and
+    /// executes it. Rows must be checked in order.
+    pub(crate) fn check_row(
+        &mut self,
+        traces: &TracesBuilder,
+        row: usize,
+        block: &Block,
+    ) -> Result<(), TraceMismatch> {
+        assert_eq!(block.steps.len(), 1, "Only k = 1 traces are supported.");
+        let step = &block.steps[0];
+        let instruction = &step.instruction;
+        // System calls may write registers without reporting it, resume from the recorded state.
+        let replayed = self.regs.get_or_insert(block.regs);
+
+        let syscall = matches!(
+            instruction.opcode.builtin(),
+            Some(BuiltinOpcode::ECALL | BuiltinOpcode::EBREAK)
+        );
+        let value_a = match instruction.ins_type {
+            InstructionType::BType | InstructionType::SType => replayed.read(instruction.op_a),
+            _ => step.result.unwrap_or(replayed.read(instruction.op_a)),
+        };
+        let value_b = if syscall {
+            replayed.read(Register::X17)
+        } else {
+            replayed.read(instruction.op_b)
+        };
+        let value_c = match instruction.ins_type {
+            InstructionType::RType => replayed.read(Register::from(instruction.op_c as u8)),
+            InstructionType::Unimpl => 0,
+            _ => instruction.op_c,
+        };
+
+        match (syscall, instruction.ins_type, step.result) {
+            (true, ..) => self.regs = None,
+            (_, InstructionType::BType | InstructionType::SType, _) => {}
+            (_, _, Some(result)) => replayed.write(instruction.op_a, result),
+            _ => {}
+        }
+
+        for (column, expected) in [
+            (Column::Pc, step.pc),
+            (Column::ValueA, value_a),
+            (Column::ValueB, value_b),
+            (Column::ValueC, value_c),
+        ] {
+            let actual = read_word(traces, row, column);
+            if actual != expected {
+                return Err(TraceMismatch {
+                    row,
+                    pc: step.pc,
+                    column,
+                    expected,
+                    actual,
+                });
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads a word stored as four byte limbs in `column`.
+fn read_word(traces: &TracesBuilder, row: usize, column: Column) -> u32 {
+    let limbs: [_; 4] = traces.column(row, column);
+    u32::from_le_bytes(limbs.map(|limb| limb.0 as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        machine::BaseComponent,
+        trace::{
+            program::iter_program_steps, program_trace::ProgramTracesBuilder, sidenote::SideNote,
+            PreprocessedTraces,
+        },
+        traits::MachineChip,
+    };
+    use nexus_vm::{
+        riscv::{BasicBlock, Instruction, Opcode},
+        trace::k_trace_direct,
+    };
+    use stwo_prover::core::fields::m31::BaseField;
+
+    const LOG_SIZE: u32 = PreprocessedTraces::MIN_LOG_SIZE;
+
+    #[test]
+    fn test_cross_check() {
+        let basic_block = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 5),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 1, 7),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 3, 1, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SUB), 4, 3, 1),
+        ])];
+        let (view, vm_traces) = k_trace_direct(&basic_block, 1).expect("Failed to create trace");
+        let program_info = view.get_program_memory();
+
+        let mut traces = TracesBuilder::new(LOG_SIZE);
+        let program_traces = ProgramTracesBuilder::new_with_empty_memory(LOG_SIZE, program_info);
+        let mut side_note = SideNote::new(&program_traces, &view);
+        for (row_idx, program_step) in iter_program_steps(&vm_traces, traces.num_rows()).enumerate()
+        {
+            BaseComponent::fill_main_trace(&mut traces, row_idx, &program_step, &mut side_note);
+        }
+        cross_check_trace(&traces, &vm_traces).unwrap();
+
+        // Corrupt the result of the addition.
+        *traces.column_mut::<4>(2, Column::ValueA)[0] += BaseField::from(1);
+        let mismatch = cross_check_trace(&traces, &vm_traces).unwrap_err();
+        assert_eq!(mismatch.row, 2);
+        assert_eq!(mismatch.column, Column::ValueA);
+        assert_eq!((mismatch.expected, mismatch.actual), (12, 13));
+    }
+}
@@ -3,6 +3,7 @@ use std::array;
 use num_traits::Zero;
 use stwo_prover::constraint_framework::{preprocessed_columns::PreProcessedColumnId, EvalAtRow};
 
+use super::preprocessed::CustomPreprocessedColumn;
 use crate::column::{
     Column, {PreprocessedColumn, ProgramColumn},
 };
@@ -15,18 +16,43 @@ pub use stwo_prover::constraint_framework::{
 pub struct TraceEval<E: EvalAtRow> {
     evals: Vec<[E::F; 2]>,
     preprocessed_evals: Vec<E::F>,
+    custom_preprocessed_evals: Vec<(&'static str, E::F)>,
     program_evals: Vec<E::F>, // only the current row
 }
 
 impl<E: EvalAtRow> TraceEval<E> {
-    pub(crate) fn new(eval: &mut E) -> Self {
+    /// Reads the columns of the main component, including `custom_columns` contributed by chips, for the
+    /// `batch_index`-th execution of a batch proof. Preprocessed columns are shared by id, the columns of executions
+    /// other than the first one are given distinct ids.
+    pub(crate) fn for_component(
+        eval: &mut E,
+        custom_columns: &[CustomPreprocessedColumn],
+        batch_index: usize,
+    ) -> Self {
+        let column_id = |id: &str| PreProcessedColumnId {
+            id: if batch_index == 0 {
+                id.to_owned()
+            } else {
+                format!("{id}_batch_{batch_index}")
+            },
+        };
         let preprocessed_evals = PreprocessedColumn::STRING_IDS
             .iter()
-            .map(|&id| eval.get_preprocessed_column(PreProcessedColumnId { id: id.to_owned() }))
+            .map(|&id| eval.get_preprocessed_column(column_id(id)))
+            .collect();
+        // Custom columns are committed right after the standard ones.
+        let custom_preprocessed_evals = custom_columns
+            .iter()
+            .map(|column| {
+                (
+                    column.id,
+                    eval.get_preprocessed_column(column_id(column.id)),
+                )
+            })
             .collect();
         let program_evals = ProgramColumn::STRING_IDS
             .iter()
-            .map(|&id| eval.get_preprocessed_column(PreProcessedColumnId { id: id.to_owned() }))
+            .map(|&id| eval.get_preprocessed_column(column_id(id)))
             .collect();
         let evals = Column::ALL_VARIANTS
             .iter()
@@ -45,6 +71,7 @@ impl<E: EvalAtRow> TraceEval<E> {
         Self {
             evals,
             preprocessed_evals,
+            custom_preprocessed_evals,
             program_evals,
         }
     }
@@ -76,6 +103,18 @@ impl<E: EvalAtRow> TraceEval<E> {
         array::from_fn(|i| self.preprocessed_evals[offset + i].clone())
     }
 
+    /// Returns the evaluation of a column contributed by a chip, see [`CustomPreprocessedColumn`].
+    ///
+    /// Panics if no chip of the component registered the column.
+    pub fn custom_preprocessed_column_eval(&self, id: &str) -> E::F {
+        self.custom_preprocessed_evals
+            .iter()
+            .find(|(custom_id, _)| *custom_id == id)
+            .unwrap_or_else(|| panic!("preprocessed column {id} isn't registered"))
+            .1
+            .clone()
+    }
+
     // #[doc(hidden)]
     // pub fn preprocessed_column_eval_next_row<const N: usize>(
     //     &self,
@@ -99,7 +138,7 @@ impl<E: EvalAtRow> TraceEval<E> {
 /// Returns evaluations for a given column.
 ///
 /// ```ignore
-/// let trace_eval = TraceEval::new(&mut eval);
+/// let trace_eval = TraceEval::for_component(&mut eval, &[], 0);
 /// let curr = trace_eval!(trace_eval, Column::IsAdd);
 /// eval.add_constraint(curr[0]);
 /// ```
@@ -114,7 +153,7 @@ pub(crate) use trace_eval;
 /// Returns evaluations for a given column on the next row.
 ///
 /// ```ignore
-/// let trace_eval_next_row = TraceEval::new(&mut eval);
+/// let trace_eval_next_row = TraceEval::for_component(&mut eval, &[], 0);
 /// let next = trace_eval_next_row!(trace_eval, Column::IsPadding);
 /// eval.add_constraint(next[0]);
 /// ```
@@ -129,7 +168,7 @@ pub(crate) use trace_eval_next_row;
 /// Returns evaluations for a given column in preprocessed trace.
 ///
 /// ```ignore
-/// let trace_eval = TraceEval::new(&mut eval);
+/// let trace_eval = TraceEval::for_component(&mut eval, &[], 0);
 /// let curr_pc = trace_eval!(trace_eval, Column::Pc);
 /// let is_first = preprocessed_trace_eval!(trace_eval, PreprocessedColumn::IsFirst);
 /// for i in 0..WORD_SIZE {
@@ -147,7 +186,7 @@ pub(crate) use preprocessed_trace_eval;
 // /// Returns evaluations for a given column in preprocessed trace.
 // ///
 // /// ```ignore
-// /// let trace_eval = TraceEval::new(&mut eval);
+// /// let trace_eval = TraceEval::for_component(&mut eval, &[], 0);
 // /// let curr_pc = trace_eval!(trace_eval, Column::Pc);
 // /// // When the next row has IsFirst, the current row is the last row.
 // /// let is_last = preprocessed_trace_eval_next_row!(trace_eval, PreprocessedColumn::IsFirst);
@@ -166,7 +205,7 @@ pub(crate) use preprocessed_trace_eval;
 /// Returns evaluations for a given column in program trace.
 ///
 /// ```ignore
-/// let trace_eval = TraceEval::new(&mut eval);
+/// let trace_eval = TraceEval::for_component(&mut eval, &[], 0);
 /// let curr_pc = trace_eval!(trace_eval, Column::Pc);
 /// let program_flag = program_trace_eval!(trace_eval, ProgramColumn::PrgMemoryFlag);
 /// for i in 0..WORD_SIZE {
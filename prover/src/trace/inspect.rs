@@ -0,0 +1,99 @@
+//! Named view of a single row of the main trace, for debugging chips.
+
+use std::fmt;
+
+use stwo_prover::core::fields::m31::BaseField;
+
+use crate::column::Column;
+
+/// Values of every main trace column at a row, grouped by [`Column`].
+///
+/// Obtained from [`super::TracesBuilder::inspect_row`] or [`super::FinalizedTraces::inspect_row`],
+/// the [`fmt::Display`] implementation prints one column per line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowView {
+    row: usize,
+    values: Vec<(Column, Vec<BaseField>)>,
+}
+
+impl RowView {
+    /// Collects the values of all columns, `read` returns the value of a raw column index.
+    pub(crate) fn new(row: usize, read: impl Fn(usize) -> BaseField) -> Self {
+        let values = Column::ALL_VARIANTS
+            .iter()
+            .map(|&col| {
+                (
+                    col,
+                    (col.offset()..col.offset() + col.size())
+                        .map(&read)
+                        .collect(),
+                )
+            })
+            .collect();
+        Self { row, values }
+    }
+
+    /// Index of the row in the trace, the row of the step at clock `clk` is `clk - 1`.
+    pub fn row(&self) -> usize {
+        self.row
+    }
+
+    /// Values of `col` at the row, one per limb.
+    pub fn get(&self, col: Column) -> &[BaseField] {
+        let (_, values) = &self.values[Column::ALL_VARIANTS
+            .iter()
+            .position(|&c| c == col)
+            .expect("column is a variant")];
+        values
+    }
+
+    /// Columns with their values, in layout order.
+    pub fn iter(&self) -> impl Iterator<Item = (Column, &[BaseField])> + '_ {
+        self.values
+            .iter()
+            .map(|(col, values)| (*col, values.as_slice()))
+    }
+}
+
+impl fmt::Display for RowView {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "row {} (clk {})", self.row, self.row + 1)?;
+        for (col, values) in self.iter() {
+            match values {
+                [value] => writeln!(f, "  {col:?}: {value}")?,
+                values => {
+                    let limbs: Vec<String> = values.iter().map(ToString::to_string).collect();
+                    writeln!(f, "  {col:?}: [{}]", limbs.join(", "))?
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::trace::TracesBuilder;
+
+    #[test]
+    fn inspect_row_before_and_after_finalize() {
+        let mut traces = TracesBuilder::new(6);
+        traces.fill_columns_bytes(5, &[1, 2, 3, 4], Column::ValueA);
+        traces.fill_columns_bytes(5, &[1], Column::IsAdd);
+
+        let row = traces.inspect_row(5);
+        assert_eq!(
+            row.get(Column::ValueA),
+            [1u32, 2, 3, 4].map(BaseField::from)
+        );
+        assert_eq!(row.get(Column::IsAdd), [BaseField::from(1u32)]);
+        assert_eq!(row.get(Column::ValueB), [BaseField::from(0u32); 4]);
+        let printed = row.to_string();
+        assert!(printed.starts_with("row 5 (clk 6)"));
+        assert!(printed.contains("ValueA: [1, 2, 3, 4]"));
+        assert!(printed.contains("IsAdd: 1"));
+
+        assert_eq!(traces.finalize().inspect_row(5), row);
+    }
+}
@@ -0,0 +1,193 @@
+//! Parallel generation of the interaction trace.
+//!
+//! stwo's [`LogupTraceGenerator`] builds the columns of a component one after another. Chips instead fill
+//! their logup columns into a [`LogupTraceBuilder`] of their own, so that the chips of a machine can fill
+//! them concurrently. The fractions of all columns are then computed in parallel chunks, and appended in
+//! the order of the chips to the generator of the component, which computes the running sum.
+//!
+//! The builder keeps numerators and denominators of every column until the end, which takes twice the
+//! memory of the finished columns.
+
+use num_traits::{One, Zero};
+use rayon::prelude::*;
+use stwo_prover::{
+    constraint_framework::logup::LogupTraceGenerator,
+    core::{
+        backend::simd::{m31::LOG_N_LANES, qm31::PackedSecureField},
+        fields::FieldExpOps,
+    },
+};
+
+/// Number of packed rows inverted together, the unit of parallelism within a column.
+const CHUNK_SIZE: usize = 1 << 10;
+
+/// Logup columns of a chip, see [`crate::traits::MachineChip::fill_interaction_trace`].
+#[derive(Debug, Clone)]
+pub struct LogupTraceBuilder {
+    log_size: u32,
+    columns: Vec<LogupColumn>,
+}
+
+#[derive(Debug, Clone)]
+struct LogupColumn {
+    numerators: Vec<PackedSecureField>,
+    denominators: Vec<PackedSecureField>,
+}
+
+impl LogupTraceBuilder {
+    pub fn new(log_size: u32) -> Self {
+        Self {
+            log_size,
+            columns: Vec::new(),
+        }
+    }
+
+    pub fn log_size(&self) -> u32 {
+        self.log_size
+    }
+
+    /// Starts a new column, rows that aren't written are zero.
+    pub fn new_col(&mut self) -> LogupColBuilder<'_> {
+        let n_vec_rows = 1 << (self.log_size - LOG_N_LANES);
+        LogupColBuilder {
+            builder: self,
+            column: LogupColumn {
+                numerators: vec![PackedSecureField::zero(); n_vec_rows],
+                denominators: vec![PackedSecureField::one(); n_vec_rows],
+            },
+        }
+    }
+
+    /// Appends the columns of `other` after the columns of `self`.
+    pub(crate) fn extend(&mut self, other: Self) {
+        assert_eq!(self.log_size, other.log_size, "log_size mismatch");
+        self.columns.extend(other.columns);
+    }
+
+    /// Computes the fractions of all columns and appends them to `logup_trace_gen`.
+    pub(crate) fn write_into(self, logup_trace_gen: &mut LogupTraceGenerator) {
+        let columns: Vec<Vec<PackedSecureField>> = self
+            .columns
+            .into_par_iter()
+            .map(LogupColumn::into_fractions)
+            .collect();
+        for fractions in columns {
+            let mut logup_col_gen = logup_trace_gen.new_col();
+            for (vec_row, fraction) in fractions.into_iter().enumerate() {
+                logup_col_gen.write_frac(vec_row, fraction, PackedSecureField::one());
+            }
+            logup_col_gen.finalize_col();
+        }
+    }
+}
+
+/// Column being filled, has the same interface as stwo's `LogupColGenerator`.
+pub struct LogupColBuilder<'a> {
+    builder: &'a mut LogupTraceBuilder,
+    column: LogupColumn,
+}
+
+impl LogupColBuilder<'_> {
+    /// Sets the packed rows at `vec_row` to `numerator / denominator`.
+    pub fn write_frac(
+        &mut self,
+        vec_row: usize,
+        numerator: PackedSecureField,
+        denominator: PackedSecureField,
+    ) {
+        self.column.numerators[vec_row] = numerator;
+        self.column.denominators[vec_row] = denominator;
+    }
+
+    pub fn finalize_col(self) {
+        self.builder.columns.push(self.column);
+    }
+}
+
+impl LogupColumn {
+    fn into_fractions(self) -> Vec<PackedSecureField> {
+        let mut fractions = self.denominators;
+        fractions
+            .par_chunks_mut(CHUNK_SIZE)
+            .zip(self.numerators.par_chunks(CHUNK_SIZE))
+            .for_each(|(chunk, numerators)| {
+                batch_inverse_in_place(chunk);
+                for (fraction, &numerator) in chunk.iter_mut().zip(numerators) {
+                    *fraction *= numerator;
+                }
+            });
+        fractions
+    }
+}
+
+/// Montgomery's trick, inverts all `values` with a single field inversion.
+fn batch_inverse_in_place(values: &mut [PackedSecureField]) {
+    let mut prefix_products = Vec::with_capacity(values.len());
+    let mut product = PackedSecureField::one();
+    for &value in values.iter() {
+        prefix_products.push(product);
+        product *= value;
+    }
+
+    let mut inverse = product.inverse();
+    for (value, prefix_product) in values.iter_mut().zip(prefix_products).rev() {
+        let next_inverse = inverse * *value;
+        *value = inverse * prefix_product;
+        inverse = next_inverse;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use stwo_prover::core::{
+        backend::simd::m31::PackedBaseField,
+        fields::{m31::BaseField, qm31::SecureField},
+    };
+
+    #[test]
+    fn test_batch_inverse() {
+        let mut values: Vec<PackedSecureField> = (1..=2 * CHUNK_SIZE as u32 + 3)
+            .map(|i| PackedBaseField::broadcast(BaseField::from(i)).into())
+            .collect();
+        let expected: Vec<_> = values
+            .iter()
+            .map(|value| value.inverse().to_array())
+            .collect();
+        batch_inverse_in_place(&mut values);
+        let inverses: Vec<_> = values.iter().map(|value| value.to_array()).collect();
+        assert_eq!(inverses, expected);
+    }
+
+    #[test]
+    fn test_fractions_of_unwritten_rows_are_zero() {
+        let mut builder = LogupTraceBuilder::new(LOG_N_LANES + 2);
+        let mut logup_col_gen = builder.new_col();
+        let secure = |value: u32| SecureField::from(BaseField::from(value));
+        logup_col_gen.write_frac(
+            1,
+            PackedSecureField::broadcast(secure(2)),
+            PackedSecureField::broadcast(secure(4)),
+        );
+        logup_col_gen.finalize_col();
+
+        let fractions: Vec<_> = builder
+            .columns
+            .pop()
+            .unwrap()
+            .into_fractions()
+            .iter()
+            .map(|fraction| fraction.to_array()[0])
+            .collect();
+        assert_eq!(
+            fractions,
+            [
+                SecureField::zero(),
+                secure(2).inverse(),
+                SecureField::zero(),
+                SecureField::zero()
+            ]
+        );
+    }
+}
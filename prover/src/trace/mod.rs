@@ -1,4 +1,8 @@
+pub mod audit;
+pub mod cross_check;
 pub mod eval;
+pub mod inspect;
+pub mod logup;
 pub mod preprocessed;
 pub mod program;
 pub mod program_trace;
@@ -8,6 +12,10 @@ pub mod trace_builder;
 pub mod utils;
 pub mod utils_external;
 
-pub use preprocessed::PreprocessedTraces;
-pub use program::{BoolWord, ProgramStep, Word, WordWithEffectiveBits};
+pub use audit::{audit_rows, RowDisagreement};
+pub use cross_check::cross_check_trace;
+pub use inspect::RowView;
+pub use logup::LogupTraceBuilder;
+pub use preprocessed::{CustomPreprocessedColumn, PreprocessedTraces};
+pub use program::{BoolWord, BoolWordN, ProgramStep, Word, WordN, WordWithEffectiveBits};
 pub use trace_builder::{FinalizedTraces, TracesBuilder};
@@ -11,7 +11,10 @@ use stwo_prover::core::{
 
 use nexus_vm::WORD_SIZE;
 
-use super::{utils::finalize_columns, TracesBuilder};
+use super::{
+    utils::{finalize_columns, row_values},
+    TracesBuilder,
+};
 use crate::column::PreprocessedColumn;
 
 /// Preprocessed (constant) traces builder corresponding to [`PreprocessedColumn`].
@@ -105,10 +108,26 @@ impl PreprocessedBuilder {
         let log_size = self.log_size();
         let cols = finalize_columns(self.0.cols);
 
-        PreprocessedTraces { cols, log_size }
+        PreprocessedTraces {
+            cols,
+            log_size,
+            custom_ids: Vec::new(),
+        }
     }
 }
 
+/// A preprocessed column contributed by a chip, e.g. a lookup table, see [`crate::traits::MachineChip::preprocessed_columns`].
+///
+/// Columns are committed after [`PreprocessedColumn`]s in the order chips register them, chips read them with
+/// [`crate::trace::eval::TraceEval::custom_preprocessed_column_eval`].
+#[derive(Debug, Clone, Copy)]
+pub struct CustomPreprocessedColumn {
+    /// Unique id of the column.
+    pub id: &'static str,
+    /// Values of the column for a trace of `2.pow(log_size)` rows, in row order.
+    pub generate: fn(log_size: u32) -> Vec<BaseField>,
+}
+
 /// Preprocessed (constant) traces corresponding to [`PreprocessedColumn`].
 ///
 /// These columns are predefined and must not be altered during trace generation.
@@ -116,6 +135,7 @@ impl PreprocessedBuilder {
 pub struct PreprocessedTraces {
     cols: Vec<BaseColumn>,
     log_size: u32,
+    custom_ids: Vec<&'static str>,
 }
 
 impl PreprocessedTraces {
@@ -125,6 +145,32 @@ impl PreprocessedTraces {
         PreprocessedBuilder::new(log_size).finalize()
     }
 
+    /// Returns [`PreprocessedColumn`]s followed by `custom_columns` contributed by chips.
+    pub fn with_custom_columns(log_size: u32, custom_columns: &[CustomPreprocessedColumn]) -> Self {
+        let mut builder = PreprocessedBuilder::new(log_size);
+        let mut custom_ids = Vec::with_capacity(custom_columns.len());
+        for column in custom_columns {
+            assert!(
+                !custom_ids.contains(&column.id),
+                "preprocessed column {} is registered twice",
+                column.id
+            );
+            let values = (column.generate)(log_size);
+            assert_eq!(
+                values.len(),
+                builder.num_rows(),
+                "preprocessed column {} has a wrong length",
+                column.id
+            );
+            builder.0.cols.push(values);
+            custom_ids.push(column.id);
+        }
+        Self {
+            custom_ids,
+            ..builder.finalize()
+        }
+    }
+
     pub fn log_size(&self) -> u32 {
         self.log_size
     }
@@ -137,6 +183,20 @@ impl PreprocessedTraces {
         std::array::from_fn(|i| &self.cols[col.offset() + i])
     }
 
+    /// Values of [`PreprocessedColumn`]s followed by custom columns at `row`, as numbered before finalization.
+    pub(crate) fn row_values(&self, row: usize) -> Vec<BaseField> {
+        row_values(&self.cols, self.log_size, row)
+    }
+
+    /// Returns the column contributed by a chip with the given id, see [`CustomPreprocessedColumn`].
+    pub fn get_custom_column(&self, id: &str) -> Option<&BaseColumn> {
+        let idx = self
+            .custom_ids
+            .iter()
+            .position(|&custom_id| custom_id == id)?;
+        Some(&self.cols[PreprocessedColumn::COLUMNS_NUM + idx])
+    }
+
     pub fn into_circle_evaluation(
         self,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
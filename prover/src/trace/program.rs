@@ -15,17 +15,34 @@ pub struct ProgramStep {
     pub(crate) step: Step,
 }
 
+/// Represents an `N`-byte word as `N` 8-bit limbs in little-endian order.
+///
+/// Only the carry and borrow helpers ([`crate::chips::add_with_carries`] and
+/// [`crate::chips::subtract_with_borrow`]) are generic over the number of limbs. The chips
+/// themselves, Add, Sub, Slt and the branches included, and their columns still assume 32-bit
+/// words.
+pub type WordN<const N: usize> = [u8; N];
+/// Represents an `N`-byte word as `N` 1-bit limbs in little-endian order.
+/// It is used for carry/borrow bits.
+pub type BoolWordN<const N: usize> = [bool; N];
+
 /// Represents a 32-bit word as 4 8-bit limbs in little-endian order
-pub type Word = [u8; WORD_SIZE];
+pub type Word = WordN<WORD_SIZE>;
 /// Represents a 32-bit word as 4 1-bit limbs in little-endian order
 /// It is used for carry/borrow bits.
-pub type BoolWord = [bool; WORD_SIZE];
+pub type BoolWord = BoolWordN<WORD_SIZE>;
 
 /// Represents a 32-bit word as 4 8-bit limbs in little-endian order,
 /// along with the count of effective bits.
 pub type WordWithEffectiveBits = (Word, usize);
 
 impl ProgramStep {
+    /// Creates a step from the register file as it was before executing `step`.
+    #[cfg(any(test, feature = "testing"))]
+    pub fn new(regs: RegisterFile, step: Step) -> Self {
+        Self { regs, step }
+    }
+
     /// Returns the value of the first operand (rd or rs1) as bytes.
     /// Always a register value in range u32.
     pub(crate) fn get_value_a(&self) -> Word {
@@ -80,9 +97,13 @@ impl ProgramStep {
         if let Some(syscall_value) = self.get_syscall_code() {
             let syscall_number = SyscallCode::from(syscall_value);
             match syscall_number {
-                SyscallCode::ReadFromPrivateInput | SyscallCode::OverwriteHeapPointer => {
-                    Register::X10
-                }
+                SyscallCode::ReadFromPrivateInput
+                | SyscallCode::OverwriteHeapPointer
+                | SyscallCode::QueryMemoryLayout
+                | SyscallCode::HeapStats
+                | SyscallCode::ReadClock
+                | SyscallCode::Alloc
+                | SyscallCode::Dealloc => Register::X10,
                 SyscallCode::OverwriteStackPointer => Register::X2,
                 _ => Register::X0,
             }
@@ -1,4 +1,5 @@
 use num_traits::Zero;
+use serde::{Deserialize, Serialize};
 use stwo_prover::core::{
     backend::simd::{column::BaseColumn, m31::LOG_N_LANES, SimdBackend},
     fields::m31::BaseField,
@@ -10,10 +11,10 @@ use stwo_prover::core::{
 };
 
 use super::{
-    utils::{finalize_columns, IntoBaseFields},
+    utils::{finalize_columns, row_values, IntoBaseFields},
     TracesBuilder,
 };
-use crate::column::ProgramColumn;
+use crate::{column::ProgramColumn, error::FinalMemoryError};
 
 use nexus_vm::{
     emulator::{MemoryInitializationEntry, ProgramInfo, ProgramMemoryEntry, PublicOutputEntry},
@@ -36,12 +37,30 @@ impl ProgramTracesBuilder {
         init_memory: &[MemoryInitializationEntry],
         exit_code: &[PublicOutputEntry],
         output_memory: &[PublicOutputEntry],
+    ) -> Self {
+        let program_memory = ProgramInfo {
+            initial_pc: program_memory.initial_pc,
+            program: program_memory.program.iter().copied(),
+        };
+        Self::from_program_info(
+            log_size,
+            program_memory,
+            init_memory,
+            exit_code,
+            output_memory,
+        )
+    }
+
+    /// Same as [`Self::new`], but consumes the program memory entries from any iterator, e.g.
+    /// [`ProgramInfo::stream_from_elf`], without collecting them first.
+    pub fn from_program_info<I: IntoIterator<Item = ProgramMemoryEntry>>(
+        log_size: u32,
+        program_memory: ProgramInfo<I>,
+        init_memory: &[MemoryInitializationEntry],
+        exit_code: &[PublicOutputEntry],
+        output_memory: &[PublicOutputEntry],
     ) -> Self {
         assert!(log_size >= LOG_N_LANES);
-        assert!(
-            program_memory.program.len() <= 1 << log_size,
-            "Program is longer than program trace size"
-        );
         assert!(init_memory.len() + exit_code.len() + output_memory.len() <= 1 << log_size);
 
         let cols = vec![vec![BaseField::zero(); 1 << log_size]; ProgramColumn::COLUMNS_NUM];
@@ -59,25 +78,29 @@ impl ProgramTracesBuilder {
                 pc,
                 instruction_word,
             },
-        ) in program_memory.program.iter().enumerate()
+        ) in program_memory.program.into_iter().enumerate()
         {
+            assert!(
+                row_idx < 1 << log_size,
+                "Program is longer than program trace size"
+            );
             if row_idx == 0 {
-                ret.pc_offset = *pc;
+                ret.pc_offset = pc;
             }
             ret.num_instructions += 1;
             assert_eq!(
                 row_idx * WORD_SIZE + ret.pc_offset as usize,
-                *pc as usize,
+                pc as usize,
                 "The program is assumed to be in contiguous memory."
             );
-            let (pc_low, pc_high) = (*pc & 0xFFFF, *pc >> 16);
+            let (pc_low, pc_high) = (pc & 0xFFFF, pc >> 16);
             ret.fill_program_columns(
                 row_idx,
                 [pc_low, pc_high].map(BaseField::from),
                 ProgramColumn::PrgMemoryPc,
             );
             let (instruction_low, instruction_high) =
-                (*instruction_word & 0xFFFF, *instruction_word >> 16);
+                (instruction_word & 0xFFFF, instruction_word >> 16);
             ret.fill_program_columns(
                 row_idx,
                 [instruction_low, instruction_high].map(BaseField::from),
@@ -89,13 +112,20 @@ impl ProgramTracesBuilder {
         let init_memory_len = init_memory.len();
         let exit_code_len = exit_code.len();
 
-        for (row_idx, MemoryInitializationEntry { address, value }) in
-            init_memory.iter().enumerate()
+        for (
+            row_idx,
+            MemoryInitializationEntry {
+                address,
+                value,
+                read_only,
+            },
+        ) in init_memory.iter().enumerate()
         {
             ret.fill_program_columns(row_idx, *address, ProgramColumn::PublicRamAddr);
 
             ret.fill_program_columns(row_idx, true, ProgramColumn::PublicInitialMemoryFlag);
             ret.fill_program_columns(row_idx, *value, ProgramColumn::PublicInitialMemoryValue);
+            ret.fill_program_columns(row_idx, *read_only, ProgramColumn::PublicReadOnlyFlag);
         }
         let offset = init_memory_len;
 
@@ -117,13 +147,47 @@ impl ProgramTracesBuilder {
         ret
     }
 
-    #[cfg(test)]
-    pub(crate) fn new_with_empty_memory(log_size: u32, program_memory: &ProgramInfo) -> Self {
+    /// Fills the final content of the RAM claimed by the proof, see [`crate::Proof::final_memory_digest`].
+    ///
+    /// `final_memory` must list every address under the memory check, sorted by address.
+    pub fn fill_final_memory(
+        &mut self,
+        final_memory: &[PublicOutputEntry],
+    ) -> Result<(), FinalMemoryError> {
+        let num_rows = 1 << self.traces_builder.log_size;
+        if final_memory.len() > num_rows {
+            return Err(FinalMemoryError::TooLarge {
+                len: final_memory.len(),
+                num_rows,
+            });
+        }
+        if let Some(pair) = final_memory
+            .windows(2)
+            .find(|pair| pair[0].address >= pair[1].address)
+        {
+            return Err(FinalMemoryError::Unsorted {
+                address: pair[1].address,
+            });
+        }
+
+        for row_idx in 0..num_rows {
+            self.fill_program_columns(row_idx, true, ProgramColumn::PublicFinalMemoryMode);
+        }
+        for (row_idx, PublicOutputEntry { address, value }) in final_memory.iter().enumerate() {
+            self.fill_program_columns(row_idx, *address, ProgramColumn::PublicFinalMemoryAddr);
+            self.fill_program_columns(row_idx, true, ProgramColumn::PublicFinalMemoryFlag);
+            self.fill_program_columns(row_idx, *value, ProgramColumn::PublicFinalMemoryValue);
+        }
+        Ok(())
+    }
+
+    #[cfg(any(test, feature = "testing"))]
+    pub fn new_with_empty_memory(log_size: u32, program_memory: &ProgramInfo) -> Self {
         Self::new(log_size, program_memory, &[], &[], &[])
     }
 
-    #[cfg(test)]
-    pub(crate) fn dummy(log_size: u32) -> Self {
+    #[cfg(any(test, feature = "testing"))]
+    pub fn dummy(log_size: u32) -> Self {
         Self::new_with_empty_memory(log_size, &ProgramInfo::dummy())
     }
 
@@ -167,8 +231,9 @@ impl ProgramTracesBuilder {
 /// These columns contain the whole program and the first program counter. They don't depend on the runtime information.
 /// Moreover, the publicly known initial memory and the public output are included in the program trace. These depend on the runtime information.
 /// The commitment to the program trace will be checked by the verifier.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProgramTraces {
+    #[serde(with = "super::utils::base_columns_serde")]
     cols: Vec<BaseColumn>,
     log_size: u32,
 }
@@ -187,6 +252,11 @@ impl ProgramTraces {
         std::array::from_fn(|i| &self.cols[col.offset() + i])
     }
 
+    /// Values of all columns at `row`, as numbered before finalization.
+    pub(crate) fn row_values(&self, row: usize) -> Vec<BaseField> {
+        row_values(&self.cols, self.log_size, row)
+    }
+
     pub fn into_circle_evaluation(
         self,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
@@ -1,8 +1,9 @@
 use nexus_common::riscv::register::NUM_REGISTERS;
+use serde::{Deserialize, Serialize};
 
 // This file contains utilities for register memory checking
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct RegisterMemCheckSideNote {
     pub(crate) last_access_timestamp: [u32; NUM_REGISTERS],
     pub(crate) last_access_value: [u32; NUM_REGISTERS],
@@ -1,6 +1,8 @@
 // This file defines the side note structures for main trace filling
 
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
+
+use serde::{Deserialize, Serialize};
 
 use nexus_vm::{
     emulator::{InternalView, MemoryInitializationEntry, PublicOutputEntry, View},
@@ -9,6 +11,7 @@ use nexus_vm::{
 
 use super::{program_trace::ProgramTracesBuilder, regs::RegisterMemCheckSideNote};
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ProgramMemCheckSideNote {
     /// For each Pc, the number of accesses to that Pc so far (None if never)
     pub(crate) last_access_counter: BTreeMap<u32, u32>,
@@ -19,12 +22,14 @@ pub struct ProgramMemCheckSideNote {
 }
 
 /// Side note for committing to the final RW memory content and for computing the final read digest
-#[derive(Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct ReadWriteMemCheckSideNote {
     /// u32 is the access counter, u8 is the value of the byte
     pub(crate) last_access: BTreeMap<u32, (u32, u8)>,
     /// Public output with the exit code.
     pub(crate) public_output: BTreeMap<u32, u8>,
+    /// Addresses of the read-only data.
+    pub(crate) read_only: BTreeSet<u32>,
 }
 
 impl ReadWriteMemCheckSideNote {
@@ -38,9 +43,17 @@ impl ReadWriteMemCheckSideNote {
         exit_code: &[PublicOutputEntry],
     ) -> Self {
         let mut ret: Self = Default::default();
-        for MemoryInitializationEntry { address, value } in init_memory {
+        for MemoryInitializationEntry {
+            address,
+            value,
+            read_only,
+        } in init_memory
+        {
             let old = ret.last_access.insert(*address, (0, *value));
             assert!(old.is_none(), "Duplicate memory initialization entry");
+            if *read_only {
+                ret.read_only.insert(*address);
+            }
         }
         let mut public_output: BTreeMap<u32, u8> = public_output
             .iter()
@@ -54,6 +67,17 @@ impl ReadWriteMemCheckSideNote {
         ret.public_output = public_output;
         ret
     }
+
+    /// Final value of every address under the memory check, sorted by address.
+    pub(crate) fn final_memory(&self) -> Vec<PublicOutputEntry> {
+        self.last_access
+            .iter()
+            .map(|(address, (_, value))| PublicOutputEntry {
+                address: *address,
+                value: *value,
+            })
+            .collect()
+    }
 }
 
 impl ProgramMemCheckSideNote {
@@ -76,11 +100,35 @@ impl ProgramMemCheckSideNote {
 }
 
 /// Side note for Range check {0,.., LEN - 1}
+#[derive(Clone, Serialize, Deserialize)]
 pub struct RangeCheckSideNote<const LEN: usize> {
     /// `multiplicity[i]` is the number how many times value `i` is checked
+    #[serde(with = "multiplicity_serde")]
     pub(crate) multiplicity: [u32; LEN],
 }
 
+// serde only implements its traits for arrays of up to 32 elements
+mod multiplicity_serde {
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub(super) fn serialize<S: Serializer, const LEN: usize>(
+        multiplicity: &[u32; LEN],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        multiplicity.as_slice().serialize(serializer)
+    }
+
+    pub(super) fn deserialize<'de, D: Deserializer<'de>, const LEN: usize>(
+        deserializer: D,
+    ) -> Result<[u32; LEN], D::Error> {
+        let multiplicity = Vec::<u32>::deserialize(deserializer)?;
+        let len = multiplicity.len();
+        multiplicity
+            .try_into()
+            .map_err(|_| D::Error::invalid_length(len, &"one multiplicity per checked value"))
+    }
+}
+
 impl<const LEN: usize> Default for RangeCheckSideNote<LEN> {
     fn default() -> Self {
         Self {
@@ -90,13 +138,14 @@ impl<const LEN: usize> Default for RangeCheckSideNote<LEN> {
 }
 
 /// Side note for bitwise operations. Each multiplicity counter stores (b * 16 + c) as a key.
-#[derive(Default)]
+#[derive(Clone, Default, Serialize, Deserialize)]
 pub struct BitOpSideNote {
     pub(crate) multiplicity_and: BTreeMap<u8, u32>,
     pub(crate) multiplicity_or: BTreeMap<u8, u32>,
     pub(crate) multiplicity_xor: BTreeMap<u8, u32>,
 }
 
+#[derive(Clone, Serialize, Deserialize)]
 pub struct SideNote {
     pub program_mem_check: ProgramMemCheckSideNote,
     pub(crate) register_mem_check: RegisterMemCheckSideNote,
@@ -1,6 +1,7 @@
 use itertools::Itertools;
 use nexus_vm::WORD_SIZE;
 use num_traits::Zero;
+use serde::{Deserialize, Serialize};
 use stwo_prover::core::{
     backend::simd::{column::BaseColumn, m31::LOG_N_LANES, SimdBackend},
     fields::m31::BaseField,
@@ -8,10 +9,14 @@ use stwo_prover::core::{
         circle::{CanonicCoset, CircleEvaluation},
         BitReversedOrder,
     },
+    utils::{bit_reverse_index, coset_index_to_circle_domain_index},
     ColumnVec,
 };
 
-use super::utils::{finalize_columns, IntoBaseFields};
+use super::{
+    inspect::RowView,
+    utils::{finalize_columns, IntoBaseFields},
+};
 use crate::column::Column;
 
 /// Main ([`stwo_prover::constraint_framework::ORIGINAL_TRACE_IDX`]) trace builder which implements
@@ -71,6 +76,12 @@ impl TracesBuilder {
         })
     }
 
+    /// Returns the values of all columns at `row`, e.g. to print what was filled for a step.
+    pub fn inspect_row(&self, row: usize) -> RowView {
+        assert!(row < self.num_rows(), "row out of bounds");
+        RowView::new(row, |idx| self.cols[idx][row])
+    }
+
     /// Fills four columns with u32 value.
     pub(crate) fn fill_columns<const N: usize, T: IntoBaseFields<N>>(
         &mut self,
@@ -139,8 +150,9 @@ impl TracesBuilder {
 }
 
 /// Finalized main trace that stores columns in (bit reversed) circle domain order.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FinalizedTraces {
+    #[serde(with = "super::utils::base_columns_serde")]
     cols: Vec<BaseColumn>,
     log_size: u32,
 }
@@ -155,6 +167,16 @@ impl FinalizedTraces {
         std::array::from_fn(|i| &self.cols[col.offset() + i])
     }
 
+    /// Returns the values of all columns at `row`, as numbered before finalization.
+    pub fn inspect_row(&self, row: usize) -> RowView {
+        assert!(row < 1 << self.log_size, "row out of bounds");
+        let idx = bit_reverse_index(
+            coset_index_to_circle_domain_index(row, self.log_size),
+            self.log_size,
+        );
+        RowView::new(row, |col| self.cols[col].at(idx))
+    }
+
     pub fn into_circle_evaluation(
         self,
     ) -> ColumnVec<CircleEvaluation<SimdBackend, BaseField, BitReversedOrder>> {
@@ -2,6 +2,7 @@ use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterato
 use stwo_prover::core::{
     backend::simd::{column::BaseColumn, SimdBackend},
     fields::m31::BaseField,
+    utils::{bit_reverse_index, coset_index_to_circle_domain_index},
 };
 
 use nexus_vm::WORD_SIZE;
@@ -13,6 +14,13 @@ use super::{
     utils_external::coset_order_to_circle_domain_order,
 };
 
+/// Values of the finalized `cols` at `row`, as numbered before finalization.
+pub(crate) fn row_values(cols: &[BaseColumn], log_size: u32, row: usize) -> Vec<BaseField> {
+    assert!(row < 1 << log_size, "row out of bounds");
+    let idx = bit_reverse_index(coset_index_to_circle_domain_index(row, log_size), log_size);
+    cols.iter().map(|col| col.at(idx)).collect()
+}
+
 /// Trait for BaseField representation
 pub(crate) trait IntoBaseFields<const N: usize> {
     fn into_base_fields(self) -> [BaseField; N];
@@ -91,6 +99,43 @@ impl FromBaseFields<WORD_SIZE> for u32 {
     }
 }
 
+/// (De)serializes finalized columns as their values, stwo's columns don't implement serde.
+pub(crate) mod base_columns_serde {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use stwo_prover::core::{
+        backend::simd::column::BaseColumn,
+        fields::m31::{BaseField, P},
+    };
+
+    pub(crate) fn serialize<S: Serializer>(
+        cols: &[BaseColumn],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(cols.iter().map(|col| {
+            col.as_slice()
+                .iter()
+                .map(|value| value.0)
+                .collect::<Vec<u32>>()
+        }))
+    }
+
+    pub(crate) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<BaseColumn>, D::Error> {
+        Vec::<Vec<u32>>::deserialize(deserializer)?
+            .into_iter()
+            .map(|values| {
+                if values.iter().any(|&value| value >= P) {
+                    return Err(D::Error::custom("column value out of the field"));
+                }
+                Ok(BaseColumn::from_iter(
+                    values.into_iter().map(BaseField::from),
+                ))
+            })
+            .collect()
+    }
+}
+
 pub fn finalize_columns(columns: Vec<Vec<BaseField>>) -> Vec<BaseColumn> {
     let mut ret = Vec::with_capacity(columns.len());
     columns
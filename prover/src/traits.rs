@@ -1,4 +1,5 @@
 use impl_trait_for_tuples::impl_for_tuples;
+use rayon::prelude::*;
 
 use num_traits::Zero;
 use stwo_prover::{
@@ -13,10 +14,14 @@ use stwo_prover::{
 };
 
 use crate::{
-    components::AllLookupElements,
+    components::{AllLookupElements, LOG_CONSTRAINT_DEGREE},
     trace::{
-        eval::TraceEval, preprocessed::PreprocessedTraces, program_trace::ProgramTraces,
-        sidenote::SideNote, FinalizedTraces, ProgramStep, TracesBuilder,
+        eval::TraceEval,
+        logup::LogupTraceBuilder,
+        preprocessed::{CustomPreprocessedColumn, PreprocessedTraces},
+        program_trace::ProgramTraces,
+        sidenote::SideNote,
+        FinalizedTraces, ProgramStep, TracesBuilder,
     },
 };
 
@@ -26,6 +31,14 @@ pub trait ExecuteChip {
     fn execute(program_step: &ProgramStep) -> Self::ExecutionResult;
 }
 
+type FillInteractionTrace = fn(
+    &mut LogupTraceBuilder,
+    &FinalizedTraces,
+    &PreprocessedTraces,
+    &ProgramTraces,
+    &AllLookupElements,
+);
+
 pub trait MachineChip {
     /// Called on each row during main trace generation.
     fn fill_main_trace(
@@ -48,8 +61,11 @@ pub trait MachineChip {
     ///
     /// The signature of this method is intentionally similar to `gen_interaction_trace()` in stwo examples.
     /// This method isn't called row-by-row because stwo logup library fills 16 rows of the interaction trace at a time.
+    ///
+    /// Chips of a tuple fill their columns concurrently, each into a builder of its own, the columns are
+    /// appended in the order of the chips.
     fn fill_interaction_trace(
-        _logup_trace_gen: &mut LogupTraceGenerator,
+        _logup_trace_gen: &mut LogupTraceBuilder,
         _original_traces: &FinalizedTraces,
         _preprocessed_trace: &PreprocessedTraces,
         _program_traces: &ProgramTraces,
@@ -73,9 +89,28 @@ pub trait MachineChip {
     /// }
     /// ```
     fn draw_lookup_elements(_: &mut AllLookupElements, _: &mut impl Channel) {}
+
+    /// Preprocessed columns the chip needs in addition to [`crate::column::PreprocessedColumn`]s, e.g. lookup
+    /// tables of a precompile.
+    ///
+    /// The columns are generated and committed along with the preprocessed trace, the chip reads them in
+    /// [`Self::add_constraints`] with [`TraceEval::custom_preprocessed_column_eval`] and in
+    /// [`Self::fill_interaction_trace`] with [`PreprocessedTraces::get_custom_column`].
+    fn preprocessed_columns() -> Vec<CustomPreprocessedColumn> {
+        Vec::new()
+    }
+
+    /// Log2 of the highest degree of the constraints added in [`Self::add_constraints`].
+    ///
+    /// The constraints of all chips are evaluated on a domain blown up by the largest degree bound of the
+    /// machine, a chip with constraints of degree above 4 has to raise it. A higher bound makes proving slower
+    /// and lowers the largest supported trace for every chip of the machine.
+    fn log_constraint_degree() -> u32 {
+        LOG_CONSTRAINT_DEGREE
+    }
 }
 
-#[impl_for_tuples(1, 26)]
+#[impl_for_tuples(1, 30)]
 impl MachineChip for Tuple {
     fn fill_main_trace(
         traces: &mut TracesBuilder,
@@ -95,18 +130,50 @@ impl MachineChip for Tuple {
     }
 
     fn fill_interaction_trace(
-        logup_trace_gen: &mut LogupTraceGenerator,
+        logup_trace_gen: &mut LogupTraceBuilder,
         original_traces: &FinalizedTraces,
         preprocessed_traces: &PreprocessedTraces,
         program_traces: &ProgramTraces,
         lookup_elements: &AllLookupElements,
     ) {
-        for_tuples!( #( Tuple::fill_interaction_trace(logup_trace_gen, original_traces, preprocessed_traces, program_traces, lookup_elements); )* );
+        let mut fills: Vec<FillInteractionTrace> = Vec::new();
+        for_tuples!( #( fills.push(Tuple::fill_interaction_trace); )* );
+
+        let log_size = logup_trace_gen.log_size();
+        let chip_traces: Vec<LogupTraceBuilder> = fills
+            .into_par_iter()
+            .map(|fill| {
+                let mut chip_trace = LogupTraceBuilder::new(log_size);
+                fill(
+                    &mut chip_trace,
+                    original_traces,
+                    preprocessed_traces,
+                    program_traces,
+                    lookup_elements,
+                );
+                chip_trace
+            })
+            .collect();
+        for chip_trace in chip_traces {
+            logup_trace_gen.extend(chip_trace);
+        }
     }
 
     fn draw_lookup_elements(all_elements: &mut AllLookupElements, channel: &mut impl Channel) {
         for_tuples!( #( Tuple::draw_lookup_elements(all_elements, channel); )* );
     }
+
+    fn preprocessed_columns() -> Vec<CustomPreprocessedColumn> {
+        let mut columns = Vec::new();
+        for_tuples!( #( columns.extend(Tuple::preprocessed_columns()); )* );
+        columns
+    }
+
+    fn log_constraint_degree() -> u32 {
+        let mut log_degree = LOG_CONSTRAINT_DEGREE;
+        for_tuples!( #( log_degree = log_degree.max(Tuple::log_constraint_degree()); )* );
+        log_degree
+    }
 }
 
 pub fn generate_interaction_trace<C: MachineChip>(
@@ -121,13 +188,15 @@ pub fn generate_interaction_trace<C: MachineChip>(
     if lookup_elements.is_empty() {
         return (ColumnVec::new(), SecureField::zero());
     }
-    let mut logup_trace_gen = LogupTraceGenerator::new(original_traces.log_size());
+    let mut logup_trace_builder = LogupTraceBuilder::new(original_traces.log_size());
     C::fill_interaction_trace(
-        &mut logup_trace_gen,
+        &mut logup_trace_builder,
         original_traces,
         preprocessed_trace,
         program_traces,
         lookup_elements,
     );
+    let mut logup_trace_gen = LogupTraceGenerator::new(original_traces.log_size());
+    logup_trace_builder.write_into(&mut logup_trace_gen);
     logup_trace_gen.finalize_last()
 }
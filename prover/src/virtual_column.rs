@@ -8,9 +8,10 @@ use stwo_prover::{
 
 use crate::{
     column::Column::{
-        self, ImmC, IsAdd, IsAnd, IsAuipc, IsBeq, IsBge, IsBgeu, IsBlt, IsBltu, IsBne, IsEbreak,
-        IsEcall, IsJal, IsJalr, IsLb, IsLbu, IsLh, IsLhu, IsLui, IsLw, IsOr, IsSb, IsSh, IsSll,
-        IsSlt, IsSltu, IsSra, IsSrl, IsSub, IsSw, IsXor,
+        self, ImmC, IsAdd, IsAnd, IsAuipc, IsBeq, IsBge, IsBgeu, IsBlt, IsBltu, IsBne, IsDiv,
+        IsDivu, IsEbreak, IsEcall, IsJal, IsJalr, IsLb, IsLbu, IsLh, IsLhu, IsLui, IsLw, IsMul,
+        IsMulh, IsMulhsu, IsMulhu, IsOr, IsRem, IsRemu, IsSb, IsSh, IsSll, IsSlt, IsSltu, IsSra,
+        IsSrl, IsSub, IsSw, IsXor,
     },
     trace::{eval::trace_eval, eval::TraceEval, FinalizedTraces, TracesBuilder},
 };
@@ -65,8 +66,9 @@ impl<S: VirtualColumnForSum> VirtualColumn<1> for S {
 pub(crate) struct IsTypeR;
 
 impl IsTypeR {
-    const TYPE_R_OPS: [Column; 10] = [
-        IsAdd, IsSub, IsSlt, IsSltu, IsXor, IsOr, IsAnd, IsSll, IsSrl, IsSra,
+    const TYPE_R_OPS: [Column; 18] = [
+        IsAdd, IsSub, IsSlt, IsSltu, IsXor, IsOr, IsAnd, IsSll, IsSrl, IsSra, IsMul, IsMulh,
+        IsMulhsu, IsMulhu, IsDiv, IsDivu, IsRem, IsRemu,
     ];
 }
 
@@ -119,7 +121,19 @@ pub(crate) struct IsAlu;
 impl VirtualColumnForSum for IsAlu {
     fn columns() -> &'static [Column] {
         &[
-            IsAdd, IsSub, IsSlt, IsSltu, IsXor, IsOr, IsAnd, IsSll, IsSrl, IsSra,
+            IsAdd, IsSub, IsSlt, IsSltu, IsXor, IsOr, IsAnd, IsSll, IsSrl, IsSra, IsMul, IsMulh,
+            IsMulhsu, IsMulhu, IsDiv, IsDivu, IsRem, IsRemu,
+        ]
+    }
+}
+
+/// Flag on whether the row is an instruction of the M extension
+pub(crate) struct IsMulDiv;
+
+impl VirtualColumnForSum for IsMulDiv {
+    fn columns() -> &'static [Column] {
+        &[
+            IsMul, IsMulh, IsMulhsu, IsMulhu, IsDiv, IsDivu, IsRem, IsRemu,
         ]
     }
 }
@@ -360,8 +374,8 @@ impl VirtualColumnForSum for OpBFlag {
     fn columns() -> &'static [Column] {
         &[
             IsSb, IsSh, IsSw, IsLb, IsLh, IsLw, IsLbu, IsLhu, IsJalr, IsAdd, IsSub, IsSlt, IsSltu,
-            IsXor, IsOr, IsAnd, IsSll, IsSrl, IsSra, IsBeq, IsBne, IsBlt, IsBge, IsBltu, IsBgeu,
-            IsEcall, IsEbreak,
+            IsXor, IsOr, IsAnd, IsSll, IsSrl, IsSra, IsMul, IsMulh, IsMulhsu, IsMulhu, IsDiv,
+            IsDivu, IsRem, IsRemu, IsBeq, IsBne, IsBlt, IsBge, IsBltu, IsBgeu, IsEcall, IsEbreak,
         ]
     }
 }
@@ -376,7 +390,7 @@ pub(crate) struct Reg3Accessed;
 // reg3_accessed =
 // (is_type_s + is_type_b) +   // When reading from rs1
 // (is_type_r + is_type_i + is_type_u + is_type_j)  + // For instructions with rd
-// (is_type_sys)·(is_sys_priv_input + is_sys_heap_reset + is_sys_stack_reset) // For some syscalls
+// (is_type_sys)·(is_sys_priv_input + is_sys_heap_reset + is_sys_stack_reset + is_sys_memory_layout + is_sys_heap_stats + is_sys_read_clock) // For some syscalls
 impl VirtualColumn<1> for Reg3Accessed {
     fn read_from_traces_builder(traces: &TracesBuilder, row_idx: usize) -> [BaseField; 1] {
         let [is_type_s] = IsTypeS::read_from_traces_builder(traces, row_idx);
@@ -389,6 +403,11 @@ impl VirtualColumn<1> for Reg3Accessed {
         let [is_sys_priv_input] = traces.column(row_idx, Column::IsSysPrivInput);
         let [is_sys_heap_reset] = traces.column(row_idx, Column::IsSysHeapReset);
         let [is_sys_stack_reset] = traces.column(row_idx, Column::IsSysStackReset);
+        let [is_sys_memory_layout] = traces.column(row_idx, Column::IsSysMemoryLayout);
+        let [is_sys_heap_stats] = traces.column(row_idx, Column::IsSysHeapStats);
+        let [is_sys_read_clock] = traces.column(row_idx, Column::IsSysReadClock);
+        let [is_sys_alloc] = traces.column(row_idx, Column::IsSysAlloc);
+        let [is_sys_dealloc] = traces.column(row_idx, Column::IsSysDealloc);
 
         let ret = is_type_s
             + is_type_b
@@ -396,7 +415,15 @@ impl VirtualColumn<1> for Reg3Accessed {
             + is_type_i
             + is_type_u
             + is_type_j
-            + is_type_sys * (is_sys_priv_input + is_sys_heap_reset + is_sys_stack_reset);
+            + is_type_sys
+                * (is_sys_priv_input
+                    + is_sys_heap_reset
+                    + is_sys_stack_reset
+                    + is_sys_memory_layout
+                    + is_sys_heap_stats
+                    + is_sys_read_clock
+                    + is_sys_alloc
+                    + is_sys_dealloc);
         [ret]
     }
     fn read_from_finalized_traces(
@@ -416,13 +443,29 @@ impl VirtualColumn<1> for Reg3Accessed {
             traces.get_base_column::<1>(Column::IsSysHeapReset)[0].data[vec_idx];
         let is_sys_stack_reset =
             traces.get_base_column::<1>(Column::IsSysStackReset)[0].data[vec_idx];
+        let is_sys_memory_layout =
+            traces.get_base_column::<1>(Column::IsSysMemoryLayout)[0].data[vec_idx];
+        let is_sys_heap_stats =
+            traces.get_base_column::<1>(Column::IsSysHeapStats)[0].data[vec_idx];
+        let is_sys_read_clock =
+            traces.get_base_column::<1>(Column::IsSysReadClock)[0].data[vec_idx];
+        let is_sys_alloc = traces.get_base_column::<1>(Column::IsSysAlloc)[0].data[vec_idx];
+        let is_sys_dealloc = traces.get_base_column::<1>(Column::IsSysDealloc)[0].data[vec_idx];
         let ret = is_type_s
             + is_type_b
             + is_type_r
             + is_type_i
             + is_type_u
             + is_type_j
-            + is_type_sys * (is_sys_priv_input + is_sys_heap_reset + is_sys_stack_reset);
+            + is_type_sys
+                * (is_sys_priv_input
+                    + is_sys_heap_reset
+                    + is_sys_stack_reset
+                    + is_sys_memory_layout
+                    + is_sys_heap_stats
+                    + is_sys_read_clock
+                    + is_sys_alloc
+                    + is_sys_dealloc);
         [ret]
     }
     fn eval<E: EvalAtRow>(trace_eval: &TraceEval<E>) -> [E::F; 1] {
@@ -436,13 +479,26 @@ impl VirtualColumn<1> for Reg3Accessed {
         let [is_sys_priv_input] = trace_eval!(trace_eval, Column::IsSysPrivInput);
         let [is_sys_heap_reset] = trace_eval!(trace_eval, Column::IsSysHeapReset);
         let [is_sys_stack_reset] = trace_eval!(trace_eval, Column::IsSysStackReset);
+        let [is_sys_memory_layout] = trace_eval!(trace_eval, Column::IsSysMemoryLayout);
+        let [is_sys_heap_stats] = trace_eval!(trace_eval, Column::IsSysHeapStats);
+        let [is_sys_read_clock] = trace_eval!(trace_eval, Column::IsSysReadClock);
+        let [is_sys_alloc] = trace_eval!(trace_eval, Column::IsSysAlloc);
+        let [is_sys_dealloc] = trace_eval!(trace_eval, Column::IsSysDealloc);
         let ret = is_type_s
             + is_type_b
             + is_type_r
             + is_type_i
             + is_type_u
             + is_type_j
-            + is_type_sys * (is_sys_priv_input + is_sys_heap_reset + is_sys_stack_reset);
+            + is_type_sys
+                * (is_sys_priv_input
+                    + is_sys_heap_reset
+                    + is_sys_stack_reset
+                    + is_sys_memory_layout
+                    + is_sys_heap_stats
+                    + is_sys_read_clock
+                    + is_sys_alloc
+                    + is_sys_dealloc);
         [ret]
     }
 }
@@ -78,3 +78,66 @@ pub unsafe extern "C" fn sys_alloc_aligned(bytes: usize, align: usize) -> *mut u
     HEAP_POS = heap_pos;
     ptr
 }
+
+/// A block freed by [`free_list_dealloc`], linked to the next one through its first words.
+struct FreeBlock {
+    size: usize,
+    next: *mut FreeBlock,
+}
+
+// Freed blocks, in the order they were freed in. Blocks are never merged.
+static mut FREE_LIST: *mut FreeBlock = core::ptr::null_mut();
+
+// Sizes are rounded up so that every block, and the remainder of a split one, can hold a
+// `FreeBlock`.
+fn block_size(bytes: usize) -> usize {
+    const UNIT: usize = core::mem::size_of::<FreeBlock>();
+    bytes.max(1).div_ceil(UNIT) * UNIT
+}
+
+/// Allocates from the first freed block that is large and aligned enough, or else from the top of
+/// the heap like [`sys_alloc_aligned`]. The unused end of a reused block stays free.
+///
+/// # Safety
+///
+/// `align` must be a power of two.
+pub(crate) unsafe fn free_list_alloc(bytes: usize, align: usize) -> *mut u8 {
+    let size = block_size(bytes);
+
+    // SAFETY: Single threaded, so nothing else can touch the list while we're working.
+    let mut link = core::ptr::addr_of_mut!(FREE_LIST);
+    while !(*link).is_null() {
+        let block = *link;
+        if (*block).size >= size && block as usize & (align - 1) == 0 {
+            let rest = (*block).size - size;
+            if rest == 0 {
+                *link = (*block).next;
+            } else {
+                let tail = (block as *mut u8).add(size) as *mut FreeBlock;
+                tail.write(FreeBlock {
+                    size: rest,
+                    next: (*block).next,
+                });
+                *link = tail;
+            }
+            return block as *mut u8;
+        }
+        link = core::ptr::addr_of_mut!((*block).next);
+    }
+
+    sys_alloc_aligned(size, align.max(core::mem::align_of::<FreeBlock>()))
+}
+
+/// Returns a block of [`free_list_alloc`] to the free list.
+///
+/// # Safety
+///
+/// `ptr` must have been returned by [`free_list_alloc`] for `bytes`, and not be freed already.
+pub(crate) unsafe fn free_list_dealloc(ptr: *mut u8, bytes: usize) {
+    let block = ptr as *mut FreeBlock;
+    block.write(FreeBlock {
+        size: block_size(bytes),
+        next: FREE_LIST,
+    });
+    FREE_LIST = block;
+}
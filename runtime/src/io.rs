@@ -1,13 +1,39 @@
 pub use core::fmt::Write;
 extern crate alloc;
 
+/// Severity of a message sent with [`log`] to the `tracing` subscriber of the host.
+pub use nexus_common::abi::LogLevel;
+
+/// Addresses of the memory layout the program is running with.
+///
+/// Start addresses are inclusive and end addresses exclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemoryLayout {
+    pub heap_start: u32,
+    pub heap_end: u32,
+    pub stack_bottom: u32,
+    pub stack_top: u32,
+    pub public_input_start: u32,
+    pub public_input_end: u32,
+    pub public_output_start: u32,
+    pub public_output_end: u32,
+    pub ad_start: u32,
+    pub ad_end: u32,
+    pub exit_code: u32,
+}
+
 #[cfg(target_arch = "riscv32")]
 mod riscv32 {
     extern crate alloc;
+    use super::LogLevel;
+    use super::MemoryLayout;
     use crate::{
-        ecall, read_input, write_output, SYS_CYCLE_COUNT, SYS_EXIT, SYS_LOG,
-        SYS_READ_PRIVATE_INPUT, WORD_SIZE,
+        alloc::{free_list_alloc, free_list_dealloc},
+        ecall, read_input, write_output, SYS_ADD_MOD, SYS_CYCLE_COUNT, SYS_EXIT, SYS_HEAP_STATS,
+        SYS_INV_MOD, SYS_KECCAK_PERMUTE, SYS_LOG, SYS_MEMORY_LAYOUT, SYS_MUL_MOD, SYS_READ_CLOCK,
+        SYS_READ_PRIVATE_INPUT, SYS_SHA256_COMPRESS, SYS_TRACE_LOG, SYS_YIELD, WORD_SIZE,
     };
+    use nexus_common::abi::{MemoryLayoutField, EXIT_CODE_OFFSET, PUBLIC_OUTPUT_DATA_OFFSET};
     use serde::{de::DeserializeOwned, Serialize};
 
     /// Write a string to the output console (if any).
@@ -26,7 +52,7 @@ mod riscv32 {
     /// Exit the program with the given exit code.
     pub fn exit(exit_code: i32) -> ! {
         // Write the exit code to the output.
-        let _ = write_output!(0, exit_code);
+        let _ = write_output!(EXIT_CODE_OFFSET, exit_code);
         // Exit the program.
         let _ = ecall!(SYS_EXIT, exit_code);
         // Ecall will trigger exit syscall, so we will never return.
@@ -84,7 +110,7 @@ mod riscv32 {
         // Write bytes in word chunks to output memory.
         bytes.chunks(WORD_SIZE).enumerate().for_each(|(i, chunk)| {
             let word = u32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]);
-            write_output!((i + 1) * WORD_SIZE, word); // word 0 is reserved for the exit code
+            write_output!(PUBLIC_OUTPUT_DATA_OFFSET as usize + i * WORD_SIZE, word);
         });
 
         Ok(())
@@ -97,6 +123,160 @@ mod riscv32 {
         let _ = ecall!(SYS_CYCLE_COUNT, buf, ("a1", len));
     }
 
+    /// Query the memory layout from the VM.
+    ///
+    /// The layout is only reported if the host fixes it before running the program. Otherwise it's
+    /// derived from a first run, and `None` is returned in every run, so the program should fall
+    /// back to the addresses provided by the linker.
+    ///
+    /// The layout is host state that the proof doesn't cover: a program calling this runs in the
+    /// emulators, but proving it fails with `ProveError::UnsupportedSyscall`.
+    pub fn memory_layout() -> Option<MemoryLayout> {
+        let field = |field: MemoryLayoutField| ecall!(SYS_MEMORY_LAYOUT, field as u32);
+
+        let heap_start = field(MemoryLayoutField::HeapStart);
+        if heap_start == 0 {
+            return None;
+        }
+
+        Some(MemoryLayout {
+            heap_start,
+            heap_end: field(MemoryLayoutField::HeapEnd),
+            stack_bottom: field(MemoryLayoutField::StackBottom),
+            stack_top: field(MemoryLayoutField::StackTop),
+            public_input_start: field(MemoryLayoutField::PublicInputStart),
+            public_input_end: field(MemoryLayoutField::PublicInputEnd),
+            public_output_start: field(MemoryLayoutField::PublicOutputStart),
+            public_output_end: field(MemoryLayoutField::PublicOutputEnd),
+            ad_start: field(MemoryLayoutField::AdStart),
+            ad_end: field(MemoryLayoutField::AdEnd),
+            exit_code: field(MemoryLayoutField::ExitCode),
+        })
+    }
+
+    /// Number of heap bytes used so far, as tracked by the VM.
+    ///
+    /// The statistics are host state that the proof doesn't cover: a program calling this or
+    /// [`heap_remaining`] runs in the emulators, but proving it fails with
+    /// `ProveError::UnsupportedSyscall`.
+    pub fn heap_used() -> u32 {
+        ecall!(SYS_HEAP_STATS, 0u32)
+    }
+
+    /// Number of heap bytes left before the heap runs into its limit or the stack. Can't be proven,
+    /// see [`heap_used`].
+    pub fn heap_remaining() -> u32 {
+        ecall!(SYS_HEAP_STATS, 1u32)
+    }
+
+    /// Number of instructions executed so far, saturating at `u32::MAX`. Unlike the profiling of
+    /// [`cycle_count_ecall`], the clock is part of the execution trace, so it can drive the
+    /// program, e.g. to stop work once a cycle budget is spent.
+    pub fn clock() -> u32 {
+        ecall!(SYS_READ_CLOCK, 0u32)
+    }
+
+    /// Send `s` to the `tracing` subscriber of the host at `level`. Unlike [`write_log`], messages
+    /// aren't part of the output of the program.
+    pub fn log(level: LogLevel, s: &str) {
+        let buf_ptr = s.as_ptr();
+        let buf_len = s.len();
+        let _ = ecall!(
+            SYS_TRACE_LOG,
+            level as u32,
+            ("a1", buf_ptr),
+            ("a2", buf_len)
+        );
+    }
+
+    #[doc(hidden)]
+    pub fn log_fmt(level: LogLevel, args: core::fmt::Arguments) {
+        log(level, &alloc::format!("{args}"));
+    }
+
+    /// Allocates `size` bytes aligned to `align` from the heap, e.g. to implement `GlobalAlloc` in
+    /// a `no_std` program. Unlike the default global allocator of the runtime, freed blocks are
+    /// reused. The allocator runs in the guest, so programs using it can be proven.
+    ///
+    /// Panics if `align` isn't a power of two, or if the heap runs into the stack.
+    pub fn heap_alloc(size: usize, align: usize) -> *mut u8 {
+        assert!(align.is_power_of_two(), "alignment must be a power of two");
+        // SAFETY: The alignment is checked above.
+        unsafe { free_list_alloc(size, align) }
+    }
+
+    /// Frees a block of `size` bytes allocated with [`heap_alloc`].
+    ///
+    /// # Safety
+    ///
+    /// `ptr` must have been returned by [`heap_alloc`] for `size` bytes, and not be used after it's
+    /// freed.
+    pub unsafe fn heap_dealloc(ptr: *mut u8, size: usize) {
+        free_list_dealloc(ptr, size)
+    }
+
+    /// Suspend the program and return control to the host, which resumes it after this call, e.g.
+    /// to run other programs in the meantime. Doesn't change the outcome of the program.
+    pub fn yield_now() {
+        let _ = ecall!(SYS_YIELD, 0u32);
+    }
+
+    /// Update the SHA-256 `state` with a 512-bit `block`, whose words are read big-endian from the
+    /// message. The compression is executed by the host in a single step. Padding the message and
+    /// reading the digest big-endian from the final state is left to the caller.
+    ///
+    /// The prover has no chip for this syscall yet: a program calling it runs in the emulators, but
+    /// proving it fails with `ProveError::UnsupportedSyscall`.
+    pub fn sha256_compress(state: &mut [u32; 8], block: &[u32; 16]) {
+        let _ = ecall!(
+            SYS_SHA256_COMPRESS,
+            state.as_mut_ptr() as u32,
+            ("a1", block.as_ptr())
+        );
+    }
+
+    /// Apply Keccak-f[1600] to the 25 lanes of `state`, lane x + 5・y being at index x + 5・y. The
+    /// permutation is executed by the host in a single step, absorbing and squeezing, e.g. for
+    /// Keccak-256, is left to the caller.
+    ///
+    /// The prover has no chip for this syscall yet: a program calling it runs in the emulators, but
+    /// proving it fails with `ProveError::UnsupportedSyscall`.
+    pub fn keccak_permute(state: &mut [u64; 25]) {
+        let _ = ecall!(SYS_KECCAK_PERMUTE, state.as_mut_ptr() as u32);
+    }
+
+    /// Replace `x` with `(x + y) mod modulus`. Integers are 256-bit, least significant word
+    /// first, and may exceed the modulus. Returns false, leaving `x` unchanged, if the modulus is
+    /// zero.
+    ///
+    /// The prover has no chip for the modular arithmetic syscalls yet: a program calling this,
+    /// [`mul_mod`] or [`inv_mod`] runs in the emulators, but proving it fails with
+    /// `ProveError::UnsupportedSyscall`.
+    pub fn add_mod(x: &mut [u32; 8], y: &[u32; 8], modulus: &[u32; 8]) -> bool {
+        ecall!(
+            SYS_ADD_MOD,
+            x.as_mut_ptr() as u32,
+            ("a1", y.as_ptr()),
+            ("a2", modulus.as_ptr())
+        ) == 0
+    }
+
+    /// Replace `x` with `(x・y) mod modulus`, see [`add_mod`].
+    pub fn mul_mod(x: &mut [u32; 8], y: &[u32; 8], modulus: &[u32; 8]) -> bool {
+        ecall!(
+            SYS_MUL_MOD,
+            x.as_mut_ptr() as u32,
+            ("a1", y.as_ptr()),
+            ("a2", modulus.as_ptr())
+        ) == 0
+    }
+
+    /// Replace `x` with its inverse modulo `modulus`, see [`add_mod`]. Returns false, leaving `x`
+    /// unchanged, if `x` isn't invertible, e.g. for a multiple of a prime modulus.
+    pub fn inv_mod(x: &mut [u32; 8], modulus: &[u32; 8]) -> bool {
+        ecall!(SYS_INV_MOD, x.as_mut_ptr() as u32, ("a1", modulus.as_ptr())) == 0
+    }
+
     /// An empty type representing the debug VM terminal
     pub struct NexusLog;
 
@@ -111,6 +291,15 @@ mod riscv32 {
 #[cfg(target_arch = "riscv32")]
 pub use riscv32::*;
 
+/// Prints to the `tracing` subscriber of the host at the given [`LogLevel`]
+#[cfg(target_arch = "riscv32")]
+#[macro_export]
+macro_rules! log {
+    ($level:expr, $($as:tt)*) => {
+        nexus_rt::log_fmt($level, core::format_args!($($as)*))
+    }
+}
+
 /// Prints to the VM terminal
 #[cfg(target_arch = "riscv32")]
 #[macro_export]
@@ -164,6 +353,72 @@ mod native {
     pub fn write_public_output<UNUSABLE: RequiresRV32Target, T: Serialize + ?Sized>(_val: &T) {
         unimplemented!()
     }
+
+    pub fn memory_layout<UNUSABLE: RequiresRV32Target>() -> Option<super::MemoryLayout> {
+        unimplemented!()
+    }
+
+    pub fn heap_used<UNUSABLE: RequiresRV32Target>() -> u32 {
+        unimplemented!()
+    }
+
+    pub fn heap_remaining<UNUSABLE: RequiresRV32Target>() -> u32 {
+        unimplemented!()
+    }
+
+    pub fn clock<UNUSABLE: RequiresRV32Target>() -> u32 {
+        unimplemented!()
+    }
+
+    pub fn log<UNUSABLE: RequiresRV32Target>(_level: super::LogLevel, _s: &str) {
+        unimplemented!()
+    }
+
+    pub fn yield_now<UNUSABLE: RequiresRV32Target>() {
+        unimplemented!()
+    }
+
+    pub fn sha256_compress<UNUSABLE: RequiresRV32Target>(
+        _state: &mut [u32; 8],
+        _block: &[u32; 16],
+    ) {
+        unimplemented!()
+    }
+
+    pub fn keccak_permute<UNUSABLE: RequiresRV32Target>(_state: &mut [u64; 25]) {
+        unimplemented!()
+    }
+
+    pub fn add_mod<UNUSABLE: RequiresRV32Target>(
+        _x: &mut [u32; 8],
+        _y: &[u32; 8],
+        _modulus: &[u32; 8],
+    ) -> bool {
+        unimplemented!()
+    }
+
+    pub fn mul_mod<UNUSABLE: RequiresRV32Target>(
+        _x: &mut [u32; 8],
+        _y: &[u32; 8],
+        _modulus: &[u32; 8],
+    ) -> bool {
+        unimplemented!()
+    }
+
+    pub fn inv_mod<UNUSABLE: RequiresRV32Target>(_x: &mut [u32; 8], _modulus: &[u32; 8]) -> bool {
+        unimplemented!()
+    }
+
+    pub fn heap_alloc<UNUSABLE: RequiresRV32Target>(_size: usize, _align: usize) -> *mut u8 {
+        unimplemented!()
+    }
+
+    /// # Safety
+    ///
+    /// Not available on the host.
+    pub unsafe fn heap_dealloc<UNUSABLE: RequiresRV32Target>(_ptr: *mut u8, _size: usize) {
+        unimplemented!()
+    }
 }
 #[cfg(not(target_arch = "riscv32"))]
 pub use native::*;
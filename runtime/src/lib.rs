@@ -16,31 +16,52 @@ pub use nexus_rt_macros::{
 
 mod io;
 pub use io::*;
+/// Guest ABI implemented by the zkVM.
+pub use nexus_common::abi;
+#[cfg(target_arch = "riscv32")]
+use nexus_common::abi::SyscallCode;
 pub use postcard;
 
-// Ecall codes. Allow dead code here because these are only used in the RISC-V runtime, not when
-// compiling for the host.
+// Ecall codes, see `nexus_common::abi`. Only used in the RISC-V runtime, not when compiling for the
+// host.
 #[cfg(target_arch = "riscv32")]
-pub(crate) const SYS_LOG: u32 = 0x200;
+pub(crate) const SYS_LOG: u32 = SyscallCode::Write.code();
 #[cfg(target_arch = "riscv32")]
-pub(crate) const SYS_EXIT: u32 = 0x201;
+pub(crate) const SYS_EXIT: u32 = SyscallCode::Exit.code();
 #[cfg(target_arch = "riscv32")]
-pub(crate) const SYS_READ_PRIVATE_INPUT: u32 = 0x400;
+pub(crate) const SYS_READ_PRIVATE_INPUT: u32 = SyscallCode::ReadFromPrivateInput.code();
 #[cfg(target_arch = "riscv32")]
-pub(crate) const SYS_CYCLE_COUNT: u32 = 0x401;
+pub(crate) const SYS_CYCLE_COUNT: u32 = SyscallCode::CycleCount.code();
 #[cfg(target_arch = "riscv32")]
-#[allow(dead_code)]
-pub(crate) const SYS_OVERWRITE_SP: u32 = 0x402;
+pub(crate) const SYS_OVERWRITE_SP: u32 = SyscallCode::OverwriteStackPointer.code();
 #[cfg(target_arch = "riscv32")]
-pub(crate) const SYS_ALLOC_ALIGNED: u32 = 0x403;
-// Error codes.
+pub(crate) const SYS_ALLOC_ALIGNED: u32 = SyscallCode::OverwriteHeapPointer.code();
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_MEMORY_LAYOUT: u32 = SyscallCode::QueryMemoryLayout.code();
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_HEAP_STATS: u32 = SyscallCode::HeapStats.code();
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_READ_CLOCK: u32 = SyscallCode::ReadClock.code();
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_TRACE_LOG: u32 = SyscallCode::Log.code();
 #[cfg(target_arch = "riscv32")]
-pub(crate) const EXIT_SUCCESS: u32 = 0;
+pub(crate) const SYS_YIELD: u32 = SyscallCode::Yield.code();
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_SHA256_COMPRESS: u32 = SyscallCode::Sha256Compress.code();
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_KECCAK_PERMUTE: u32 = SyscallCode::KeccakPermute.code();
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_ADD_MOD: u32 = SyscallCode::AddMod.code();
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_MUL_MOD: u32 = SyscallCode::MulMod.code();
+#[cfg(target_arch = "riscv32")]
+pub(crate) const SYS_INV_MOD: u32 = SyscallCode::InvMod.code();
+// Error codes.
 #[cfg(target_arch = "riscv32")]
-pub(crate) const EXIT_PANIC: u32 = 1;
+pub(crate) use nexus_common::abi::{EXIT_PANIC, EXIT_SUCCESS};
 // Constants.
 #[cfg(target_arch = "riscv32")]
-pub(crate) const WORD_SIZE: usize = 4;
+pub(crate) use nexus_common::constants::WORD_SIZE;
 
 /// Macro for making an ecall with variable number of parameters:
 /// - First parameter: syscall code (placed in a7)
@@ -93,12 +114,13 @@ macro_rules! read_input {
         let mut out: u32;
         unsafe {
             core::arch::asm!(
-                "lw {0}, 0x80(x0)", // 0x80 stores the public input start address
+                "lw {0}, {address}(x0)", // stores the public input start address
                 "add {0}, {0}, {1}",
                 ".insn i 0b0101011, 0b000, {2}, 0({0})",
                 out(reg) _,
                 in(reg) $i,
                 out(reg) out,
+                address = const $crate::abi::PUBLIC_INPUT_ADDRESS,
             );
         }
         out
@@ -111,12 +133,13 @@ macro_rules! write_output {
     ($i:expr, $data:expr) => {
         unsafe {
             core::arch::asm!(
-                "lw {0}, 0x84(x0)", // 0x84 stores the output start address
+                "lw {0}, {address}(x0)", // stores the output start address
                 "add {0}, {0}, {1}",
                 ".insn s 0b1011011, 0b000, {2}, 0({0})",
                 out(reg) _,
                 in(reg) $i,
                 in(reg) $data,
+                address = const $crate::abi::PUBLIC_OUTPUT_ADDRESS,
             )
         }
     };
@@ -1,15 +1,48 @@
 // Nexus VM runtime environment
 // Note: adapted from riscv-rt, which was adapted from cortex-m.
+
 use crate::alloc::sys_alloc_aligned;
-use crate::{ecall, write_output, EXIT_PANIC, EXIT_SUCCESS, SYS_EXIT};
+use crate::{ecall, write_output, LogLevel, EXIT_PANIC, EXIT_SUCCESS, SYS_EXIT, SYS_TRACE_LOG};
 use core::alloc::{GlobalAlloc, Layout};
+use core::fmt::Write;
 use core::panic::PanicInfo;
+use nexus_common::abi::EXIT_CODE_OFFSET;
+
+// Longer panic messages are truncated.
+const PANIC_MESSAGE_CAPACITY: usize = 256;
+
+/// Formats the panic message on the stack, as the heap may be what the program panicked on.
+struct PanicMessage {
+    buf: [u8; PANIC_MESSAGE_CAPACITY],
+    len: usize,
+}
+
+impl Write for PanicMessage {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        let n = s.len().min(PANIC_MESSAGE_CAPACITY - self.len);
+        self.buf[self.len..self.len + n].copy_from_slice(&s.as_bytes()[..n]);
+        self.len += n;
+        Ok(())
+    }
+}
 
 #[inline(never)]
 #[panic_handler]
-fn panic(_info: &PanicInfo) -> ! {
+fn panic(info: &PanicInfo) -> ! {
+    // Log the message at the error level, leaving the public output of the program as is.
+    let mut message = PanicMessage {
+        buf: [0; PANIC_MESSAGE_CAPACITY],
+        len: 0,
+    };
+    let _ = write!(message, "{}", info.message());
+    let _ = ecall!(
+        SYS_TRACE_LOG,
+        LogLevel::Error as u32,
+        ("a1", message.buf.as_ptr()),
+        ("a2", message.len)
+    );
     // Write the exit code to the output.
-    let _ = write_output!(0, EXIT_PANIC);
+    let _ = write_output!(EXIT_CODE_OFFSET, EXIT_PANIC);
     // Finish with exit syscall.
     let _ = ecall!(SYS_EXIT, EXIT_PANIC);
     // Ecall will trigger exit syscall, so we will never return.
@@ -51,7 +84,7 @@ pub unsafe extern "C" fn start_rust() -> u32 {
     main();
 
     // Write the exit code to the output.
-    let _ = write_output!(0, EXIT_SUCCESS);
+    let _ = write_output!(EXIT_CODE_OFFSET, EXIT_SUCCESS);
     // Finish with exit syscall.
     ecall!(SYS_EXIT, EXIT_SUCCESS)
 }
@@ -78,14 +111,15 @@ core::arch::global_asm!(
         // but make an ecall to potentially overwrite it
         // we embed an ecall instruction to avoid any possibility of updating `sp` by the compiler
         // generating a function call
-        li a7, 0x402 // SYS_OVERWRITE_SP
+        li a7, {overwrite_sp}
         ecall
 
         mv fp, sp
 
         jal ra, _start_rust
-"#
+"#,
+    overwrite_sp = const crate::SYS_OVERWRITE_SP,
 );
 
 #[no_mangle]
-pub static __memory_top: u32 = 0x80400000;
+pub static __memory_top: u32 = nexus_common::constants::MEMORY_TOP;
@@ -12,11 +12,11 @@ use crate::error::{BuildError, ConfigurationError, IOError, PathError};
 pub enum Error {
     /// An error occurred during proving a zkVM execution.
     #[error(transparent)]
-    ProvingError(#[from] nexus_core::stwo::ProvingError),
+    ProvingError(#[from] nexus_core::stwo::ProveError),
 
     /// An error occurred verifying a claimed proof of a zkVM execution.
     #[error(transparent)]
-    VerificationError(#[from] nexus_core::stwo::VerificationError),
+    VerificationError(#[from] nexus_core::stwo::VerifyError),
 
     /// An error occurred building the guest program dynamically.
     #[error(transparent)]
@@ -63,6 +63,13 @@ pub struct Proof {
     memory_layout: nexus_core::nvm::internals::LinearMemoryLayout,
 }
 
+impl Proof {
+    /// Size and shape of the Stwo proof, see [`nexus_core::stwo::ProofStats`].
+    pub fn stats(&self) -> nexus_core::stwo::ProofStats {
+        self.proof.stats()
+    }
+}
+
 impl<C: Compute> ByGuestCompilation for Stwo<C>
 where
     Stwo<C>: Prover,
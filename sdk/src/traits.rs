@@ -41,19 +41,21 @@ impl CheckedView for nexus_core::nvm::View {
         let emulator = LinearEmulator::default();
 
         // Replace custom instructions `rin` and `wou` with `lw` and `sw`.
-        let instructions = expected_elf
-            .instructions
-            .iter()
-            .map(|instr| convert_instruction(&emulator.executor.instruction_executor, instr))
-            .collect();
-
-        let converted_elf = nexus_core::nvm::ElfFile {
-            instructions,
-            ..expected_elf.clone()
-        };
+        let mut converted_elf = expected_elf.clone();
+        for instr in converted_elf.instructions.iter_mut() {
+            *instr = convert_instruction(&emulator.executor.instruction_executor, instr);
+        }
 
         let program_memory = elf_into_program_info(&converted_elf, memory_layout);
 
+        let rom_memory: Vec<MemoryInitializationEntry> =
+            map_into_io_entries::<MemoryInitializationEntry>(&expected_elf.rom_image)
+                .into_iter()
+                .map(|entry| MemoryInitializationEntry {
+                    read_only: true,
+                    ..entry
+                })
+                .collect();
         let initial_memory = slice_into_io_entries::<MemoryInitializationEntry>(
             memory_layout.public_input_start_location(),
             &[
@@ -63,7 +65,7 @@ impl CheckedView for nexus_core::nvm::View {
             .concat(),
         )
         .iter()
-        .chain(map_into_io_entries::<MemoryInitializationEntry>(&expected_elf.rom_image).iter())
+        .chain(rom_memory.iter())
         .chain(map_into_io_entries::<MemoryInitializationEntry>(&expected_elf.ram_image).iter())
         .chain(
             slice_into_io_entries::<MemoryInitializationEntry>(
@@ -5,6 +5,7 @@ mod test {
         compile_multi, emulate, parse_output, EmulatorType, IOArgs, Input, Output,
     };
     use nexus_common_testing::program_trace;
+    use nexus_common_testing::vectors::{TestVector, TestVectorError};
     use nexus_vm::elf::ElfFile;
     use nexus_vm::emulator::InternalView;
     use nexus_vm::trace::{k_trace, k_trace_direct};
@@ -208,6 +209,26 @@ mod test {
         verify(proof, &view).unwrap();
     }
 
+    #[test]
+    #[serial]
+    fn test_golden_vector_fib() {
+        let elfs = compile_multi("examples/src/bin/fib", &["-C opt-level=3"], &HOME_PATH);
+        let vector = TestVector::generate("fib", elfs[0].clone(), &[], &[], &[1, 2, 3])
+            .expect("error generating test vector");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("fib.vector");
+        vector.save(&path).unwrap();
+        TestVector::load(&path).unwrap().check().unwrap();
+
+        let mut tampered = vector.clone();
+        tampered.ad = vec![3, 2, 1];
+        assert!(matches!(
+            tampered.check(),
+            Err(TestVectorError::TraceDigestMismatch { .. } | TestVectorError::ViewMismatch)
+        ));
+    }
+
     #[test]
     #[serial]
     fn test_emulate_fib1000() {
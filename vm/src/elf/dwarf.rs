@@ -0,0 +1,308 @@
+//! Source locations of the guest, from the DWARF line tables and functions of the ELF file.
+//!
+//! Only what maps an address back to the source is kept, see [`super::ElfFile::source_location`].
+//! Compressed debug sections aren't supported, they read as missing.
+
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter, Result as FmtResult},
+    ops::Range,
+    path::PathBuf,
+};
+
+use elf::{endian::LittleEndian, ElfBytes};
+use gimli::{AttributeValue, DebuggingInformationEntry, Dwarf, LineProgramHeader, Reader, Unit};
+use serde::{Deserialize, Serialize};
+
+/// Where an instruction of the guest comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SourceLocation {
+    pub file: String,
+    /// Line in the file, 0 if the compiler couldn't attribute the instruction to a line.
+    pub line: u32,
+    /// The function the instruction is in, not counting functions inlined into it.
+    pub function: Option<String>,
+}
+
+impl Display for SourceLocation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}:{}", self.file, self.line)?;
+        if let Some(function) = &self.function {
+            write!(f, " in {function}")?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct LineRow {
+    address: u32,
+    // Index into the files, none for the end of a sequence
+    file: Option<u32>,
+    line: u32,
+}
+
+/// Line tables and function ranges of the program.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct DebugInfo {
+    files: Vec<String>,
+    // Sorted by address, each row covering the addresses up to the next one
+    rows: Vec<LineRow>,
+    // Sorted by start
+    functions: Vec<(Range<u32>, String)>,
+}
+
+impl DebugInfo {
+    pub(crate) fn parse(elf: &ElfBytes<'_, LittleEndian>) -> gimli::Result<Self> {
+        let dwarf = Dwarf::load(|id| -> gimli::Result<_> {
+            let data = match elf.section_header_by_name(id.name()) {
+                Ok(Some(header)) => match elf.section_data(&header) {
+                    Ok((data, None)) => data,
+                    _ => &[],
+                },
+                _ => &[],
+            };
+            Ok(gimli::EndianSlice::new(data, gimli::LittleEndian))
+        })?;
+
+        let mut info = Self::default();
+        let mut units = dwarf.units();
+        while let Some(header) = units.next()? {
+            let unit = dwarf.unit(header)?;
+            info.parse_lines(&dwarf, &unit)?;
+            info.parse_functions(&dwarf, &unit)?;
+        }
+
+        // A sequence may start where another ends, the end must come first.
+        info.rows
+            .sort_by_key(|row| (row.address, row.file.is_some()));
+        info.functions.sort_by_key(|(range, _)| range.start);
+        Ok(info)
+    }
+
+    fn parse_lines<R: Reader>(&mut self, dwarf: &Dwarf<R>, unit: &Unit<R>) -> gimli::Result<()> {
+        let Some(program) = unit.line_program.clone() else {
+            return Ok(());
+        };
+
+        // Indices of the files of the unit into the files of the program
+        let mut files: HashMap<u64, u32> = HashMap::new();
+        let mut rows = program.rows();
+        while let Some((header, row)) = rows.next_row()? {
+            let address = row.address() as u32;
+            if row.end_sequence() {
+                self.rows.push(LineRow {
+                    address,
+                    file: None,
+                    line: 0,
+                });
+                continue;
+            }
+
+            let file = match files.get(&row.file_index()) {
+                Some(&file) => file,
+                None => {
+                    self.files
+                        .push(file_path(dwarf, unit, header, row.file_index())?);
+                    let file = self.files.len() as u32 - 1;
+                    files.insert(row.file_index(), file);
+                    file
+                }
+            };
+            self.rows.push(LineRow {
+                address,
+                file: Some(file),
+                line: row.line().map_or(0, |line| line.get() as u32),
+            });
+        }
+        Ok(())
+    }
+
+    fn parse_functions<R: Reader>(
+        &mut self,
+        dwarf: &Dwarf<R>,
+        unit: &Unit<R>,
+    ) -> gimli::Result<()> {
+        let mut entries = unit.entries();
+        while let Some((_, entry)) = entries.next_dfs()? {
+            if entry.tag() != gimli::DW_TAG_subprogram {
+                continue;
+            }
+            let Some(name) = function_name(dwarf, unit, entry)? else {
+                continue;
+            };
+
+            let mut ranges = dwarf.die_ranges(unit, entry)?;
+            while let Some(range) = ranges.next()? {
+                // The linker moves functions it removes to address 0.
+                if range.begin != 0 && range.begin < range.end {
+                    self.functions
+                        .push((range.begin as u32..range.end as u32, name.clone()));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.rows.is_empty()
+    }
+
+    pub(crate) fn source_location(&self, pc: u32) -> Option<SourceLocation> {
+        let index = self.rows.partition_point(|row| row.address <= pc);
+        let row = self.rows.get(index.checked_sub(1)?)?;
+        let file = row.file?;
+
+        let index = self
+            .functions
+            .partition_point(|(range, _)| range.start <= pc);
+        let function = self.functions[..index]
+            .iter()
+            .rev()
+            .find(|(range, _)| range.contains(&pc))
+            .map(|(_, name)| name.clone());
+
+        Some(SourceLocation {
+            file: self.files[file as usize].clone(),
+            line: row.line,
+            function,
+        })
+    }
+
+    /// Moves the addresses within `text` to start at `to`, like the code of the program.
+    pub(crate) fn relocate(&self, text: Range<u32>, to: u32) -> Self {
+        // The end of the last sequence is one past the code.
+        let relocate = |address: u32| {
+            if text.start <= address && address <= text.end {
+                to + (address - text.start)
+            } else {
+                address
+            }
+        };
+
+        let mut info = self.clone();
+        for row in &mut info.rows {
+            row.address = relocate(row.address);
+        }
+        for (range, _) in &mut info.functions {
+            *range = relocate(range.start)..relocate(range.end);
+        }
+        info
+    }
+}
+
+/// Full path of the file `index` of the line table.
+fn file_path<R: Reader>(
+    dwarf: &Dwarf<R>,
+    unit: &Unit<R>,
+    header: &LineProgramHeader<R>,
+    index: u64,
+) -> gimli::Result<String> {
+    let Some(file) = header.file(index) else {
+        return Ok(format!("<file {index}>"));
+    };
+
+    // Pushing an absolute directory replaces the ones before it.
+    let mut path = PathBuf::new();
+    if let Some(comp_dir) = &unit.comp_dir {
+        path.push(comp_dir.to_string_lossy()?.as_ref());
+    }
+    if let Some(directory) = file.directory(header) {
+        path.push(
+            dwarf
+                .attr_string(unit, directory)?
+                .to_string_lossy()?
+                .as_ref(),
+        );
+    }
+    path.push(
+        dwarf
+            .attr_string(unit, file.path_name())?
+            .to_string_lossy()?
+            .as_ref(),
+    );
+    Ok(path.display().to_string())
+}
+
+fn function_name<R: Reader>(
+    dwarf: &Dwarf<R>,
+    unit: &Unit<R>,
+    entry: &DebuggingInformationEntry<'_, '_, R>,
+) -> gimli::Result<Option<String>> {
+    let name = |entry: &DebuggingInformationEntry<'_, '_, R>| -> gimli::Result<Option<String>> {
+        match entry.attr_value(gimli::DW_AT_name)? {
+            Some(name) => Ok(Some(
+                dwarf
+                    .attr_string(unit, name)?
+                    .to_string_lossy()?
+                    .into_owned(),
+            )),
+            None => Ok(None),
+        }
+    };
+    if let Some(name) = name(entry)? {
+        return Ok(Some(name));
+    }
+
+    // Definitions of methods and of inlined functions name their declaration instead.
+    for attr in [gimli::DW_AT_specification, gimli::DW_AT_abstract_origin] {
+        if let Some(AttributeValue::UnitRef(offset)) = entry.attr_value(attr)? {
+            if let Some(name) = name(&unit.entry(offset)?)? {
+                return Ok(Some(name));
+            }
+        }
+    }
+    Ok(None)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn row(address: u32, file: Option<u32>, line: u32) -> LineRow {
+        LineRow {
+            address,
+            file,
+            line,
+        }
+    }
+
+    #[test]
+    fn test_source_location() {
+        let info = DebugInfo {
+            files: vec!["src/main.rs".to_string(), "src/lib.rs".to_string()],
+            rows: vec![
+                row(0x1000, Some(0), 3),
+                row(0x1008, Some(0), 4),
+                row(0x1010, None, 0),
+                row(0x1020, Some(1), 10),
+                row(0x1024, None, 0),
+            ],
+            functions: vec![
+                (0x1000..0x1010, "main".to_string()),
+                (0x1020..0x1024, "fib".to_string()),
+            ],
+        };
+
+        let location = info.source_location(0x100C).unwrap();
+        assert_eq!(
+            location,
+            SourceLocation {
+                file: "src/main.rs".to_string(),
+                line: 4,
+                function: Some("main".to_string()),
+            }
+        );
+        assert_eq!(location.to_string(), "src/main.rs:4 in main");
+        assert_eq!(info.source_location(0xFFC), None);
+        assert_eq!(info.source_location(0x1014), None);
+        assert_eq!(info.source_location(0x1024), None);
+
+        let relocated = info.relocate(0x1000..0x1024, 0x80);
+        assert_eq!(
+            relocated.source_location(0x80 + 0x20),
+            info.source_location(0x1020)
+        );
+        assert_eq!(relocated.source_location(0x80 + 0x24), None);
+    }
+}
@@ -2,6 +2,7 @@ use thiserror::Error;
 
 /// Errors related to VM initialization and execution
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum ParserError {
     /// Not a 32-bit ELF file
     #[error("not a 32-bit ELF file")]
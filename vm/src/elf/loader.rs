@@ -20,8 +20,14 @@
 //!   - Read-only memory image (ROM)
 //!   - Read-write memory image (RAM)
 //!
-//! - `ElfFile::from_bytes`: Allows creation of `ElfFile` from raw bytes
+//! - `ElfFile::from_bytes`: Allows creation of `ElfFile` from raw bytes, e.g. embedded with
+//!   `include_bytes!`
+//! - `ElfFile::from_reader`: Allows creation of `ElfFile` from any reader, e.g. a network stream
 //! - `ElfFile::from_path`: Allows creation of `ElfFile` from a file path
+//! - `ElfFile::symbols` and `ElfFile::symbol_for_addr`: Give access to the function and object
+//!   symbols, e.g. to name the function an address is in
+//! - `ElfFile::source_location`: Maps an address to its source file, line and function, for ELF
+//!   files built with debug info
 //!
 //! # Usage
 //!
@@ -43,11 +49,16 @@
 //! This loader is designed for little-endian RISC-V 32-bit executables and implements
 //! a Harvard architecture model. Ensure your ELF files are compatible with these specifications.
 
-use crate::elf::parser;
+use crate::elf::{parser, DebugInfo, SourceLocation};
+use crate::riscv::{
+    scan_undecodable_instructions, scan_unsupported_extensions, UndecodableInstruction,
+    UnsupportedInstruction,
+};
 
 use elf::{endian::LittleEndian, ElfBytes};
 use std::collections::BTreeMap;
 use std::fs::File;
+use std::io::Read;
 use std::path::Path;
 
 use super::error::ParserError;
@@ -72,6 +83,22 @@ pub struct ElfFile {
 
     /// Nexus-specific metadata embedded in the ELF file.
     pub nexus_metadata: Vec<u32>,
+
+    /// Function and object symbols sorted by address, empty for stripped ELF files.
+    #[serde(default)]
+    pub(crate) symbols: Vec<Symbol>,
+
+    /// Line tables and functions of the DWARF debug info, empty for ELF files built without it.
+    #[serde(default)]
+    pub(crate) debug_info: DebugInfo,
+}
+
+/// A named function or object in the ELF file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Symbol {
+    pub name: String,
+    pub address: u32,
+    pub size: u32,
 }
 
 impl ElfFile {
@@ -90,13 +117,54 @@ impl ElfFile {
             rom_image,
             ram_image,
             nexus_metadata,
+            symbols: Vec::new(),
+            debug_info: DebugInfo::default(),
         }
     }
 
+    /// Function and object symbols sorted by address, empty for stripped ELF files.
+    pub fn symbols(&self) -> &[Symbol] {
+        &self.symbols
+    }
+
+    /// The symbol containing `address`, the one starting last if several do. A symbol of size 0
+    /// contains its own address only.
+    pub fn symbol_for_addr(&self, address: u32) -> Option<&Symbol> {
+        Some(&self.symbols[find_symbol(&self.symbols, address)?])
+    }
+
+    /// Look up a symbol by its (mangled) name.
+    pub fn symbol(&self, name: &str) -> Option<&Symbol> {
+        self.symbols.iter().find(|symbol| symbol.name == name)
+    }
+
+    /// Instructions of the program from standard extensions the VM doesn't support, e.g. floating
+    /// point instructions of a program built for the wrong target.
+    pub fn unsupported_instructions(&self) -> Vec<UnsupportedInstruction> {
+        scan_unsupported_extensions(&self.instructions, self.base)
+    }
+
+    /// Words of the instruction segment the VM can't decode, with their address, including
+    /// instructions of unsupported extensions and data embedded in the segment.
+    pub fn undecodable_instructions(&self) -> Vec<UndecodableInstruction> {
+        scan_undecodable_instructions(&self.instructions, self.base)
+    }
+
+    /// Whether the ELF file has DWARF line tables, see [`Self::source_location`].
+    pub fn has_debug_info(&self) -> bool {
+        !self.debug_info.is_empty()
+    }
+
+    /// Source file, line and function of the instruction at `pc`, if the ELF file has debug info.
+    pub fn source_location(&self, pc: u32) -> Option<SourceLocation> {
+        self.debug_info.source_location(pc)
+    }
+
     pub fn get_instructions(&self, address: usize, n: usize) -> &[u32] {
         &self.instructions[address..address + n]
     }
 
+    /// Parses an ELF file already in memory, without going through the filesystem.
     pub fn from_bytes(data: &[u8]) -> Result<Self, ParserError> {
         let elf = ElfBytes::<LittleEndian>::minimal_parse(data).map_err(ParserError::ELFError)?;
 
@@ -117,18 +185,36 @@ impl ElfFile {
             rom_image: parsed_elf_data.readonly_memory,
             ram_image: parsed_elf_data.writable_memory,
             nexus_metadata: parsed_elf_data.nexus_metadata,
+            symbols: parser::parse_symbols(&elf)?,
+            // The program runs the same without debug info, so it doesn't fail loading it.
+            debug_info: DebugInfo::parse(&elf).unwrap_or_else(|e| {
+                tracing::warn!(target: "nexus_vm::elf", "ignoring invalid DWARF debug info: {e}");
+                DebugInfo::default()
+            }),
         })
     }
 
+    /// Reads the whole ELF file from `reader` before parsing it.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, ParserError> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Self::from_bytes(&data)
+    }
+
     pub fn from_path<P: AsRef<Path> + ?Sized>(path: &P) -> Result<Self, ParserError> {
-        let file = File::open(path)?;
-        let data: Vec<u8> = std::io::Read::bytes(file)
-            .map(|b| b.expect("Failed to read byte"))
-            .collect();
-        Self::from_bytes(data.as_slice())
+        Self::from_reader(File::open(path)?)
     }
 }
 
+/// Index of the symbol containing `address`, see [`ElfFile::symbol_for_addr`]. `symbols` are
+/// sorted by address.
+pub(crate) fn find_symbol(symbols: &[Symbol], address: u32) -> Option<usize> {
+    let index = symbols.partition_point(|symbol| symbol.address <= address);
+    symbols[..index]
+        .iter()
+        .rposition(|symbol| address - symbol.address < symbol.size.max(1))
+}
+
 #[cfg(test)]
 mod tests {
 
@@ -153,11 +239,15 @@ mod tests {
 
     #[allow(dead_code)]
     fn debug_elf_file(elf: &ElfFile, file_path: &str) {
-        dbg!(elf.instructions.len());
-        dbg!(elf.entry);
-        dbg!(elf.base);
-        dbg!(elf.ram_image.len());
-        dbg!(elf.rom_image.len());
+        tracing::debug!(
+            target: "nexus_vm::elf",
+            instructions = elf.instructions.len(),
+            entry = elf.entry,
+            base = elf.base,
+            ram_image = elf.ram_image.len(),
+            rom_image = elf.rom_image.len(),
+            "loaded ELF file"
+        );
 
         // Write elf.instructions to a file
         write_instruction_to_file(&elf.instructions, &format!("{}.inst.bin", file_path));
@@ -190,4 +280,47 @@ mod tests {
             assert_eq!(elf.instructions.len(), *number_of_instruction);
         }
     }
+
+    #[test]
+    fn test_load_from_memory() {
+        let bytes = include_bytes!("../../test/fib_10.elf");
+        let from_path = ElfFile::from_path("test/fib_10.elf").unwrap();
+
+        for elf in [
+            ElfFile::from_bytes(bytes).unwrap(),
+            ElfFile::from_reader(&bytes[..]).unwrap(),
+        ] {
+            assert_eq!(elf.instructions, from_path.instructions);
+            assert_eq!(elf.entry, from_path.entry);
+            assert_eq!(elf.ram_image, from_path.ram_image);
+            assert_eq!(elf.rom_image, from_path.rom_image);
+        }
+
+        assert!(ElfFile::from_reader(&bytes[..64]).is_err());
+    }
+
+    #[test]
+    fn test_symbols() {
+        let elf = ElfFile::from_path("test/fib_10.elf").unwrap();
+        let symbols = elf.symbols();
+        assert!(!symbols.is_empty());
+        assert!(symbols.windows(2).all(|w| w[0].address <= w[1].address));
+
+        let main = elf.symbol("main").unwrap();
+        assert_eq!(elf.symbol_for_addr(main.address), Some(main));
+        assert_eq!(
+            elf.symbol_for_addr(main.address + main.size - 4),
+            Some(main)
+        );
+        assert_ne!(elf.symbol_for_addr(main.address + main.size), Some(main));
+        // The entry point `_start` has no type, only functions and objects are parsed.
+        assert_eq!(elf.symbol_for_addr(elf.entry), None);
+    }
+
+    #[test]
+    fn test_no_debug_info() {
+        let elf = ElfFile::from_path("test/fib_10.elf").unwrap();
+        assert!(!elf.has_debug_info());
+        assert_eq!(elf.source_location(elf.entry), None);
+    }
 }
@@ -1,7 +1,11 @@
+mod dwarf;
 mod error;
 mod loader;
 mod parser;
 
+pub(crate) use dwarf::DebugInfo;
+pub use dwarf::SourceLocation;
 pub use error::ParserError as ElfError;
-pub use loader::ElfFile;
+pub(crate) use loader::find_symbol;
+pub use loader::{ElfFile, Symbol};
 pub use nexus_common::constants::WORD_SIZE;
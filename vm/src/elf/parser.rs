@@ -47,6 +47,7 @@ use std::fmt;
 use tracing::debug;
 
 use super::error::{ParserError, Result};
+use super::loader::Symbol;
 
 type Instructions = Vec<u32>;
 type Metadata = Vec<u32>;
@@ -411,24 +412,26 @@ fn parse_precompile_metadata(
         precompiles.insert(precompile_index, str_value.into());
     }
 
-    debug!("Loaded precompile metadata: {precompiles:?}");
+    debug!(target: "nexus_vm::elf", "Loaded precompile metadata: {precompiles:?}");
 
     Ok(precompiles)
 }
 
 #[allow(dead_code)]
 fn debug_segment_info(segment: &ProgramHeader, section_map: &HashMap<&str, (u64, u64)>) {
-    println!("Program Header Information:");
-    println!("  Segment Type: 0x{:08x}", segment.p_type);
-    println!("  File Offset: 0x{:016x}", segment.p_offset);
-    println!("  Virtual Address: 0x{:016x}", segment.p_vaddr);
-    println!("  Physical Address: 0x{:016x}", segment.p_paddr);
-    println!("  File Size: {} bytes", segment.p_filesz);
-    println!("  Memory Size: {} bytes", segment.p_memsz);
-    println!("  Flags: 0x{:08x}", segment.p_flags);
-    println!("  Alignment: 0x{:016x}", segment.p_align);
-    println!(
-        "  LOADABLE: 0x{:08x} -> 0x{:08x}",
+    debug!(
+        target: "nexus_vm::elf",
+        "segment type: 0x{:08x}, file offset: 0x{:016x}, virtual address: 0x{:016x}, \
+         physical address: 0x{:016x}, file size: {} bytes, memory size: {} bytes, \
+         flags: 0x{:08x}, alignment: 0x{:016x}, loadable: 0x{:08x} -> 0x{:08x}",
+        segment.p_type,
+        segment.p_offset,
+        segment.p_vaddr,
+        segment.p_paddr,
+        segment.p_filesz,
+        segment.p_memsz,
+        segment.p_flags,
+        segment.p_align,
         segment.p_offset,
         segment.p_offset + segment.p_memsz
     );
@@ -437,9 +440,43 @@ fn debug_segment_info(segment: &ProgramHeader, section_map: &HashMap<&str, (u64,
         if !(*end < segment.p_offset
             || *start > segment.p_offset + segment.p_offset + segment.p_filesz)
         {
-            println!("Section {}: 0x{:08x} -> 0x{:08x}", key, start, end);
+            debug!(target: "nexus_vm::elf", "section {}: 0x{:08x} -> 0x{:08x}", key, start, end);
+        }
+    }
+}
+
+/// Parses the named function and object symbols of the ELF file, sorted by address. A stripped
+/// ELF file has no symbols.
+pub fn parse_symbols(elf: &ElfBytes<LittleEndian>) -> Result<Vec<Symbol>> {
+    let Some((symbol_table, symbol_string_table)) =
+        elf.symbol_table().map_err(ParserError::ELFError)?
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut symbols = Vec::new();
+    for symbol in symbol_table {
+        if !matches!(symbol.st_symtype(), abi::STT_FUNC | abi::STT_OBJECT) || symbol.st_value == 0 {
+            continue;
         }
+
+        let name = symbol_string_table.get(symbol.st_name as usize)?;
+        if name.is_empty() {
+            continue;
+        }
+
+        symbols.push(Symbol {
+            name: name.to_string(),
+            address: symbol
+                .st_value
+                .try_into()
+                .map_err(|_| ParserError::InvalidVirtualAddress(symbol.st_value))?,
+            size: symbol.st_size as u32,
+        });
     }
+
+    symbols.sort_by(|a, b| (a.address, &a.name).cmp(&(b.address, &b.name)));
+    Ok(symbols)
 }
 
 /// Parses the segments of an ELF file and extracts relevant information.
@@ -0,0 +1,197 @@
+//! # Host-Backed Heap Allocator
+//!
+//! Serves the `Alloc` and `Dealloc` syscalls, so that guests without an allocator of their own,
+//! e.g. `no_std` ones, can allocate from the heap without bumping the heap pointer themselves.
+//!
+//! Allocations are placed first-fit in the blocks freed so far, and otherwise bumped from the top
+//! of the heap. Freed blocks are merged with their free neighbours, and given back to the top of
+//! the heap when they end at it. The placement only depends on the sequence of requests, relative
+//! to the heap start, so both passes of two-pass tracing hand out the same offsets into their heaps.
+//!
+//! A guest should use either this allocator or its own heap pointer, the allocator doesn't know
+//! about memory handed out by the latter.
+
+use std::{
+    cmp::{max, min},
+    collections::BTreeMap,
+};
+
+/// Activity of the host-backed heap allocator.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AllocatorStats {
+    /// Number of successful allocations.
+    pub allocations: u64,
+    /// Number of blocks freed.
+    pub deallocations: u64,
+    /// Number of allocations that failed for lack of heap space.
+    pub out_of_memory: u64,
+    /// Bytes currently allocated.
+    pub in_use: u32,
+    /// Highest number of bytes allocated at once.
+    pub peak_in_use: u32,
+}
+
+/// Allocator over the heap addresses from `start` up to `limit`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct HeapAllocator {
+    limit: u32,
+    // Address past the highest block handed out
+    top: u32,
+    // Free blocks below `top`, by address
+    free: BTreeMap<u32, u32>,
+    // Allocated blocks, by address
+    allocated: BTreeMap<u32, u32>,
+    stats: AllocatorStats,
+}
+
+impl HeapAllocator {
+    pub(crate) fn new(start: u32, limit: u32) -> Self {
+        Self {
+            limit,
+            top: start,
+            ..Default::default()
+        }
+    }
+
+    /// Allocates `size` bytes aligned to `align`, below both the heap limit and the stack pointer.
+    ///
+    /// Returns `None` if the heap is exhausted or `align` isn't a power of two.
+    pub(crate) fn alloc(&mut self, size: u32, align: u32, stack_pointer: u32) -> Option<u32> {
+        if !align.is_power_of_two() {
+            return None;
+        }
+        // Zero-sized requests still get a distinct address.
+        let size = max(size, 1);
+
+        let address = self
+            .take_free(size, align)
+            .or_else(|| self.bump(size, align, min(self.limit, stack_pointer)));
+        match address {
+            Some(address) => {
+                self.allocated.insert(address, size);
+                self.stats.allocations += 1;
+                self.stats.in_use += size;
+                self.stats.peak_in_use = max(self.stats.peak_in_use, self.stats.in_use);
+            }
+            None => self.stats.out_of_memory += 1,
+        }
+        address
+    }
+
+    /// Frees the block allocated at `address`.
+    ///
+    /// Returns `false` if there is no such block, e.g. on a double free.
+    pub(crate) fn dealloc(&mut self, address: u32) -> bool {
+        let Some(size) = self.allocated.remove(&address) else {
+            return false;
+        };
+        self.stats.deallocations += 1;
+        self.stats.in_use -= size;
+        self.release(address, size);
+        true
+    }
+
+    pub(crate) fn stats(&self) -> &AllocatorStats {
+        &self.stats
+    }
+
+    fn take_free(&mut self, size: u32, align: u32) -> Option<u32> {
+        let (block, block_size, address) = self.free.iter().find_map(|(&block, &block_size)| {
+            let address = block.checked_next_multiple_of(align)?;
+            (address.checked_add(size)? <= block + block_size)
+                .then_some((block, block_size, address))
+        })?;
+
+        self.free.remove(&block);
+        if address > block {
+            self.free.insert(block, address - block);
+        }
+        let end = address + size;
+        if end < block + block_size {
+            self.free.insert(end, block + block_size - end);
+        }
+        Some(address)
+    }
+
+    fn bump(&mut self, size: u32, align: u32, limit: u32) -> Option<u32> {
+        let address = self.top.checked_next_multiple_of(align)?;
+        let end = address.checked_add(size).filter(|&end| end <= limit)?;
+
+        if address > self.top {
+            self.release(self.top, address - self.top);
+        }
+        self.top = end;
+        Some(address)
+    }
+
+    fn release(&mut self, mut address: u32, mut size: u32) {
+        if let Some((&prev, &prev_size)) = self.free.range(..address).next_back() {
+            if prev + prev_size == address {
+                self.free.remove(&prev);
+                address = prev;
+                size += prev_size;
+            }
+        }
+        if let Some(next_size) = self.free.remove(&(address + size)) {
+            size += next_size;
+        }
+
+        if address + size == self.top {
+            self.top = address;
+        } else {
+            self.free.insert(address, size);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SP: u32 = u32::MAX;
+
+    #[test]
+    fn test_alloc_reuses_freed_blocks() {
+        let mut allocator = HeapAllocator::new(0x1000, 0x2000);
+
+        let a = allocator.alloc(10, 4, SP).unwrap();
+        let b = allocator.alloc(8, 8, SP).unwrap();
+        let c = allocator.alloc(8, 4, SP).unwrap();
+        assert_eq!((a, b, c), (0x1000, 0x1010, 0x1018));
+
+        // The padding before `b` and the freed `a` are merged and reused.
+        assert!(allocator.dealloc(a));
+        assert!(!allocator.dealloc(a));
+        assert_eq!(allocator.alloc(16, 4, SP), Some(0x1000));
+
+        // Blocks freed at the top of the heap shrink it.
+        assert!(allocator.dealloc(c));
+        assert!(allocator.dealloc(b));
+        assert_eq!(allocator.alloc(4, 4, SP), Some(0x1010));
+
+        assert_eq!(
+            *allocator.stats(),
+            AllocatorStats {
+                allocations: 5,
+                deallocations: 3,
+                out_of_memory: 0,
+                in_use: 20,
+                peak_in_use: 32,
+            }
+        );
+    }
+
+    #[test]
+    fn test_alloc_out_of_memory() {
+        let mut allocator = HeapAllocator::new(0x1000, 0x1100);
+
+        assert_eq!(allocator.alloc(0x80, 4, SP), Some(0x1000));
+        assert_eq!(allocator.alloc(0x100, 4, SP), None);
+        // The stack pointer limits the heap as well.
+        assert_eq!(allocator.alloc(0x40, 4, 0x1090), None);
+        assert_eq!(allocator.alloc(0x40, 4, SP), Some(0x1080));
+        assert_eq!(allocator.alloc(4, 3, SP), None);
+        assert_eq!(allocator.stats().out_of_memory, 2);
+        assert_eq!(allocator.stats().allocations, 2);
+    }
+}
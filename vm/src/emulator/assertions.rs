@@ -0,0 +1,194 @@
+//! # Memory-Safety Assertions
+//!
+//! A guest corrupting its own memory often keeps running for a long time before it fails, if it
+//! fails at all, which makes such bugs hard to find by fuzzing. In assertion mode the linear
+//! emulator checks memory-safety invariants after every instruction, and stops at the first
+//! violation with the pc of the offending instruction:
+//!
+//! - The stack pointer is within the stack segment whenever memory is addressed relative to it.
+//! - Heap bytes are written before they are read.
+//! - The write-only public output is never read.
+//!
+//! The checks need the memory layout, the Harvard emulator doesn't perform them.
+
+use std::{
+    collections::HashSet,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+use nexus_common::memory::{LoadOp, MemAccessSize, StoreOp};
+
+use super::LinearMemoryLayout;
+use crate::riscv::{Instruction, Register};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssertionKind {
+    StackPointerOutOfBounds,
+    UninitializedHeapRead,
+    WriteOnlyRead,
+}
+
+impl Display for AssertionKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(match self {
+            Self::StackPointerOutOfBounds => "stack pointer outside of the stack",
+            Self::UninitializedHeapRead => "read of never-written heap memory",
+            Self::WriteOnlyRead => "read from the write-only public output",
+        })
+    }
+}
+
+/// A violated memory-safety invariant, `address` is the offending stack pointer for
+/// [`AssertionKind::StackPointerOutOfBounds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemorySafetyViolation {
+    pub kind: AssertionKind,
+    pub pc: u32,
+    pub address: u32,
+    pub clock: usize,
+}
+
+impl Display for MemorySafetyViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "[clock {}] pc=0x{:08X}: {} at 0x{:08X}",
+            self.clock, self.pc, self.kind, self.address
+        )
+    }
+}
+
+/// State of the memory-safety assertions of an executor.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MemoryAssertions {
+    // Heap bytes written so far
+    written_heap: HashSet<u32>,
+}
+
+impl MemoryAssertions {
+    /// Checks the memory accesses of `instruction`, executed at `pc` with the stack pointer `sp`.
+    pub(crate) fn check(
+        &mut self,
+        layout: &LinearMemoryLayout,
+        instruction: &Instruction,
+        (pc, clock, sp): (u32, usize, u32),
+        load_ops: &HashSet<LoadOp>,
+        store_ops: &HashSet<StoreOp>,
+    ) -> Result<(), MemorySafetyViolation> {
+        let violation = |kind, address| MemorySafetyViolation {
+            kind,
+            pc,
+            address,
+            clock,
+        };
+        let accessed =
+            |size: MemAccessSize, address: u32| (0..size as u32).map(move |i| address + i);
+        let heap = layout.heap_start()..layout.heap_end();
+
+        let addresses_stack =
+            !(load_ops.is_empty() && store_ops.is_empty()) && instruction.op_b == Register::X2;
+        if addresses_stack && !(layout.stack_bottom()..=layout.stack_top()).contains(&sp) {
+            return Err(violation(AssertionKind::StackPointerOutOfBounds, sp));
+        }
+
+        for op in load_ops {
+            for address in accessed(op.get_size(), op.get_address()) {
+                if (layout.exit_code()..layout.public_output_end()).contains(&address) {
+                    return Err(violation(AssertionKind::WriteOnlyRead, address));
+                }
+                if heap.contains(&address) && !self.written_heap.contains(&address) {
+                    return Err(violation(AssertionKind::UninitializedHeapRead, address));
+                }
+            }
+        }
+
+        for op in store_ops {
+            let StoreOp::Op(size, address, ..) = *op;
+            self.written_heap
+                .extend(accessed(size, address).filter(|address| heap.contains(address)));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        elf::ElfFile,
+        emulator::{Emulator, EmulatorOptions, LinearEmulator},
+        error::VMError,
+        riscv::{BuiltinOpcode, Opcode},
+    };
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_assertions_pass_on_well_behaved_guest() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let mut emulator =
+            LinearEmulator::from_elf(LinearMemoryLayout::default(), &[], &elf_file, &[], &[]);
+        emulator
+            .executor
+            .set_options(&EmulatorOptions {
+                memory_assertions: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
+    }
+
+    #[test]
+    fn test_assertion_violations() {
+        let layout = LinearMemoryLayout::default();
+        let mut assertions = MemoryAssertions::default();
+        let lw = Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 1, 3, 0);
+        let sw_sp = Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 0);
+        let context = (0x1000, 7, layout.stack_top());
+        let heap = layout.heap_start();
+        let load = |address| HashSet::from([LoadOp::Op(MemAccessSize::Word, address, 0)]);
+        let store = |address| HashSet::from([StoreOp::Op(MemAccessSize::Word, address, 0, 0)]);
+
+        let err = assertions
+            .check(&layout, &lw, context, &load(heap), &HashSet::new())
+            .unwrap_err();
+        assert_eq!(err.kind, AssertionKind::UninitializedHeapRead);
+        assert_eq!((err.pc, err.address, err.clock), (0x1000, heap, 7));
+
+        // Once written, the heap word can be read.
+        assertions
+            .check(&layout, &lw, context, &HashSet::new(), &store(heap))
+            .unwrap();
+        assertions
+            .check(&layout, &lw, context, &load(heap), &HashSet::new())
+            .unwrap();
+
+        let err = assertions
+            .check(
+                &layout,
+                &lw,
+                context,
+                &load(layout.exit_code()),
+                &HashSet::new(),
+            )
+            .unwrap_err();
+        assert_eq!(err.kind, AssertionKind::WriteOnlyRead);
+
+        let sp = layout.stack_bottom() - 4;
+        let err = assertions
+            .check(
+                &layout,
+                &sw_sp,
+                (0x1000, 7, sp),
+                &HashSet::new(),
+                &store(sp),
+            )
+            .unwrap_err();
+        assert_eq!(err.kind, AssertionKind::StackPointerOutOfBounds);
+        assert_eq!(err.address, sp);
+        assert!(err
+            .to_string()
+            .contains("stack pointer outside of the stack"));
+    }
+}
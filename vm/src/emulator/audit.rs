@@ -0,0 +1,224 @@
+//! # Permission Audit
+//!
+//! Porting an existing binary usually fails on the first memory permission violation, one at a
+//! time. In audit mode the executor records every violation instead, so that all of them can be
+//! reviewed at once. Depending on the [`AuditPolicy`], execution either stops at the first one as
+//! usual, or skips the offending instruction and continues.
+//!
+//! Executing outside of the instruction segment can't be skipped, it is recorded and always stops
+//! the execution.
+
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use nexus_common::error::MemoryError;
+
+use crate::error::VMError;
+
+/// What to do once a permission violation is recorded.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AuditPolicy {
+    /// Skip the offending instruction and keep executing.
+    Continue,
+    /// Stop the execution with the original error.
+    #[default]
+    Fail,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ViolationKind {
+    WriteToReadOnly,
+    ReadFromWriteOnly,
+    ExecuteOutsideProgram,
+}
+
+impl Display for ViolationKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        f.write_str(match self {
+            Self::WriteToReadOnly => "write to read-only memory",
+            Self::ReadFromWriteOnly => "read from write-only memory",
+            Self::ExecuteOutsideProgram => "execution outside the instruction segment",
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PermissionViolation {
+    pub kind: ViolationKind,
+    pub pc: u32,
+    pub address: u32,
+    pub clock: usize,
+}
+
+impl Display for PermissionViolation {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "[clock {}] pc=0x{:08X}: {} at 0x{:08X}",
+            self.clock, self.pc, self.kind, self.address
+        )
+    }
+}
+
+/// Permission violations recorded during execution, in order.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PermissionAudit {
+    policy: AuditPolicy,
+    violations: Vec<PermissionViolation>,
+}
+
+impl PermissionAudit {
+    pub fn new(policy: AuditPolicy) -> Self {
+        Self {
+            policy,
+            violations: Vec::new(),
+        }
+    }
+
+    pub fn policy(&self) -> AuditPolicy {
+        self.policy
+    }
+
+    pub fn violations(&self) -> &[PermissionViolation] {
+        &self.violations
+    }
+
+    pub fn is_clean(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// Number of recorded violations of `kind`.
+    pub fn count(&self, kind: ViolationKind) -> usize {
+        self.violations.iter().filter(|v| v.kind == kind).count()
+    }
+
+    /// Records `error` if it is a permission violation.
+    ///
+    /// Returns `Ok` if the offending instruction should be skipped, and the error otherwise.
+    pub(crate) fn record(&mut self, error: VMError, pc: u32, clock: usize) -> Result<(), VMError> {
+        let (kind, address) = match error {
+            VMError::MemoryError(MemoryError::UnauthorizedWrite(address)) => {
+                (ViolationKind::WriteToReadOnly, address)
+            }
+            VMError::MemoryError(MemoryError::UnauthorizedRead(address)) => {
+                (ViolationKind::ReadFromWriteOnly, address)
+            }
+            VMError::SelfModifyingCode(_, address) => (ViolationKind::WriteToReadOnly, address),
+            VMError::UnauthorizedExecution(address) => {
+                (ViolationKind::ExecuteOutsideProgram, address)
+            }
+            _ => return Err(error),
+        };
+
+        self.violations.push(PermissionViolation {
+            kind,
+            pc,
+            address,
+            clock,
+        });
+
+        match (self.policy, kind) {
+            (AuditPolicy::Continue, ViolationKind::WriteToReadOnly)
+            | (AuditPolicy::Continue, ViolationKind::ReadFromWriteOnly) => Ok(()),
+            _ => Err(error),
+        }
+    }
+}
+
+impl Display for PermissionAudit {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        writeln!(
+            f,
+            "permission audit: {} violation(s) ({} write(s) to RO, {} read(s) from WO, {} execution(s) outside the program)",
+            self.violations.len(),
+            self.count(ViolationKind::WriteToReadOnly),
+            self.count(ViolationKind::ReadFromWriteOnly),
+            self.count(ViolationKind::ExecuteOutsideProgram),
+        )?;
+        for violation in &self.violations {
+            writeln!(f, "  {violation}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulator::{Emulator, EmulatorOptions, HarvardEmulator};
+    use crate::memory::{FixedMemory, RO};
+    use crate::riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode, Register};
+
+    fn emulator() -> HarvardEmulator {
+        let basic_blocks = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 0x100),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 0x100),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 7),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JALR), 0, 0, 0x10),
+        ])];
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator
+            .data_memory
+            .add_fixed_ro(FixedMemory::<RO>::from_words(0x100, 4, &[0]))
+            .unwrap();
+        emulator
+    }
+
+    #[test]
+    fn test_without_audit() {
+        let mut emulator = emulator();
+        assert_eq!(
+            emulator.execute(false),
+            Err(VMError::MemoryError(MemoryError::UnauthorizedWrite(0x100)))
+        );
+        assert!(emulator.executor.permission_audit().is_none());
+    }
+
+    #[test]
+    fn test_audit_continue() {
+        let mut emulator = emulator();
+        emulator
+            .executor
+            .set_options(&EmulatorOptions {
+                permission_audit: Some(AuditPolicy::Continue),
+                ..Default::default()
+            })
+            .unwrap();
+
+        // The write is skipped, but execution can't continue outside of the program.
+        assert_eq!(
+            emulator.execute(false),
+            Err(VMError::UnauthorizedExecution(0x10))
+        );
+        assert_eq!(emulator.executor.cpu.registers[Register::X3], 7);
+
+        let audit = emulator.executor.permission_audit().unwrap();
+        assert_eq!(audit.count(ViolationKind::WriteToReadOnly), 1);
+        assert_eq!(audit.count(ViolationKind::ExecuteOutsideProgram), 1);
+        assert_eq!(audit.violations()[0].address, 0x100);
+        assert!(audit
+            .to_string()
+            .starts_with("permission audit: 2 violation(s)"));
+    }
+
+    #[test]
+    fn test_audit_fail() {
+        let mut emulator = emulator();
+        emulator
+            .executor
+            .set_options(&EmulatorOptions {
+                permission_audit: Some(AuditPolicy::Fail),
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(
+            emulator.execute(false),
+            Err(VMError::MemoryError(MemoryError::UnauthorizedWrite(0x100)))
+        );
+        let audit = emulator.executor.permission_audit().unwrap();
+        assert_eq!(audit.violations().len(), 1);
+        assert_eq!(audit.violations()[0].kind, ViolationKind::WriteToReadOnly);
+    }
+}
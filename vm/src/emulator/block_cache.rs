@@ -0,0 +1,122 @@
+//! # Persistent Basic-Block Cache
+//!
+//! Decoding is a noticeable part of short executions of large guests. The decoded basic blocks of
+//! a run can be written to disk and loaded by later runs of the same program, which then only
+//! decode blocks they reach for the first time, see [`super::Executor::save_block_cache`].
+//!
+//! A cache file is keyed by a hash of the program and its load address, files of other programs
+//! are ignored when loaded. The hash detects stale files, it is not meant to resist files crafted
+//! to collide.
+
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{error::BlockCacheError, riscv::Instruction};
+
+/// Identifies a program loaded at `base` with the encoded `instructions`, 64-bit FNV-1a.
+pub(crate) fn program_hash(base: u32, instructions: &[u32]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+    std::iter::once(base)
+        .chain(instructions.iter().copied())
+        .flat_map(u32::to_le_bytes)
+        .fold(OFFSET_BASIS, |hash, byte| {
+            (hash ^ byte as u64).wrapping_mul(PRIME)
+        })
+}
+
+/// Contents of a cache file.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub(crate) struct BlockCacheFile {
+    pub(crate) program_hash: u64,
+    // Decoded blocks, by their start address
+    pub(crate) blocks: Vec<(u32, Vec<Instruction>)>,
+    // Encodings of the undecodable instructions in the blocks, by their address
+    pub(crate) undecodable: Vec<(u32, u32)>,
+}
+
+impl BlockCacheFile {
+    pub(crate) fn write<P: AsRef<Path> + ?Sized>(&self, path: &P) -> Result<(), BlockCacheError> {
+        std::fs::write(path, postcard::to_allocvec(self)?)?;
+        Ok(())
+    }
+
+    pub(crate) fn read<P: AsRef<Path> + ?Sized>(path: &P) -> Result<Self, BlockCacheError> {
+        Ok(postcard::from_bytes(&std::fs::read(path)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        elf::ElfFile,
+        emulator::{
+            Emulator, EmulatorOptions, HarvardEmulator, LinearEmulator, LinearMemoryLayout,
+        },
+        error::VMError,
+        riscv::{BasicBlock, BuiltinOpcode, Opcode, Register},
+    };
+    use serial_test::serial;
+
+    #[test]
+    fn test_program_hash_covers_base_and_instructions() {
+        let hash = program_hash(0x1000, &[0x13, 0x73]);
+        assert_eq!(hash, program_hash(0x1000, &[0x13, 0x73]));
+        assert_ne!(hash, program_hash(0x2000, &[0x13, 0x73]));
+        assert_ne!(hash, program_hash(0x1000, &[0x73, 0x13]));
+    }
+
+    #[test]
+    #[serial]
+    fn test_block_cache_round_trip() {
+        let path = std::env::temp_dir().join("nexus_vm_test_block_cache.bin");
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let linear =
+            || LinearEmulator::from_elf(LinearMemoryLayout::default(), &[], &elf_file, &[], &[]);
+
+        let mut emulator = linear();
+        assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
+        emulator.executor.save_block_cache(&path).unwrap();
+
+        // A later run of the same program executes the same, starting from the saved blocks.
+        let mut cached = linear();
+        assert!(cached.executor.load_block_cache(&path).unwrap());
+        assert_eq!(
+            cached.executor.cached_blocks(),
+            emulator.executor.cached_blocks()
+        );
+        assert_eq!(cached.execute(false), Err(VMError::VMExited(0)));
+        assert_eq!(cached.executor.global_clock, emulator.executor.global_clock);
+
+        // Blocks of other programs don't apply, and a program patching itself can't be cached.
+        let mut patching = HarvardEmulator::from_basic_blocks(&vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::AUIPC), 1, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 8),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 1),
+        ])]);
+        let patch = Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 42);
+        patching.set_initial_registers(&[(Register::X2, patch.encode())]);
+        patching
+            .executor
+            .set_options(&EmulatorOptions {
+                allow_self_modifying_code: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert!(!patching.executor.load_block_cache(&path).unwrap());
+        assert_eq!(patching.executor.cached_blocks(), 0);
+        assert_eq!(patching.execute(false), Err(VMError::VMOutOfInstructions));
+        assert!(matches!(
+            patching.executor.save_block_cache(&path),
+            Err(BlockCacheError::ProgramModified)
+        ));
+
+        std::fs::write(&path, b"not a cache").unwrap();
+        let err = linear().executor.load_block_cache(&path).unwrap_err();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(err, BlockCacheError::Encoding(_)));
+    }
+}
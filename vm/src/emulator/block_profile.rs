@@ -0,0 +1,151 @@
+//! # Hot Basic Blocks
+//!
+//! Cycles spent in each basic block, to show guest developers where their cycle budget goes. A block
+//! is identified by the pc execution entered it at, which is its start unless execution resumed in
+//! the middle of it, e.g. after a trap. See [`super::Executor::hot_block_report`] for a report with
+//! the disassembly and symbol of the hottest blocks.
+
+use std::{
+    collections::BTreeMap,
+    fmt::{Result as FmtResult, Write},
+};
+
+use crate::{
+    elf::{find_symbol, Symbol},
+    riscv::Instruction,
+};
+
+/// Number of instructions of a block shown in a report.
+const SNIPPET_LEN: usize = 4;
+
+/// Cycles executed by the guest, by the pc basic blocks were entered at.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BlockProfile {
+    cycles: BTreeMap<u32, u64>,
+    // Block executing since the given clock, not yet counted
+    current: Option<(u32, usize)>,
+}
+
+impl BlockProfile {
+    /// Starts counting the cycles of the block entered at `pc`, after finishing the previous one.
+    pub(crate) fn enter(&mut self, pc: u32, clock: usize) {
+        self.finish(clock);
+        self.current = Some((pc, clock));
+    }
+
+    /// Counts the cycles of the current block up to `clock`.
+    pub(crate) fn finish(&mut self, clock: usize) {
+        if let Some((pc, start)) = self.current.take() {
+            *self.cycles.entry(pc).or_default() += clock.saturating_sub(start) as u64;
+        }
+    }
+
+    /// Cycles executed in the block entered at `pc`, if it was executed.
+    pub fn get(&self, pc: u32) -> Option<u64> {
+        self.cycles.get(&pc).copied()
+    }
+
+    /// Cycles of all executed blocks, ordered by pc.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, u64)> + '_ {
+        self.cycles.iter().map(|(&pc, &cycles)| (pc, cycles))
+    }
+
+    /// Cycles executed in all blocks.
+    pub fn total_cycles(&self) -> u64 {
+        self.cycles.values().sum()
+    }
+
+    /// The `n` blocks taking the most cycles, hottest first.
+    pub fn hottest(&self, n: usize) -> Vec<(u32, u64)> {
+        let mut blocks: Vec<_> = self.iter().collect();
+        blocks.sort_by_key(|(pc, cycles)| (std::cmp::Reverse(*cycles), *pc));
+        blocks.truncate(n);
+        blocks
+    }
+
+    /// Writes the `n` hottest blocks to `w`, with the symbol containing each of them and the first
+    /// instructions from `disassemble`.
+    pub(crate) fn write_report(
+        &self,
+        w: &mut impl Write,
+        n: usize,
+        symbols: &[Symbol],
+        disassemble: impl Fn(u32) -> Vec<Instruction>,
+    ) -> FmtResult {
+        let total = self.total_cycles().max(1);
+        writeln!(w, "{:>12} {:>7}  {:<10}  symbol", "cycles", "share", "pc")?;
+        for (pc, cycles) in self.hottest(n) {
+            let share = cycles as f64 * 100.0 / total as f64;
+            let symbol = symbolize(symbols, pc).unwrap_or_else(|| "?".to_string());
+            writeln!(w, "{cycles:>12} {share:>6.2}%  0x{pc:08x}  {symbol}")?;
+
+            let instructions = disassemble(pc);
+            for instruction in instructions.iter().take(SNIPPET_LEN) {
+                writeln!(w, "{:>34}{instruction}", "")?;
+            }
+            if instructions.len() > SNIPPET_LEN {
+                writeln!(w, "{:>34}...", "")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Names `pc` by the symbol containing it, e.g. `main+0x10`. `symbols` are sorted by address.
+fn symbolize(symbols: &[Symbol], pc: u32) -> Option<String> {
+    let symbol = &symbols[find_symbol(symbols, pc)?];
+    Some(match pc - symbol.address {
+        0 => symbol.name.clone(),
+        offset => format!("{}+0x{offset:x}", symbol.name),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::riscv::{BuiltinOpcode, Opcode};
+
+    #[test]
+    fn test_hot_block_report() {
+        let mut profile = BlockProfile::default();
+        profile.enter(0x1000, 0);
+        profile.enter(0x1010, 2);
+        profile.enter(0x1000, 8);
+        profile.enter(0x2000, 10);
+        profile.finish(11);
+
+        assert_eq!(profile.get(0x1010), Some(6));
+        assert_eq!(profile.get(0x1000), Some(4));
+        assert_eq!(profile.total_cycles(), 11);
+        let hottest: Vec<u32> = profile.hottest(2).into_iter().map(|(pc, _)| pc).collect();
+        assert_eq!(hottest, [0x1010, 0x1000]);
+
+        let symbols = [
+            Symbol {
+                name: "main".to_string(),
+                address: 0x1000,
+                size: 0x20,
+            },
+            Symbol {
+                name: "helper".to_string(),
+                address: 0x1008,
+                size: 0x4,
+            },
+        ];
+        let addi = Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 1);
+        let mut report = String::new();
+        profile
+            .write_report(&mut report, 3, &symbols, |pc| {
+                vec![addi.clone(); if pc == 0x1010 { 5 } else { 1 }]
+            })
+            .unwrap();
+
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 1 + 3 + 6 + 1);
+        assert!(lines[1].contains("0x00001010") && lines[1].ends_with("main+0x10"));
+        assert!(lines[1].contains("54.55%"));
+        assert_eq!(lines[6].trim(), "...");
+        assert!(lines[7].ends_with("  main"));
+        assert!(lines[9].ends_with("  ?"));
+    }
+}
@@ -0,0 +1,112 @@
+use std::collections::BTreeMap;
+
+use nexus_common::cpu::Registers;
+
+use crate::riscv::{BuiltinOpcode, Instruction};
+
+/// Whether the conditional branch `instruction` is taken with `registers`, none for other
+/// instructions. Branches don't write registers, so this holds before and after executing one.
+pub(crate) fn branch_taken(instruction: &Instruction, registers: &impl Registers) -> Option<bool> {
+    let (rs1, rs2) = (registers[instruction.op_a], registers[instruction.op_b]);
+    Some(match instruction.opcode.builtin()? {
+        BuiltinOpcode::BEQ => rs1 == rs2,
+        BuiltinOpcode::BNE => rs1 != rs2,
+        BuiltinOpcode::BLT => (rs1 as i32) < (rs2 as i32),
+        BuiltinOpcode::BGE => (rs1 as i32) >= (rs2 as i32),
+        BuiltinOpcode::BLTU => rs1 < rs2,
+        BuiltinOpcode::BGEU => rs1 >= rs2,
+        _ => return None,
+    })
+}
+
+/// Number of times a conditional branch was taken and not taken.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BranchCounts {
+    pub taken: u64,
+    pub not_taken: u64,
+}
+
+impl BranchCounts {
+    /// Number of times the branch was executed.
+    pub fn total(&self) -> u64 {
+        self.taken + self.not_taken
+    }
+}
+
+/// Outcomes of conditional branches executed by the guest, by the pc of the branch.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BranchStats {
+    branches: BTreeMap<u32, BranchCounts>,
+}
+
+impl BranchStats {
+    pub(crate) fn record(&mut self, pc: u32, taken: bool) {
+        let counts = self.branches.entry(pc).or_default();
+        if taken {
+            counts.taken += 1;
+        } else {
+            counts.not_taken += 1;
+        }
+    }
+
+    /// Outcomes of the branch at `pc`, if it was executed.
+    pub fn get(&self, pc: u32) -> Option<BranchCounts> {
+        self.branches.get(&pc).copied()
+    }
+
+    /// Outcomes of all executed branches, ordered by pc.
+    pub fn iter(&self) -> impl Iterator<Item = (u32, BranchCounts)> + '_ {
+        self.branches.iter().map(|(&pc, &counts)| (pc, counts))
+    }
+
+    /// Outcomes of all branches together.
+    pub fn total(&self) -> BranchCounts {
+        self.branches
+            .values()
+            .fold(BranchCounts::default(), |total, counts| BranchCounts {
+                taken: total.taken + counts.taken,
+                not_taken: total.not_taken + counts.not_taken,
+            })
+    }
+
+    /// The `n` most executed branches, most executed first.
+    pub fn hottest(&self, n: usize) -> Vec<(u32, BranchCounts)> {
+        let mut branches: Vec<_> = self.iter().collect();
+        branches.sort_by_key(|(pc, counts)| (std::cmp::Reverse(counts.total()), *pc));
+        branches.truncate(n);
+        branches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hottest_branches() {
+        let mut stats = BranchStats::default();
+        for taken in [true, true, false] {
+            stats.record(0x1008, taken);
+        }
+        stats.record(0x1000, false);
+        stats.record(0x1010, true);
+
+        assert_eq!(
+            stats.get(0x1008),
+            Some(BranchCounts {
+                taken: 2,
+                not_taken: 1
+            })
+        );
+        assert_eq!(stats.get(0x1004), None);
+        let hottest: Vec<u32> = stats.hottest(2).into_iter().map(|(pc, _)| pc).collect();
+        assert_eq!(hottest, [0x1008, 0x1000]);
+        assert_eq!(
+            stats.total(),
+            BranchCounts {
+                taken: 3,
+                not_taken: 2
+            }
+        );
+    }
+}
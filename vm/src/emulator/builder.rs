@@ -0,0 +1,387 @@
+//! # Emulator Builder
+//!
+//! Configures and creates either kind of emulator from an ELF file. The positional `from_elf`
+//! constructors of [`HarvardEmulator`] and [`LinearEmulator`] are shorthands for it.
+//!
+//! ```rust
+//! use nexus_vm::elf::ElfFile;
+//! use nexus_vm::emulator::{Emulator, EmulatorBuilder};
+//! use nexus_vm::error::VMError;
+//!
+//! let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+//! let builder = EmulatorBuilder::new(elf_file).capture_logs(true);
+//!
+//! let mut harvard = builder.build_harvard();
+//! assert_eq!(harvard.execute(false), Err(VMError::VMExited(0)));
+//!
+//! // Without an explicit layout, the linear emulator sizes its memory from a first pass.
+//! let mut linear = builder.build_linear().unwrap();
+//! assert_eq!(linear.execute(false), Err(VMError::VMExited(0)));
+//! ```
+
+use std::sync::Arc;
+
+use super::{Emulator, Executor, HarvardEmulator, LinearEmulator, LinearMemoryLayout};
+use crate::{
+    elf::ElfFile,
+    error::{Result, VMError},
+    riscv::Register,
+};
+use serde::Serialize;
+
+/// Registers hooks on the executor of each emulator built, see [`EmulatorBuilder::hooks`].
+type HookInstaller = Arc<dyn Fn(&mut Executor) + Send + Sync>;
+
+/// The kind of emulator created by [`EmulatorBuilder::build`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmulatorKind {
+    Harvard,
+    Linear,
+}
+
+#[derive(Clone)]
+pub struct EmulatorBuilder {
+    elf: ElfFile,
+    public_input: Vec<u8>,
+    private_input: Vec<u8>,
+    ad: Vec<u8>,
+    layout: Option<LinearMemoryLayout>,
+    registers: Vec<(Register, u32)>,
+    capture_logs: bool,
+    max_cycles: Option<u64>,
+    hooks: Vec<HookInstaller>,
+}
+
+impl EmulatorBuilder {
+    pub fn new(elf: ElfFile) -> Self {
+        Self {
+            elf,
+            public_input: Vec::new(),
+            private_input: Vec::new(),
+            ad: Vec::new(),
+            layout: None,
+            registers: Vec::new(),
+            capture_logs: false,
+            max_cycles: None,
+            hooks: Vec::new(),
+        }
+    }
+
+    pub fn public_input(mut self, public_input: &[u8]) -> Self {
+        self.public_input = public_input.to_vec();
+        self
+    }
+
+    /// Serialize `value` as the public input, to be read by the guest with
+    /// `nexus_rt::read_public_input`. Both sides use the COBS encoding of postcard.
+    pub fn set_public_input<T: Serialize + ?Sized>(
+        mut self,
+        value: &T,
+    ) -> std::result::Result<Self, postcard::Error> {
+        self.public_input = postcard::to_allocvec_cobs(value)?;
+        Ok(self)
+    }
+
+    pub fn private_input(mut self, private_input: &[u8]) -> Self {
+        self.private_input = private_input.to_vec();
+        self
+    }
+
+    /// Associated data, only used by the linear emulator.
+    pub fn ad(mut self, ad: &[u8]) -> Self {
+        self.ad = ad.to_vec();
+        self
+    }
+
+    /// Memory layout of the linear emulator. The Harvard emulator only reports it to the guest, so
+    /// that a program querying it takes the same path in both passes.
+    pub fn layout(mut self, layout: LinearMemoryLayout) -> Self {
+        self.layout = Some(layout);
+        self
+    }
+
+    /// Initial value of a general purpose register, see [`Emulator::set_initial_registers`].
+    pub fn register(mut self, reg: Register, value: u32) -> Self {
+        self.registers.push((reg, value));
+        self
+    }
+
+    /// Capture the debug logs of the guest in the `View` rather than printing them.
+    pub fn capture_logs(mut self, capture: bool) -> Self {
+        self.capture_logs = capture;
+        self
+    }
+
+    /// Stop the execution with `VMError::OutOfFuel` after `max_cycles` cycles, see
+    /// [`super::Executor::set_max_cycles`]. Also bounds the first pass of the linear emulator.
+    pub fn max_cycles(mut self, max_cycles: u64) -> Self {
+        self.max_cycles = Some(max_cycles);
+        self
+    }
+
+    /// Register hooks on the executor of every emulator built, e.g. with
+    /// [`Executor::add_pre_instruction_hook`]. `install` is called once per emulator, so that
+    /// each gets its own hooks. The hooks don't run in the first pass of [`Self::build_linear`].
+    pub fn hooks(mut self, install: impl Fn(&mut Executor) + Send + Sync + 'static) -> Self {
+        self.hooks.push(Arc::new(install));
+        self
+    }
+
+    pub fn build_harvard(&self) -> HarvardEmulator {
+        let mut emulator = self.harvard();
+        self.install_hooks(&mut emulator.executor);
+        emulator
+    }
+
+    fn harvard(&self) -> HarvardEmulator {
+        let mut emulator =
+            HarvardEmulator::load(&self.elf, &self.public_input, &self.private_input);
+        emulator.set_initial_registers(&self.registers);
+        emulator.executor.capture_logs(self.capture_logs);
+        emulator.executor.report_layout(self.layout);
+        if let Some(max_cycles) = self.max_cycles {
+            emulator.executor.set_max_cycles(max_cycles);
+        }
+        emulator
+    }
+
+    fn install_hooks(&self, executor: &mut Executor) {
+        for install in &self.hooks {
+            install(executor);
+        }
+    }
+
+    /// Creates a linear emulator.
+    ///
+    /// If no layout was given, the program is executed once by a Harvard emulator to find the
+    /// smallest layout fitting it, like in the first pass of tracing.
+    pub fn build_linear(&self) -> Result<LinearEmulator> {
+        let Some(layout) = self.layout else {
+            let harvard = self.run_first_pass()?;
+            return self.build_linear_after(&harvard);
+        };
+
+        let emulator = LinearEmulator::load(
+            layout,
+            &self.ad,
+            &self.elf,
+            &self.public_input,
+            &self.private_input,
+        );
+        Ok(self.configure_linear(emulator))
+    }
+
+    /// Creates a linear emulator after `harvard`, the first pass of [`Self::first_pass`] executed
+    /// to completion. Its memory usage sizes the layout, unless one was given.
+    pub fn build_linear_after(&self, harvard: &HarvardEmulator) -> Result<LinearEmulator> {
+        if self.layout.is_some() {
+            return self.build_linear();
+        }
+        let emulator =
+            LinearEmulator::from_harvard(harvard, self.elf.clone(), &self.ad, &self.private_input)?;
+        Ok(self.configure_linear(emulator))
+    }
+
+    /// The Harvard emulator of the first pass of the program, capturing the debug logs and
+    /// without the hooks.
+    pub fn first_pass(&self) -> HarvardEmulator {
+        let mut harvard = self.harvard();
+        harvard.executor.capture_logs(true);
+        harvard
+    }
+
+    /// Executes the first pass of [`Self::first_pass`] until the program exits. Breakpoints and
+    /// yields don't end the pass, it's resumed after them.
+    pub fn run_first_pass(&self) -> Result<HarvardEmulator> {
+        let mut harvard = self.first_pass();
+        loop {
+            match harvard.execute(false) {
+                Err(VMError::VMExited(_)) => return Ok(harvard),
+                Err(VMError::Breakpoint(_) | VMError::Yielded(_)) => {}
+                Err(e) => return Err(e),
+                Ok(_) => unreachable!("execution only ends with an error"),
+            }
+        }
+    }
+
+    fn configure_linear(&self, mut emulator: LinearEmulator) -> LinearEmulator {
+        emulator.set_initial_registers(&self.registers);
+        emulator.executor.capture_logs(self.capture_logs);
+        emulator.executor.report_layout(self.layout);
+        if let Some(max_cycles) = self.max_cycles {
+            emulator.executor.set_max_cycles(max_cycles);
+        }
+        self.install_hooks(&mut emulator.executor);
+        emulator
+    }
+
+    pub fn build(&self, kind: EmulatorKind) -> Result<Box<dyn Emulator>> {
+        Ok(match kind {
+            EmulatorKind::Harvard => Box::new(self.build_harvard()),
+            EmulatorKind::Linear => Box::new(self.build_linear()?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_builder_matches_constructors() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let builder = EmulatorBuilder::new(elf_file.clone()).capture_logs(true);
+
+        let mut expected = HarvardEmulator::from_elf(&elf_file, &[], &[]);
+        expected.executor.capture_logs(true);
+        let mut harvard = builder.build_harvard();
+        assert_eq!(expected.execute(false), harvard.execute(false));
+        assert_eq!(
+            expected.executor.global_clock,
+            harvard.executor.global_clock
+        );
+
+        let layout = LinearMemoryLayout::default();
+        let mut expected = LinearEmulator::from_elf(layout, &[], &elf_file, &[], &[]);
+        let mut linear = builder.clone().layout(layout).build_linear().unwrap();
+        assert_eq!(expected.execute(false), linear.execute(false));
+        assert_eq!(expected.executor.global_clock, linear.executor.global_clock);
+    }
+
+    #[test]
+    #[serial]
+    fn test_builder_initial_registers() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let builder = EmulatorBuilder::new(elf_file).register(Register::X10, 42);
+
+        let harvard = builder.build_harvard();
+        assert_eq!(harvard.executor.cpu.registers[Register::X10], 42);
+
+        let layout = LinearMemoryLayout::default();
+        let linear = builder.layout(layout).build_linear().unwrap();
+        assert_eq!(linear.executor.cpu.registers[Register::X10], 42);
+    }
+
+    #[test]
+    #[serial]
+    fn test_builder_typed_public_input() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let input = (42u32, String::from("fib"));
+        let builder = EmulatorBuilder::new(elf_file)
+            .set_public_input(&input)
+            .unwrap();
+        assert_eq!(
+            builder.public_input,
+            postcard::to_allocvec_cobs(&input).unwrap()
+        );
+
+        let mut linear = builder.build_linear().unwrap();
+        assert_eq!(linear.execute(false), Err(VMError::VMExited(0)));
+        let mut bytes = linear.finalize().view_public_input().unwrap();
+        let decoded: (u32, String) = postcard::from_bytes_cobs(&mut bytes).unwrap();
+        assert_eq!(decoded, input);
+    }
+
+    #[test]
+    #[serial]
+    fn test_builder_derives_layout() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let builder = EmulatorBuilder::new(elf_file);
+
+        for kind in [EmulatorKind::Harvard, EmulatorKind::Linear] {
+            let mut emulator = builder.build(kind).unwrap();
+            assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_builder_reported_layout() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let builder = EmulatorBuilder::new(elf_file);
+
+        // A derived layout isn't known to the first pass, so neither pass reports it.
+        let mut harvard = builder.first_pass();
+        assert_eq!(harvard.execute(false), Err(VMError::VMExited(0)));
+        let linear = builder.build_linear_after(&harvard).unwrap();
+        assert!(harvard.executor.reported_layout().is_none());
+        assert!(linear.executor.reported_layout().is_none());
+
+        let layout = LinearMemoryLayout::default();
+        let builder = builder.layout(layout);
+        let harvard = builder.first_pass();
+        let linear = builder.build_linear_after(&harvard).unwrap();
+        for reported in [
+            harvard.executor.reported_layout(),
+            linear.executor.reported_layout(),
+        ] {
+            assert_eq!(
+                reported.map(|layout| layout.heap_start()),
+                Some(layout.heap_start())
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_builder_hooks() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let executed = Arc::new(AtomicUsize::new(0));
+        let counter = executed.clone();
+        let builder = EmulatorBuilder::new(elf_file).hooks(move |executor| {
+            let counter = counter.clone();
+            executor.add_post_instruction_hook(move |_, _| {
+                counter.fetch_add(1, Ordering::Relaxed);
+            });
+        });
+
+        for kind in [EmulatorKind::Harvard, EmulatorKind::Linear] {
+            executed.store(0, Ordering::Relaxed);
+            let mut emulator = builder.build(kind).unwrap();
+            // Not even in the first pass of the linear emulator.
+            assert_eq!(executed.load(Ordering::Relaxed), 0);
+            assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
+            assert!(executed.load(Ordering::Relaxed) > 0);
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_builder_max_cycles() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let layout = LinearMemoryLayout::default();
+        let builder = EmulatorBuilder::new(elf_file)
+            .layout(layout)
+            .max_cycles(100);
+
+        let mut harvard = builder.build_harvard();
+        let mut linear = builder.build_linear().unwrap();
+        for executor in [harvard.get_executor(), linear.get_executor()] {
+            assert_eq!(executor.max_cycles(), Some(100));
+        }
+        for emulator in [&mut harvard as &mut dyn Emulator, &mut linear] {
+            let pc = match emulator.execute(false) {
+                Err(VMError::OutOfFuel { pc, cycles: 100 }) => pc,
+                other => panic!("unexpected result {other:?}"),
+            };
+            assert_eq!(emulator.get_executor().cpu.pc.value, pc);
+            assert_eq!(emulator.get_executor().cycles(), 100);
+
+            // Raising the limit resumes execution.
+            emulator.get_executor_mut().set_max_cycles(u64::MAX);
+            assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
+        }
+
+        // The first pass of the linear emulator is bounded too.
+        let mut builder = builder.max_cycles(1);
+        builder.layout = None;
+        assert!(matches!(
+            builder.build_linear(),
+            Err(VMError::OutOfFuel { cycles: 1, .. })
+        ));
+    }
+}
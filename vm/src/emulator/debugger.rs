@@ -0,0 +1,148 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::{
+    error::{Result, VMError},
+    memory::{MemoryRecord, MemoryRecords},
+};
+
+/// Accesses a watchpoint stops execution on, see [`super::Executor::add_watchpoint`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchKind {
+    Read,
+    Write,
+    ReadWrite,
+}
+
+impl WatchKind {
+    fn matches(self, access: WatchKind) -> bool {
+        self == WatchKind::ReadWrite || self == access
+    }
+}
+
+/// Breakpoints and watchpoints set by the host.
+#[derive(Debug, Default)]
+pub(crate) struct Debugger {
+    breakpoints: BTreeSet<u32>,
+    watchpoints: BTreeMap<u32, WatchKind>,
+    // Pc and clock of the breakpoint execution stopped at, stepped over when it resumes
+    stopped_at: Option<(u32, usize)>,
+}
+
+impl Debugger {
+    pub(crate) fn add_breakpoint(&mut self, pc: u32) {
+        self.breakpoints.insert(pc);
+    }
+
+    pub(crate) fn remove_breakpoint(&mut self, pc: u32) -> bool {
+        self.breakpoints.remove(&pc)
+    }
+
+    pub(crate) fn add_watchpoint(&mut self, address: u32, kind: WatchKind) {
+        self.watchpoints.insert(address, kind);
+    }
+
+    pub(crate) fn remove_watchpoint(&mut self, address: u32) -> bool {
+        self.watchpoints.remove(&address).is_some()
+    }
+
+    /// The breakpoint execution stopped at, if it hasn't resumed from it yet.
+    pub(crate) fn stopped_at(&self) -> Option<(u32, usize)> {
+        self.stopped_at
+    }
+
+    pub(crate) fn set_stopped_at(&mut self, stopped_at: Option<(u32, usize)>) {
+        self.stopped_at = stopped_at;
+    }
+
+    /// Stops before the instruction at `pc`, unless execution is resuming from it.
+    pub(crate) fn check_breakpoint(&mut self, pc: u32, clock: usize) -> Result<()> {
+        if !self.breakpoints.contains(&pc) || self.stopped_at.take() == Some((pc, clock)) {
+            return Ok(());
+        }
+        self.stopped_at = Some((pc, clock));
+        Err(VMError::Breakpoint(pc))
+    }
+
+    /// Stops after an instruction accessing a watched byte, the access has already happened.
+    pub(crate) fn check_watchpoints(&self, records: &MemoryRecords) -> Result<()> {
+        if self.watchpoints.is_empty() {
+            return Ok(());
+        }
+        for record in records {
+            let access = match record {
+                MemoryRecord::LoadRecord(..) => WatchKind::Read,
+                MemoryRecord::StoreRecord(..) => WatchKind::Write,
+            };
+            let start = record.get_address();
+            let end = start.saturating_add(record.get_size() as u32);
+            if let Some((&address, _)) = self
+                .watchpoints
+                .range(start..end)
+                .find(|(_, kind)| kind.matches(access))
+            {
+                return Err(VMError::Watchpoint { address, access });
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemAccessSize;
+
+    #[test]
+    fn test_breakpoint_is_stepped_over() {
+        let mut debugger = Debugger::default();
+        debugger.add_breakpoint(8);
+        assert_eq!(debugger.check_breakpoint(4, 1), Ok(()));
+        assert_eq!(debugger.check_breakpoint(8, 2), Err(VMError::Breakpoint(8)));
+        // Resuming executes the instruction, reaching it again stops once more.
+        assert_eq!(debugger.check_breakpoint(8, 2), Ok(()));
+        assert_eq!(debugger.check_breakpoint(8, 5), Err(VMError::Breakpoint(8)));
+
+        assert!(debugger.remove_breakpoint(8));
+        assert_eq!(debugger.check_breakpoint(8, 9), Ok(()));
+    }
+
+    #[test]
+    fn test_watchpoints() {
+        let mut debugger = Debugger::default();
+        debugger.add_watchpoint(0x102, WatchKind::Write);
+        debugger.add_watchpoint(0x200, WatchKind::ReadWrite);
+
+        let load = |address| {
+            MemoryRecords::from([MemoryRecord::LoadRecord(
+                (MemAccessSize::Word, address, 0),
+                1,
+            )])
+        };
+        let store = |size, address| {
+            MemoryRecords::from([MemoryRecord::StoreRecord((size, address, 0, 0), 1)])
+        };
+
+        assert_eq!(debugger.check_watchpoints(&load(0x100)), Ok(()));
+        assert_eq!(
+            debugger.check_watchpoints(&store(MemAccessSize::Byte, 0x101)),
+            Ok(())
+        );
+        assert_eq!(
+            debugger.check_watchpoints(&store(MemAccessSize::Word, 0x100)),
+            Err(VMError::Watchpoint {
+                address: 0x102,
+                access: WatchKind::Write
+            })
+        );
+        assert_eq!(
+            debugger.check_watchpoints(&load(0x200)),
+            Err(VMError::Watchpoint {
+                address: 0x200,
+                access: WatchKind::Read
+            })
+        );
+
+        assert!(debugger.remove_watchpoint(0x200));
+        assert_eq!(debugger.check_watchpoints(&load(0x200)), Ok(()));
+    }
+}
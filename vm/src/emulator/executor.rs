@@ -81,6 +81,25 @@
 //! }
 //! ```
 //!
+//! ### Choosing the Emulator at Runtime
+//!
+//! ```rust
+//! use nexus_vm::elf::ElfFile;
+//! use nexus_vm::emulator::{Emulator, HarvardEmulator, LinearEmulator, LinearMemoryLayout};
+//! use nexus_vm::error::VMError;
+//!
+//! let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+//! let linear = true;
+//!
+//! let mut emulator: Box<dyn Emulator> = if linear {
+//!     Box::new(LinearEmulator::from_elf(LinearMemoryLayout::default(), &[], &elf_file, &[], &[]))
+//! } else {
+//!     Box::new(HarvardEmulator::from_elf(&elf_file, &[], &[]))
+//! };
+//!
+//! assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
+//! ```
+//!
 //! ### Creating a Linear Emulator from an ELF file
 //!
 //! ```rust
@@ -132,32 +151,70 @@
 //! basic block caching, custom instruction support, debug logging, and associated data handling.
 
 use super::{
-    layout::LinearMemoryLayout, memory_stats::*, registry::InstructionExecutorRegistry, *,
+    assertions::MemoryAssertions,
+    block_cache::{program_hash, BlockCacheFile},
+    branch_stats::branch_taken,
+    builder::EmulatorBuilder,
+    debugger::Debugger,
+    harts::Harts,
+    hooks::Hooks,
+    instruction_stats::ExecutionStats,
+    layout::{LayoutOffsets, LinearMemoryLayout},
+    machine::{exception_cause, Privilege, SYSTEM_OPCODE},
+    memory_stats::*,
+    options::{BlockTracer, EmulatorOptions, JsonlTracer},
+    registry::InstructionExecutorRegistry,
+    symbol_profile::SymbolProfiler,
+    *,
 };
 use crate::{
     cpu::{instructions::InstructionResult, Cpu},
-    elf::ElfFile,
-    error::{Result, VMError},
+    elf::{DebugInfo, ElfFile, SourceLocation, Symbol},
+    error::{BlockCacheError, Result, VMError},
     memory::{
-        FixedMemory, LoadOp, MemoryProcessor, MemoryRecords, Modes, StoreOp, UnifiedMemory,
-        VariableMemory, NA, RO, RW, WO,
+        FixedMemory, LoadOp, MemoryProcessor, MemoryRecords, MemorySegmentImage, Modes, StoreOp,
+        UnifiedMemory, VariableMemory, NA, RO, RW, WO,
     },
-    riscv::{decode_until_end_of_a_block, BasicBlock, Instruction, Opcode, Register},
-    system::SyscallInstruction,
+    riscv::{
+        decode_until_end_of_a_block, required_extension, BasicBlock, BuiltinOpcode, Instruction,
+        Opcode, Register, UndecodableInstruction,
+    },
+    system::{SyscallInstruction, ToHost},
+    trace::Step,
 };
 
 use nexus_common::{
+    abi::PUBLIC_INPUT_ADDRESS,
     constants::{ELF_TEXT_START, MEMORY_TOP, WORD_SIZE},
     cpu::{InstructionExecutor, Registers},
     memory::MemAccessSize,
     word_align,
 };
 use rangemap::RangeMap;
+use serde::de::DeserializeOwned;
 use std::{
     cmp::max,
     collections::{BTreeMap, HashMap, HashSet, VecDeque},
+    io::Write,
+    ops::Range,
+    path::Path,
 };
 
+/// Whether `error` is raised by executing an instruction the VM doesn't define, see
+/// [`UndefinedInstructionPolicy`].
+fn is_undefined_instruction(error: &VMError) -> bool {
+    matches!(
+        error,
+        VMError::UndefinedInstruction(_)
+            | VMError::UnimplementedInstruction(_)
+            | VMError::UnsupportedExtension(..)
+            | VMError::UndecodableInstruction(..)
+    )
+}
+
+/// Return address of functions run by [`Emulator::call`], no program is loaded at it.
+pub const CALL_RETURN_ADDRESS: u32 = 0;
+
 #[derive(Debug, Default)]
 pub struct Executor {
     // The CPU
@@ -191,8 +248,141 @@ pub struct Executor {
     // Debug logs written by the guest program
     pub logs: Option<Vec<Vec<u8>>>,
 
+    // Error logs and stdout of the guest program
+    output: GuestOutput,
+
     // A map of memory addresses to the last timestamp when they were accessed
     pub access_timestamps: HashMap<u32, usize>,
+
+    // Symbols, debug info and decoding state of the program
+    program: ProgramState,
+
+    // Heap of the guest program and the layout it's told about
+    guest_memory: GuestMemory,
+
+    // Traces, statistics, profiles and checks enabled by the emulator options
+    diagnostics: Diagnostics,
+
+    // Set once the program is patched or control is transferred by an instruction that doesn't
+    // end its basic block, to stop executing the rest of the current block
+    end_block: bool,
+
+    // Fuel, suspension, breakpoints and callbacks of the host
+    control: HostControl,
+
+    // Machine mode and the handling of faults
+    traps: Traps,
+
+    // Harts other than the running one, in multi-hart mode
+    harts: Harts,
+}
+
+/// Output of the guest program besides the debug logs, see [`Executor`].
+#[derive(Debug, Default)]
+struct GuestOutput {
+    // Last message logged by the guest at the error level, e.g. its panic message
+    last_error_log: Option<String>,
+
+    // Receives the stdout of the guest instead of the terminal, if set
+    stdout_handler: Option<StdoutHandler>,
+}
+
+/// The program executed by an [`Executor`].
+#[derive(Debug, Default)]
+struct ProgramState {
+    // Symbols of the program, relocated to where the program is loaded
+    symbols: Vec<Symbol>,
+
+    // Line tables of the program, relocated like the symbols
+    debug_info: DebugInfo,
+
+    // Encodings of the undecodable instructions fetched so far, by their address
+    undecodable_instructions: BTreeMap<u32, u32>,
+
+    // Addresses of the instruction segment
+    program_range: Range<u32>,
+
+    // Hash of the program the cached blocks are decoded from, unset once the program is patched
+    program_hash: Option<u64>,
+
+    // Whether stores into the instruction segment patch the program
+    allow_self_modifying_code: bool,
+}
+
+/// Heap of the guest program and the memory layout it's told about, see [`Executor`].
+#[derive(Debug, Default)]
+struct GuestMemory {
+    // Heap usage of the guest program
+    heap_stats: HeapStats,
+
+    // Serves the heap allocations the guest requests from the host
+    heap_allocator: HeapAllocator,
+
+    // Seed of the offsets of the heap and the stack, in the layout randomization test mode
+    layout_seed: Option<u64>,
+
+    // Layout returned to the guest when queried, only set if it's fixed before the first pass
+    reported_layout: Option<LinearMemoryLayout>,
+}
+
+/// Diagnostics of an [`Executor`], set by [`Executor::set_options`].
+#[derive(Debug, Default)]
+struct Diagnostics {
+    // Disassembly of executed basic blocks, if enabled
+    block_tracer: BlockTracer,
+
+    // Trace of executed instructions as JSON lines, if enabled
+    jsonl_tracer: JsonlTracer,
+
+    // Outcomes of conditional branches, if enabled
+    branch_stats: Option<BranchStats>,
+
+    // Instructions executed by opcode and memory accesses, if enabled
+    stats: Option<ExecutionStats>,
+
+    // Cycles spent in each basic block, if enabled
+    block_profile: Option<BlockProfile>,
+
+    // Cycles spent in each function, if enabled
+    symbol_profile: Option<SymbolProfiler>,
+
+    // Permission violations, if auditing is enabled
+    audit: Option<PermissionAudit>,
+
+    // Memory-safety assertions, if enabled
+    memory_assertions: Option<MemoryAssertions>,
+}
+
+/// How the host controls the execution of an [`Executor`].
+#[derive(Debug, Default)]
+struct HostControl {
+    // Number of cycles execution stops at, if limited
+    max_cycles: Option<u64>,
+
+    // Pc of the yield syscall execution is suspended at, until it's returned to the host
+    yielded: Option<u32>,
+
+    // Breakpoints and watchpoints set by the host
+    debugger: Debugger,
+
+    // Callbacks registered by the host
+    hooks: Hooks,
+
+    // The tohost/fromhost symbols, if the protocol is enabled
+    tohost: Option<ToHost>,
+}
+
+/// Machine mode and the handling of faults of an [`Executor`].
+#[derive(Debug, Default)]
+struct Traps {
+    // Machine-mode CSRs and timer, if enabled
+    machine: Option<MachineState>,
+
+    // Whether faults are delivered to the trap handler of the guest
+    trap_exceptions: bool,
+
+    // What to do when an undefined instruction is executed
+    undefined_instructions: UndefinedInstructionPolicy,
 }
 
 impl Executor {
@@ -201,11 +391,503 @@ impl Executor {
         self.instruction_executor.add_opcode::<IE>(op)
     }
 
+    /// Replaces the executor of an opcode, builtin or not.
+    fn override_opcode<IE: InstructionExecutor>(&mut self, op: &Opcode) {
+        self.instruction_executor.override_opcode::<IE>(op);
+        // Cached blocks don't hold executors, they are looked up on every execution.
+    }
+
     /// Set or overwrite private input into the private input tape
     fn set_private_input(&mut self, private_input: &[u8]) {
         self.private_input_tape = VecDeque::<u8>::from(private_input.to_vec());
     }
 
+    /// Apply runtime diagnostics options, opening the block trace file if one is configured.
+    pub fn set_options(&mut self, options: &EmulatorOptions) -> std::io::Result<()> {
+        self.diagnostics.block_tracer = BlockTracer::new(options)?;
+        self.diagnostics.jsonl_tracer = JsonlTracer::new(options)?;
+        self.diagnostics.audit = options.permission_audit.map(PermissionAudit::new);
+        self.diagnostics.memory_assertions =
+            options.memory_assertions.then(MemoryAssertions::default);
+        self.program.allow_self_modifying_code = options.allow_self_modifying_code;
+        self.guest_memory.layout_seed = options.randomize_layout;
+        self.traps.undefined_instructions = options.undefined_instructions;
+        self.output.stdout_handler = options.stdout_handler.clone();
+        self.traps.machine = (options.machine_mode
+            || options.trap_exceptions
+            || options.undefined_instructions == UndefinedInstructionPolicy::Trap)
+            .then(MachineState::new);
+        self.traps.trap_exceptions = options.trap_exceptions;
+        self.diagnostics.branch_stats =
+            (options.branch_stats || options.stats).then(BranchStats::default);
+        self.diagnostics.stats = options.stats.then(ExecutionStats::default);
+        self.diagnostics.block_profile = options.block_profile.then(BlockProfile::default);
+        self.diagnostics.symbol_profile = options.symbol_profile.then(SymbolProfiler::default);
+        self.harts = Harts::new(options.harts, &mut self.cpu, self.traps.machine.as_mut());
+        self.control.tohost = None;
+        if options.tohost {
+            self.control.tohost = ToHost::from_symbols(&self.program.symbols);
+            if self.control.tohost.is_none() {
+                tracing::warn!(
+                    target: "nexus_vm::emulator",
+                    "the program has no tohost symbol, ignoring the tohost protocol"
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the target of `instruction` if it stores into the instruction segment, which is
+    /// an error unless self-modifying code is allowed.
+    fn program_store(
+        &self,
+        instruction: &Instruction,
+    ) -> Result<Option<(u32, MemAccessSize, u32)>> {
+        let size = match instruction.opcode.builtin() {
+            Some(BuiltinOpcode::SB) => MemAccessSize::Byte,
+            Some(BuiltinOpcode::SH) => MemAccessSize::HalfWord,
+            Some(BuiltinOpcode::SW) => MemAccessSize::Word,
+            _ => return Ok(None),
+        };
+
+        let address = self.cpu.registers[instruction.op_a].wrapping_add(instruction.op_c);
+        if !self.program.program_range.contains(&address) {
+            return Ok(None);
+        }
+        if !self.program.allow_self_modifying_code {
+            return Err(VMError::SelfModifyingCode(self.cpu.pc.value, address));
+        }
+
+        Ok(Some((address, size, self.cpu.registers[instruction.op_b])))
+    }
+
+    /// Completes a store into the instruction segment: the cached basic block containing it is
+    /// dropped, so that it is decoded again on the next fetch.
+    fn patched_program(&mut self, op: StoreOp) -> (InstructionResult, MemoryRecords) {
+        let address = op.get_address();
+        if let Some(&start) = self.basic_block_ref_cache.get(&address) {
+            if let Some(entry) = self.basic_block_cache.remove(&start) {
+                self.basic_block_ref_cache.remove(entry.start..entry.end);
+            }
+        }
+        self.program.program_hash = None;
+        self.end_block = true;
+
+        let records = MemoryRecords::from([op.as_record(self.global_clock)]);
+        self.cpu.pc.step();
+        self.global_clock += 1;
+        (None, records)
+    }
+
+    /// Executes `instruction` if it is a machine-mode instruction and machine mode is enabled.
+    fn machine_instruction(
+        &mut self,
+        instruction: &Instruction,
+    ) -> Result<Option<(InstructionResult, MemoryRecords)>> {
+        let Some(machine) = self.traps.machine.as_mut() else {
+            return Ok(None);
+        };
+        let pc = self.cpu.pc.value;
+        let user = machine.privilege() == Privilege::User;
+        // Only environment calls from machine mode are syscalls.
+        if user && instruction.opcode.builtin() == Some(BuiltinOpcode::ECALL) {
+            return Err(VMError::EnvironmentCallFromUser(pc));
+        }
+        if instruction.opcode.is_builtin() || instruction.opcode.raw() != SYSTEM_OPCODE {
+            return Ok(None);
+        }
+
+        let result = match instruction.opcode.name() {
+            "mret" if user => {
+                return Err(VMError::PrivilegedInstruction(
+                    instruction.opcode.clone(),
+                    pc,
+                ))
+            }
+            "mret" => {
+                self.cpu.pc.value = machine.mret();
+                self.end_block = true;
+                self.global_clock += 1;
+                return Ok(Some((None, MemoryRecords::new())));
+            }
+            "wfi" => None,
+            _ => {
+                let old = machine
+                    .execute_csr(instruction, &self.cpu.registers, self.global_clock as u64)
+                    .ok_or(VMError::IllegalCsrAccess(pc, instruction.op_c))?;
+                self.cpu.registers.write(instruction.op_a, old);
+                Some(old)
+            }
+        };
+
+        self.cpu.pc.step();
+        self.global_clock += 1;
+        Ok(Some((result, MemoryRecords::new())))
+    }
+
+    /// Serves a request of the program through `tohost`, if it stored to it.
+    fn handle_tohost(
+        &mut self,
+        store_ops: &HashSet<StoreOp>,
+        memory: &mut impl MemoryProcessor,
+    ) -> Result<()> {
+        match self.control.tohost {
+            Some(tohost) => tohost.handle(store_ops, memory, &mut self.logs),
+            None => Ok(()),
+        }
+    }
+
+    /// Delivers the exception `error` of `instruction` to the trap handler of the guest, if
+    /// exceptions are trapped and the guest can handle it. Returns the error otherwise.
+    fn take_exception(&mut self, error: VMError, instruction: &Instruction) -> Result<()> {
+        let cause = exception_cause(&error, instruction);
+        let trap = self.traps.trap_exceptions
+            || (self.traps.undefined_instructions == UndefinedInstructionPolicy::Trap
+                && is_undefined_instruction(&error));
+        let (Some(machine), true, Some((cause, tval))) = (self.traps.machine.as_mut(), trap, cause)
+        else {
+            return Err(error);
+        };
+
+        self.cpu.pc.value = machine.take_exception(cause, self.cpu.pc.value, tval);
+        self.end_block = true;
+        self.global_clock += 1;
+        Ok(())
+    }
+
+    /// Delivers a pending timer interrupt in machine mode, by moving the pc to the trap handler.
+    fn deliver_interrupt(&mut self) {
+        if let Some(machine) = self.traps.machine.as_mut() {
+            if let Some(handler) =
+                machine.take_interrupt(self.cpu.pc.value, self.global_clock as u64)
+            {
+                self.cpu.pc.value = handler;
+            }
+        }
+    }
+
+    /// Suspends execution after the current instruction, a yield syscall. The rest of the block
+    /// is executed once the host resumes it.
+    pub(crate) fn request_yield(&mut self) {
+        self.control.yielded = Some(self.cpu.pc.value);
+        self.end_block = true;
+    }
+
+    /// Id of the running hart, whose state is the one of the executor. Harts are only switched
+    /// between basic blocks, see [`EmulatorOptions::harts`].
+    pub fn hart_id(&self) -> usize {
+        self.harts.current()
+    }
+
+    /// Number of harts, 1 unless multi-hart mode is enabled.
+    pub fn num_harts(&self) -> usize {
+        self.harts.count()
+    }
+
+    /// Machine-mode CSRs, if machine mode is enabled.
+    pub fn machine_state(&self) -> Option<&MachineState> {
+        self.traps.machine.as_ref()
+    }
+
+    /// Stream every executed instruction to `writer` as a JSON object per line, with the fields
+    /// `clock`, `pc`, `opcode`, the operands `op_a`, `op_b` and `op_c`, the `result` written to
+    /// the destination register, if any, and the `memory` accesses.
+    ///
+    /// Lines are written as instructions execute, wrap unbuffered writers in a `BufWriter`.
+    pub fn trace_jsonl_to(&mut self, writer: impl Write + Send + 'static) {
+        self.diagnostics.jsonl_tracer = JsonlTracer::to_writer(writer);
+    }
+
+    /// Permission violations recorded so far, if auditing is enabled.
+    pub fn permission_audit(&self) -> Option<&PermissionAudit> {
+        self.diagnostics.audit.as_ref()
+    }
+
+    /// Checks that execution can continue at `pc`, recording violations in audit mode.
+    fn check_pc(&mut self, pc: u32) -> Result<()> {
+        if pc == self.program.program_range.end {
+            return Err(VMError::VMOutOfInstructions);
+        }
+        if self.program.program_range.contains(&pc) {
+            return Ok(());
+        }
+
+        let error = VMError::UnauthorizedExecution(pc);
+        match self.diagnostics.audit.as_mut() {
+            Some(audit) => audit.record(error, pc, self.global_clock),
+            None => Err(error),
+        }
+    }
+
+    /// Records a permission violation of the instruction at the current pc in audit mode.
+    ///
+    /// Returns `Ok` if the instruction should be skipped, and the error otherwise.
+    fn audit(&mut self, error: VMError) -> Result<()> {
+        let (pc, clock) = (self.cpu.pc.value, self.global_clock);
+        match self.diagnostics.audit.as_mut() {
+            Some(audit) => audit.record(error, pc, clock),
+            None => Err(error),
+        }
+    }
+
+    /// Heap usage of the guest program so far.
+    pub fn heap_stats(&self) -> &HeapStats {
+        &self.guest_memory.heap_stats
+    }
+
+    /// Activity of the allocator serving the `Alloc` and `Dealloc` syscalls so far.
+    pub fn allocator_stats(&self) -> &AllocatorStats {
+        self.guest_memory.heap_allocator.stats()
+    }
+
+    pub(crate) fn heap_allocator(&mut self) -> &mut HeapAllocator {
+        &mut self.guest_memory.heap_allocator
+    }
+
+    /// Offsets the heap and the stack of the guest start at into their segments of `layout`.
+    pub fn layout_offsets(&self, layout: &LinearMemoryLayout) -> LayoutOffsets {
+        self.guest_memory
+            .layout_seed
+            .map(|seed| layout.random_offsets(seed))
+            .unwrap_or_default()
+    }
+
+    /// The layout returned to the guest by [`crate::system::SyscallCode::QueryMemoryLayout`].
+    pub fn reported_layout(&self) -> Option<LinearMemoryLayout> {
+        self.guest_memory.reported_layout
+    }
+
+    /// Sets the layout returned to the guest when it's queried. It's only known ahead of both
+    /// passes if it's fixed, otherwise neither pass reports it, so that the guest takes the same
+    /// path in both.
+    pub(crate) fn report_layout(&mut self, layout: Option<LinearMemoryLayout>) {
+        self.guest_memory.reported_layout = layout;
+    }
+
+    /// Number of cycles executed so far.
+    pub fn cycles(&self) -> u64 {
+        // The clock starts at 1, 0 is the timestamp of the initial memory.
+        self.global_clock.saturating_sub(1) as u64
+    }
+
+    /// Limits execution to `max_cycles` cycles in total, after which it stops with
+    /// [`VMError::OutOfFuel`] before executing the next instruction. Raising the limit lets
+    /// execution resume from there.
+    pub fn set_max_cycles(&mut self, max_cycles: u64) {
+        self.control.max_cycles = Some(max_cycles);
+    }
+
+    /// The cycle limit set by [`Executor::set_max_cycles`], if any.
+    pub fn max_cycles(&self) -> Option<u64> {
+        self.control.max_cycles
+    }
+
+    /// Stops execution with [`VMError::Breakpoint`] before the instruction at `pc` is executed.
+    /// Execution resumes at the breakpoint, see [`Emulator::resume`].
+    pub fn add_breakpoint(&mut self, pc: u32) {
+        self.control.debugger.add_breakpoint(pc);
+    }
+
+    /// Removes the breakpoint at `pc`, returning whether there was one.
+    pub fn remove_breakpoint(&mut self, pc: u32) -> bool {
+        self.control.debugger.remove_breakpoint(pc)
+    }
+
+    /// Stops execution with [`VMError::Watchpoint`] after an instruction accesses the byte at
+    /// `address` in a way matching `kind`. Syscalls reading or writing memory are watched too.
+    pub fn add_watchpoint(&mut self, address: u32, kind: WatchKind) {
+        self.control.debugger.add_watchpoint(address, kind);
+    }
+
+    /// Removes the watchpoint at `address`, returning whether there was one.
+    pub fn remove_watchpoint(&mut self, address: u32) -> bool {
+        self.control.debugger.remove_watchpoint(address)
+    }
+
+    /// Runs `hook` before each instruction is executed, e.g. to trace or meter execution.
+    pub fn add_pre_instruction_hook(
+        &mut self,
+        hook: impl FnMut(&Instruction, &Cpu) + Send + 'static,
+    ) {
+        self.control.hooks.pre_instruction.push(Box::new(hook));
+    }
+
+    /// Runs `hook` after each instruction is executed, with the updated state of the CPU.
+    /// Instructions stopping execution with an error aren't passed to it.
+    pub fn add_post_instruction_hook(
+        &mut self,
+        hook: impl FnMut(&Instruction, &Cpu) + Send + 'static,
+    ) {
+        self.control.hooks.post_instruction.push(Box::new(hook));
+    }
+
+    /// Runs `hook` with the code in a7 before each syscall. Returning a value intercepts the
+    /// syscall: it isn't executed, and the value is returned in a0 instead, e.g. to answer a host
+    /// oracle with a code the VM doesn't define. Intercepted syscalls aren't provable.
+    pub fn add_syscall_hook(
+        &mut self,
+        hook: impl FnMut(u32, &Cpu) -> Option<u32> + Send + 'static,
+    ) {
+        self.control.hooks.syscall.push(Box::new(hook));
+    }
+
+    /// Outcomes of the conditional branches executed so far, if enabled in [`EmulatorOptions`].
+    pub fn branch_stats(&self) -> Option<&BranchStats> {
+        self.diagnostics.branch_stats.as_ref()
+    }
+
+    /// Instructions executed by opcode, branch outcomes and memory accesses so far, if enabled in
+    /// [`EmulatorOptions`].
+    pub fn stats(&self) -> Option<&ExecutionStats> {
+        self.diagnostics.stats.as_ref()
+    }
+
+    /// Cycles spent in each basic block so far, if enabled in [`EmulatorOptions`].
+    pub fn block_profile(&self) -> Option<BlockProfile> {
+        let mut profile = self.diagnostics.block_profile.clone()?;
+        profile.finish(self.global_clock);
+        Some(profile)
+    }
+
+    /// Report of the `n` basic blocks taking the most cycles so far, with their share of all
+    /// cycles, the symbol they're in and their first instructions, if enabled in [`EmulatorOptions`].
+    pub fn hot_block_report(&self, n: usize) -> Option<String> {
+        let disassemble = |pc: u32| {
+            let Some(entry) = self
+                .basic_block_ref_cache
+                .get(&pc)
+                .and_then(|start| self.basic_block_cache.get(start))
+            else {
+                return Vec::new();
+            };
+            let at = (pc - entry.start) as usize / WORD_SIZE;
+            entry.block.0.get(at..).unwrap_or_default().to_vec()
+        };
+
+        let mut report = String::new();
+        self.block_profile()?
+            .write_report(&mut report, n, &self.program.symbols, disassemble)
+            .expect("writing to a String can't fail");
+        Some(report)
+    }
+
+    /// Source file, line and function of the instruction at `pc`, if the ELF file has debug info.
+    pub fn source_location(&self, pc: u32) -> Option<SourceLocation> {
+        self.program.debug_info.source_location(pc)
+    }
+
+    /// Where in the source of the guest `error` happened, at the pc the error records or at the
+    /// current pc otherwise, i.e. at the instruction that failed.
+    pub fn error_location(&self, error: &VMError) -> Option<SourceLocation> {
+        self.source_location(error.pc().unwrap_or(self.cpu.pc.value))
+    }
+
+    /// Cycles spent in each function and call stack so far, if enabled in [`EmulatorOptions`].
+    /// Cycles are attributed to the symbols of the ELF file.
+    pub fn profile_report(&self) -> Option<ProfileReport> {
+        Some(
+            self.diagnostics
+                .symbol_profile
+                .as_ref()?
+                .report(&self.program.symbols),
+        )
+    }
+
+    /// Number of basic blocks decoded so far, or loaded by [`Executor::load_block_cache`].
+    pub fn cached_blocks(&self) -> usize {
+        self.basic_block_cache.len()
+    }
+
+    /// Write the basic blocks decoded so far to `path`, so that later runs of the same program can
+    /// skip decoding them, see [`Executor::load_block_cache`].
+    ///
+    /// Fails if the program was patched at runtime.
+    pub fn save_block_cache<P: AsRef<Path> + ?Sized>(
+        &self,
+        path: &P,
+    ) -> std::result::Result<(), BlockCacheError> {
+        let program_hash = self
+            .program
+            .program_hash
+            .ok_or(BlockCacheError::ProgramModified)?;
+        BlockCacheFile {
+            program_hash,
+            blocks: self
+                .basic_block_cache
+                .iter()
+                .map(|(&start, entry)| (start, entry.block.0.clone()))
+                .collect(),
+            undecodable: self
+                .program
+                .undecodable_instructions
+                .iter()
+                .map(|(&pc, &word)| (pc, word))
+                .collect(),
+        }
+        .write(path)
+    }
+
+    /// Add the basic blocks saved by [`Executor::save_block_cache`] to the cache, before
+    /// executing. Returns whether they were saved for this program, blocks of other programs are
+    /// ignored.
+    pub fn load_block_cache<P: AsRef<Path> + ?Sized>(
+        &mut self,
+        path: &P,
+    ) -> std::result::Result<bool, BlockCacheError> {
+        let file = BlockCacheFile::read(path)?;
+        if self.program.program_hash != Some(file.program_hash) {
+            return Ok(false);
+        }
+
+        for (start, instructions) in file.blocks {
+            let entry = BasicBlockEntry::new(start, BasicBlock::new(instructions));
+            self.basic_block_ref_cache
+                .insert(entry.start..entry.end, start);
+            self.basic_block_cache.insert(start, entry);
+        }
+        self.program
+            .undecodable_instructions
+            .extend(file.undecodable);
+        Ok(true)
+    }
+
+    /// Decode the basic block starting at `pc` from `u32_instructions`, remembering the encoding
+    /// of undecodable instructions to report them when executed.
+    fn decode_block(&mut self, pc: u32, u32_instructions: &[u32]) -> BasicBlock {
+        let block = decode_until_end_of_a_block(u32_instructions);
+        for (idx, (instruction, &word)) in block.0.iter().zip(u32_instructions).enumerate() {
+            if *instruction == Instruction::unimpl() {
+                self.program
+                    .undecodable_instructions
+                    .insert(pc + (idx * WORD_SIZE) as u32, word);
+            }
+        }
+        block
+    }
+
+    /// Instructions the VM couldn't decode, among those fetched so far.
+    pub fn undecodable_instructions(&self) -> Vec<UndecodableInstruction> {
+        self.program
+            .undecodable_instructions
+            .iter()
+            .map(|(&pc, &instruction)| UndecodableInstruction { pc, instruction })
+            .collect()
+    }
+
+    /// The last message the guest logged at the error level. The panic handler of `nexus_rt` logs
+    /// the panic message this way before exiting.
+    pub fn last_error_log(&self) -> Option<&str> {
+        self.output.last_error_log.as_deref()
+    }
+
+    pub(crate) fn set_last_error_log(&mut self, message: String) {
+        self.output.last_error_log = Some(message);
+    }
+
+    pub(crate) fn stdout_handler(&self) -> Option<&StdoutHandler> {
+        self.output.stdout_handler.as_ref()
+    }
+
     /// Set whether to capture logs or print out.
     pub(crate) fn capture_logs(&mut self, capture: bool) {
         if capture && self.logs.is_none() {
@@ -218,6 +900,10 @@ impl Executor {
     }
 }
 
+/// Common interface of the emulators.
+///
+/// The trait is object-safe, so the emulator kind can be chosen at runtime behind a
+/// `Box<dyn Emulator>`. Generic methods are only available on concrete emulators.
 pub trait Emulator {
     /// Execute a system call instruction
     ///
@@ -233,7 +919,31 @@ pub trait Emulator {
         memory_layout: Option<LinearMemoryLayout>,
         bare_instruction: &Instruction,
         force_provable_transcript: bool,
-    ) -> Result<(InstructionResult, (HashSet<LoadOp>, HashSet<StoreOp>))> {
+    ) -> Result<(InstructionResult, (HashSet<LoadOp>, HashSet<StoreOp>))>
+    where
+        Self: Sized,
+    {
+        if matches!(
+            bare_instruction.opcode.builtin(),
+            Some(BuiltinOpcode::EBREAK)
+        ) {
+            let pc = executor.cpu.pc.value;
+            // Step over the breakpoint, so that the host can resume execution after inspecting
+            // the state. The breakpoint itself doesn't take a cycle.
+            executor.cpu.pc.step();
+            return Err(VMError::Breakpoint(pc));
+        }
+
+        let code = executor.cpu.registers.read(Register::X17);
+        if let Some(value) = executor
+            .control
+            .hooks
+            .intercept_syscall(code, &executor.cpu)
+        {
+            executor.cpu.registers.write(Register::X10, value);
+            return Ok((Some(value), (HashSet::new(), HashSet::new())));
+        }
+
         let mut syscall_instruction = SyscallInstruction::decode(bare_instruction, &executor.cpu)?;
         let load_ops = syscall_instruction.memory_read(memory)?;
         syscall_instruction.execute(executor, memory, memory_layout, force_provable_transcript)?;
@@ -241,7 +951,7 @@ pub trait Emulator {
         let store_ops = syscall_instruction.memory_write(memory)?;
         syscall_instruction.write_back(&mut executor.cpu);
 
-        // Safety: during the first pass, the Write and CycleCount syscalls can read from memory
+        // Safety: during the first pass, the Write, CycleCount and Log syscalls can read from memory
         //         however, during the second pass these are no-ops, so we never need a record
         Ok((result, (load_ops, store_ops)))
     }
@@ -275,61 +985,324 @@ pub trait Emulator {
     /// Return a mutable reference to the internal executor component used by the emulator.
     fn get_executor_mut(&mut self) -> &mut Executor;
 
+    /// Return the initial stack pointer, the stack grows down from it.
+    fn stack_top(&self) -> u32;
+
+    /// Return the contents of all writable memory, e.g. to extract results a program leaves in
+    /// memory beyond its public output. Meant to be called after execution.
+    fn final_memory(&self) -> Vec<MemorySegmentImage>;
+
     /// Execute an entire basic block.
     fn execute_basic_block(
         &mut self,
         basic_block_entry: &BasicBlockEntry,
         force_provable_transcript: bool,
     ) -> Result<(Vec<InstructionResult>, MemoryTranscript)> {
-        #[cfg(debug_assertions)]
-        basic_block_entry
-            .block
-            .print_with_offset(self.get_executor().cpu.pc.value as usize);
-
         let mut results: Vec<InstructionResult> = Vec::new();
         let mut transcript: MemoryTranscript = Vec::new();
+        self.execute_basic_block_into(
+            basic_block_entry,
+            force_provable_transcript,
+            &mut results,
+            &mut transcript,
+        )?;
+        Ok((results, transcript))
+    }
+
+    /// Like [`Emulator::execute_basic_block`], appending the results and the memory records of
+    /// the instructions to `results` and `transcript`, which keep those executed before an error.
+    fn execute_basic_block_into(
+        &mut self,
+        basic_block_entry: &BasicBlockEntry,
+        force_provable_transcript: bool,
+        results: &mut Vec<InstructionResult>,
+        transcript: &mut MemoryTranscript,
+    ) -> Result<()> {
+        let executor = self.get_executor_mut();
+        if let Some(profile) = executor.diagnostics.block_profile.as_mut() {
+            profile.enter(executor.cpu.pc.value, executor.global_clock);
+        }
+        if self.get_executor().diagnostics.block_tracer.is_enabled() {
+            let executor = self.get_executor_mut();
+            let pc = executor.cpu.pc.value;
+            let location = executor.program.debug_info.source_location(pc);
+            executor.diagnostics.block_tracer.trace(
+                &basic_block_entry.block,
+                pc as usize,
+                location.as_ref(),
+            );
+        }
 
         let at = (self.get_executor().cpu.pc.value as usize - basic_block_entry.start as usize)
             / WORD_SIZE;
 
         // Execute the instructions in the basic block
         for instruction in basic_block_entry.block.0[at..].iter() {
-            let (res, mem) = self.execute_instruction(instruction, force_provable_transcript)?;
+            let (pc, clock) = (
+                self.get_executor().cpu.pc.value,
+                self.get_executor().global_clock,
+            );
+            let cycles = self.get_executor().cycles();
+            if self
+                .get_executor()
+                .control
+                .max_cycles
+                .is_some_and(|max_cycles| cycles >= max_cycles)
+            {
+                return Err(VMError::OutOfFuel { pc, cycles });
+            }
+            self.get_executor_mut()
+                .control
+                .debugger
+                .check_breakpoint(pc, clock)?;
+            let executor = self.get_executor_mut();
+            executor.control.hooks.before(instruction, &executor.cpu);
+            let (res, mem) = match self.execute_instruction(instruction, force_provable_transcript)
+            {
+                Ok(step) => {
+                    let executor = self.get_executor_mut();
+                    if let Some(stats) = executor.diagnostics.branch_stats.as_mut() {
+                        if let Some(taken) = branch_taken(instruction, &executor.cpu.registers) {
+                            stats.record(pc, taken);
+                        }
+                    }
+                    if let Some(profile) = executor.diagnostics.symbol_profile.as_mut() {
+                        profile.record(&executor.program.symbols, pc, instruction);
+                    }
+                    if let Some(stats) = executor.diagnostics.stats.as_mut() {
+                        stats.record(instruction, &step.1);
+                    }
+                    step
+                }
+                Err(e) => {
+                    let executor = self.get_executor_mut();
+                    let e = match (e, executor.program.undecodable_instructions.get(&pc)) {
+                        (VMError::UnimplementedInstruction(_), Some(&word)) => {
+                            match required_extension(word) {
+                                Some(extension) => {
+                                    VMError::UnsupportedExtension(extension, word, pc)
+                                }
+                                None => VMError::UndecodableInstruction(word, pc),
+                            }
+                        }
+                        (e, _) => e,
+                    };
+                    if executor.traps.undefined_instructions == UndefinedInstructionPolicy::Skip
+                        && is_undefined_instruction(&e)
+                    {
+                        tracing::warn!(
+                            target: "nexus_vm::emulator",
+                            "skipping undefined instruction at pc=0x{pc:08X}: {e}"
+                        );
+                        executor.cpu.pc.step();
+                        executor.global_clock += 1;
+                    } else if let Err(e) = executor.take_exception(e, instruction) {
+                        // Skipping the instruction is only allowed when auditing.
+                        executor.audit(e)?;
+                        executor.cpu.pc.step();
+                        executor.global_clock += 1;
+                    }
+                    (None, MemoryRecords::new())
+                }
+            };
+            let executor = self.get_executor_mut();
+            executor.control.hooks.after(instruction, &executor.cpu);
+            if self.get_executor().diagnostics.jsonl_tracer.is_enabled() {
+                self.get_executor_mut().diagnostics.jsonl_tracer.trace(
+                    clock,
+                    pc,
+                    instruction,
+                    res,
+                    &mem,
+                );
+            }
+            let watchpoint = self.get_executor().control.debugger.check_watchpoints(&mem);
             results.push(res);
             transcript.push(mem);
+
+            // The rest of the block may have been patched or skipped, fetch it again.
+            let end_block = std::mem::take(&mut self.get_executor_mut().end_block);
+            watchpoint?;
+            if end_block {
+                break;
+            }
         }
+        let executor = self.get_executor_mut();
+        executor.deliver_interrupt();
+        executor
+            .harts
+            .switch(&mut executor.cpu, &mut executor.traps.machine);
 
-        Ok((results, transcript))
+        Ok(())
+    }
+
+    /// Execute the single instruction at the current pc, e.g. to drive the emulator from an
+    /// interactive debugger. A yield doesn't suspend a step, the host already has control.
+    fn step(&mut self) -> Result<StepOutcome> {
+        let pc = self.get_executor().cpu.pc.value;
+        let entry = self.fetch_block(pc)?;
+        let instruction = entry.block.0[(pc - entry.start) as usize / WORD_SIZE].clone();
+        let registers = self.get_executor().cpu.registers;
+        let timestamp = self.get_executor().global_clock as u32;
+
+        let single = BasicBlockEntry::new(pc, BasicBlock::new(vec![instruction.clone()]));
+        let (mut results, mut transcript) = self.execute_basic_block(&single, false)?;
+        self.get_executor_mut().control.yielded = None;
+
+        let cpu = &self.get_executor().cpu;
+        let register_writes = (0..32)
+            .map(Register::from)
+            .filter(|&reg| cpu.registers[reg] != registers[reg])
+            .map(|reg| (reg, cpu.registers[reg]))
+            .collect();
+        Ok(StepOutcome {
+            step: Step {
+                timestamp,
+                pc,
+                next_pc: cpu.pc.value,
+                raw_instruction: instruction.encode(),
+                instruction,
+                result: results.pop().flatten(),
+                memory_records: transcript.pop().unwrap_or_default(),
+            },
+            register_writes,
+        })
+    }
+
+    /// Continue an execution stopped by a breakpoint, a watchpoint, a yield or running out of
+    /// fuel, the same as calling [`Emulator::execute`] again.
+    fn resume(
+        &mut self,
+        force_provable_transcript: bool,
+    ) -> Result<(Vec<InstructionResult>, MemoryTranscript)> {
+        self.execute(force_provable_transcript)
     }
 
     /// Execute an entire program.
+    ///
+    /// Returns [`VMError::Yielded`] when the guest yields, execution resumes after the yield
+    /// syscall on the next call. This lets a host timeslice many guests on a single thread.
+    ///
+    /// Execution only ends with an error, the results and the memory transcript of the executed
+    /// instructions are returned with it by [`Emulator::execute_partial`].
     fn execute(
         &mut self,
         force_provable_transcript: bool,
     ) -> Result<(Vec<InstructionResult>, MemoryTranscript)> {
-        let mut results: Vec<InstructionResult> = Vec::new();
-        let mut transcript: MemoryTranscript = Vec::new();
-
-        loop {
-            let basic_block_entry = self.fetch_block(self.get_executor().cpu.pc.value)?;
-            let (res, mem) =
-                self.execute_basic_block(&basic_block_entry, force_provable_transcript)?;
-
-            results.extend(res);
-            transcript.extend(mem);
-        }
+        Err(self.execute_partial(force_provable_transcript).error)
     }
 
-    /// Adds a new opcode and its corresponding execution function to the emulator.
-    fn add_opcode<IE: InstructionExecutor>(&mut self, op: &Opcode) -> Result<()> {
+    /// Like [`Emulator::execute`], returning the results and the memory transcript of the
+    /// instructions executed until execution stopped along with the error that stopped it. After a
+    /// breakpoint, a yield or running out of fuel, execution can be resumed, and the next call
+    /// returns the instructions executed from there.
+    fn execute_partial(&mut self, force_provable_transcript: bool) -> PartialExecution {
+        let mut results: Vec<InstructionResult> = Vec::new();
+        let mut transcript = CompressedTranscript::new();
+        // Records of the current block, compressed once it's executed.
+        let mut block_transcript: MemoryTranscript = Vec::new();
+
+        let error = loop {
+            let step = self
+                .fetch_block(self.get_executor().cpu.pc.value)
+                .and_then(|entry| {
+                    self.execute_basic_block_into(
+                        &entry,
+                        force_provable_transcript,
+                        &mut results,
+                        &mut block_transcript,
+                    )
+                });
+            transcript.extend(block_transcript.drain(..));
+            if let Err(e) = step {
+                break e;
+            }
+
+            if let Some(pc) = self.get_executor_mut().control.yielded.take() {
+                break VMError::Yielded(pc);
+            }
+        };
+        self.get_executor_mut().diagnostics.jsonl_tracer.flush();
+
+        PartialExecution {
+            error,
+            results,
+            transcript,
+        }
+    }
+
+    /// Adds a new opcode and its corresponding execution function to the emulator.
+    ///
+    /// Trait objects can register opcodes through [`Emulator::get_executor_mut`] instead.
+    fn add_opcode<IE: InstructionExecutor>(&mut self, op: &Opcode) -> Result<()>
+    where
+        Self: Sized,
+    {
         self.get_executor_mut().add_opcode::<IE>(op)
     }
 
+    /// Replaces the executor of an opcode, including builtin ones, see
+    /// [`InstructionExecutorRegistry::override_opcode`]. This is meant for experiments on the host,
+    /// programs executed with overridden builtins can't be proven.
+    fn override_opcode<IE: InstructionExecutor>(&mut self, op: &Opcode)
+    where
+        Self: Sized,
+    {
+        self.get_executor_mut().override_opcode::<IE>(op)
+    }
+
     /// Set or overwrite private input into the private input tape
     fn set_private_input(&mut self, private_input: &[u8]) {
         self.get_executor_mut().set_private_input(private_input)
     }
 
+    /// Call the function `symbol` of the program with up to eight word-sized `args`, following the
+    /// RISC-V calling convention, and return the value of `a0` once it returns.
+    ///
+    /// The function runs on a fresh stack and returns to [`CALL_RETURN_ADDRESS`], where execution
+    /// stops. Memory written by previous calls is kept, so calls can build on each other.
+    fn call(&mut self, symbol: &str, args: &[u32]) -> Result<u32> {
+        let address = self
+            .get_executor()
+            .program
+            .symbols
+            .iter()
+            .find(|s| s.name == symbol)
+            .ok_or_else(|| VMError::UndefinedSymbol(symbol.to_string()))?
+            .address;
+        if args.len() > 8 {
+            return Err(VMError::TooManyArguments(args.len()));
+        }
+
+        let stack_top = self.stack_top();
+        let cpu = &mut self.get_executor_mut().cpu;
+        for (reg, &arg) in (Register::X10 as u8..).zip(args) {
+            cpu.registers.write(Register::from(reg), arg);
+        }
+        cpu.registers.write(Register::X1, CALL_RETURN_ADDRESS);
+        cpu.registers.write(Register::X2, stack_top);
+        cpu.pc.value = address;
+
+        while self.get_executor().cpu.pc.value != CALL_RETURN_ADDRESS {
+            let basic_block_entry = self.fetch_block(self.get_executor().cpu.pc.value)?;
+            self.execute_basic_block(&basic_block_entry, false)?;
+        }
+
+        Ok(self.get_executor().cpu.registers.read(Register::X10))
+    }
+
+    /// Set the initial value of general purpose registers before execution, e.g. the arguments
+    /// of a function run in isolation or the state of a resumed execution. Writes to x0 are ignored.
+    ///
+    /// The prover assumes all registers start at zero, so only traces without initial register
+    /// values can be proven.
+    fn set_initial_registers(&mut self, registers: &[(Register, u32)]) {
+        let cpu = &mut self.get_executor_mut().cpu;
+        for &(reg, value) in registers {
+            cpu.registers.write(reg, value);
+        }
+    }
+
     /// Update and return previous timestamps, but it currently works word-wise, so not used.
     #[allow(dead_code)]
     fn manage_timestamps(&mut self, size: &MemAccessSize, address: &u32) -> usize {
@@ -384,6 +1357,29 @@ pub trait Emulator {
 
     /// Return a `View` capturing the end-state of the emulator.
     fn finalize(&self) -> View;
+
+    /// Execute the program until it exits, returning its exit code and output rather than
+    /// `VMError::VMExited`. Other errors, e.g. `VMError::Yielded`, are returned as is.
+    fn run(&mut self) -> Result<ExecutionOutcome> {
+        match self.execute(false) {
+            Err(VMError::VMExited(exit_code)) => Ok(ExecutionOutcome::new(
+                exit_code,
+                &self.finalize(),
+                self.get_executor().cycles(),
+                self.get_executor().last_error_log(),
+            )),
+            Err(e) => Err(e),
+            Ok(_) => unreachable!("execution only ends with an error"),
+        }
+    }
+
+    /// Deserialize the public output written so far, see [`View::output_as`].
+    fn read_output<T: DeserializeOwned>(&self) -> std::result::Result<T, postcard::Error>
+    where
+        Self: Sized,
+    {
+        self.finalize().output_as()
+    }
 }
 
 #[derive(Debug)]
@@ -430,23 +1426,23 @@ impl Default for HarvardEmulator {
 }
 
 impl HarvardEmulator {
+    /// Creates a Harvard emulator from an ELF file, see [`EmulatorBuilder`] for more options.
     pub fn from_elf(elf: &ElfFile, public_input: &[u8], private_input: &[u8]) -> Self {
+        EmulatorBuilder::new(elf.clone())
+            .public_input(public_input)
+            .private_input(private_input)
+            .build_harvard()
+    }
+
+    pub(crate) fn load(elf: &ElfFile, public_input: &[u8], private_input: &[u8]) -> Self {
         // the stack and heap will also be stored in this variable memory segment
         let text_end = (elf.instructions.len() * WORD_SIZE) as u32 + elf.base;
         let mut data_end = *elf.ram_image.last_key_value().unwrap_or((&text_end, &0)).0;
         let mut data_memory =
             UnifiedMemory::from(VariableMemory::<RW>::from(elf.ram_image.clone()));
 
-        let ro_data_base_address: u32 = *elf.rom_image.first_key_value().unwrap_or((&0, &0)).0;
         let ro_data_end = *elf.rom_image.keys().max().unwrap_or(&0);
-        if !elf.rom_image.is_empty() {
-            let mut ro_data: Vec<u32> =
-                vec![0; ro_data_end as usize + 1 - ro_data_base_address as usize];
-
-            for (addr, &value) in &elf.rom_image {
-                ro_data[(addr - ro_data_base_address) as usize] = value;
-            }
-
+        if let Some(ro_data_memory) = FixedMemory::<RO>::from_image(&elf.rom_image) {
             // Linker places data after rodata, but need to guard against edge case of empty data.
             // We also advance the `data_end` past the end of the last address in the data segment
             // because that address is used to set the base address of the heap, which needs to be
@@ -460,19 +1456,17 @@ impl HarvardEmulator {
                 }
             };
 
-            let ro_data_memory = FixedMemory::<RO>::from_vec(
-                ro_data_base_address,
-                ro_data.len() * WORD_SIZE,
-                ro_data,
-            );
-
             // this unwrap will never fail for a well-formed elf file, and we've already validated
-            data_memory.add_fixed_ro(&ro_data_memory).unwrap();
+            data_memory.add_fixed_ro(ro_data_memory).unwrap();
         }
 
         // Zero out the public input and public output start locations since no offset is needed for harvard emulator.
         data_memory
-            .add_fixed_ro(&FixedMemory::<RO>::from_words(0x80, 8, &[0, 0]))
+            .add_fixed_ro(FixedMemory::<RO>::from_words(
+                PUBLIC_INPUT_ADDRESS,
+                8,
+                &[0, 0],
+            ))
             .unwrap();
 
         // Add the public input length to the beginning of the public input.
@@ -509,6 +1503,18 @@ impl HarvardEmulator {
                 base_address: elf.base,
                 entrypoint: elf.entry,
                 global_clock: 1, // global_clock = 0 captures initalization for memory records
+                program: ProgramState {
+                    symbols: elf.symbols.clone(),
+                    debug_info: elf.debug_info.clone(),
+                    program_range: elf.base..elf.base + (elf.instructions.len() * WORD_SIZE) as u32,
+                    program_hash: Some(program_hash(elf.base, &elf.instructions)),
+                    ..Default::default()
+                },
+                guest_memory: GuestMemory {
+                    heap_stats: HeapStats::new(data_end, MEMORY_TOP),
+                    heap_allocator: HeapAllocator::new(data_end, MEMORY_TOP),
+                    ..Default::default()
+                },
                 ..Default::default()
             },
             instruction_memory: FixedMemory::<RO>::from_vec(
@@ -542,6 +1548,12 @@ impl HarvardEmulator {
                 base_address: ELF_TEXT_START,
                 entrypoint: ELF_TEXT_START,
                 global_clock: 1, // global_clock = 0 captures initalization for memory records
+                program: ProgramState {
+                    program_range: ELF_TEXT_START
+                        ..ELF_TEXT_START + (encoded_basic_blocks.len() * WORD_SIZE) as u32,
+                    program_hash: Some(program_hash(ELF_TEXT_START, &encoded_basic_blocks)),
+                    ..Default::default()
+                },
                 ..Default::default()
             },
             instruction_memory: FixedMemory::<RO>::from_vec(
@@ -569,6 +1581,16 @@ impl Emulator for HarvardEmulator {
         bare_instruction: &Instruction,
         force_provable_transcript: bool,
     ) -> Result<(InstructionResult, MemoryRecords)> {
+        if let Some((address, size, value)) = self.executor.program_store(bare_instruction)? {
+            let op = self
+                .instruction_memory
+                .execute_write(address, size, value)?;
+            return Ok(self.executor.patched_program(op));
+        }
+        if let Some(step) = self.executor.machine_instruction(bare_instruction)? {
+            return Ok(step);
+        }
+
         let ((res, (load_ops, store_ops)), accessed_io_memory) = match (
             self.executor
                 .instruction_executor
@@ -616,6 +1638,8 @@ impl Emulator for HarvardEmulator {
             ),
             (_, _, Err(e)) => return Err(e),
         };
+        self.executor
+            .handle_tohost(&store_ops, &mut self.data_memory)?;
 
         let mut memory_records = MemoryRecords::new();
 
@@ -629,6 +1653,10 @@ impl Emulator for HarvardEmulator {
 
         // Update the memory size statistics.
         if !accessed_io_memory {
+            self.executor
+                .guest_memory
+                .heap_stats
+                .update(&store_ops, self.executor.cpu.registers.read(Register::X2));
             self.memory_stats.update(
                 load_ops,
                 store_ops,
@@ -663,7 +1691,10 @@ impl Emulator for HarvardEmulator {
             return Ok(self.executor.basic_block_cache.get(start).unwrap().clone());
         }
 
-        let block = decode_until_end_of_a_block(self.instruction_memory.segment(pc, None));
+        self.executor.check_pc(pc)?;
+        let block = self
+            .executor
+            .decode_block(pc, self.instruction_memory.segment(pc, None));
         if block.is_empty() {
             return Err(VMError::VMOutOfInstructions);
         }
@@ -686,6 +1717,14 @@ impl Emulator for HarvardEmulator {
         &mut self.executor
     }
 
+    fn stack_top(&self) -> u32 {
+        MEMORY_TOP
+    }
+
+    fn final_memory(&self) -> Vec<MemorySegmentImage> {
+        self.data_memory.rw_segments()
+    }
+
     /// Return a `View` capturing the end-state of the emulator.
     fn finalize(&self) -> View {
         let mut exit_code: Vec<PublicOutputEntry> = Vec::new();
@@ -721,6 +1760,7 @@ impl Emulator for HarvardEmulator {
             .map(|(i, byte)| MemoryInitializationEntry {
                 address: self.input_memory.base_address + i as u32,
                 value: *byte,
+                read_only: false,
             })
             .collect();
         let rom_iter =
@@ -729,6 +1769,7 @@ impl Emulator for HarvardEmulator {
                 .map(|(addr, &value)| MemoryInitializationEntry {
                     address: *addr,
                     value,
+                    read_only: true,
                 });
         // FIXME: ram_iter should contain the initial memory content, not the final content
         let ram_iter =
@@ -737,6 +1778,7 @@ impl Emulator for HarvardEmulator {
                 .map(|(addr, &value)| MemoryInitializationEntry {
                     address: *addr,
                     value,
+                    read_only: false,
                 });
 
         let debug_logs: Vec<Vec<u8>> = if self.get_executor().logs.is_some() {
@@ -756,16 +1798,12 @@ impl Emulator for HarvardEmulator {
             debug_logs,
             program_memory: ProgramInfo {
                 initial_pc: self.executor.entrypoint,
-                program: self
-                    .instruction_memory
-                    .segment(self.executor.base_address, None)
-                    .iter()
-                    .enumerate()
-                    .map(|(pc_offset, instruction)| ProgramMemoryEntry {
-                        pc: self.executor.base_address + (pc_offset * WORD_SIZE) as u32,
-                        instruction_word: *instruction,
-                    })
-                    .collect(),
+                program: ElfProgramEntries::new(
+                    self.executor.base_address,
+                    self.instruction_memory
+                        .segment(self.executor.base_address, None),
+                )
+                .collect(),
             },
             initial_memory: rom_iter
                 .into_iter()
@@ -854,13 +1892,15 @@ impl LinearEmulator {
             )
             .unwrap();
 
-        Ok(Self::from_elf(
-            memory_layout,
-            ad,
-            &elf,
-            public_input.as_slice(),
-            private_input,
-        ))
+        let mut emulator = EmulatorBuilder::new(elf)
+            .layout(memory_layout)
+            .ad(ad)
+            .public_input(&public_input)
+            .private_input(private_input)
+            .build_linear()?;
+        // The first pass couldn't report the layout derived from it.
+        emulator.executor.report_layout(None);
+        Ok(emulator)
     }
 
     /// Creates a Linear Emulator from an ELF file.
@@ -879,6 +1919,22 @@ impl LinearEmulator {
         elf: &ElfFile,
         public_input: &[u8],
         private_input: &[u8],
+    ) -> Self {
+        EmulatorBuilder::new(elf.clone())
+            .layout(memory_layout)
+            .ad(ad)
+            .public_input(public_input)
+            .private_input(private_input)
+            .build_linear()
+            .expect("the program isn't executed when the layout is given")
+    }
+
+    pub(crate) fn load(
+        memory_layout: LinearMemoryLayout,
+        ad: &[u8],
+        elf: &ElfFile,
+        public_input: &[u8],
+        private_input: &[u8],
     ) -> Self {
         let mut memory = UnifiedMemory::default();
 
@@ -891,48 +1947,20 @@ impl LinearEmulator {
             elf.instructions.len() * WORD_SIZE,
             elf.instructions.clone(),
         );
-        let instruction_index = memory.add_fixed_ro(&code_memory).unwrap();
-
-        let elf_rom_image_index = if elf.rom_image.is_empty() {
-            None
-        } else {
-            let ro_data_base_address: u32 = *elf.rom_image.first_key_value().unwrap().0;
-            let ro_data_len = (*elf.rom_image.keys().max().unwrap() as usize + WORD_SIZE
-                - ro_data_base_address as usize)
-                / WORD_SIZE;
-            let mut ro_data: Vec<u32> = vec![0; ro_data_len];
-
-            for (addr, &value) in &elf.rom_image {
-                ro_data[(addr - ro_data_base_address) as usize] = value;
-            }
-
-            let ro_data_memory = FixedMemory::<RO>::from_vec(
-                ro_data_base_address,
-                ro_data.len() * WORD_SIZE,
-                ro_data,
-            );
-
-            Some(memory.add_fixed_ro(&ro_data_memory).unwrap())
-        };
-
-        let _elf_ram_image_index = if elf.ram_image.is_empty() {
-            None
-        } else {
-            let data_base_address: u32 = *elf.ram_image.first_key_value().unwrap().0;
-            let data_len = (*elf.ram_image.keys().max().unwrap() as usize + WORD_SIZE
-                - data_base_address as usize)
-                / WORD_SIZE;
-            let mut data: Vec<u32> = vec![0; data_len];
-
-            for (addr, &value) in &elf.ram_image {
-                data[((addr - data_base_address) as usize) / WORD_SIZE] = value;
-            }
-
-            let data_memory =
-                FixedMemory::<RW>::from_vec(data_base_address, data.len() * WORD_SIZE, data);
+        let instruction_index = memory.add_fixed_ro(code_memory).unwrap();
+        memory.set_label(instruction_index, "program").unwrap();
+
+        let elf_rom_image_index =
+            FixedMemory::<RO>::from_image(&elf.rom_image).map(|ro_data_memory| {
+                let index = memory.add_fixed_ro(ro_data_memory).unwrap();
+                memory.set_label(index, "rodata").unwrap();
+                index
+            });
 
-            Some(memory.add_fixed_rw(&data_memory).unwrap())
-        };
+        if let Some(data_memory) = FixedMemory::<RW>::from_image(&elf.ram_image) {
+            let index = memory.add_fixed_rw(data_memory).unwrap();
+            memory.set_label(index, "data").unwrap();
+        }
 
         // Add the public input length to the beginning of the public input.
         let len_bytes = public_input.len() as u32;
@@ -945,13 +1973,15 @@ impl LinearEmulator {
             memory_layout.public_input_start(),
             &public_input_with_len,
         );
-        let public_input_index = memory.add_fixed_ro(&input_memory).unwrap();
+        let public_input_index = memory.add_fixed_ro(input_memory).unwrap();
+        memory.set_label(public_input_index, "input").unwrap();
 
         let ad_len = (memory_layout.ad_end() - memory_layout.ad_start()) as usize;
         assert_eq!(ad_len, word_align!(ad.len()));
         if ad_len > 0 {
             let ad_memory = FixedMemory::<NA>::from_bytes(memory_layout.ad_start(), ad);
-            let _ = memory.add_fixed_na(&ad_memory).unwrap();
+            let index = memory.add_fixed_na(ad_memory).unwrap();
+            memory.set_label(index, "associated data").unwrap();
         }
 
         let output_len = (memory_layout.public_output_end() - memory_layout.exit_code()) as usize; // we include the exit code in the output segment
@@ -959,7 +1989,8 @@ impl LinearEmulator {
             let init = vec![0; output_len / WORD_SIZE];
             let output_memory =
                 FixedMemory::<WO>::from_vec(memory_layout.exit_code(), output_len, init);
-            let _ = memory.add_fixed_wo(&output_memory).unwrap();
+            let index = memory.add_fixed_wo(output_memory).unwrap();
+            memory.set_label(index, "output").unwrap();
         }
 
         let heap_len = (memory_layout.heap_end() - memory_layout.heap_start()) as usize;
@@ -967,7 +1998,8 @@ impl LinearEmulator {
             let init = vec![0; heap_len / WORD_SIZE];
             let heap_memory =
                 FixedMemory::<RW>::from_vec(memory_layout.heap_start(), heap_len, init);
-            let _ = memory.add_fixed_rw(&heap_memory).unwrap();
+            let index = memory.add_fixed_rw(heap_memory).unwrap();
+            memory.set_label(index, "heap").unwrap();
         }
 
         let stack_len = (memory_layout.stack_top() - memory_layout.stack_bottom()) as usize;
@@ -975,13 +2007,14 @@ impl LinearEmulator {
             let init = vec![0; stack_len / WORD_SIZE];
             let stack_memory =
                 FixedMemory::<RW>::from_vec(memory_layout.stack_bottom(), stack_len, init);
-            let _ = memory.add_fixed_rw(&stack_memory).unwrap();
+            let index = memory.add_fixed_rw(stack_memory).unwrap();
+            memory.set_label(index, "stack").unwrap();
         }
 
         // Add the public input and public output start locations.
         let public_io_location_index = memory
-            .add_fixed_ro(&FixedMemory::<RO>::from_words(
-                0x80,
+            .add_fixed_ro(FixedMemory::<RO>::from_words(
+                PUBLIC_INPUT_ADDRESS,
                 8,
                 &[
                     memory_layout.public_input_start(),
@@ -989,6 +2022,9 @@ impl LinearEmulator {
                 ],
             ))
             .unwrap();
+        memory
+            .set_label(public_io_location_index, "io locations")
+            .unwrap();
 
         let initial_static_ram_image: BTreeMap<u32, u8> = elf
             .ram_image
@@ -1002,12 +2038,43 @@ impl LinearEmulator {
             })
             .collect();
 
+        // The program is moved to `code_start`, and so are its symbols and debug info.
+        let text = elf.base..elf.base + (elf.instructions.len() * WORD_SIZE) as u32;
         let mut emulator = Self {
             executor: Executor {
                 private_input_tape: VecDeque::<u8>::from(private_input.to_vec()),
                 base_address: code_start,
                 entrypoint: code_start + (elf.entry - elf.base),
                 global_clock: 1, // global_clock = 0 captures initalization for memory records
+                program: ProgramState {
+                    debug_info: elf.debug_info.relocate(text.clone(), code_start),
+                    symbols: elf
+                        .symbols
+                        .iter()
+                        .map(|symbol| {
+                            let mut symbol = symbol.clone();
+                            if text.contains(&symbol.address) {
+                                symbol.address = code_start + (symbol.address - elf.base);
+                            }
+                            symbol
+                        })
+                        .collect(),
+                    program_range: code_start
+                        ..code_start + (elf.instructions.len() * WORD_SIZE) as u32,
+                    program_hash: Some(program_hash(code_start, &elf.instructions)),
+                    ..Default::default()
+                },
+                guest_memory: GuestMemory {
+                    heap_stats: HeapStats::new(
+                        memory_layout.heap_start(),
+                        memory_layout.heap_end(),
+                    ),
+                    heap_allocator: HeapAllocator::new(
+                        memory_layout.heap_start(),
+                        memory_layout.heap_end(),
+                    ),
+                    ..Default::default()
+                },
                 ..Default::default()
             },
             instruction_index,
@@ -1022,6 +2089,86 @@ impl LinearEmulator {
         emulator.executor.cpu.pc.value = emulator.executor.entrypoint;
         emulator
     }
+
+    /// Captures the state execution depends on, to return to it with [`LinearEmulator::restore`].
+    pub fn snapshot(&self) -> EmulatorSnapshot {
+        let executor = &self.executor;
+        EmulatorSnapshot {
+            cpu: executor.cpu.clone(),
+            memory: self.memory.clone(),
+            global_clock: executor.global_clock,
+            private_input_tape: executor.private_input_tape.clone(),
+            access_timestamps: executor.access_timestamps.clone(),
+            heap_stats: executor.guest_memory.heap_stats,
+            heap_allocator: executor.guest_memory.heap_allocator.clone(),
+            machine: executor.traps.machine.clone(),
+            harts: executor.harts.clone(),
+            stopped_at: executor.control.debugger.stopped_at(),
+            yielded: executor.control.yielded,
+            memory_assertions: executor.diagnostics.memory_assertions.clone(),
+            program_hash: executor.program.program_hash,
+            undecodable_instructions: executor.program.undecodable_instructions.clone(),
+        }
+    }
+
+    /// Returns to the state captured by [`LinearEmulator::snapshot`] of this emulator, e.g. to
+    /// execute again from a checkpoint. Host-side state, such as the debug logs, profiles,
+    /// breakpoints and hooks, is kept.
+    pub fn restore(&mut self, snapshot: EmulatorSnapshot) {
+        let executor = &mut self.executor;
+        executor.cpu = snapshot.cpu;
+        executor.global_clock = snapshot.global_clock;
+        executor.private_input_tape = snapshot.private_input_tape;
+        executor.access_timestamps = snapshot.access_timestamps;
+        executor.guest_memory.heap_stats = snapshot.heap_stats;
+        executor.guest_memory.heap_allocator = snapshot.heap_allocator;
+        executor.traps.machine = snapshot.machine;
+        executor.harts = snapshot.harts;
+        executor
+            .control
+            .debugger
+            .set_stopped_at(snapshot.stopped_at);
+        executor.control.yielded = snapshot.yielded;
+        executor.diagnostics.memory_assertions = snapshot.memory_assertions;
+        executor.program.program_hash = snapshot.program_hash;
+        executor.program.undecodable_instructions = snapshot.undecodable_instructions;
+        executor.end_block = false;
+        // The program may have been patched since, decode it again.
+        executor.basic_block_cache.clear();
+        executor.basic_block_ref_cache.clear();
+        self.memory = snapshot.memory;
+    }
+}
+
+/// State of a [`LinearEmulator`] captured by [`LinearEmulator::snapshot`].
+#[derive(Debug, Clone)]
+pub struct EmulatorSnapshot {
+    cpu: Cpu,
+    memory: UnifiedMemory,
+    global_clock: usize,
+    private_input_tape: VecDeque<u8>,
+    access_timestamps: HashMap<u32, usize>,
+    heap_stats: HeapStats,
+    heap_allocator: HeapAllocator,
+    machine: Option<MachineState>,
+    harts: Harts,
+    stopped_at: Option<(u32, usize)>,
+    yielded: Option<u32>,
+    memory_assertions: Option<MemoryAssertions>,
+    program_hash: Option<u64>,
+    undecodable_instructions: BTreeMap<u32, u32>,
+}
+
+impl EmulatorSnapshot {
+    /// Number of cycles executed when the snapshot was taken.
+    pub fn cycles(&self) -> u64 {
+        self.global_clock.saturating_sub(1) as u64
+    }
+
+    /// The pc execution resumes at.
+    pub fn pc(&self) -> u32 {
+        self.cpu.pc.value
+    }
 }
 
 impl Emulator for LinearEmulator {
@@ -1036,6 +2183,16 @@ impl Emulator for LinearEmulator {
         bare_instruction: &Instruction,
         _force_second_pass: bool, // Linear Emulator always does second pass
     ) -> Result<(InstructionResult, MemoryRecords)> {
+        if let Some((address, size, value)) = self.executor.program_store(bare_instruction)? {
+            let op = self
+                .memory
+                .patch_fixed_ro(self.instruction_index, address, size, value)?;
+            return Ok(self.executor.patched_program(op));
+        }
+        if let Some(step) = self.executor.machine_instruction(bare_instruction)? {
+            return Ok(step);
+        }
+
         let (res, (load_ops, store_ops)) = match (
             self.executor
                 .instruction_executor
@@ -1067,6 +2224,29 @@ impl Emulator for LinearEmulator {
             }
             (_, _, Err(e)) => return Err(e),
         };
+        self.executor.handle_tohost(&store_ops, &mut self.memory)?;
+
+        if let Some(assertions) = self.executor.diagnostics.memory_assertions.as_mut() {
+            let context = (
+                self.executor.cpu.pc.value,
+                self.executor.global_clock,
+                self.executor.cpu.registers.read(Register::X2),
+            );
+            assertions
+                .check(
+                    &self.memory_layout,
+                    bare_instruction,
+                    context,
+                    &load_ops,
+                    &store_ops,
+                )
+                .map_err(VMError::MemorySafety)?;
+        }
+
+        self.executor
+            .guest_memory
+            .heap_stats
+            .update(&store_ops, self.executor.cpu.registers.read(Register::X2));
 
         let mut memory_records = MemoryRecords::new();
 
@@ -1105,8 +2285,10 @@ impl Emulator for LinearEmulator {
             return Ok(self.executor.basic_block_cache.get(start).unwrap().clone());
         }
 
-        let block =
-            decode_until_end_of_a_block(self.memory.segment(self.instruction_index, pc, None)?);
+        self.executor.check_pc(pc)?;
+        let block = self
+            .executor
+            .decode_block(pc, self.memory.segment(self.instruction_index, pc, None)?);
         if block.is_empty() {
             return Err(VMError::VMOutOfInstructions);
         }
@@ -1129,6 +2311,14 @@ impl Emulator for LinearEmulator {
         &mut self.executor
     }
 
+    fn stack_top(&self) -> u32 {
+        self.memory_layout.stack_top()
+    }
+
+    fn final_memory(&self) -> Vec<MemorySegmentImage> {
+        self.memory.rw_segments()
+    }
+
     /// Return a `View` capturing the end-state of the emulator.
     fn finalize(&self) -> View {
         let mut exit_code: Vec<PublicOutputEntry> = Vec::new();
@@ -1179,22 +2369,24 @@ impl Emulator for LinearEmulator {
                     .map(move |(j, byte)| MemoryInitializationEntry {
                         address: base_address + j as u32,
                         value: byte,
+                        read_only: false,
                     })
             });
         let public_io_loc_iter = self
             .memory
-            .segment(self.public_io_location_index, 0x80, None)
+            .segment(self.public_io_location_index, PUBLIC_INPUT_ADDRESS, None)
             .expect("Cannot find public io location in LinearEmulator")
             .iter()
             .enumerate()
             .flat_map(|(i, word_content)| {
-                let base_address = 0x80 + i as u32 * WORD_SIZE as u32;
+                let base_address = PUBLIC_INPUT_ADDRESS + i as u32 * WORD_SIZE as u32;
                 let word = word_content.to_le_bytes();
                 word.into_iter()
                     .enumerate()
                     .map(move |(j, byte)| MemoryInitializationEntry {
                         address: base_address + j as u32,
                         value: byte,
+                        read_only: false,
                     })
             });
         // TODO: avoid creating a BtreeMap and produce an iterator directly
@@ -1210,6 +2402,7 @@ impl Emulator for LinearEmulator {
             .map(|(addr, byte)| MemoryInitializationEntry {
                 address: *addr,
                 value: *byte,
+                read_only: true,
             });
         let ram_initialization = &self.initial_static_ram_image;
         let ram_iter = ram_initialization
@@ -1217,6 +2410,7 @@ impl Emulator for LinearEmulator {
             .map(|(addr, byte)| MemoryInitializationEntry {
                 address: *addr,
                 value: *byte,
+                read_only: false,
             });
 
         let debug_logs: Vec<Vec<u8>> = if self.get_executor().logs.is_some() {
@@ -1245,21 +2439,17 @@ impl Emulator for LinearEmulator {
                 // todo: this likely isn't robust, we need to rely on elf.entry,
                 //       but it seems to be working with the current runtime
                 initial_pc: self.memory_layout.program_start(),
-                program: self
-                    .memory
-                    .segment(
-                        self.instruction_index,
-                        self.memory_layout.program_start(),
-                        None,
-                    )
-                    .expect("Cannot find program memory in LinearEmulator")
-                    .iter()
-                    .enumerate()
-                    .map(|(pc_offset, instruction)| ProgramMemoryEntry {
-                        pc: self.memory_layout.program_start() + (pc_offset * WORD_SIZE) as u32,
-                        instruction_word: *instruction,
-                    })
-                    .collect(),
+                program: ElfProgramEntries::new(
+                    self.memory_layout.program_start(),
+                    self.memory
+                        .segment(
+                            self.instruction_index,
+                            self.memory_layout.program_start(),
+                            None,
+                        )
+                        .expect("Cannot find program memory in LinearEmulator"),
+                )
+                .collect(),
             },
             initial_memory: public_io_loc_iter
                 .into_iter()
@@ -1279,7 +2469,7 @@ impl Emulator for LinearEmulator {
 mod tests {
     use super::*;
     use crate::elf::ElfFile;
-    use crate::riscv::{BuiltinOpcode, Instruction, Opcode};
+    use crate::riscv::{BuiltinOpcode, Extension, Instruction, InstructionType, Opcode};
     use serial_test::serial;
 
     fn setup_basic_block_ir() -> Vec<BasicBlock> {
@@ -1399,6 +2589,912 @@ mod tests {
         assert_eq!(emulator.executor.private_input_tape, private_input_vec);
     }
 
+    #[test]
+    #[serial]
+    fn test_dyn_emulator() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let emulators: Vec<Box<dyn Emulator>> = vec![
+            Box::new(HarvardEmulator::from_elf(&elf_file, &[], &[])),
+            Box::new(LinearEmulator::from_elf(
+                LinearMemoryLayout::default(),
+                &[],
+                &elf_file,
+                &[],
+                &[],
+            )),
+        ];
+
+        for mut emulator in emulators {
+            assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
+            assert!(emulator.get_executor().global_clock > 0);
+        }
+    }
+
+    #[test]
+    fn test_breakpoint() {
+        let basic_block_entry = BasicBlockEntry::new(
+            0,
+            BasicBlock::new(vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::EBREAK), 0, 0, 1),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 2),
+            ]),
+        );
+        let mut emulator = HarvardEmulator::default();
+
+        let res = emulator.execute_basic_block(&basic_block_entry, false);
+        assert_eq!(res, Err(VMError::Breakpoint(4)));
+        assert_eq!(emulator.executor.cpu.pc.value, 8);
+        assert_eq!(emulator.executor.cpu.registers[Register::X1], 1);
+        assert_eq!(emulator.executor.cpu.registers[Register::X2], 0);
+
+        // Resume after the breakpoint.
+        emulator
+            .execute_basic_block(&basic_block_entry, false)
+            .unwrap();
+        assert_eq!(emulator.executor.cpu.registers[Register::X2], 2);
+    }
+
+    #[test]
+    fn test_host_breakpoints_and_watchpoints() {
+        let exit_code = u32::from(crate::system::SyscallCode::Exit);
+        let mut emulator = HarvardEmulator::from_basic_blocks(&vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 0x100),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 7),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 3, 1, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, exit_code),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+        ])]);
+        let start = emulator.executor.cpu.pc.value;
+        emulator.executor.add_breakpoint(start + 4);
+        emulator.executor.add_watchpoint(0x100, WatchKind::Write);
+        emulator.executor.add_watchpoint(0x103, WatchKind::Read);
+
+        // Breakpoints stop before the instruction.
+        assert_eq!(emulator.execute(false), Err(VMError::Breakpoint(start + 4)));
+        assert_eq!(emulator.executor.cpu.pc.value, start + 4);
+        assert_eq!(emulator.executor.cpu.registers[Register::X2], 0);
+
+        // Watchpoints stop after the accessing instruction.
+        let watchpoint = VMError::Watchpoint {
+            address: 0x100,
+            access: WatchKind::Write,
+        };
+        assert_eq!(emulator.resume(false), Err(watchpoint));
+        assert_eq!(emulator.executor.cpu.pc.value, start + 12);
+        let watchpoint = VMError::Watchpoint {
+            address: 0x103,
+            access: WatchKind::Read,
+        };
+        assert_eq!(emulator.resume(false), Err(watchpoint));
+        assert_eq!(emulator.executor.cpu.registers[Register::X3], 7);
+
+        assert_eq!(emulator.resume(false), Err(VMError::VMExited(0)));
+    }
+
+    #[test]
+    fn test_step() {
+        let mut emulator = HarvardEmulator::from_basic_blocks(&vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 0x100),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 7),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 0),
+        ])]);
+        let start = emulator.executor.cpu.pc.value;
+
+        let outcome = emulator.step().unwrap();
+        assert_eq!((outcome.step.pc, outcome.step.next_pc), (start, start + 4));
+        assert_eq!(outcome.step.result, Some(0x100));
+        assert_eq!(outcome.register_writes, vec![(Register::X1, 0x100)]);
+        assert_eq!(emulator.executor.cpu.registers[Register::X2], 0);
+
+        emulator.step().unwrap();
+        let outcome = emulator.step().unwrap();
+        assert_eq!(
+            outcome.step.instruction.opcode.builtin(),
+            Some(BuiltinOpcode::SW)
+        );
+        assert!(outcome.register_writes.is_empty());
+        assert_eq!(outcome.step.memory_records.len(), 1);
+        let record = outcome.step.memory_records.iter().next().unwrap();
+        assert_eq!((record.get_address(), record.get_value()), (0x100, 7));
+
+        // Writing a register with its current value doesn't change it.
+        let outcome = emulator.step().unwrap();
+        assert!(outcome.register_writes.is_empty());
+        assert_eq!(emulator.executor.global_clock, 5);
+    }
+
+    #[test]
+    fn test_hooks() {
+        use std::sync::{Arc, Mutex};
+
+        const ORACLE: u32 = 0x7FF;
+        let exit_code = u32::from(crate::system::SyscallCode::Exit);
+        let mut emulator = HarvardEmulator::from_basic_blocks(&vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, ORACLE),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 20),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 11, 10, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, exit_code),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+        ])]);
+
+        let executed = Arc::new(Mutex::new(Vec::new()));
+        let pcs = executed.clone();
+        emulator
+            .executor
+            .add_pre_instruction_hook(move |_, cpu| pcs.lock().unwrap().push(cpu.pc.value));
+        let writes = Arc::new(Mutex::new(Vec::new()));
+        let a1 = writes.clone();
+        emulator.executor.add_post_instruction_hook(move |_, cpu| {
+            a1.lock().unwrap().push(cpu.registers[Register::X11])
+        });
+        // An oracle doubling a0, the VM doesn't define its code.
+        emulator.executor.add_syscall_hook(|code, cpu| {
+            (code == ORACLE).then(|| 2 * cpu.registers[Register::X10])
+        });
+
+        assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
+        assert_eq!(emulator.executor.cpu.registers[Register::X11], 40);
+
+        let start = executed.lock().unwrap()[0];
+        let expected: Vec<u32> = (0..7).map(|i| start + 4 * i).collect();
+        assert_eq!(*executed.lock().unwrap(), expected);
+        // The exit syscall stops execution before the post-instruction hooks run.
+        assert_eq!(*writes.lock().unwrap(), [0, 0, 0, 40, 40, 40]);
+    }
+
+    #[test]
+    fn test_yield() {
+        let yield_code = u32::from(crate::system::SyscallCode::Yield);
+        let exit_code = u32::from(crate::system::SyscallCode::Exit);
+        let mut emulator = HarvardEmulator::from_basic_blocks(&vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, yield_code),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, exit_code),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+        ])]);
+
+        let start = emulator.executor.cpu.pc.value;
+
+        assert_eq!(emulator.execute(false), Err(VMError::Yielded(start + 8)));
+        assert_eq!(emulator.executor.cpu.pc.value, start + 12);
+        assert_eq!(emulator.executor.cpu.registers[Register::X1], 1);
+        assert_eq!(emulator.executor.cpu.registers[Register::X2], 0);
+
+        // Resume after the yield.
+        assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
+        assert_eq!(emulator.executor.cpu.registers[Register::X2], 2);
+    }
+
+    #[test]
+    fn test_execute_partial() {
+        let yield_code = u32::from(crate::system::SyscallCode::Yield);
+        let exit_code = u32::from(crate::system::SyscallCode::Exit);
+        let mut emulator = HarvardEmulator::from_basic_blocks(&vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, yield_code),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::EBREAK), 0, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, exit_code),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+        ])]);
+        emulator.executor.set_max_cycles(5);
+
+        let start = emulator.executor.cpu.pc.value;
+        let partial = emulator.execute_partial(false);
+        assert_eq!(partial.error, VMError::Yielded(start + 8));
+        assert_eq!(partial.results, [Some(1), Some(yield_code), None]);
+        assert_eq!(partial.transcript.len(), 3);
+        assert!(partial.transcript.iter().all(|records| records.is_empty()));
+
+        // The breakpoint itself isn't executed.
+        let partial = emulator.execute_partial(false);
+        assert_eq!(partial.error, VMError::Breakpoint(start + 12));
+        assert!(partial.results.is_empty());
+
+        // The instructions executed in a block before it stops are kept too.
+        let partial = emulator.execute_partial(false);
+        assert!(matches!(
+            partial.error,
+            VMError::OutOfFuel { cycles: 5, .. }
+        ));
+        assert_eq!(partial.results, [Some(2), Some(exit_code)]);
+
+        emulator.executor.set_max_cycles(u64::MAX);
+        let partial = emulator.execute_partial(false);
+        assert_eq!(partial.error, VMError::VMExited(0));
+        assert_eq!(partial.results.len(), 1);
+    }
+
+    #[test]
+    fn test_add_and_override_opcode() {
+        use crate::cpu::instructions::{AddInstruction, SubInstruction};
+
+        let add = Opcode::from(BuiltinOpcode::ADD);
+        let custom = Opcode::new(0b0001011, Some(0b000), Some(0b0000000), "custom");
+
+        let mut emulator = HarvardEmulator::default();
+        assert_eq!(
+            emulator.add_opcode::<AddInstruction>(&add),
+            Err(VMError::DuplicateInstruction(add.clone()))
+        );
+        assert_eq!(emulator.add_opcode::<AddInstruction>(&custom), Ok(()));
+        assert_eq!(
+            emulator.add_opcode::<AddInstruction>(&custom),
+            Err(VMError::DuplicateInstruction(custom.clone()))
+        );
+
+        emulator.override_opcode::<SubInstruction>(&add);
+        emulator.set_initial_registers(&[(Register::X1, 10), (Register::X2, 3)]);
+        let basic_block_entry = BasicBlockEntry::new(
+            0,
+            BasicBlock::new(vec![
+                Instruction::new_ir(add.clone(), 3, 1, 2),
+                Instruction::new_ir(custom, 4, 1, 2),
+            ]),
+        );
+        emulator
+            .execute_basic_block(&basic_block_entry, false)
+            .unwrap();
+        assert_eq!(emulator.executor.cpu.registers[Register::X3], 7);
+        assert_eq!(emulator.executor.cpu.registers[Register::X4], 13);
+    }
+
+    #[test]
+    fn test_sub_word_io_instructions() {
+        let rin = |fn3, rd, offset| {
+            Instruction::new(
+                Opcode::new(0b0101011, Some(fn3), None, "rin"),
+                Register::from(rd),
+                Register::X0,
+                offset,
+                InstructionType::IType,
+            )
+        };
+        let wou = |fn3, rs2, offset| {
+            Instruction::new(
+                Opcode::new(0b1011011, Some(fn3), None, "wou"),
+                Register::X0,
+                Register::from(rs2),
+                offset,
+                InstructionType::SType,
+            )
+        };
+        let program = vec![
+            rin(0b001, 1, 4), // rinb x1, 4(x0)
+            rin(0b010, 2, 6), // rinh x2, 6(x0)
+            rin(0b000, 3, 4), // rin x3, 4(x0)
+            wou(0b001, 1, 4), // woub x1, 4(x0)
+            wou(0b010, 2, 6), // wouh x2, 6(x0)
+        ];
+
+        let mut emulator =
+            HarvardEmulator::from_basic_blocks(&vec![BasicBlock::new(program.clone())]);
+        // The public input starts after its length.
+        emulator.input_memory =
+            FixedMemory::<RO>::from_bytes(0, &[4, 0, 0, 0, 0x80, 1, 0xFF, 0x7F]);
+        assert_eq!(emulator.execute(false), Err(VMError::VMOutOfInstructions));
+
+        let registers = &emulator.executor.cpu.registers;
+        assert_eq!(registers.read(Register::X1), 0xFFFF_FF80);
+        assert_eq!(registers.read(Register::X2), 0x7FFF);
+        assert_eq!(registers.read(Register::X3), 0x7FFF_0180);
+        assert_eq!(
+            emulator.output_memory.segment_bytes(4, None).unwrap(),
+            [0x80, 0, 0xFF, 0x7F]
+        );
+
+        // The second pass executes them as the loads and stores of the same width.
+        let registry = &emulator.executor.instruction_executor;
+        let converted: Vec<_> = program
+            .iter()
+            .map(|instruction| {
+                let converted = crate::riscv::decode_instruction(
+                    super::super::convert_instruction(registry, &instruction.encode()),
+                );
+                (
+                    converted.opcode.builtin(),
+                    converted.op_a,
+                    converted.op_b,
+                    converted.op_c,
+                )
+            })
+            .collect();
+        assert_eq!(
+            converted,
+            [
+                (Some(BuiltinOpcode::LB), Register::X1, Register::X0, 4),
+                (Some(BuiltinOpcode::LH), Register::X2, Register::X0, 6),
+                (Some(BuiltinOpcode::LW), Register::X3, Register::X0, 4),
+                (Some(BuiltinOpcode::SB), Register::X0, Register::X1, 4),
+                (Some(BuiltinOpcode::SH), Register::X0, Register::X2, 6),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_m_extension() {
+        let basic_blocks = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MUL), 3, 1, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MULH), 4, 1, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MULHSU), 5, 1, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::MULHU), 6, 1, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::DIV), 7, 1, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::DIVU), 8, 1, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::REM), 9, 1, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::REMU), 10, 1, 2),
+            // Division by zero doesn't trap.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::DIV), 11, 1, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::REM), 12, 1, 0),
+        ])];
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator.set_initial_registers(&[(Register::X1, -7i32 as u32), (Register::X2, 2)]);
+        assert_eq!(emulator.execute(false), Err(VMError::VMOutOfInstructions));
+
+        let registers = &emulator.executor.cpu.registers;
+        let results: Vec<u32> = (3..=12).map(|r| registers[Register::from(r)]).collect();
+        assert_eq!(
+            results,
+            [
+                -14i32 as u32,
+                u32::MAX,
+                u32::MAX,
+                1,
+                -3i32 as u32,
+                0x7FFF_FFFC,
+                -1i32 as u32,
+                1,
+                u32::MAX,
+                -7i32 as u32,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_initial_registers() {
+        let basic_blocks = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 3, 1, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADD), 4, 0, 3),
+        ])];
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator.set_initial_registers(&[
+            (Register::X0, 9),
+            (Register::X1, 5),
+            (Register::X2, 0xFFFF_FFFF),
+        ]);
+        assert_eq!(emulator.execute(false), Err(VMError::VMOutOfInstructions));
+
+        let registers = &emulator.executor.cpu.registers;
+        assert_eq!(registers[Register::X0], 0);
+        assert_eq!(registers[Register::X3], 4);
+        assert_eq!(registers[Register::X4], 4);
+    }
+
+    #[test]
+    #[serial]
+    fn test_call_function() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let emulators: Vec<Box<dyn Emulator>> = vec![
+            Box::new(HarvardEmulator::from_elf(&elf_file, &[], &[])),
+            Box::new(LinearEmulator::from_elf(
+                LinearMemoryLayout::default(),
+                &[],
+                &elf_file,
+                &[],
+                &[],
+            )),
+        ];
+
+        for mut emulator in emulators {
+            assert_eq!(emulator.call("__mulsi3", &[6, 7]), Ok(42));
+            assert_eq!(emulator.call("__udivsi3", &[100, 7]), Ok(14));
+            assert_eq!(
+                emulator.call("does_not_exist", &[]),
+                Err(VMError::UndefinedSymbol("does_not_exist".to_string()))
+            );
+            assert_eq!(
+                emulator.call("__mulsi3", &[0; 9]),
+                Err(VMError::TooManyArguments(9))
+            );
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_final_memory() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let layout = LinearMemoryLayout::default();
+        let mut emulator = LinearEmulator::from_elf(layout, &[], &elf_file, &[], &[]);
+        assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
+
+        let segments = emulator.final_memory();
+        assert!(segments.windows(2).all(|w| w[0].end <= w[1].start));
+        let stack = segments
+            .iter()
+            .find(|segment| segment.start == layout.stack_bottom())
+            .expect("stack is a read-write segment");
+        assert!(stack.words.values().any(|&word| word != 0));
+
+        let mut harvard = HarvardEmulator::from_elf(&elf_file, &[], &[]);
+        assert_eq!(harvard.execute(false), Err(VMError::VMExited(0)));
+        let segments = harvard.final_memory();
+        assert_eq!(segments.len(), 1);
+        assert!(segments[0].end <= MEMORY_TOP);
+        assert!(segments[0].words.values().any(|&word| word != 0));
+    }
+
+    #[test]
+    #[serial]
+    fn test_stream_program_info_from_elf() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let layout = LinearMemoryLayout::default();
+        let emulator = LinearEmulator::from_elf(layout, &[], &elf_file, &[], &[]);
+        let view = emulator.finalize();
+
+        let streamed = ProgramInfo::stream_from_elf(&elf_file, &layout);
+        assert_eq!(streamed.program.len(), elf_file.instructions.len());
+        assert_eq!(streamed.initial_pc, view.get_program_memory().initial_pc);
+        assert!(streamed
+            .program
+            .zip(&view.get_program_memory().program)
+            .all(|(a, b)| (a.pc, a.instruction_word) == (b.pc, b.instruction_word)));
+    }
+
+    #[test]
+    #[serial]
+    fn test_snapshot_restore() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let mut emulator =
+            LinearEmulator::from_elf(LinearMemoryLayout::default(), &[], &elf_file, &[], &[]);
+        emulator.executor.set_max_cycles(50);
+        assert!(matches!(
+            emulator.execute(false),
+            Err(VMError::OutOfFuel { cycles: 50, .. })
+        ));
+        let snapshot = emulator.snapshot();
+        assert_eq!(snapshot.cycles(), 50);
+        assert_eq!(snapshot.pc(), emulator.executor.cpu.pc.value);
+
+        emulator.executor.set_max_cycles(u64::MAX);
+        assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
+        let (cpu, clock) = (
+            emulator.executor.cpu.clone(),
+            emulator.executor.global_clock,
+        );
+        let memory = emulator.memory.clone();
+
+        emulator.restore(snapshot);
+        assert_eq!(emulator.executor.cycles(), 50);
+        assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
+        assert_eq!(emulator.executor.cpu, cpu);
+        assert_eq!(emulator.executor.global_clock, clock);
+        assert!(emulator.memory == memory);
+    }
+
+    #[test]
+    fn test_snapshot_restore_machine_state() {
+        use std::sync::{Arc, Mutex};
+
+        let instructions = vec![
+            0x00500093, // addi x1, x0, 5
+            0x34009073, // csrw mscratch, x1
+            0x00108093, // addi x1, x1, 1
+            0x3400A173, // csrrs x2, mscratch, x1
+            0x340021F3, // csrr x3, mscratch
+        ];
+        let elf = ElfFile::new(
+            instructions,
+            ELF_TEXT_START,
+            ELF_TEXT_START,
+            BTreeMap::new(),
+            BTreeMap::new(),
+            vec![],
+        );
+        let options = EmulatorOptions {
+            machine_mode: true,
+            ..Default::default()
+        };
+        let run = |emulator: &mut LinearEmulator| {
+            let trace = Arc::new(Mutex::new(Vec::new()));
+            let hook_trace = trace.clone();
+            emulator.executor.add_post_instruction_hook(move |_, cpu| {
+                hook_trace.lock().unwrap().push(cpu.clone())
+            });
+            let result = emulator.execute(false);
+            emulator.executor.control.hooks = Hooks::default();
+            let trace = trace.lock().unwrap().clone();
+            (result, trace)
+        };
+
+        let mut uninterrupted =
+            LinearEmulator::from_elf(LinearMemoryLayout::default(), &[], &elf, &[], &[]);
+        uninterrupted.executor.set_options(&options).unwrap();
+        let (result, trace) = run(&mut uninterrupted);
+
+        let mut emulator =
+            LinearEmulator::from_elf(LinearMemoryLayout::default(), &[], &elf, &[], &[]);
+        emulator.executor.set_options(&options).unwrap();
+        // Stop right after mscratch is written.
+        emulator.executor.set_max_cycles(2);
+        assert!(matches!(
+            emulator.execute(false),
+            Err(VMError::OutOfFuel { cycles: 2, .. })
+        ));
+        let snapshot = emulator.snapshot();
+        emulator.executor.set_max_cycles(u64::MAX);
+        assert_eq!(run(&mut emulator).0, result);
+
+        emulator.restore(snapshot);
+        let (resumed_result, resumed_trace) = run(&mut emulator);
+        assert_eq!(resumed_result, result);
+        assert_eq!(resumed_trace, trace[2..]);
+        assert_eq!(emulator.executor.cpu.registers[Register::X2], 5);
+        assert_eq!(
+            emulator.executor.machine_state(),
+            uninterrupted.executor.machine_state()
+        );
+        assert_eq!(
+            emulator.executor.global_clock,
+            uninterrupted.executor.global_clock
+        );
+    }
+
+    #[test]
+    #[serial]
+    fn test_typed_output() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let mut emulator =
+            LinearEmulator::from_elf(LinearMemoryLayout::default(), &[], &elf_file, &[], &[]);
+        assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
+        assert_eq!(emulator.finalize().output_exit_code(), Some(0));
+
+        let layout = LinearMemoryLayout::default();
+        let mut bytes = postcard::to_allocvec_cobs(&(42u32, String::from("fib"))).unwrap();
+        bytes.resize(bytes.len().next_multiple_of(WORD_SIZE), 0);
+        let output = bytes
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| {
+                PublicOutputEntry::new_from_offset(layout.public_output_start(), i as u32, value)
+            })
+            .collect();
+        let exit_code = 7u32
+            .to_le_bytes()
+            .iter()
+            .enumerate()
+            .map(|(i, &value)| {
+                PublicOutputEntry::new_from_offset(layout.exit_code(), i as u32, value)
+            })
+            .collect();
+        let view = View::new(
+            &Some(layout),
+            &vec![],
+            &ProgramInfo::dummy(),
+            &vec![],
+            0,
+            &exit_code,
+            &output,
+            &vec![],
+        );
+
+        assert_eq!(view.output_exit_code(), Some(7));
+        assert_eq!(
+            view.output_as::<(u32, String)>().unwrap(),
+            (42, String::from("fib"))
+        );
+        assert!(view.output_as::<(u32, String, u64)>().is_err());
+    }
+
+    #[test]
+    fn test_branch_stats() {
+        // Count x1 down from 3, the loop branch is taken twice.
+        let basic_blocks = vec![
+            BasicBlock::new(vec![Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::ADDI),
+                1,
+                0,
+                3,
+            )]),
+            BasicBlock::new(vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, -1i32 as u32),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::BNE), 1, 0, -4i32 as u32),
+            ]),
+        ];
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        assert!(emulator.executor.branch_stats().is_none());
+        emulator
+            .executor
+            .set_options(&EmulatorOptions {
+                branch_stats: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(emulator.execute(false), Err(VMError::VMOutOfInstructions));
+
+        let stats = emulator.executor.branch_stats().unwrap();
+        let branch_pc = ELF_TEXT_START + 2 * WORD_SIZE as u32;
+        assert_eq!(
+            stats.get(branch_pc),
+            Some(BranchCounts {
+                taken: 2,
+                not_taken: 1
+            })
+        );
+        assert_eq!(stats.iter().count(), 1);
+    }
+
+    #[test]
+    fn test_execution_stats() {
+        // Store x1 at 0x100 and load it back while counting x1 down from 2, then take a branch to
+        // the next instruction.
+        let basic_blocks = vec![
+            BasicBlock::new(vec![Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::ADDI),
+                1,
+                0,
+                2,
+            )]),
+            BasicBlock::new(vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 0, 1, 0x100),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 2, 0, 0x100),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, -1i32 as u32),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::BNE), 1, 0, -12i32 as u32),
+            ]),
+            BasicBlock::new(vec![Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::BEQ),
+                0,
+                0,
+                WORD_SIZE as u32,
+            )]),
+        ];
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        assert!(emulator.executor.stats().is_none());
+        emulator
+            .executor
+            .set_options(&EmulatorOptions {
+                stats: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(emulator.execute(false), Err(VMError::VMOutOfInstructions));
+
+        let stats = emulator.executor.stats().unwrap();
+        assert_eq!(stats.instructions(), 10);
+        assert_eq!(stats.opcode_count(BuiltinOpcode::ADDI), 3);
+        assert_eq!(stats.opcode_count(BuiltinOpcode::MUL), 0);
+        assert_eq!(
+            stats.opcodes()[0],
+            (BuiltinOpcode::ADDI, 3),
+            "the most executed opcode comes first"
+        );
+        assert_eq!((stats.loads(), stats.stores()), (2, 2));
+        assert_eq!(stats.custom_instructions(), 0);
+        assert!(stats.to_string().starts_with("opcode"));
+
+        // The branch to the next instruction is taken all the same.
+        let branches = emulator.executor.branch_stats().unwrap();
+        assert_eq!(
+            branches.total(),
+            BranchCounts {
+                taken: 2,
+                not_taken: 1
+            }
+        );
+        assert_eq!(
+            branches.get(ELF_TEXT_START + 5 * WORD_SIZE as u32),
+            Some(BranchCounts {
+                taken: 1,
+                not_taken: 0
+            })
+        );
+    }
+
+    #[test]
+    fn test_block_profile() {
+        // Count x1 down from 3, re-entering the block after its first instruction.
+        let basic_blocks = vec![
+            BasicBlock::new(vec![Instruction::new_ir(
+                Opcode::from(BuiltinOpcode::ADDI),
+                1,
+                0,
+                3,
+            )]),
+            BasicBlock::new(vec![
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, -1i32 as u32),
+                Instruction::new_ir(Opcode::from(BuiltinOpcode::BNE), 1, 0, -4i32 as u32),
+            ]),
+        ];
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        assert!(emulator.executor.hot_block_report(10).is_none());
+        emulator
+            .executor
+            .set_options(&EmulatorOptions {
+                block_profile: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(emulator.execute(false), Err(VMError::VMOutOfInstructions));
+
+        let profile = emulator.executor.block_profile().unwrap();
+        let loop_pc = ELF_TEXT_START + WORD_SIZE as u32;
+        assert_eq!(profile.hottest(2), [(loop_pc, 4), (ELF_TEXT_START, 3)]);
+        assert_eq!(profile.total_cycles(), 7);
+
+        let report = emulator.executor.hot_block_report(1).unwrap();
+        let lines: Vec<&str> = report.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[1].contains(&format!("0x{loop_pc:08x}")));
+        assert!(lines[2].contains("addi"));
+        assert!(lines[3].contains("bne"));
+    }
+
+    #[test]
+    fn test_unsupported_extension() {
+        let instructions = vec![
+            0x00150513, // addi a0, a0, 1
+            0x02a57053, // fadd.d ft0, fa0, fa0
+        ];
+        let elf = ElfFile::new(
+            instructions,
+            ELF_TEXT_START,
+            ELF_TEXT_START,
+            BTreeMap::new(),
+            BTreeMap::new(),
+            vec![],
+        );
+        assert_eq!(elf.unsupported_instructions().len(), 1);
+
+        let mut emulator = HarvardEmulator::from_elf(&elf, &[], &[]);
+        assert_eq!(
+            emulator.execute(false),
+            Err(VMError::UnsupportedExtension(
+                Extension::D,
+                0x02a57053,
+                ELF_TEXT_START + WORD_SIZE as u32
+            ))
+        );
+    }
+
+    #[test]
+    fn test_undecodable_instruction() {
+        let instructions = vec![
+            0x00150513, // addi a0, a0, 1
+            0x00000000, // illegal
+        ];
+        let elf = ElfFile::new(
+            instructions,
+            ELF_TEXT_START,
+            ELF_TEXT_START,
+            BTreeMap::new(),
+            BTreeMap::new(),
+            vec![],
+        );
+        let pc = ELF_TEXT_START + WORD_SIZE as u32;
+        let undecodable = UndecodableInstruction { pc, instruction: 0 };
+        assert_eq!(elf.undecodable_instructions(), [undecodable]);
+
+        let mut emulator = HarvardEmulator::from_elf(&elf, &[], &[]);
+        let err = emulator.execute(false).unwrap_err();
+        assert_eq!(err, VMError::UndecodableInstruction(0, pc));
+        assert_eq!(
+            err.to_string(),
+            format!("Instruction 0x00000000 at pc=0x{pc:08X} can't be decoded")
+        );
+        assert_eq!(emulator.executor.undecodable_instructions(), [undecodable]);
+    }
+
+    #[test]
+    fn test_trace_blocks_to_file() {
+        let path = std::env::temp_dir().join(format!("nexus-blocks-{}.txt", std::process::id()));
+        let basic_blocks = setup_basic_block_ir();
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator
+            .executor
+            .set_options(&EmulatorOptions {
+                trace_blocks_file: Some(path.clone()),
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(emulator.execute(false), Err(VMError::VMOutOfInstructions));
+        drop(emulator);
+
+        let trace = std::fs::read_to_string(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(
+            trace.lines().filter(|line| line.starts_with('┌')).count(),
+            basic_blocks.len()
+        );
+        assert!(trace.contains("add"));
+    }
+
+    #[test]
+    #[serial]
+    fn test_trace_jsonl() {
+        #[derive(Clone, Default)]
+        struct SharedBuffer(std::sync::Arc<std::sync::Mutex<Vec<u8>>>);
+
+        impl Write for SharedBuffer {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().write(buf)
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let mut emulator = HarvardEmulator::from_elf(&elf_file, &[], &[]);
+        let buffer = SharedBuffer::default();
+        emulator.executor.trace_jsonl_to(buffer.clone());
+
+        assert_eq!(emulator.execute(false), Err(VMError::VMExited(0)));
+
+        let trace = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let steps: Vec<serde_json::Value> = trace
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        // The clock starts at 1, and the final ecall exits before it is traced.
+        assert_eq!(steps.len(), emulator.executor.global_clock - 1);
+        assert_eq!(steps[0]["pc"], ELF_TEXT_START);
+        assert_eq!(steps[0]["clock"], 1);
+        assert!(steps
+            .iter()
+            .any(|step| step["opcode"] == "sw" && step["memory"][0]["store"] == true));
+    }
+
+    #[test]
+    fn test_self_modifying_code() {
+        let patch = Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 42);
+        let basic_blocks = vec![BasicBlock::new(vec![
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::AUIPC), 1, 0, 0),
+            // Overwrite the last instruction of the block, which was already decoded.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 12),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 4, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 1),
+        ])];
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator.set_initial_registers(&[(Register::X2, patch.encode())]);
+        assert_eq!(
+            emulator.execute(false),
+            Err(VMError::SelfModifyingCode(
+                ELF_TEXT_START + 4,
+                ELF_TEXT_START + 12
+            ))
+        );
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator.set_initial_registers(&[(Register::X2, patch.encode())]);
+        emulator
+            .executor
+            .set_options(&EmulatorOptions {
+                allow_self_modifying_code: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(emulator.execute(false), Err(VMError::VMOutOfInstructions));
+        assert_eq!(emulator.executor.cpu.registers[Register::X3], 42);
+        assert_eq!(emulator.executor.cpu.registers[Register::X4], 1);
+    }
+
     #[test]
     fn test_unimplemented_instruction() {
         let op = Opcode::new(0, None, None, "unsupported");
@@ -0,0 +1,122 @@
+//! # Multi-Hart Emulation
+//!
+//! An experimental mode executing several harts on shared memory, so that multi-threaded guest
+//! runtimes can run in the first pass. Proving remains single-hart.
+//!
+//! All harts start at the entry point with the same registers, except for `a0` holding the hart
+//! id, which is also the value of `mhartid` in machine mode. Like on bare metal, the guest has to
+//! set up a stack per hart itself. Each hart has its own registers and machine-mode CSRs.
+//!
+//! Harts are interleaved deterministically: they take turns in round-robin order, executing one
+//! basic block at a time. The execution ends as soon as any hart ends it.
+
+use std::collections::VecDeque;
+
+use nexus_common::cpu::Registers;
+
+use super::MachineState;
+use crate::{cpu::Cpu, riscv::Register};
+
+/// The state of a hart waiting for its turn.
+#[derive(Debug, Clone)]
+struct ParkedHart {
+    id: usize,
+    cpu: Cpu,
+    machine: Option<MachineState>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Harts {
+    // Id of the running hart, whose state is the one of the executor
+    current: usize,
+
+    // Harts waiting for their turn, in order
+    parked: VecDeque<ParkedHart>,
+}
+
+impl Harts {
+    /// Creates `count` harts starting from the state of the running hart, which becomes hart 0.
+    pub(crate) fn new(count: usize, cpu: &mut Cpu, machine: Option<&mut MachineState>) -> Self {
+        let parked = (1..count)
+            .map(|id| {
+                let mut cpu = cpu.clone();
+                cpu.registers.write(Register::X10, id as u32);
+                let machine = machine.as_deref().map(|machine| {
+                    let mut machine = machine.clone();
+                    machine.set_hartid(id as u32);
+                    machine
+                });
+                ParkedHart { id, cpu, machine }
+            })
+            .collect();
+
+        if count > 1 {
+            cpu.registers.write(Register::X10, 0);
+        }
+
+        Self { current: 0, parked }
+    }
+
+    pub(crate) fn current(&self) -> usize {
+        self.current
+    }
+
+    pub(crate) fn count(&self) -> usize {
+        self.parked.len() + 1
+    }
+
+    /// Parks the running hart, whose state is `cpu` and `machine`, and resumes the next one.
+    pub(crate) fn switch(&mut self, cpu: &mut Cpu, machine: &mut Option<MachineState>) {
+        let Some(mut next) = self.parked.pop_front() else {
+            return;
+        };
+
+        std::mem::swap(cpu, &mut next.cpu);
+        std::mem::swap(machine, &mut next.machine);
+        std::mem::swap(&mut self.current, &mut next.id);
+        self.parked.push_back(next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::emulator::{Emulator, EmulatorOptions, HarvardEmulator};
+    use crate::error::VMError;
+    use crate::memory::{MemAccessSize, MemoryProcessor};
+    use crate::riscv::{BasicBlock, BuiltinOpcode, Instruction, Opcode};
+
+    #[test]
+    fn test_round_robin_harts() {
+        let basic_blocks = vec![BasicBlock::new(vec![
+            // Every hart stores its id + 1 to its slot.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SLLI), 5, 10, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 6, 10, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 5, 6, 0x100),
+            // Wait for the slot of hart 1.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 7, 0, 0x104),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::BEQ), 7, 0, -4i32 as u32),
+        ])];
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator
+            .executor
+            .set_options(&EmulatorOptions {
+                harts: 2,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(emulator.executor.num_harts(), 2);
+
+        // Hart 0 waits once, then hart 1 runs out of instructions first.
+        assert_eq!(emulator.execute(false), Err(VMError::VMOutOfInstructions));
+        assert_eq!(emulator.executor.hart_id(), 1);
+        assert_eq!(emulator.executor.global_clock, 1 + 5 + 5 + 2);
+        for (hart, slot) in [(0, 0x100), (1, 0x104)] {
+            let value = emulator
+                .data_memory
+                .read(slot, MemAccessSize::Word)
+                .unwrap();
+            assert_eq!(value.get_value(), hart + 1);
+        }
+    }
+}
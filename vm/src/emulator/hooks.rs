@@ -0,0 +1,46 @@
+use std::fmt::Debug;
+
+use crate::{cpu::Cpu, riscv::Instruction};
+
+/// Callback run before or after each instruction, see [`super::Executor::add_pre_instruction_hook`].
+pub type InstructionHook = Box<dyn FnMut(&Instruction, &Cpu) + Send>;
+
+/// Callback run on each syscall with its code, see [`super::Executor::add_syscall_hook`].
+pub type SyscallHook = Box<dyn FnMut(u32, &Cpu) -> Option<u32> + Send>;
+
+/// Callbacks registered by the host, run in the order they're added.
+#[derive(Default)]
+pub(crate) struct Hooks {
+    pub(crate) pre_instruction: Vec<InstructionHook>,
+    pub(crate) post_instruction: Vec<InstructionHook>,
+    pub(crate) syscall: Vec<SyscallHook>,
+}
+
+impl Hooks {
+    pub(crate) fn before(&mut self, instruction: &Instruction, cpu: &Cpu) {
+        for hook in &mut self.pre_instruction {
+            hook(instruction, cpu);
+        }
+    }
+
+    pub(crate) fn after(&mut self, instruction: &Instruction, cpu: &Cpu) {
+        for hook in &mut self.post_instruction {
+            hook(instruction, cpu);
+        }
+    }
+
+    /// The result of the first hook intercepting the syscall, if any. Later hooks aren't run.
+    pub(crate) fn intercept_syscall(&mut self, code: u32, cpu: &Cpu) -> Option<u32> {
+        self.syscall.iter_mut().find_map(|hook| hook(code, cpu))
+    }
+}
+
+impl Debug for Hooks {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Hooks")
+            .field("pre_instruction", &self.pre_instruction.len())
+            .field("post_instruction", &self.post_instruction.len())
+            .field("syscall", &self.syscall.len())
+            .finish()
+    }
+}
@@ -0,0 +1,88 @@
+use std::{
+    collections::HashMap,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+use crate::{
+    memory::{MemoryRecord, MemoryRecords},
+    riscv::{BuiltinOpcode, Instruction},
+};
+
+/// Counts of the instructions executed by the guest, by opcode, and of the memory they accessed.
+/// Outcomes of branches are in [`super::BranchStats`], enabled together with these counts.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExecutionStats {
+    opcodes: HashMap<BuiltinOpcode, u64>,
+    custom_instructions: u64,
+    loads: u64,
+    stores: u64,
+}
+
+impl ExecutionStats {
+    /// Counts `instruction` once it's executed, with the memory it accessed.
+    pub(crate) fn record(&mut self, instruction: &Instruction, records: &MemoryRecords) {
+        match instruction.opcode.builtin() {
+            Some(opcode) => *self.opcodes.entry(opcode).or_default() += 1,
+            None => self.custom_instructions += 1,
+        }
+        for record in records {
+            match record {
+                MemoryRecord::LoadRecord(..) => self.loads += 1,
+                MemoryRecord::StoreRecord(..) => self.stores += 1,
+            }
+        }
+    }
+
+    /// Number of instructions executed.
+    pub fn instructions(&self) -> u64 {
+        self.opcodes.values().sum::<u64>() + self.custom_instructions
+    }
+
+    /// Number of times `opcode` was executed.
+    pub fn opcode_count(&self, opcode: BuiltinOpcode) -> u64 {
+        self.opcodes.get(&opcode).copied().unwrap_or_default()
+    }
+
+    /// Executed builtin opcodes with their counts, most executed first.
+    pub fn opcodes(&self) -> Vec<(BuiltinOpcode, u64)> {
+        let mut opcodes: Vec<_> = self
+            .opcodes
+            .iter()
+            .map(|(&opcode, &count)| (opcode, count))
+            .collect();
+        opcodes
+            .sort_by_cached_key(|&(opcode, count)| (std::cmp::Reverse(count), opcode.to_string()));
+        opcodes
+    }
+
+    /// Number of custom and precompile instructions executed.
+    pub fn custom_instructions(&self) -> u64 {
+        self.custom_instructions
+    }
+
+    /// Number of memory reads, a precompile may read several times.
+    pub fn loads(&self) -> u64 {
+        self.loads
+    }
+
+    /// Number of memory writes, a precompile may write several times.
+    pub fn stores(&self) -> u64 {
+        self.stores
+    }
+}
+
+impl Display for ExecutionStats {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let total = self.instructions().max(1);
+        let share = |count: u64| count as f64 * 100.0 / total as f64;
+        writeln!(f, "{:<10} {:>12} {:>7}", "opcode", "count", "share")?;
+        for (opcode, count) in self.opcodes() {
+            writeln!(f, "{:<10} {count:>12} {:>6.2}%", opcode, share(count))?;
+        }
+        if self.custom_instructions > 0 {
+            let count = self.custom_instructions;
+            writeln!(f, "{:<10} {count:>12} {:>6.2}%", "<custom>", share(count))?;
+        }
+        write!(f, "memory: {} loads, {} stores", self.loads, self.stores)
+    }
+}
@@ -48,6 +48,47 @@
 //! let stack_top = layout.stack_top();
 //! ```
 //!
+//! Segment sizes can also be set one at a time with a [`LinearMemoryLayoutBuilder`], which reports
+//! which segment is invalid instead of rounding sizes up:
+//!
+//! ```rust
+//! use nexus_vm::emulator::LinearMemoryLayout;
+//!
+//! let layout = LinearMemoryLayout::builder()
+//!     .program_size(0x10000)
+//!     .public_input_size(0x1000)
+//!     .heap_size(0x100000)
+//!     .stack_size(0x100000)
+//!     .build()
+//!     .unwrap();
+//! assert_eq!(layout.config().ad_size, 0);
+//! ```
+//!
+//! # Configuration Files
+//!
+//! Layouts can also be described declaratively by a [`LayoutConfig`], stored as TOML or JSON, and
+//! checked against the program they will run:
+//!
+//! ```rust
+//! use nexus_vm::elf::ElfFile;
+//! use nexus_vm::emulator::LinearMemoryLayout;
+//!
+//! let layout = LinearMemoryLayout::from_toml(
+//!     r#"
+//!     program_size = 0x80000
+//!     public_input_size = 0
+//!     public_output_size = 0
+//!     ad_size = 0
+//!     max_heap_size = 0x800000
+//!     max_stack_size = 0x100000
+//!     "#,
+//! )
+//! .unwrap();
+//!
+//! let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+//! layout.validate_elf(&elf_file).unwrap();
+//! ```
+//!
 //! # Memory Layout Visualization
 //!
 //! ```text
@@ -99,10 +140,13 @@
 //!
 //! This module is crucial for managing the memory layout in the RISC-V emulator,
 //! ensuring proper allocation and access to different memory regions during program execution.
-use crate::error::{Result, VMError};
+use crate::elf::ElfFile;
+use crate::error::{LayoutConfigError, LayoutError, Result, VMError};
+use nexus_common::abi::MemoryLayoutField;
 use nexus_common::constants::{ELF_TEXT_START, MEMORY_GAP, NUM_REGISTERS, WORD_SIZE};
 use nexus_common::word_align;
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 
 // nb: all measurements are in terms of virtual memory
 #[derive(Clone, Copy, Debug, Serialize, Deserialize)]
@@ -267,6 +311,23 @@ impl LinearMemoryLayout {
         self.heap
     }
 
+    /// Address of `field`, as returned to the guest by [`crate::SyscallCode::QueryMemoryLayout`].
+    pub fn field_address(&self, field: MemoryLayoutField) -> u32 {
+        match field {
+            MemoryLayoutField::HeapStart => self.heap_start(),
+            MemoryLayoutField::HeapEnd => self.heap_end(),
+            MemoryLayoutField::StackBottom => self.stack_bottom(),
+            MemoryLayoutField::StackTop => self.stack_top(),
+            MemoryLayoutField::PublicInputStart => self.public_input_start(),
+            MemoryLayoutField::PublicInputEnd => self.public_input_end(),
+            MemoryLayoutField::PublicOutputStart => self.public_output_start(),
+            MemoryLayoutField::PublicOutputEnd => self.public_output_end(),
+            MemoryLayoutField::AdStart => self.ad_start(),
+            MemoryLayoutField::AdEnd => self.ad_end(),
+            MemoryLayoutField::ExitCode => self.exit_code(),
+        }
+    }
+
     pub fn public_output_addresses(&self) -> impl Iterator<Item = u32> {
         self.public_output_start()..self.public_output_end()
     }
@@ -295,6 +356,24 @@ impl LinearMemoryLayout {
         self.stack_top - WORD_SIZE as u32
     }
 
+    /// Name of the segment containing `address`, matching the labels of the memory of the linear
+    /// emulator. The program segment also holds the static data of the program.
+    pub fn segment_label(&self, address: u32) -> Option<&'static str> {
+        let within = |start: u32, end: u32| (start..end).contains(&address);
+
+        [
+            (self.program_start(), self.program_end(), "program"),
+            (self.public_input_start(), self.public_input_end(), "input"),
+            (self.ad_start(), self.ad_end(), "associated data"),
+            (self.exit_code(), self.public_output_end(), "output"),
+            (self.heap_start(), self.heap_end(), "heap"),
+            (self.stack_bottom(), self.stack_top, "stack"),
+        ]
+        .into_iter()
+        .find(|&(start, end, _)| within(start, end))
+        .map(|(_, _, label)| label)
+    }
+
     pub fn tracked_ram_size(&self, static_memory_size: usize) -> usize {
         let stack_size: usize =
             self.stack_top
@@ -329,3 +408,374 @@ impl LinearMemoryLayout {
         total
     }
 }
+
+/// Sizes of the memory segments of a [`LinearMemoryLayout`], in bytes, as stored in configuration
+/// files. Segments are placed in the fixed order of the layout, so their sizes determine it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LayoutConfig {
+    pub program_size: u32,
+    #[serde(default)]
+    pub public_input_size: u32,
+    #[serde(default)]
+    pub public_output_size: u32,
+    #[serde(default)]
+    pub ad_size: u32,
+    pub max_heap_size: u32,
+    pub max_stack_size: u32,
+}
+
+impl LinearMemoryLayout {
+    pub fn from_config(config: &LayoutConfig) -> Result<Self> {
+        Self::new(
+            config.max_heap_size,
+            config.max_stack_size,
+            config.public_input_size,
+            config.public_output_size,
+            config.program_size,
+            config.ad_size,
+        )
+    }
+
+    /// Recover the segment sizes of the layout, e.g. to store a layout derived from a first pass.
+    pub fn config(&self) -> LayoutConfig {
+        LayoutConfig {
+            program_size: self.public_input - ELF_TEXT_START,
+            public_input_size: self.ad - self.public_input - WORD_SIZE as u32,
+            public_output_size: self.heap - self.public_output,
+            ad_size: self.exit_code - self.ad,
+            max_heap_size: self.gap - self.heap,
+            max_stack_size: self.stack_top - self.stack_bottom,
+        }
+    }
+
+    pub fn from_toml(config: &str) -> Result<Self, LayoutConfigError> {
+        Ok(Self::from_config(&toml::from_str(config)?)?)
+    }
+
+    pub fn from_json(config: &str) -> Result<Self, LayoutConfigError> {
+        Ok(Self::from_config(&serde_json::from_str(config)?)?)
+    }
+
+    /// Load a layout from a `.toml` or `.json` configuration file.
+    pub fn from_config_file<P: AsRef<Path> + ?Sized>(path: &P) -> Result<Self, LayoutConfigError> {
+        let path = path.as_ref();
+        let config = std::fs::read_to_string(path)?;
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(&config),
+            Some("json") => Self::from_json(&config),
+            _ => Err(LayoutConfigError::UnsupportedFormat(path.to_path_buf())),
+        }
+    }
+
+    /// Check that the program segment of the layout can hold the code and static data of `elf`.
+    pub fn validate_elf(&self, elf: &ElfFile) -> Result<(), LayoutConfigError> {
+        let text_end = elf.base as usize + elf.instructions.len() * WORD_SIZE;
+        let data_end = elf
+            .rom_image
+            .keys()
+            .chain(elf.ram_image.keys())
+            .map(|&addr| addr as usize + WORD_SIZE)
+            .max()
+            .unwrap_or_default();
+
+        let required = text_end.max(data_end) - self.program_start() as usize;
+        let available = (self.program_end() - self.program_start()) as usize;
+        if required > available {
+            return Err(LayoutConfigError::ProgramTooLarge {
+                required,
+                available,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Builds a [`LinearMemoryLayout`] from the sizes of its segments, in bytes, starting from the
+/// sizes of [`LinearMemoryLayout::default`].
+///
+/// Unlike [`LinearMemoryLayout::new`], sizes must be word-aligned and [`Self::build`] names the
+/// segment that doesn't fit. Segments are still placed in the fixed order of the layout.
+#[derive(Clone, Copy, Debug)]
+pub struct LinearMemoryLayoutBuilder {
+    config: LayoutConfig,
+}
+
+impl Default for LinearMemoryLayoutBuilder {
+    fn default() -> Self {
+        Self {
+            config: LinearMemoryLayout::default().config(),
+        }
+    }
+}
+
+impl LinearMemoryLayoutBuilder {
+    pub fn program_size(mut self, size: u32) -> Self {
+        self.config.program_size = size;
+        self
+    }
+
+    pub fn public_input_size(mut self, size: u32) -> Self {
+        self.config.public_input_size = size;
+        self
+    }
+
+    pub fn public_output_size(mut self, size: u32) -> Self {
+        self.config.public_output_size = size;
+        self
+    }
+
+    /// Size of the associated data segment.
+    pub fn ad_size(mut self, size: u32) -> Self {
+        self.config.ad_size = size;
+        self
+    }
+
+    pub fn heap_size(mut self, size: u32) -> Self {
+        self.config.max_heap_size = size;
+        self
+    }
+
+    pub fn stack_size(mut self, size: u32) -> Self {
+        self.config.max_stack_size = size;
+        self
+    }
+
+    pub fn build(&self) -> Result<LinearMemoryLayout, LayoutError> {
+        let config = &self.config;
+        let word = WORD_SIZE as u32;
+        let segments = [
+            ("program", config.program_size),
+            ("public input", config.public_input_size),
+            ("associated data", config.ad_size),
+            ("public output", config.public_output_size),
+            ("heap", config.max_heap_size),
+            ("stack", config.max_stack_size),
+        ];
+        if let Some(&(segment, size)) = segments.iter().find(|(_, size)| size % word != 0) {
+            return Err(LayoutError::Unaligned { segment, size });
+        }
+        if config.program_size == 0 {
+            return Err(LayoutError::EmptyProgram);
+        }
+
+        // Every segment must end within the address space, or it would overlap the first ones.
+        let extents = [
+            ("program", config.program_size),
+            ("public input", word),
+            ("public input", config.public_input_size),
+            ("associated data", config.ad_size),
+            ("exit code", word),
+            ("public output", config.public_output_size),
+            ("heap", config.max_heap_size),
+            ("memory gap", MEMORY_GAP),
+            ("stack", config.max_stack_size),
+        ];
+        extents
+            .into_iter()
+            .try_fold(ELF_TEXT_START, |end, (segment, size)| {
+                end.checked_add(size)
+                    .ok_or(LayoutError::Overflow { segment })
+            })?;
+
+        let layout = LinearMemoryLayout::new_unchecked(
+            config.max_heap_size,
+            config.max_stack_size,
+            config.public_input_size,
+            config.public_output_size,
+            config.program_size,
+            config.ad_size,
+        );
+        debug_assert!(layout.validate().is_ok());
+        Ok(layout)
+    }
+}
+
+impl LinearMemoryLayout {
+    pub fn builder() -> LinearMemoryLayoutBuilder {
+        LinearMemoryLayoutBuilder::default()
+    }
+}
+
+/// Largest offset of the heap or the stack into its segment, see [`LayoutOffsets`].
+const MAX_LAYOUT_OFFSET: u32 = 0x10000;
+
+/// Offsets into their segments the heap and the stack of the guest start at.
+///
+/// They are zero, except in the layout randomization test mode, see
+/// [`super::EmulatorOptions::randomize_layout`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LayoutOffsets {
+    /// Bytes skipped at the start of the heap.
+    pub heap: u32,
+    /// Bytes skipped below the top of the stack.
+    pub stack: u32,
+}
+
+impl LinearMemoryLayout {
+    /// Offsets derived from `seed`, of at most an eighth of the segment and [`MAX_LAYOUT_OFFSET`]
+    /// bytes, aligned to 16 bytes like the stack pointer.
+    pub fn random_offsets(&self, seed: u64) -> LayoutOffsets {
+        let mut state = seed;
+        let mut offset = |segment_size: u32| {
+            let max_offset = (segment_size / 8).min(MAX_LAYOUT_OFFSET) / 16;
+            (splitmix64(&mut state) % (max_offset as u64 + 1)) as u32 * 16
+        };
+
+        LayoutOffsets {
+            heap: offset(self.heap_end() - self.heap_start()),
+            stack: offset(self.stack_top() - self.stack_bottom()),
+        }
+    }
+}
+
+fn splitmix64(state: &mut u64) -> u64 {
+    *state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    let mut z = *state;
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_random_offsets() {
+        let layout = LinearMemoryLayout::new(0x1000, 0x100000, 0x10, 0x20, 0x400, 0x8).unwrap();
+        let offsets: Vec<_> = (0..16).map(|seed| layout.random_offsets(seed)).collect();
+
+        for offsets in &offsets {
+            assert!(offsets.heap <= 0x1000 / 8 && offsets.heap % 16 == 0);
+            assert!(offsets.stack <= MAX_LAYOUT_OFFSET && offsets.stack % 16 == 0);
+        }
+        assert_eq!(layout.random_offsets(3), offsets[3]);
+        assert!(offsets.iter().any(|o| o.stack != offsets[0].stack));
+    }
+
+    #[test]
+    fn test_config_roundtrip() {
+        let layout = LinearMemoryLayout::new(0x1000, 0x2000, 0x10, 0x20, 0x400, 0x8).unwrap();
+        let config = layout.config();
+        assert_eq!(
+            config,
+            LayoutConfig {
+                program_size: 0x400,
+                public_input_size: 0x10,
+                public_output_size: 0x20,
+                ad_size: 0x8,
+                max_heap_size: 0x1000,
+                max_stack_size: 0x2000,
+            }
+        );
+
+        let json = serde_json::to_string(&config).unwrap();
+        let from_json = LinearMemoryLayout::from_json(&json).unwrap();
+        assert_eq!(from_json.config(), config);
+
+        let from_toml = LinearMemoryLayout::from_toml(&toml::to_string(&config).unwrap()).unwrap();
+        assert_eq!(from_toml.config(), config);
+    }
+
+    #[test]
+    fn test_config_errors() {
+        assert!(matches!(
+            LinearMemoryLayout::from_toml("program_size = 0x400\nmax_stack_size = 0x1000\n"),
+            Err(LayoutConfigError::Toml(_))
+        ));
+        assert!(matches!(
+            LinearMemoryLayout::from_json(
+                r#"{"program_size": 0, "max_heap_size": 0, "max_stack_size": 0}"#
+            ),
+            Err(LayoutConfigError::InvalidLayout(
+                VMError::InvalidMemoryLayout
+            ))
+        ));
+
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let layout = LinearMemoryLayout::new(0x1000, 0x1000, 0, 0, 0x100, 0).unwrap();
+        assert!(matches!(
+            layout.validate_elf(&elf_file),
+            Err(LayoutConfigError::ProgramTooLarge {
+                available: 0x100,
+                ..
+            })
+        ));
+        LinearMemoryLayout::default()
+            .validate_elf(&elf_file)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_builder() {
+        let layout = LinearMemoryLayout::builder()
+            .program_size(0x400)
+            .public_input_size(0x10)
+            .public_output_size(0x20)
+            .ad_size(0x8)
+            .heap_size(0x1000)
+            .stack_size(0x2000)
+            .build()
+            .unwrap();
+        assert_eq!(
+            layout.config(),
+            LinearMemoryLayout::new(0x1000, 0x2000, 0x10, 0x20, 0x400, 0x8)
+                .unwrap()
+                .config()
+        );
+        assert_eq!(
+            LinearMemoryLayout::builder().build().unwrap().config(),
+            LinearMemoryLayout::default().config()
+        );
+
+        assert_eq!(
+            LinearMemoryLayout::builder()
+                .ad_size(0x6)
+                .build()
+                .unwrap_err(),
+            LayoutError::Unaligned {
+                segment: "associated data",
+                size: 0x6
+            }
+        );
+        assert_eq!(
+            LinearMemoryLayout::builder()
+                .program_size(0)
+                .build()
+                .unwrap_err(),
+            LayoutError::EmptyProgram
+        );
+        assert_eq!(
+            LinearMemoryLayout::builder()
+                .heap_size(0xFFFF_0000)
+                .build()
+                .unwrap_err(),
+            LayoutError::Overflow { segment: "heap" }
+        );
+        assert_eq!(
+            LinearMemoryLayout::builder()
+                .stack_size(0xFFF0_0000)
+                .build()
+                .unwrap_err(),
+            LayoutError::Overflow { segment: "stack" }
+        );
+    }
+
+    #[test]
+    fn test_segment_label() {
+        let layout = LinearMemoryLayout::new(0x1000, 0x2000, 0x10, 0x20, 0x400, 0x8).unwrap();
+        assert_eq!(
+            layout.segment_label(layout.program_start()),
+            Some("program")
+        );
+        assert_eq!(
+            layout.segment_label(layout.public_input_start()),
+            Some("input")
+        );
+        assert_eq!(layout.segment_label(layout.exit_code()), Some("output"));
+        assert_eq!(layout.segment_label(layout.heap_start()), Some("heap"));
+        assert_eq!(layout.segment_label(layout.stack_top()), Some("stack"));
+        assert_eq!(layout.segment_label(layout.gap_start()), None);
+    }
+}
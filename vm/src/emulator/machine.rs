@@ -0,0 +1,573 @@
+//! # Machine Mode
+//!
+//! A minimal model of the RISC-V machine mode, so that preemptive guest runtimes and guest logic
+//! based on timeouts can be emulated. It is off by default, see
+//! [`super::EmulatorOptions::machine_mode`]: CSR instructions aren't part of the provable ISA, so
+//! programs using them can only be executed.
+//!
+//! Supported are the subset of `mstatus` (`MIE`, `MPIE`, with `MPP` always machine), `mie` and
+//! `mip` needed for the machine timer interrupt, the trap CSRs `mtvec`, `mepc`, `mcause`, `mtval`
+//! and `mscratch`, and the read-only `misa`, `mhartid` and counters.
+//!
+//! Time is measured in executed instructions: `mtime` is the global clock, read through the
+//! `time`, `cycle` and `instret` CSRs. There is no memory-mapped timer, `mtimecmp` is accessed
+//! through the custom CSRs [`MTIMECMP`] and [`MTIMECMPH`] instead. A pending timer interrupt is
+//! delivered between basic blocks, and `wfi` is a no-op.
+//!
+//! With [`super::EmulatorOptions::trap_exceptions`], faults are delivered to the trap handler of
+//! the guest instead of stopping the execution: illegal instructions, misaligned accesses, access
+//! faults and environment calls from user mode, see [`exception_cause`]. `mret` can enter user
+//! mode through `mstatus.MPP`, environment calls from machine mode remain Nexus syscalls.
+
+use nexus_common::error::MemoryError;
+
+use crate::cpu::RegisterFile;
+use crate::error::VMError;
+use crate::riscv::{BuiltinOpcode, Instruction};
+
+/// The RISC-V opcode of system instructions.
+pub(crate) const SYSTEM_OPCODE: u8 = 0b1110011;
+
+pub const MSTATUS: u32 = 0x300;
+pub const MISA: u32 = 0x301;
+pub const MIE: u32 = 0x304;
+pub const MTVEC: u32 = 0x305;
+pub const MSCRATCH: u32 = 0x340;
+pub const MEPC: u32 = 0x341;
+pub const MCAUSE: u32 = 0x342;
+pub const MTVAL: u32 = 0x343;
+pub const MIP: u32 = 0x344;
+/// Low word of `mtimecmp`, in the custom machine read/write CSR space.
+pub const MTIMECMP: u32 = 0x7C0;
+/// High word of `mtimecmp`, in the custom machine read/write CSR space.
+pub const MTIMECMPH: u32 = 0x7C1;
+pub const CYCLE: u32 = 0xC00;
+pub const TIME: u32 = 0xC01;
+pub const INSTRET: u32 = 0xC02;
+pub const CYCLEH: u32 = 0xC80;
+pub const TIMEH: u32 = 0xC81;
+pub const INSTRETH: u32 = 0xC82;
+pub const MHARTID: u32 = 0xF14;
+
+const MSTATUS_MIE: u32 = 1 << 3;
+const MSTATUS_MPIE: u32 = 1 << 7;
+const MSTATUS_MPP: u32 = 0b11 << 11;
+
+/// Interrupt enable and pending bits of `mie` and `mip`.
+const MSIP: u32 = 1 << 3;
+const MTIP: u32 = 1 << 7;
+const MEIP: u32 = 1 << 11;
+
+/// RV32IM.
+const MISA_VALUE: u32 = (1 << 30) | (1 << 8) | (1 << 12);
+
+/// `mcause` code of the machine timer interrupt.
+pub const MACHINE_TIMER_INTERRUPT: u32 = 7;
+const INTERRUPT_FLAG: u32 = 1 << 31;
+
+/// `mcause` codes of the exceptions delivered to the guest.
+pub const ILLEGAL_INSTRUCTION: u32 = 2;
+pub const LOAD_ADDRESS_MISALIGNED: u32 = 4;
+pub const LOAD_ACCESS_FAULT: u32 = 5;
+pub const STORE_ADDRESS_MISALIGNED: u32 = 6;
+pub const STORE_ACCESS_FAULT: u32 = 7;
+pub const ECALL_FROM_USER: u32 = 8;
+
+/// Privilege level of the emulated hart.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Privilege {
+    User = 0,
+    #[default]
+    Machine = 3,
+}
+
+/// The exception `error` raised by `instruction` corresponds to, as its `mcause` and `mtval`, or
+/// `None` if it can't be handled by the guest.
+pub fn exception_cause(error: &VMError, instruction: &Instruction) -> Option<(u32, u32)> {
+    let store = matches!(
+        instruction.opcode.builtin(),
+        Some(BuiltinOpcode::SB | BuiltinOpcode::SH | BuiltinOpcode::SW)
+    );
+    let access_fault = if store {
+        STORE_ACCESS_FAULT
+    } else {
+        LOAD_ACCESS_FAULT
+    };
+
+    Some(match *error {
+        VMError::UndefinedInstruction(_)
+        | VMError::UnimplementedInstruction(_)
+        | VMError::IllegalCsrAccess(..)
+        | VMError::PrivilegedInstruction(..) => (ILLEGAL_INSTRUCTION, instruction.encode()),
+        VMError::UnsupportedExtension(_, word, _) | VMError::UndecodableInstruction(word, _) => {
+            (ILLEGAL_INSTRUCTION, word)
+        }
+        VMError::EnvironmentCallFromUser(_) => (ECALL_FROM_USER, 0),
+        VMError::SelfModifyingCode(_, address) => (STORE_ACCESS_FAULT, address),
+        VMError::MemoryError(ref error) => match *error {
+            MemoryError::UnalignedMemoryRead(address) => (LOAD_ADDRESS_MISALIGNED, address),
+            MemoryError::UnalignedMemoryWrite(address) => (STORE_ADDRESS_MISALIGNED, address),
+            MemoryError::UnauthorizedRead(address) => (LOAD_ACCESS_FAULT, address),
+            MemoryError::UnauthorizedWrite(address) => (STORE_ACCESS_FAULT, address),
+            MemoryError::InvalidMemoryAccess(address)
+            | MemoryError::OutOfBounds { address, .. } => (access_fault, address),
+            _ => return None,
+        },
+        _ => return None,
+    })
+}
+
+/// Machine-mode CSRs of the emulated hart.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MachineState {
+    mstatus: u32,
+    mie: u32,
+    mtvec: u32,
+    mscratch: u32,
+    mepc: u32,
+    mcause: u32,
+    mtval: u32,
+    mtimecmp: u64,
+    privilege: Privilege,
+    hartid: u32,
+}
+
+impl Default for MachineState {
+    fn default() -> Self {
+        Self {
+            mstatus: MSTATUS_MPP,
+            mie: 0,
+            mtvec: 0,
+            mscratch: 0,
+            mepc: 0,
+            mcause: 0,
+            mtval: 0,
+            // No interrupt is pending until the guest arms the timer.
+            mtimecmp: u64::MAX,
+            privilege: Privilege::Machine,
+            hartid: 0,
+        }
+    }
+}
+
+impl MachineState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn mtimecmp(&self) -> u64 {
+        self.mtimecmp
+    }
+
+    pub fn mepc(&self) -> u32 {
+        self.mepc
+    }
+
+    pub fn mcause(&self) -> u32 {
+        self.mcause
+    }
+
+    pub fn privilege(&self) -> Privilege {
+        self.privilege
+    }
+
+    pub(crate) fn set_hartid(&mut self, hartid: u32) {
+        self.hartid = hartid;
+    }
+
+    /// Whether the machine timer interrupt is pending at `mtime`, regardless of whether it is
+    /// enabled.
+    pub fn timer_pending(&self, mtime: u64) -> bool {
+        mtime >= self.mtimecmp
+    }
+
+    /// Reads `csr`, or returns `None` if it doesn't exist.
+    pub fn read_csr(&self, csr: u32, mtime: u64) -> Option<u32> {
+        Some(match csr {
+            MSTATUS => self.mstatus,
+            MISA => MISA_VALUE,
+            MIE => self.mie,
+            MTVEC => self.mtvec,
+            MSCRATCH => self.mscratch,
+            MEPC => self.mepc,
+            MCAUSE => self.mcause,
+            MTVAL => self.mtval,
+            MIP if self.timer_pending(mtime) => MTIP,
+            MIP => 0,
+            MTIMECMP => self.mtimecmp as u32,
+            MTIMECMPH => (self.mtimecmp >> 32) as u32,
+            CYCLE | TIME | INSTRET => mtime as u32,
+            CYCLEH | TIMEH | INSTRETH => (mtime >> 32) as u32,
+            MHARTID => self.hartid,
+            _ => return None,
+        })
+    }
+
+    /// Writes `csr`, or returns `None` if it doesn't exist or is read-only. Unsupported bits
+    /// are ignored.
+    pub fn write_csr(&mut self, csr: u32, value: u32) -> Option<()> {
+        match csr {
+            MSTATUS => {
+                // Only user and machine mode are supported, other values of MPP are ignored.
+                let mpp = match value & MSTATUS_MPP {
+                    0 | MSTATUS_MPP => value & MSTATUS_MPP,
+                    _ => self.mstatus & MSTATUS_MPP,
+                };
+                self.mstatus = (value & (MSTATUS_MIE | MSTATUS_MPIE)) | mpp;
+            }
+            MISA => {}
+            MIE => self.mie = value & (MSIP | MTIP | MEIP),
+            // Direct and vectored modes only.
+            MTVEC => self.mtvec = value & !0b10,
+            MSCRATCH => self.mscratch = value,
+            MEPC => self.mepc = value & !0b11,
+            MCAUSE => self.mcause = value,
+            MTVAL => self.mtval = value,
+            // The timer interrupt is only cleared through `mtimecmp`.
+            MIP => {}
+            MTIMECMP => self.mtimecmp = (self.mtimecmp & !0xFFFF_FFFF) | value as u64,
+            MTIMECMPH => self.mtimecmp = (self.mtimecmp & 0xFFFF_FFFF) | (value as u64) << 32,
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// Executes a CSR instruction, returning the previous value of the CSR to write to `rd`,
+    /// or `None` if the access is illegal, including accesses to machine CSRs from user mode.
+    pub(crate) fn execute_csr(
+        &mut self,
+        instruction: &Instruction,
+        registers: &RegisterFile,
+        mtime: u64,
+    ) -> Option<u32> {
+        let csr = instruction.op_c;
+        if (csr >> 8) & 0b11 > self.privilege as u32 {
+            return None;
+        }
+
+        let fn3 = instruction.opcode.fn3().value();
+        // The immediate variants keep their 5-bit immediate in place of rs1.
+        let source = if fn3 & 0b100 != 0 {
+            instruction.op_b as u32
+        } else {
+            registers[instruction.op_b]
+        };
+
+        let old = self.read_csr(csr, mtime)?;
+        let new = match fn3 & 0b11 {
+            0b01 => Some(source),
+            // Setting or clearing no bits doesn't write, so read-only CSRs can be read.
+            _ if instruction.op_b as u32 == 0 => None,
+            0b10 => Some(old | source),
+            _ => Some(old & !source),
+        };
+        if let Some(new) = new {
+            self.write_csr(csr, new)?;
+        }
+
+        Some(old)
+    }
+
+    /// Takes the machine timer interrupt if it is pending and enabled, returning the address of
+    /// the trap handler. `pc` is where execution resumes after `mret`.
+    pub(crate) fn take_interrupt(&mut self, pc: u32, mtime: u64) -> Option<u32> {
+        // Machine interrupts are always enabled in user mode.
+        let enabled = self.privilege == Privilege::User || self.mstatus & MSTATUS_MIE != 0;
+        if !enabled || self.mie & MTIP == 0 || !self.timer_pending(mtime) {
+            return None;
+        }
+
+        Some(self.trap(INTERRUPT_FLAG | MACHINE_TIMER_INTERRUPT, pc, 0))
+    }
+
+    /// Takes the exception `cause` of the instruction at `pc`, returning the address of the
+    /// trap handler.
+    pub(crate) fn take_exception(&mut self, cause: u32, pc: u32, tval: u32) -> u32 {
+        self.trap(cause, pc, tval)
+    }
+
+    /// Enters the trap handler in machine mode, returning its address.
+    fn trap(&mut self, cause: u32, pc: u32, tval: u32) -> u32 {
+        self.mepc = pc;
+        self.mcause = cause;
+        self.mtval = tval;
+
+        self.mstatus = (self.mstatus & !MSTATUS_MPP) | (self.privilege as u32) << 11;
+        self.privilege = Privilege::Machine;
+
+        let mie = self.mstatus & MSTATUS_MIE != 0;
+        self.mstatus &= !(MSTATUS_MIE | MSTATUS_MPIE);
+        if mie {
+            self.mstatus |= MSTATUS_MPIE;
+        }
+
+        let base = self.mtvec & !0b11;
+        if self.mtvec & 0b01 != 0 && cause & INTERRUPT_FLAG != 0 {
+            base.wrapping_add(4 * (cause & !INTERRUPT_FLAG))
+        } else {
+            base
+        }
+    }
+
+    /// Returns from the trap handler to the privilege level in `mstatus.MPP`, returning the
+    /// address to resume execution at.
+    pub(crate) fn mret(&mut self) -> u32 {
+        let mpie = self.mstatus & MSTATUS_MPIE != 0;
+        self.privilege = if self.mstatus & MSTATUS_MPP == 0 {
+            Privilege::User
+        } else {
+            Privilege::Machine
+        };
+
+        self.mstatus = (self.mstatus & !(MSTATUS_MIE | MSTATUS_MPP)) | MSTATUS_MPIE;
+        if mpie {
+            self.mstatus |= MSTATUS_MIE;
+        }
+        self.mepc
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::emulator::{Emulator, EmulatorOptions, HarvardEmulator, UndefinedInstructionPolicy};
+    use crate::error::VMError;
+    use crate::riscv::{decode_instruction, BasicBlock, BuiltinOpcode, Opcode, Register};
+    use nexus_common::constants::ELF_TEXT_START;
+    use nexus_common::cpu::Registers;
+
+    fn csr(fn3: u32, rd: u32, rs1: u32, csr: u32) -> Instruction {
+        decode_instruction(csr << 20 | rs1 << 15 | fn3 << 12 | rd << 7 | SYSTEM_OPCODE as u32)
+    }
+
+    fn timer_program() -> Vec<BasicBlock> {
+        vec![BasicBlock::new(vec![
+            // Skip the trap handler.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 0, 0, 36),
+            // Trap handler: count the interrupt, disarm the timer and skip the spin loop.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 3, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 0xFFF),
+            csr(0b001, 0, 2, MTIMECMPH),
+            csr(0b010, 4, 0, MEPC),
+            csr(0b010, 5, 0, MCAUSE),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 6, 4, 4),
+            csr(0b001, 0, 6, MEPC),
+            decode_instruction(0x30200073), // mret
+            // Install the handler, arm the timer and enable its interrupt.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::AUIPC), 1, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 0xFE0), // -32
+            csr(0b001, 0, 1, MTVEC),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 50),
+            csr(0b001, 0, 2, MTIMECMP),
+            csr(0b001, 0, 0, MTIMECMPH),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, MTIP),
+            csr(0b010, 0, 2, MIE),
+            csr(0b110, 0, MSTATUS_MIE, MSTATUS),
+            // Spin until interrupted.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 0, 0, 0),
+        ])]
+    }
+
+    #[test]
+    fn test_csr_access() {
+        let mut machine = MachineState::new();
+        assert_eq!(machine.write_csr(MSTATUS, u32::MAX), Some(()));
+        assert_eq!(
+            machine.read_csr(MSTATUS, 0),
+            Some(MSTATUS_MIE | MSTATUS_MPIE | MSTATUS_MPP)
+        );
+        assert_eq!(machine.read_csr(TIMEH, 5 << 32), Some(5));
+        assert_eq!(machine.write_csr(TIME, 0), None);
+        assert_eq!(machine.read_csr(0x123, 0), None);
+
+        let mut registers = RegisterFile::default();
+        // Reading a read-only CSR is legal, writing it isn't.
+        assert_eq!(
+            machine.execute_csr(&csr(0b010, 1, 0, TIME), &registers, 42),
+            Some(42)
+        );
+        registers.write(Register::X1, 1);
+        assert_eq!(
+            machine.execute_csr(&csr(0b010, 0, 1, TIME), &registers, 42),
+            None
+        );
+        assert_eq!(
+            machine.execute_csr(&csr(0b111, 0, MSTATUS_MIE, MSTATUS), &registers, 0),
+            Some(MSTATUS_MIE | MSTATUS_MPIE | MSTATUS_MPP)
+        );
+        assert_eq!(
+            machine.read_csr(MSTATUS, 0),
+            Some(MSTATUS_MPIE | MSTATUS_MPP)
+        );
+    }
+
+    #[test]
+    fn test_timer_interrupt() {
+        let mut emulator = HarvardEmulator::from_basic_blocks(&timer_program());
+        emulator
+            .executor
+            .set_options(&EmulatorOptions {
+                machine_mode: true,
+                ..Default::default()
+            })
+            .unwrap();
+
+        assert_eq!(emulator.execute(false), Err(VMError::VMOutOfInstructions));
+        let registers = &emulator.executor.cpu.registers;
+        assert_eq!(registers[Register::X3], 1);
+        assert_eq!(registers[Register::X4], ELF_TEXT_START + 72);
+        assert_eq!(
+            registers[Register::X5],
+            INTERRUPT_FLAG | MACHINE_TIMER_INTERRUPT
+        );
+
+        let machine = emulator.executor.machine_state().unwrap();
+        assert_eq!(machine.mtimecmp(), 0xFFFF_FFFF_0000_0032);
+        assert_eq!(
+            machine.read_csr(MSTATUS, 0).unwrap() & MSTATUS_MIE,
+            MSTATUS_MIE
+        );
+    }
+
+    #[test]
+    fn test_trap_exceptions() {
+        let basic_blocks = vec![BasicBlock::new(vec![
+            // Skip the trap handler.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 0, 0, 32),
+            // Trap handler: count the exception and skip the faulting instruction.
+            csr(0b010, 5, 0, MCAUSE),
+            csr(0b010, 6, 0, MTVAL),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 7, 7, 1),
+            csr(0b010, 4, 0, MEPC),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 4, 4, 4),
+            csr(0b001, 0, 4, MEPC),
+            decode_instruction(0x30200073), // mret
+            // Install the handler.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::AUIPC), 1, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 0xFE4), // -28
+            csr(0b001, 0, 1, MTVEC),
+            // Misaligned load.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LW), 8, 0, 1),
+            // unimp
+            csr(0b001, 0, 0, CYCLE),
+            // Enter user mode at the next block.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::LUI), 9, 0, 2),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 9, 9, 0x800), // 0x1800
+            csr(0b011, 0, 9, MSTATUS),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::AUIPC), 10, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 10, 16),
+            csr(0b001, 0, 10, MEPC),
+            decode_instruction(0x30200073), // mret
+            // User mode.
+            decode_instruction(0x00000073), // ecall
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 11, 0, 1),
+        ])];
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator
+            .executor
+            .set_options(&EmulatorOptions {
+                machine_mode: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(
+            emulator.execute(false),
+            Err(VMError::MemoryError(MemoryError::UnalignedMemoryRead(1)))
+        );
+
+        let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+        emulator
+            .executor
+            .set_options(&EmulatorOptions {
+                trap_exceptions: true,
+                ..Default::default()
+            })
+            .unwrap();
+        assert_eq!(emulator.execute(false), Err(VMError::VMOutOfInstructions));
+
+        let registers = &emulator.executor.cpu.registers;
+        assert_eq!(registers[Register::X7], 3);
+        assert_eq!(registers[Register::X5], ECALL_FROM_USER);
+        assert_eq!(registers[Register::X11], 1);
+        let machine = emulator.executor.machine_state().unwrap();
+        assert_eq!(machine.privilege(), Privilege::User);
+    }
+
+    #[test]
+    fn test_undefined_instruction_policy() {
+        let basic_blocks = vec![BasicBlock::new(vec![
+            // Install the handler.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::AUIPC), 1, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 20),
+            csr(0b001, 0, 1, MTVEC),
+            Instruction::unimpl(),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 2, 0, 1),
+            // Trap handler.
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 3, 0, 1),
+        ])];
+        let run = |policy| {
+            let mut emulator = HarvardEmulator::from_basic_blocks(&basic_blocks);
+            emulator
+                .executor
+                .set_options(&EmulatorOptions {
+                    machine_mode: true,
+                    undefined_instructions: policy,
+                    ..Default::default()
+                })
+                .unwrap();
+            let result = emulator.execute(false);
+            (result, emulator)
+        };
+
+        let (result, _) = run(UndefinedInstructionPolicy::Fail);
+        assert_eq!(
+            result,
+            Err(VMError::UndecodableInstruction(
+                Instruction::unimpl().encode(),
+                ELF_TEXT_START + 12
+            ))
+        );
+
+        let (result, emulator) = run(UndefinedInstructionPolicy::Skip);
+        assert_eq!(result, Err(VMError::VMOutOfInstructions));
+        assert_eq!(emulator.executor.cpu.registers[Register::X2], 1);
+        assert_eq!(emulator.executor.cpu.registers[Register::X3], 1);
+
+        let (result, emulator) = run(UndefinedInstructionPolicy::Trap);
+        assert_eq!(result, Err(VMError::VMOutOfInstructions));
+        assert_eq!(emulator.executor.cpu.registers[Register::X2], 0);
+        assert_eq!(emulator.executor.cpu.registers[Register::X3], 1);
+        let machine = emulator.executor.machine_state().unwrap();
+        assert_eq!(machine.mcause(), ILLEGAL_INSTRUCTION);
+        assert_eq!(machine.mepc(), ELF_TEXT_START + 12);
+    }
+
+    #[test]
+    fn test_exception_cause() {
+        let unimp = csr(0b001, 0, 0, CYCLE);
+        let sw = Instruction::new_ir(Opcode::from(BuiltinOpcode::SW), 1, 2, 0);
+        let error = VMError::IllegalCsrAccess(0, CYCLE);
+        assert_eq!(
+            exception_cause(&error, &unimp),
+            Some((ILLEGAL_INSTRUCTION, 0xC0001073))
+        );
+
+        let error = VMError::MemoryError(MemoryError::InvalidMemoryAccess(0x10));
+        assert_eq!(
+            exception_cause(&error, &sw),
+            Some((STORE_ACCESS_FAULT, 0x10))
+        );
+        assert_eq!(exception_cause(&VMError::VMExited(0), &sw), None);
+    }
+
+    #[test]
+    fn test_without_machine_mode() {
+        let mut emulator = HarvardEmulator::from_basic_blocks(&timer_program());
+        assert!(matches!(
+            emulator.execute(false),
+            Err(VMError::UndefinedInstruction(_))
+        ));
+        assert!(emulator.executor.machine_state().is_none());
+    }
+}
@@ -128,6 +128,54 @@ impl MemoryStats {
     }
 }
 
+/// Heap usage of the guest, tracked from its stores so that it can be queried during execution.
+///
+/// The guest allocator never frees memory, so the highest heap address written is both the
+/// current usage and its high-water mark.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct HeapStats {
+    start: u32,
+    limit: u32,
+    high_water: u32,
+}
+
+impl HeapStats {
+    pub fn new(start: u32, limit: u32) -> Self {
+        Self {
+            start,
+            limit,
+            high_water: start,
+        }
+    }
+
+    /// Update the high-water mark with the stores below both the heap limit and the stack pointer.
+    pub fn update(&mut self, store_ops: &HashSet<StoreOp>, stack_pointer: u32) {
+        let limit = min(self.limit, stack_pointer);
+        for op in store_ops {
+            let address = op.get_address();
+            let end = address.saturating_add(op.get_size() as u32);
+            if address >= self.start && end <= limit {
+                self.high_water = max(self.high_water, end);
+            }
+        }
+    }
+
+    /// The address past the highest heap byte written.
+    pub fn high_water(&self) -> u32 {
+        self.high_water
+    }
+
+    /// Number of heap bytes in use.
+    pub fn used(&self) -> u32 {
+        self.high_water - self.start
+    }
+
+    /// Number of heap bytes left before reaching the heap limit or the stack.
+    pub fn remaining(&self, stack_pointer: u32) -> u32 {
+        min(self.limit, stack_pointer).saturating_sub(self.high_water)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -153,6 +201,28 @@ mod tests {
         assert_eq!(sizes.min_stack_access, 1000);
     }
 
+    #[test]
+    fn test_heap_stats() {
+        let mut stats = HeapStats::new(0x100, 0x1000);
+        let stack_pointer = 0x800;
+
+        let mut store_ops = HashSet::new();
+        store_ops.insert(StoreOp::Op(MemAccessSize::Word, 0x200, 0, 0));
+        store_ops.insert(StoreOp::Op(MemAccessSize::Byte, 0x80, 0, 0)); // below the heap
+        store_ops.insert(StoreOp::Op(MemAccessSize::Word, 0x900, 0, 0)); // on the stack
+        stats.update(&store_ops, stack_pointer);
+
+        assert_eq!(stats.high_water(), 0x204);
+        assert_eq!(stats.used(), 0x104);
+        assert_eq!(stats.remaining(stack_pointer), 0x5FC);
+        assert_eq!(stats.remaining(u32::MAX), 0xDFC);
+
+        // Lower stores don't decrease the high-water mark.
+        let store_ops = HashSet::from([StoreOp::Op(MemAccessSize::HalfWord, 0x100, 0, 0)]);
+        stats.update(&store_ops, stack_pointer);
+        assert_eq!(stats.used(), 0x104);
+    }
+
     #[test]
     fn test_create_optimized_layout() {
         let mut stats = MemoryStats::new(0, 1000000);
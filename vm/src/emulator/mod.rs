@@ -9,6 +9,8 @@
 //! - `HarvardEmulator`: An implementation of the emulator using Harvard architecture.
 //! - `LinearEmulator`: An implementation of the emulator using Linear architecture.
 //! - `LinearMemoryLayout`: Defines the memory layout for the linear emulator.
+//! - `EmulatorBuilder`: Configures and creates either kind of emulator.
+//! - `ExecutionOutcome`: Exit code, output and cycle count of a run, see `Emulator::run`.
 //!
 //! ## Memory Management
 //!
@@ -52,13 +54,47 @@
 //! supporting both Harvard and Linear architectures (unified memory from Harvard architecture
 //! with a single memory space, with added read and write protection), and offering detailed
 //! visibility into the emulator's state and execution results.
+mod allocator;
+mod assertions;
+mod audit;
+mod block_cache;
+mod block_profile;
+mod branch_stats;
+mod builder;
+mod debugger;
 mod executor;
+mod harts;
+mod hooks;
+mod instruction_stats;
 mod layout;
+mod machine;
 mod memory_stats;
+mod options;
+mod outcome;
 mod registry;
+mod symbol_profile;
+mod transcript;
 
-pub use executor::{Emulator, Executor, HarvardEmulator, LinearEmulator};
-pub use layout::LinearMemoryLayout;
+pub use allocator::AllocatorStats;
+pub(crate) use allocator::HeapAllocator;
+pub use assertions::{AssertionKind, MemorySafetyViolation};
+pub use audit::{AuditPolicy, PermissionAudit, PermissionViolation, ViolationKind};
+pub use block_profile::BlockProfile;
+pub use branch_stats::{BranchCounts, BranchStats};
+pub use builder::{EmulatorBuilder, EmulatorKind};
+pub use debugger::WatchKind;
+pub use executor::{
+    Emulator, EmulatorSnapshot, Executor, HarvardEmulator, LinearEmulator, CALL_RETURN_ADDRESS,
+};
+pub use hooks::{InstructionHook, SyscallHook};
+pub use instruction_stats::ExecutionStats;
+pub use layout::{LayoutConfig, LayoutOffsets, LinearMemoryLayout, LinearMemoryLayoutBuilder};
+pub use machine::{exception_cause, MachineState, Privilege};
+pub use memory_stats::HeapStats;
+pub use options::{EmulatorOptions, StdoutHandler, UndefinedInstructionPolicy};
+pub use outcome::{ExecutionOutcome, PartialExecution, StepOutcome};
+pub use symbol_profile::{FunctionCycles, ProfileReport, UNKNOWN_FUNCTION};
+pub use transcript::CompressedTranscript;
 
 mod utils;
 pub use utils::*;
@@ -0,0 +1,310 @@
+//! # Emulator Options
+//!
+//! Runtime diagnostics that are off by default, because they slow down execution of large guests.
+
+use std::{
+    fs::File,
+    io::{BufWriter, Write},
+    path::PathBuf,
+    sync::Arc,
+};
+
+use nexus_common::memory::{MemoryRecord, MemoryRecords};
+use serde::Serialize;
+
+use super::AuditPolicy;
+use crate::{
+    elf::SourceLocation,
+    riscv::{BasicBlock, Instruction},
+};
+
+/// What to do when an instruction the VM doesn't define is executed, e.g. a hint of an extension
+/// the guest was built with.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UndefinedInstructionPolicy {
+    /// Stop the execution with the original error.
+    #[default]
+    Fail,
+    /// Log a warning and continue with the next instruction. Programs skipping instructions can't
+    /// be proven.
+    Skip,
+    /// Deliver an illegal instruction exception to the trap handler of the guest, even if other
+    /// faults aren't trapped. Implies `machine_mode`.
+    Trap,
+}
+
+/// Receives everything the guest writes to stdout, see [`EmulatorOptions::stdout_handler`].
+#[derive(Clone)]
+pub struct StdoutHandler(Arc<StdoutFn>);
+
+type StdoutFn = dyn Fn(&[u8]) + Send + Sync;
+
+impl StdoutHandler {
+    pub fn new(handler: impl Fn(&[u8]) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(handler))
+    }
+
+    pub(crate) fn write(&self, bytes: &[u8]) {
+        (self.0)(bytes)
+    }
+}
+
+impl std::fmt::Debug for StdoutHandler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("StdoutHandler")
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct EmulatorOptions {
+    /// Disassemble every executed basic block as `trace` events under the `nexus_vm::emulator` target.
+    pub trace_blocks: bool,
+
+    /// Additionally write the disassembly of executed basic blocks to this file.
+    pub trace_blocks_file: Option<PathBuf>,
+
+    /// Stream every executed instruction as one JSON object per line to this file, see
+    /// [`super::Executor::trace_jsonl_to`] for the format.
+    pub trace_jsonl_file: Option<PathBuf>,
+
+    /// Record permission violations instead of failing on the first one, see [`super::PermissionAudit`].
+    pub permission_audit: Option<AuditPolicy>,
+
+    /// Let stores into the instruction segment patch the program instead of failing with
+    /// `VMError::SelfModifyingCode`. Programs modifying themselves can't be proven.
+    pub allow_self_modifying_code: bool,
+
+    /// Emulate machine-mode CSRs, the machine timer and its interrupt, see [`super::MachineState`].
+    /// Programs using them can't be proven.
+    pub machine_mode: bool,
+
+    /// Deliver faults to the trap handler of the guest at `mtvec` instead of stopping the
+    /// execution, see [`super::MachineState`]. Implies `machine_mode`.
+    pub trap_exceptions: bool,
+
+    /// Serve requests of the program to the host through its `tohost` symbol, like riscv-tests
+    /// do, see [`crate::system::ToHost`].
+    pub tohost: bool,
+
+    /// What to do when executing an instruction the VM doesn't define.
+    pub undefined_instructions: UndefinedInstructionPolicy,
+
+    /// Pass what the guest writes to stdout to this handler instead of printing it. Captured logs,
+    /// see [`super::EmulatorBuilder::capture_logs`], are collected either way. Neither is part of
+    /// the public output of the program.
+    pub stdout_handler: Option<StdoutHandler>,
+
+    /// Check memory-safety invariants after every instruction and stop at the first violation,
+    /// e.g. when fuzzing. Only the linear emulator performs the checks, see
+    /// [`super::MemorySafetyViolation`].
+    pub memory_assertions: bool,
+
+    /// Count how often each conditional branch is taken, see [`super::Executor::branch_stats`].
+    pub branch_stats: bool,
+
+    /// Count the executed instructions by opcode and the memory accesses, see
+    /// [`super::Executor::stats`]. Also counts the outcomes of branches like [`Self::branch_stats`].
+    pub stats: bool,
+
+    /// Count the cycles spent in each basic block, see [`super::Executor::hot_block_report`].
+    pub block_profile: bool,
+
+    /// Count the cycles spent in each function of the ELF symbol table, see
+    /// [`super::Executor::profile_report`].
+    pub symbol_profile: bool,
+
+    /// Test mode: start the heap and the stack of the linear emulator at offsets into their segments
+    /// derived from this seed, see [`super::LayoutOffsets`]. Running a guest under several seeds
+    /// flushes out dependencies on absolute addresses. Executions are deterministic for a given seed,
+    /// and without one.
+    pub randomize_layout: Option<u64>,
+
+    /// Number of harts to execute, experimental. Harts other than the first one can't be proven,
+    /// see [`super::Executor::hart_id`].
+    pub harts: usize,
+}
+
+/// Active block tracing configuration of an executor.
+#[derive(Default)]
+pub(crate) struct BlockTracer {
+    events: bool,
+    file: Option<BufWriter<File>>,
+}
+
+impl std::fmt::Debug for BlockTracer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlockTracer")
+            .field("events", &self.events)
+            .field("file", &self.file.is_some())
+            .finish()
+    }
+}
+
+impl BlockTracer {
+    pub(crate) fn new(options: &EmulatorOptions) -> std::io::Result<Self> {
+        let file = options
+            .trace_blocks_file
+            .as_ref()
+            .map(|path| File::create(path).map(BufWriter::new))
+            .transpose()?;
+
+        Ok(Self {
+            events: options.trace_blocks,
+            file,
+        })
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.file.is_some()
+            || (self.events
+                && tracing::enabled!(target: "nexus_vm::emulator", tracing::Level::TRACE))
+    }
+
+    /// Records the instructions of `block` executed starting from `pc`, and their source location
+    /// if the program has debug info.
+    pub(crate) fn trace(
+        &mut self,
+        block: &BasicBlock,
+        pc: usize,
+        location: Option<&SourceLocation>,
+    ) {
+        if self.events {
+            if let Some(location) = location {
+                tracing::trace!(target: "nexus_vm::emulator", "at {location}");
+            }
+            block.trace_with_offset(pc);
+        }
+
+        if let Some(file) = self.file.as_mut() {
+            let written = match location {
+                Some(location) => writeln!(file, "at {location}"),
+                None => Ok(()),
+            };
+            if let Err(e) = written.and_then(|()| block.write_with_offset(file, pc)) {
+                tracing::warn!(
+                    target: "nexus_vm::emulator",
+                    "failed to write block trace, disabling it: {e}"
+                );
+                self.file = None;
+            }
+        }
+    }
+}
+
+/// A memory access of a [`JsonlStep`].
+#[derive(Serialize)]
+struct JsonlMemoryOp {
+    store: bool,
+    size: u32,
+    address: u32,
+    value: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    prev_value: Option<u32>,
+}
+
+impl From<&MemoryRecord> for JsonlMemoryOp {
+    fn from(record: &MemoryRecord) -> Self {
+        Self {
+            store: record.get_prev_value().is_some(),
+            size: record.get_size() as u32,
+            address: record.get_address(),
+            value: record.get_value(),
+            prev_value: record.get_prev_value(),
+        }
+    }
+}
+
+/// One line of the JSONL trace.
+#[derive(Serialize)]
+struct JsonlStep<'a> {
+    clock: usize,
+    pc: u32,
+    opcode: &'a str,
+    op_a: u8,
+    op_b: u8,
+    op_c: u32,
+    result: Option<u32>,
+    memory: Vec<JsonlMemoryOp>,
+}
+
+/// Writer of the JSONL trace of an executor, if enabled.
+#[derive(Default)]
+pub(crate) struct JsonlTracer {
+    writer: Option<Box<dyn Write + Send>>,
+}
+
+impl std::fmt::Debug for JsonlTracer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsonlTracer")
+            .field("writer", &self.writer.is_some())
+            .finish()
+    }
+}
+
+impl JsonlTracer {
+    pub(crate) fn new(options: &EmulatorOptions) -> std::io::Result<Self> {
+        let writer = options
+            .trace_jsonl_file
+            .as_ref()
+            .map(|path| File::create(path).map(BufWriter::new))
+            .transpose()?
+            .map(|writer| Box::new(writer) as Box<dyn Write + Send>);
+
+        Ok(Self { writer })
+    }
+
+    pub(crate) fn to_writer(writer: impl Write + Send + 'static) -> Self {
+        Self {
+            writer: Some(Box::new(writer)),
+        }
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.writer.is_some()
+    }
+
+    /// Records the execution of `instruction` at `pc`.
+    pub(crate) fn trace(
+        &mut self,
+        clock: usize,
+        pc: u32,
+        instruction: &Instruction,
+        result: Option<u32>,
+        records: &MemoryRecords,
+    ) {
+        let Some(writer) = self.writer.as_mut() else {
+            return;
+        };
+
+        let mut memory: Vec<JsonlMemoryOp> = records.iter().map(JsonlMemoryOp::from).collect();
+        memory.sort_by_key(|op| (op.address, op.store));
+        let step = JsonlStep {
+            clock,
+            pc,
+            opcode: instruction.opcode.name(),
+            op_a: instruction.op_a as u8,
+            op_b: instruction.op_b as u8,
+            op_c: instruction.op_c,
+            result,
+            memory,
+        };
+
+        let written = serde_json::to_writer(&mut *writer, &step)
+            .map_err(std::io::Error::from)
+            .and_then(|_| writer.write_all(b"\n"));
+        if let Err(e) = written {
+            tracing::warn!(
+                target: "nexus_vm::emulator",
+                "failed to write JSONL trace, disabling it: {e}"
+            );
+            self.writer = None;
+        }
+    }
+
+    /// Flushes buffered lines, e.g. once execution stopped.
+    pub(crate) fn flush(&mut self) {
+        if let Some(writer) = self.writer.as_mut() {
+            let _ = writer.flush();
+        }
+    }
+}
@@ -0,0 +1,124 @@
+use nexus_common::abi::{EXIT_PANIC, EXIT_SUCCESS};
+
+use super::{CompressedTranscript, InternalView, View};
+use crate::{cpu::instructions::InstructionResult, error::VMError, riscv::Register, trace::Step};
+
+/// Result of an execution that ran to its exit syscall, see [`super::Emulator::run`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionOutcome {
+    /// Exit code passed to the exit syscall, 0 unless the guest panicked or exited with an error.
+    pub exit_code: u32,
+    /// Raw bytes of the public output, excluding the exit code.
+    pub public_output: Vec<u8>,
+    /// Number of instructions executed.
+    pub cycles: u64,
+    /// Message of the panic of the guest, which `nexus_rt` logs at the error level before exiting.
+    pub panic_message: Option<String>,
+}
+
+impl ExecutionOutcome {
+    pub(crate) fn new(
+        exit_code: u32,
+        view: &View,
+        cycles: u64,
+        last_error_log: Option<&str>,
+    ) -> Self {
+        let panic_message = if exit_code == EXIT_PANIC {
+            last_error_log.map(String::from)
+        } else {
+            None
+        };
+        Self {
+            exit_code,
+            public_output: view
+                .get_public_output()
+                .iter()
+                .map(|entry| entry.value)
+                .collect(),
+            cycles,
+            panic_message,
+        }
+    }
+
+    /// Whether the guest exited successfully.
+    pub fn is_success(&self) -> bool {
+        self.exit_code == EXIT_SUCCESS
+    }
+}
+
+/// Instructions executed by [`super::Emulator::execute_partial`] until execution stopped.
+#[derive(Debug)]
+pub struct PartialExecution {
+    /// Why execution stopped, [`VMError::VMExited`] if the program exited.
+    pub error: VMError,
+    /// Results of the executed instructions.
+    pub results: Vec<InstructionResult>,
+    /// Memory records of the executed instructions, compressed as they are executed to bound
+    /// the memory used by long executions.
+    pub transcript: CompressedTranscript,
+}
+
+/// A single instruction executed by [`super::Emulator::step`].
+#[derive(Debug, Clone)]
+pub struct StepOutcome {
+    /// The instruction, its result and memory records, as in a trace.
+    pub step: Step,
+    /// Registers changed by the instruction, with their new values.
+    pub register_writes: Vec<(Register, u32)>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        elf::ElfFile,
+        emulator::{
+            Emulator, HarvardEmulator, IOEntry, LinearMemoryLayout, ProgramInfo, PublicOutputEntry,
+        },
+    };
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_run_to_exit() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let mut emulator = HarvardEmulator::from_elf(&elf_file, &[], &[]);
+
+        let outcome = emulator.run().unwrap();
+        assert!(outcome.is_success());
+        assert_eq!(outcome.cycles, emulator.executor.cycles());
+        assert!(outcome.cycles > 0);
+        assert_eq!(outcome.panic_message, None);
+    }
+
+    #[test]
+    fn test_panic_message() {
+        let layout = LinearMemoryLayout::default();
+        let output = vec![PublicOutputEntry::new_from_offset(
+            layout.public_output_start(),
+            0,
+            7,
+        )];
+        let view = View::new(
+            &Some(layout),
+            &vec![],
+            &ProgramInfo::dummy(),
+            &vec![],
+            0,
+            &vec![],
+            &output,
+            &vec![],
+        );
+
+        let message = Some("attempt to divide by zero");
+        let outcome = ExecutionOutcome::new(EXIT_PANIC, &view, 10, message);
+        assert!(!outcome.is_success());
+        // The output written before the panic is left as is.
+        assert_eq!(outcome.public_output, [7]);
+        assert_eq!(outcome.panic_message.as_deref(), message);
+
+        // Errors logged by a successful run are never taken as a panic message.
+        let outcome = ExecutionOutcome::new(EXIT_SUCCESS, &view, 10, message);
+        assert_eq!(outcome.panic_message, None);
+    }
+}
@@ -27,10 +27,16 @@
 //! - `rin` is interpreted as `lw` (load word)
 //! - `wou` is interpreted as `sw` (store word)
 //!
+//! Their byte and half-word variants share the opcode and select the width with funct3, which is
+//! the access size in bytes for the sub-word variants and zero for the original word variants:
+//!
+//! - `rinb` (funct3 = 1) and `rinh` (funct3 = 2) are interpreted as `lb` and `lh`
+//! - `woub` (funct3 = 1) and `wouh` (funct3 = 2) are interpreted as `sb` and `sh`
+//!
 //! ## Error Handling
 //!
 //! The registry provides error handling for:
-//! - Duplicate instructions
+//! - Duplicate instructions (use `override_opcode` to replace an executor on purpose)
 //! - Unimplemented instructions
 //! - Undefined instructions
 //!
@@ -47,13 +53,14 @@
 //!   - A `HashMap` `precompiles` for custom instructions.
 //!   - Special `Opcode`s for read input and write output operations.
 //! - The `add_opcode` method allows adding custom instructions at runtime.
+//! - The `override_opcode` method allows shadowing the executor of any instruction, builtins included.
 //! - The `get` method retrieves the execution function for a given opcode.
 //! - Special methods `get_for_read_input` and `get_for_write_output` handle the custom I/O instructions.
 //!
 //! This registry is crucial for the emulator's operation, providing a flexible and
 //! efficient way to map opcodes to their execution functions, including support for
 //! custom and special instructions.
-use nexus_common::{cpu::InstructionExecutor, error::MemoryError};
+use nexus_common::{cpu::InstructionExecutor, error::MemoryError, memory::MemAccessSize};
 
 use crate::memory::MemoryProcessor;
 use crate::{
@@ -234,11 +241,39 @@ impl Default for InstructionExecutorRegistry {
 }
 
 impl InstructionExecutorRegistry {
+    /// Registers the executor of a custom instruction. Fails if the opcode already has one, which
+    /// includes all builtin opcodes.
     pub fn add_opcode<IE: InstructionExecutor>(&mut self, op: &Opcode) -> Result<(), VMError> {
+        if TryInto::<BuiltinOpcode>::try_into(op.clone()).is_ok()
+            || self.precompiles.contains_key(op)
+        {
+            return Err(VMError::DuplicateInstruction(op.clone()));
+        }
+
         self.precompiles
-            .insert(op.clone(), register_instruction_executor!(IE::evaluator))
-            .ok_or(VMError::DuplicateInstruction(op.clone()))
-            .map(|_| ())
+            .insert(op.clone(), register_instruction_executor!(IE::evaluator));
+        Ok(())
+    }
+
+    /// Registers the executor of an instruction, replacing the current one if there is any. Unlike
+    /// [`Self::add_opcode`], this can shadow builtin instructions, e.g. for fault injection or
+    /// instrumented semantics. System instructions and the `rin` and `wou` instructions are
+    /// dispatched before the registry, so they can't be overridden.
+    ///
+    /// The prover constrains the builtin semantics, so traces of programs executed with an
+    /// overridden builtin can't be proven.
+    ///
+    /// Returns the replaced executor.
+    pub fn override_opcode<IE: InstructionExecutor>(
+        &mut self,
+        op: &Opcode,
+    ) -> Option<InstructionExecutorFn<UnifiedMemory>> {
+        let func = register_instruction_executor!(IE::evaluator);
+        if let Ok(opcode) = TryInto::<BuiltinOpcode>::try_into(op.clone()) {
+            self.builtins[opcode as usize].replace(func)
+        } else {
+            self.precompiles.insert(op.clone(), func)
+        }
     }
 
     pub fn get(&self, op: &Opcode) -> Result<InstructionExecutorFn<UnifiedMemory>> {
@@ -262,35 +297,63 @@ impl InstructionExecutorRegistry {
         &self,
         op: &Opcode,
     ) -> Option<InstructionExecutorFn<M>> {
-        // Opcode will be parsed dynamically so the name will be different.
-        if self.is_read_input(op) {
-            // Interpret `rin` as `lw`.
-            return Some(instructions::LwInstruction::evaluator as InstructionExecutorFn<M>);
-        }
-
-        None
+        // Interpret `rin`, `rinh` and `rinb` as `lw`, `lh` and `lb`.
+        Some(match self.read_input_size(op)? {
+            MemAccessSize::Word => {
+                instructions::LwInstruction::evaluator as InstructionExecutorFn<M>
+            }
+            MemAccessSize::HalfWord => instructions::LhInstruction::evaluator,
+            MemAccessSize::Byte => instructions::LbInstruction::evaluator,
+        })
     }
 
     pub fn get_for_write_output<M: MemoryProcessor>(
         &self,
         op: &Opcode,
     ) -> Option<InstructionExecutorFn<M>> {
-        // Opcode will be parsed dynamically so the name will be different.
-        if self.is_write_output(op) {
-            // Interpret `wou` as `sw`.
-            return Some(instructions::SwInstruction::evaluator as InstructionExecutorFn<M>);
-        }
-
-        None
+        // Interpret `wou`, `wouh` and `woub` as `sw`, `sh` and `sb`.
+        Some(match self.write_output_size(op)? {
+            MemAccessSize::Word => {
+                instructions::SwInstruction::evaluator as InstructionExecutorFn<M>
+            }
+            MemAccessSize::HalfWord => instructions::ShInstruction::evaluator,
+            MemAccessSize::Byte => instructions::SbInstruction::evaluator,
+        })
     }
 
     #[inline(always)]
     pub fn is_read_input(&self, op: &Opcode) -> bool {
-        op.raw() == self.read_input.raw() && op.fn3() == self.read_input.fn3()
+        self.read_input_size(op).is_some()
     }
 
     #[inline(always)]
     pub fn is_write_output(&self, op: &Opcode) -> bool {
-        op.raw() == self.write_output.raw() && op.fn3() == self.write_output.fn3()
+        self.write_output_size(op).is_some()
+    }
+
+    /// Width of the public input read by `op`, if it's `rin` or one of its sub-word variants.
+    #[inline(always)]
+    pub fn read_input_size(&self, op: &Opcode) -> Option<MemAccessSize> {
+        // Opcode will be parsed dynamically so the name will be different.
+        Self::io_access_size(&self.read_input, op)
+    }
+
+    /// Width of the public output written by `op`, if it's `wou` or one of its sub-word variants.
+    #[inline(always)]
+    pub fn write_output_size(&self, op: &Opcode) -> Option<MemAccessSize> {
+        Self::io_access_size(&self.write_output, op)
+    }
+
+    #[inline(always)]
+    fn io_access_size(io: &Opcode, op: &Opcode) -> Option<MemAccessSize> {
+        if op.raw() != io.raw() || !op.fn3().is_set() {
+            return None;
+        }
+        match op.fn3().value() {
+            0b000 => Some(MemAccessSize::Word),
+            0b001 => Some(MemAccessSize::Byte),
+            0b010 => Some(MemAccessSize::HalfWord),
+            _ => None,
+        }
     }
 }
@@ -0,0 +1,216 @@
+//! # Cycles per Function
+//!
+//! Attributes the cycles of the guest to the functions of the ELF symbol table, following calls and
+//! returns to reconstruct call stacks. Calls are the jumps linking through `ra` and returns are
+//! `jalr zero, 0(ra)`, so a tail call replaces the function it leaves in the stack. See
+//! [`super::Executor::profile_report`].
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+use crate::{
+    elf::{find_symbol, Symbol},
+    riscv::{BuiltinOpcode, Instruction, Register},
+};
+
+/// Name of the frames of code outside of any symbol.
+pub const UNKNOWN_FUNCTION: &str = "<unknown>";
+
+/// Cycles by call stack, functions being indices into the symbols of the executor.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct SymbolProfiler {
+    // Functions that made a call still pending a return, outermost first
+    callers: Vec<Option<usize>>,
+    stacks: HashMap<Vec<Option<usize>>, u64>,
+}
+
+impl SymbolProfiler {
+    /// Counts a cycle of `instruction` at `pc`, once it's executed.
+    pub(crate) fn record(&mut self, symbols: &[Symbol], pc: u32, instruction: &Instruction) {
+        let function = find_symbol(symbols, pc);
+        self.callers.push(function);
+        match self.stacks.get_mut(self.callers.as_slice()) {
+            Some(cycles) => *cycles += 1,
+            None => {
+                self.stacks.insert(self.callers.clone(), 1);
+            }
+        }
+        self.callers.pop();
+
+        match instruction.opcode.builtin() {
+            Some(BuiltinOpcode::JAL | BuiltinOpcode::JALR) if instruction.op_a == Register::X1 => {
+                self.callers.push(function)
+            }
+            Some(BuiltinOpcode::JALR)
+                if instruction.op_a == Register::X0 && instruction.op_b == Register::X1 =>
+            {
+                self.callers.pop();
+            }
+            _ => {}
+        }
+    }
+
+    pub(crate) fn report(&self, symbols: &[Symbol]) -> ProfileReport {
+        let name = |function: &Option<usize>| match function {
+            Some(index) => symbols[*index].name.clone(),
+            None => UNKNOWN_FUNCTION.to_string(),
+        };
+        let mut stacks = BTreeMap::new();
+        for (stack, &cycles) in &self.stacks {
+            // Distinct symbols may share a name, e.g. local functions of different modules.
+            *stacks
+                .entry(stack.iter().map(name).collect::<Vec<_>>())
+                .or_default() += cycles;
+        }
+        ProfileReport { stacks }
+    }
+}
+
+/// Cycles spent in a function, see [`ProfileReport::functions`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionCycles {
+    pub name: String,
+    /// Cycles executing the function itself.
+    pub self_cycles: u64,
+    /// Cycles executing the function and the functions it called.
+    pub total_cycles: u64,
+}
+
+/// Cycles of the guest attributed to the functions of the ELF symbol table.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ProfileReport {
+    stacks: BTreeMap<Vec<String>, u64>,
+}
+
+impl ProfileReport {
+    /// Cycles by call stack, outermost function first, ordered by stack.
+    pub fn stacks(&self) -> impl Iterator<Item = (&[String], u64)> + '_ {
+        self.stacks
+            .iter()
+            .map(|(stack, &cycles)| (stack.as_slice(), cycles))
+    }
+
+    /// Cycles executed in all functions.
+    pub fn total_cycles(&self) -> u64 {
+        self.stacks.values().sum()
+    }
+
+    /// Cycles of each function, taking the most cycles itself first. A recursive function counts
+    /// the cycles of a stack once.
+    pub fn functions(&self) -> Vec<FunctionCycles> {
+        let mut functions: BTreeMap<&str, (u64, u64)> = BTreeMap::new();
+        for (stack, &cycles) in &self.stacks {
+            let Some((leaf, _)) = stack.split_last() else {
+                continue;
+            };
+            functions.entry(leaf).or_default().0 += cycles;
+
+            let mut seen: Vec<&str> = Vec::new();
+            for name in stack {
+                if !seen.contains(&name.as_str()) {
+                    seen.push(name);
+                    functions.entry(name).or_default().1 += cycles;
+                }
+            }
+        }
+
+        let mut functions: Vec<FunctionCycles> = functions
+            .into_iter()
+            .map(|(name, (self_cycles, total_cycles))| FunctionCycles {
+                name: name.to_string(),
+                self_cycles,
+                total_cycles,
+            })
+            .collect();
+        functions.sort_by_key(|function| std::cmp::Reverse(function.self_cycles));
+        functions
+    }
+
+    /// The stacks in the folded format consumed by flamegraph.pl and inferno, one
+    /// `outer;inner cycles` line per stack.
+    pub fn folded(&self) -> String {
+        self.stacks()
+            .map(|(stack, cycles)| format!("{} {cycles}\n", stack.join(";")))
+            .collect()
+    }
+}
+
+impl Display for ProfileReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let total = self.total_cycles().max(1);
+        writeln!(f, "{:>12} {:>12} {:>7}  function", "self", "total", "share")?;
+        for function in self.functions() {
+            let share = function.self_cycles as f64 * 100.0 / total as f64;
+            writeln!(
+                f,
+                "{:>12} {:>12} {share:>6.2}%  {}",
+                function.self_cycles, function.total_cycles, function.name
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::riscv::Opcode;
+
+    #[test]
+    fn test_call_stacks() {
+        let symbols = [
+            Symbol {
+                name: "main".to_string(),
+                address: 0x1000,
+                size: 0x10,
+            },
+            Symbol {
+                name: "fib".to_string(),
+                address: 0x1010,
+                size: 0x10,
+            },
+        ];
+        let addi = Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 1, 1, 1);
+        let call = Instruction::new_ir(Opcode::from(BuiltinOpcode::JAL), 1, 0, 0);
+        let ret = Instruction::new_ir(Opcode::from(BuiltinOpcode::JALR), 0, 1, 0);
+
+        let mut profiler = SymbolProfiler::default();
+        profiler.record(&symbols, 0x1000, &addi);
+        profiler.record(&symbols, 0x1004, &call);
+        // fib calls itself once.
+        profiler.record(&symbols, 0x1010, &call);
+        profiler.record(&symbols, 0x1010, &addi);
+        profiler.record(&symbols, 0x1014, &ret);
+        profiler.record(&symbols, 0x1018, &ret);
+        profiler.record(&symbols, 0x1008, &addi);
+        profiler.record(&symbols, 0x2000, &addi);
+
+        let report = profiler.report(&symbols);
+        assert_eq!(report.total_cycles(), 8);
+        assert_eq!(
+            report.folded(),
+            "<unknown> 1\nmain 3\nmain;fib 2\nmain;fib;fib 2\n"
+        );
+
+        let functions = report.functions();
+        assert_eq!(functions.len(), 3);
+        assert_eq!(
+            functions[0],
+            FunctionCycles {
+                name: "fib".to_string(),
+                self_cycles: 4,
+                total_cycles: 4,
+            }
+        );
+        assert_eq!(
+            (functions[1].self_cycles, functions[1].total_cycles),
+            (3, 7)
+        );
+
+        let table = report.to_string();
+        assert_eq!(table.lines().count(), 4);
+        assert!(table.lines().nth(1).unwrap().ends_with("50.00%  fib"));
+    }
+}
@@ -0,0 +1,260 @@
+//! # Compressed Memory Transcripts
+//!
+//! A [`MemoryTranscript`] keeps a full `MemoryRecords` set for every executed instruction. Long
+//! executions mostly touch memory in regular patterns (copies, stack spills, sequential reads of
+//! the input), so [`CompressedTranscript`] stores records as runs with constant address and
+//! timestamp strides, and the number of records per step run-length encoded. Only the values,
+//! which can't be predicted, are stored per record.
+//!
+//! Steps are expanded back into `MemoryRecords` lazily, see [`CompressedTranscript::iter`].
+//! [`super::Emulator::execute_partial`] compresses the transcript of an execution block by block,
+//! so that the records of the whole execution are never held uncompressed.
+
+use nexus_common::memory::{MemAccessSize, MemoryRecord, MemoryRecords};
+use serde::{Deserialize, Serialize};
+
+use super::MemoryTranscript;
+
+/// Records of the same kind and size, at addresses and timestamps in arithmetic progressions.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+struct Run {
+    store: bool,
+    size: MemAccessSize,
+    address: u32,
+    address_stride: u32,
+    timestamp: u32,
+    timestamp_stride: u32,
+    len: u32,
+}
+
+impl Run {
+    fn new(record: &MemoryRecord) -> Self {
+        Self {
+            store: record.get_prev_value().is_some(),
+            size: record.get_size(),
+            address: record.get_address(),
+            address_stride: 0,
+            timestamp: record.get_timestamp(),
+            timestamp_stride: 0,
+            len: 1,
+        }
+    }
+
+    /// Extends the run by `record` if it continues the progression.
+    fn try_extend(&mut self, record: &MemoryRecord) -> bool {
+        if self.store != record.get_prev_value().is_some() || self.size != record.get_size() {
+            return false;
+        }
+
+        let address = record.get_address();
+        let timestamp = record.get_timestamp();
+        if self.len == 1 {
+            self.address_stride = address.wrapping_sub(self.address);
+            self.timestamp_stride = timestamp.wrapping_sub(self.timestamp);
+        } else if address != self.address_at(self.len) || timestamp != self.timestamp_at(self.len) {
+            return false;
+        }
+
+        self.len += 1;
+        true
+    }
+
+    fn address_at(&self, i: u32) -> u32 {
+        self.address
+            .wrapping_add(self.address_stride.wrapping_mul(i))
+    }
+
+    fn timestamp_at(&self, i: u32) -> u32 {
+        self.timestamp
+            .wrapping_add(self.timestamp_stride.wrapping_mul(i))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompressedTranscript {
+    // (records per step, number of consecutive steps with that many records)
+    step_lens: Vec<(u32, u32)>,
+    runs: Vec<Run>,
+    // value, followed by the previous value for stores, of every record in order
+    values: Vec<u32>,
+}
+
+impl CompressedTranscript {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Appends the records of the next step.
+    pub fn push(&mut self, records: &MemoryRecords) {
+        let len = records.len() as u32;
+        match self.step_lens.last_mut() {
+            Some((last_len, repeat)) if *last_len == len => *repeat += 1,
+            _ => self.step_lens.push((len, 1)),
+        }
+
+        // Order within a step is irrelevant, sort it to make the encoding deterministic.
+        let mut records: Vec<&MemoryRecord> = records.iter().collect();
+        records.sort_by_key(|record| (record.get_timestamp(), record.get_address()));
+
+        for record in records {
+            if !self
+                .runs
+                .last_mut()
+                .is_some_and(|run| run.try_extend(record))
+            {
+                self.runs.push(Run::new(record));
+            }
+
+            self.values.push(record.get_value());
+            if let Some(prev_value) = record.get_prev_value() {
+                self.values.push(prev_value);
+            }
+        }
+    }
+
+    /// Number of steps in the transcript.
+    pub fn len(&self) -> usize {
+        self.step_lens
+            .iter()
+            .map(|(_, repeat)| *repeat as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.step_lens.is_empty()
+    }
+
+    /// Number of runs the records are compressed into, a measure of the compression ratio.
+    pub fn num_runs(&self) -> usize {
+        self.runs.len()
+    }
+
+    /// Lazily expands the records of every step.
+    pub fn iter(&self) -> impl Iterator<Item = MemoryRecords> + '_ {
+        let mut values = self.values.iter().copied();
+        let mut records = self
+            .runs
+            .iter()
+            .flat_map(|run| (0..run.len).map(move |i| (run, i)))
+            .map(move |(run, i)| {
+                let address = run.address_at(i);
+                let timestamp = run.timestamp_at(i);
+                let value = values.next().expect("value of every record is stored");
+                if run.store {
+                    let prev_value = values.next().expect("value of every record is stored");
+                    MemoryRecord::StoreRecord((run.size, address, value, prev_value), timestamp)
+                } else {
+                    MemoryRecord::LoadRecord((run.size, address, value), timestamp)
+                }
+            });
+
+        self.step_lens
+            .iter()
+            .flat_map(|&(len, repeat)| std::iter::repeat_n(len, repeat as usize))
+            .map(move |len| records.by_ref().take(len as usize).collect())
+    }
+
+    /// Expands the whole transcript.
+    pub fn expand(&self) -> MemoryTranscript {
+        self.iter().collect()
+    }
+}
+
+impl From<&MemoryTranscript> for CompressedTranscript {
+    fn from(transcript: &MemoryTranscript) -> Self {
+        let mut compressed = Self::new();
+        for records in transcript {
+            compressed.push(records);
+        }
+        compressed
+    }
+}
+
+impl FromIterator<MemoryRecords> for CompressedTranscript {
+    fn from_iter<T: IntoIterator<Item = MemoryRecords>>(iter: T) -> Self {
+        let mut compressed = Self::new();
+        compressed.extend(iter);
+        compressed
+    }
+}
+
+impl Extend<MemoryRecords> for CompressedTranscript {
+    fn extend<T: IntoIterator<Item = MemoryRecords>>(&mut self, iter: T) {
+        for records in iter {
+            self.push(&records);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elf::ElfFile;
+    use crate::emulator::{BasicBlockEntry, Emulator, HarvardEmulator};
+    use crate::error::VMError;
+    use serial_test::serial;
+
+    fn load(address: u32, value: u32, timestamp: u32) -> MemoryRecord {
+        MemoryRecord::LoadRecord((MemAccessSize::Word, address, value), timestamp)
+    }
+
+    fn store(address: u32, value: u32, prev_value: u32, timestamp: u32) -> MemoryRecord {
+        MemoryRecord::StoreRecord((MemAccessSize::Byte, address, value, prev_value), timestamp)
+    }
+
+    #[test]
+    fn test_sequential_accesses_form_one_run() {
+        let transcript: MemoryTranscript = (0..100)
+            .map(|i| MemoryRecords::from([load(0x1000 + 4 * i, i * i, 10 + i)]))
+            .chain(std::iter::once(MemoryRecords::new()))
+            .chain((0..50).map(|i| MemoryRecords::from([store(0x2000 - i, i, 0, 200 + 2 * i)])))
+            .collect();
+
+        let compressed = CompressedTranscript::from(&transcript);
+        assert_eq!(compressed.len(), transcript.len());
+        assert_eq!(compressed.num_runs(), 2);
+        assert_eq!(compressed.step_lens, vec![(1, 100), (0, 1), (1, 50)]);
+        assert_eq!(compressed.expand(), transcript);
+    }
+
+    #[test]
+    fn test_multiple_records_per_step() {
+        let transcript: MemoryTranscript = vec![
+            MemoryRecords::from([load(8, 1, 3), store(4, 2, 3, 3)]),
+            MemoryRecords::new(),
+            MemoryRecords::from([load(0, 5, 4), load(12, 6, 4)]),
+            MemoryRecords::from([store(u32::MAX, 7, 8, 6)]),
+        ];
+
+        let compressed: CompressedTranscript = transcript.iter().cloned().collect();
+        assert_eq!(compressed.expand(), transcript);
+    }
+
+    #[test]
+    #[serial]
+    fn test_roundtrip_program_transcript() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let mut emulator = HarvardEmulator::from_elf(&elf_file, &[], &[]);
+
+        let mut transcript = MemoryTranscript::new();
+        loop {
+            let pc = emulator.get_executor().cpu.pc.value;
+            let block: BasicBlockEntry = emulator.fetch_block(pc).unwrap();
+            match emulator.execute_basic_block(&block, false) {
+                Ok((_, records)) => transcript.extend(records),
+                Err(_) => break,
+            }
+        }
+
+        let compressed = CompressedTranscript::from(&transcript);
+        assert!(compressed.num_runs() < transcript.iter().map(|r| r.len()).sum());
+        assert_eq!(compressed.expand(), transcript);
+
+        // Compressing block by block during execution gives the same transcript.
+        let mut emulator = HarvardEmulator::from_elf(&elf_file, &[], &[]);
+        let partial = emulator.execute_partial(false);
+        assert_eq!(partial.error, VMError::VMExited(0));
+        // It also keeps the records of the last block, cut short by the exit syscall.
+        assert_eq!(partial.transcript.expand()[..transcript.len()], transcript);
+    }
+}
@@ -6,8 +6,9 @@ pub use super::layout::LinearMemoryLayout;
 use super::registry;
 
 use nexus_common::constants::WORD_SIZE;
-use nexus_common::memory::MemoryRecords;
+use nexus_common::memory::{MemAccessSize, MemoryRecords};
 use nexus_common::riscv::{opcode::BuiltinOpcode, Opcode};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::collections::BTreeMap;
 
 pub type MemoryTranscript = Vec<MemoryRecords>;
@@ -23,16 +24,21 @@ pub trait IOEntry {
 }
 
 macro_rules! io {
-    ( $id:ident ) => {
+    ( $id:ident $(, $field:ident: $default:expr)* ) => {
         impl IOEntry for $id {
             fn new(address: u32, value: u8) -> Self {
-                Self { address, value }
+                Self {
+                    address,
+                    value,
+                    $($field: $default,)*
+                }
             }
 
             fn new_from_offset(base: u32, offset: u32, value: u8) -> Self {
                 Self {
                     address: base + offset,
                     value,
+                    $($field: $default,)*
                 }
             }
 
@@ -47,19 +53,28 @@ macro_rules! io {
     };
 }
 
-/// Convert `rin` and `wou` instructions into `lb` and `sb` for the second pass in two pass tracing.
+/// Convert `rin` and `wou` instructions, and their sub-word variants, into the loads and stores of
+/// the same width for the second pass in two pass tracing.
 pub fn convert_instruction(registry: &registry::InstructionExecutorRegistry, instr: &u32) -> u32 {
     let mut decoded_ins = decode_instruction(*instr);
 
-    if registry.is_read_input(&decoded_ins.opcode) {
-        decoded_ins.opcode = Opcode::from(BuiltinOpcode::LW);
-        decoded_ins.encode()
-    } else if registry.is_write_output(&decoded_ins.opcode) {
-        decoded_ins.opcode = Opcode::from(BuiltinOpcode::SW);
-        decoded_ins.encode()
+    let opcode = if let Some(size) = registry.read_input_size(&decoded_ins.opcode) {
+        match size {
+            MemAccessSize::Word => BuiltinOpcode::LW,
+            MemAccessSize::HalfWord => BuiltinOpcode::LH,
+            MemAccessSize::Byte => BuiltinOpcode::LB,
+        }
+    } else if let Some(size) = registry.write_output_size(&decoded_ins.opcode) {
+        match size {
+            MemAccessSize::Word => BuiltinOpcode::SW,
+            MemAccessSize::HalfWord => BuiltinOpcode::SH,
+            MemAccessSize::Byte => BuiltinOpcode::SB,
+        }
     } else {
-        *instr
-    }
+        return *instr;
+    };
+    decoded_ins.opcode = Opcode::from(opcode);
+    decoded_ins.encode()
 }
 
 pub fn io_entries_into_vec<T: IOEntry>(base: u32, entries: &[T]) -> Vec<u8> {
@@ -95,49 +110,42 @@ pub fn slice_into_io_entries<T: IOEntry>(base: u32, values: &[u8]) -> Vec<T> {
 }
 
 pub fn elf_into_program_info(elf: &ElfFile, layout: &LinearMemoryLayout) -> ProgramInfo {
-    ProgramInfo {
-        initial_pc: layout.program_start(),
-        program: elf
-            .instructions
-            .iter()
-            .enumerate()
-            .map(|(pc_offset, instruction)| ProgramMemoryEntry {
-                pc: layout.program_start() + (pc_offset * WORD_SIZE) as u32,
-                instruction_word: *instruction,
-            })
-            .collect(),
-    }
+    ProgramInfo::stream_from_elf(elf, layout).materialize()
 }
 
 // One entry per byte because RO memory can be accessed bytewise
-#[derive(Debug, Copy, Clone, PartialEq)]
+#[derive(Debug, Copy, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MemoryInitializationEntry {
     pub address: u32,
     pub value: u8,
+    /// Whether the byte is in the read-only data segment, that the program can't store to.
+    #[serde(default)]
+    pub read_only: bool,
 }
 
 // One entry per byte because WO memory can be accessed bytewise
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct PublicOutputEntry {
     pub address: u32,
     pub value: u8,
 }
 
-io!(MemoryInitializationEntry);
+io!(MemoryInitializationEntry, read_only: false);
 io!(PublicOutputEntry);
 
 // One entry per instruction because program memory is always accessed instruction-wise
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
 pub struct ProgramMemoryEntry {
     pub pc: u32,
     pub instruction_word: u32,
 }
 
-#[derive(Debug, Clone)]
-pub struct ProgramInfo {
+/// Program memory, the entries are materialized by default but can be any iterator of them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProgramInfo<I = Vec<ProgramMemoryEntry>> {
     // The program counter where the execution starts
     pub initial_pc: u32,
-    pub program: Vec<ProgramMemoryEntry>,
+    pub program: I,
 }
 
 impl ProgramInfo {
@@ -149,6 +157,65 @@ impl ProgramInfo {
     }
 }
 
+impl<'a> ProgramInfo<ElfProgramEntries<'a>> {
+    /// Program memory of `elf` placed at the program start of `layout`, with the entries streamed
+    /// from the instructions of the ELF file rather than copied.
+    pub fn stream_from_elf(elf: &'a ElfFile, layout: &LinearMemoryLayout) -> Self {
+        Self {
+            initial_pc: layout.program_start(),
+            program: ElfProgramEntries::new(layout.program_start(), &elf.instructions),
+        }
+    }
+}
+
+impl<I: IntoIterator<Item = ProgramMemoryEntry>> ProgramInfo<I> {
+    /// Collect the program memory entries.
+    pub fn materialize(self) -> ProgramInfo {
+        ProgramInfo {
+            initial_pc: self.initial_pc,
+            program: self.program.into_iter().collect(),
+        }
+    }
+}
+
+/// Iterator over the program memory entries of consecutive instruction words.
+#[derive(Debug, Clone)]
+pub struct ElfProgramEntries<'a> {
+    pc: u32,
+    instructions: std::slice::Iter<'a, u32>,
+}
+
+impl<'a> ElfProgramEntries<'a> {
+    pub fn new(base: u32, instructions: &'a [u32]) -> Self {
+        Self {
+            pc: base,
+            instructions: instructions.iter(),
+        }
+    }
+}
+
+impl Iterator for ElfProgramEntries<'_> {
+    type Item = ProgramMemoryEntry;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let instruction_word = *self.instructions.next()?;
+        let entry = ProgramMemoryEntry {
+            pc: self.pc,
+            instruction_word,
+        };
+        self.pc += WORD_SIZE as u32;
+        Some(entry)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.instructions.size_hint()
+    }
+}
+
+impl ExactSizeIterator for ElfProgramEntries<'_> {}
+
+impl std::iter::FusedIterator for ElfProgramEntries<'_> {}
+
 #[derive(Default, Clone, Debug, PartialEq, Eq)]
 pub struct BasicBlockEntry {
     pub start: u32,
@@ -183,7 +250,7 @@ pub trait InternalView {
     fn add_logs(&mut self, emulator: &impl Emulator);
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct View {
     pub(crate) memory_layout: Option<LinearMemoryLayout>,
     pub(crate) debug_logs: Vec<Vec<u8>>,
@@ -252,6 +319,27 @@ impl View {
             .map(|layout| io_entries_into_vec(layout.public_output_start(), &self.output_memory))
     }
 
+    /// Return the exit code, parsed from the first word of the output, if any.
+    pub fn output_exit_code(&self) -> Option<u32> {
+        if self.exit_code.is_empty() {
+            return None;
+        }
+
+        let mut word = [0u8; WORD_SIZE];
+        for (byte, entry) in word.iter_mut().zip(&self.exit_code) {
+            *byte = entry.value;
+        }
+        Some(u32::from_le_bytes(word))
+    }
+
+    /// Deserialize the public output, as written by the guest with `nexus_rt::write_public_output`.
+    ///
+    /// The exit code is not part of the public output, see [`View::output_exit_code`].
+    pub fn output_as<T: DeserializeOwned>(&self) -> Result<T, postcard::Error> {
+        let mut bytes: Vec<u8> = self.output_memory.iter().map(|entry| entry.value).collect();
+        postcard::from_bytes_cobs(&mut bytes)
+    }
+
     /// Return the number of all addresses under RAM memory checking.
     pub fn view_tracked_ram_size(&self) -> usize {
         self.tracked_ram_size
@@ -1,10 +1,16 @@
 pub use nexus_common::error::*;
 
-use nexus_common::riscv::Opcode;
+use nexus_common::riscv::{register::Register, Opcode};
+
+use crate::{
+    emulator::{MemorySafetyViolation, WatchKind},
+    riscv::Extension,
+};
 use thiserror::Error;
 
 /// Errors related to VM operations.
 #[derive(Debug, Error, PartialEq)]
+#[non_exhaustive]
 pub enum VMError {
     // Unimplemented syscall
     #[error("Unimplemented syscall: opcode={0:08X}, pc=0x{1:08X}")]
@@ -26,14 +32,34 @@ pub enum VMError {
     #[error("VM has exited with status code {0}")]
     VMExited(u32),
 
+    // Guest hit an EBREAK, or a breakpoint set with `Executor::add_breakpoint`, execution resumes
+    // after it.
+    #[error("Breakpoint at pc=0x{0:08X}")]
+    Breakpoint(u32),
+
+    // Guest accessed an address watched with `Executor::add_watchpoint`, execution resumes after
+    // the accessing instruction.
+    #[error("Watchpoint at address 0x{address:08X} hit by a {access:?} access")]
+    Watchpoint { address: u32, access: WatchKind },
+
+    // Guest yielded at a yield syscall, execution resumes after it.
+    #[error("Yielded at pc=0x{0:08X}")]
+    Yielded(u32),
+
+    // Cycle budget set with `Executor::set_max_cycles` exhausted, the instruction at pc isn't executed.
+    #[error("Out of fuel after {cycles} cycles at pc=0x{pc:08X}")]
+    OutOfFuel { pc: u32, cycles: u64 },
+
     // Invalid Profile Label.
     #[error("Invalid profile label for cycle counter: \"{0}\"")]
     InvalidProfileLabel(String),
 
-    #[error("Wrapped MemoryError: {0}")]
+    // Memory access failed, the cause is available as the error source
+    #[error("Memory access failed")]
     MemoryError(#[from] nexus_common::error::MemoryError),
 
-    #[error("Wrapped OpcodeError: {0}")]
+    // Opcode conversion failed, the cause is available as the error source
+    #[error("Invalid opcode")]
     OpcodeError(#[from] nexus_common::error::OpcodeError),
 
     #[error("Instruction not found in registry")]
@@ -55,10 +81,204 @@ pub enum VMError {
     #[error("Unimplemented instruction \"{0}\" at pc=0x{1:08X}")]
     UnimplementedInstructionAt(Opcode, u32),
 
+    // Instruction of a standard extension the VM doesn't support
+    #[error("Instruction 0x{1:08X} at pc=0x{2:08X} requires the {0} extension")]
+    UnsupportedExtension(Extension, u32, u32),
+
+    // Instruction word the VM can't decode, with its address
+    #[error("Instruction 0x{0:08X} at pc=0x{1:08X} can't be decoded")]
+    UndecodableInstruction(u32, u32),
+
     // Unsupported instruction (i.e., one with an invalid opcode)
     #[error("Unsupported instruction \"{0}\"")]
     UnsupportedInstruction(Opcode),
+
+    // Symbol not found in the symbol table of the program
+    #[error("Undefined symbol \"{0}\"")]
+    UndefinedSymbol(String),
+
+    // Program counter outside of the instruction segment
+    #[error("Unauthorized execution: Attempted to execute outside the instruction segment at pc=0x{0:08X}")]
+    UnauthorizedExecution(u32),
+
+    // Store into the instruction segment while self-modifying code is not allowed
+    #[error("Self-modifying code: instruction at pc=0x{0:08X} writes to the program at 0x{1:08X}")]
+    SelfModifyingCode(u32, u32),
+
+    // CSR that doesn't exist or is read-only, in machine mode
+    #[error("Illegal CSR access: instruction at pc=0x{0:08X} accesses CSR 0x{1:03X}")]
+    IllegalCsrAccess(u32, u32),
+
+    // Machine-mode instruction executed in user mode
+    #[error("Privileged instruction \"{0}\" executed in user mode at pc=0x{1:08X}")]
+    PrivilegedInstruction(Opcode, u32),
+
+    // Environment call from user mode, which is not a syscall
+    #[error("Environment call from user mode at pc=0x{0:08X}")]
+    EnvironmentCallFromUser(u32),
+
+    // Memory-safety invariant violated in assertion mode
+    #[error("Memory-safety assertion failed: {0}")]
+    MemorySafety(MemorySafetyViolation),
+
+    // More arguments than argument registers
+    #[error("Too many arguments for a function call: {0}, at most 8 are supported")]
+    TooManyArguments(usize),
+}
+
+impl VMError {
+    /// The pc of the instruction the error happened at, for the errors recording it.
+    pub fn pc(&self) -> Option<u32> {
+        match self {
+            VMError::UnimplementedSyscall(_, pc)
+            | VMError::InstructionNotSyscall(_, pc)
+            | VMError::Breakpoint(pc)
+            | VMError::Yielded(pc)
+            | VMError::OutOfFuel { pc, .. }
+            | VMError::UnimplementedInstructionAt(_, pc)
+            | VMError::UnsupportedExtension(_, _, pc)
+            | VMError::UndecodableInstruction(_, pc)
+            | VMError::UnauthorizedExecution(pc)
+            | VMError::SelfModifyingCode(pc, _)
+            | VMError::IllegalCsrAccess(pc, _)
+            | VMError::PrivilegedInstruction(_, pc)
+            | VMError::EnvironmentCallFromUser(pc) => Some(*pc),
+            _ => None,
+        }
+    }
+}
+
+/// Errors related to building a memory layout from explicit segment sizes.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum LayoutError {
+    // Segments are word-aligned, sizes aren't rounded up by the builder
+    #[error("Size of the {segment} segment is not a multiple of the word size: 0x{size:X}")]
+    Unaligned { segment: &'static str, size: u32 },
+
+    // The program segment holds at least the entry point
+    #[error("The program segment is empty")]
+    EmptyProgram,
+
+    // A segment past the end of the address space would wrap around onto the program
+    #[error("The {segment} segment extends past the end of the address space")]
+    Overflow { segment: &'static str },
+}
+
+/// Errors related to loading a memory layout from a configuration file.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum LayoutConfigError {
+    #[error("Failed to read the layout configuration")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid TOML layout configuration")]
+    Toml(#[from] toml::de::Error),
+
+    #[error("Invalid JSON layout configuration")]
+    Json(#[from] serde_json::Error),
+
+    // Only .toml and .json files are recognized
+    #[error("Unsupported layout configuration format: {0}")]
+    UnsupportedFormat(std::path::PathBuf),
+
+    #[error("Configured sizes don't form a valid layout")]
+    InvalidLayout(#[from] VMError),
+
+    // The program segment can't hold the code and static data of the program
+    #[error("Program needs {required} bytes but the layout only has {available}")]
+    ProgramTooLarge { required: usize, available: usize },
+}
+
+/// Errors related to writing or reading a serialized trace file.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum TraceFileError {
+    #[error("Failed to access the trace file")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid trace file encoding")]
+    Encoding(#[from] postcard::Error),
+}
+
+/// Errors related to writing or reading a basic-block cache file, see
+/// [`crate::emulator::Executor::save_block_cache`].
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum BlockCacheError {
+    #[error("Failed to access the block cache file")]
+    Io(#[from] std::io::Error),
+
+    #[error("Invalid block cache file encoding")]
+    Encoding(#[from] postcard::Error),
+
+    // The cached blocks were decoded from a program patched at runtime
+    #[error("The program was modified at runtime, its blocks can't be cached")]
+    ProgramModified,
+}
+
+/// Inconsistencies found by [`crate::validate::validate_trace`], located by the timestamp of the step.
+#[derive(Debug, Error, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TraceValidationError {
+    #[error("Step {timestamp} at pc {pc:#x} doesn't follow step {prev}")]
+    NonMonotonicTimestamp { timestamp: u32, pc: u32, prev: u32 },
+
+    #[error("Step {timestamp} at pc {pc:#x} has a memory record from step {record}")]
+    RecordTimestamp {
+        timestamp: u32,
+        pc: u32,
+        record: u32,
+    },
+
+    #[error(
+        "Step {timestamp} at pc {pc:#x} accessed {actual:#04x} at {address:#x},          but {expected:#04x} was last written"
+    )]
+    StaleMemory {
+        timestamp: u32,
+        pc: u32,
+        address: u32,
+        expected: u8,
+        actual: u8,
+    },
+
+    #[error("Register {register} before step {timestamp} is {actual:#x}, expected {expected:#x}")]
+    RegisterMismatch {
+        timestamp: u32,
+        register: Register,
+        expected: u32,
+        actual: u32,
+    },
+
+    #[error(
+        "Step {timestamp} at pc {pc:#x} {} {address:#x} outside of a segment permitting it",
+        if *store { "stored to" } else { "loaded from" }
+    )]
+    SegmentPermission {
+        timestamp: u32,
+        pc: u32,
+        address: u32,
+        store: bool,
+    },
 }
 
 /// Result type for VM functions that can produce errors.
 pub type Result<T, E = VMError> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::error::Error as _;
+
+    #[test]
+    fn test_memory_error_is_source() {
+        let err = VMError::from(MemoryError::UnalignedMemoryRead(0x1001));
+
+        let source = err.source().expect("wrapped error must be the source");
+        assert_eq!(
+            source.downcast_ref::<MemoryError>(),
+            Some(&MemoryError::UnalignedMemoryRead(0x1001))
+        );
+        assert_eq!(err.to_string(), "Memory access failed");
+    }
+}
@@ -6,6 +6,7 @@ pub mod memory;
 pub mod riscv;
 pub mod system;
 pub mod trace;
+pub mod validate;
 
 pub use crate::elf::WORD_SIZE;
 pub use crate::system::SyscallCode;
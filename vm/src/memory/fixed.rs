@@ -112,20 +112,16 @@ impl<M: Mode> FixedMemory<M> {
     }
 
     pub fn from_bytes(base_address: u32, bytes: &[u8]) -> Self {
-        let padded_len = word_align!(bytes.len());
-        let mut padded_bytes = bytes.to_vec();
-        padded_bytes.resize(padded_len, 0);
         FixedMemory::<M> {
             base_address,
-            max_len: padded_len,
-            vec: bytes_to_words!(padded_bytes),
+            max_len: word_align!(bytes.len()),
+            vec: bytes_to_words!(bytes),
             __mode: PhantomData,
         }
     }
 
     pub fn from_words(base_address: u32, max_len: usize, words: &[u32]) -> Self {
-        let mut vec = words.to_vec();
-        vec.truncate(max_len / WORD_SIZE);
+        let vec = words[..words.len().min(max_len / WORD_SIZE)].to_vec();
 
         FixedMemory::<M> {
             base_address,
@@ -135,6 +131,24 @@ impl<M: Mode> FixedMemory<M> {
         }
     }
 
+    /// Creates the smallest memory holding a memory image of word-aligned addresses, e.g. the
+    /// `rom_image` of an ELF file, or `None` if the image is empty.
+    pub fn from_image(image: &BTreeMap<u32, u32>) -> Option<Self> {
+        let (&base_address, _) = image.first_key_value()?;
+        let (&last_address, _) = image.last_key_value()?;
+        let mut vec = vec![0; (last_address - base_address) as usize / WORD_SIZE + 1];
+        for (address, &word) in image {
+            vec[(address - base_address) as usize / WORD_SIZE] = word;
+        }
+
+        Some(FixedMemory::<M> {
+            base_address,
+            max_len: vec.len() * WORD_SIZE,
+            vec,
+            __mode: PhantomData,
+        })
+    }
+
     pub fn segment(&self, start: u32, end: Option<u32>) -> &[u32] {
         let s = (start - self.base_address) / WORD_SIZE as u32;
 
@@ -177,7 +191,7 @@ impl<M: Mode> FixedMemory<M> {
     /// # Returns
     ///
     /// The value written to memory, or an error if the operation failed.
-    fn execute_write(
+    pub(crate) fn execute_write(
         &mut self,
         raw_address: u32,
         size: MemAccessSize,
@@ -750,4 +764,24 @@ mod tests {
         memory.write(0x10FF, MemAccessSize::Byte, 0xFF).unwrap();
         assert_eq!(memory.vec.len(), 64);
     }
+
+    #[test]
+    fn test_from_image_and_bytes() {
+        let image = BTreeMap::from([(0x2000, 0x11223344), (0x200C, 0x55667788)]);
+        let memory = FixedMemory::<RO>::from_image(&image).unwrap();
+        assert_eq!(memory.vec, vec![0x11223344, 0, 0, 0x55667788]);
+        assert_eq!(memory.max_len, 16);
+        assert_eq!(
+            memory.read(0x200C, MemAccessSize::Word).unwrap(),
+            LoadOp::Op(MemAccessSize::Word, 0x200C, 0x55667788)
+        );
+        assert!(FixedMemory::<RO>::from_image(&BTreeMap::new()).is_none());
+
+        // A trailing partial word is zero-padded, and the bytes round-trip.
+        let bytes = [1, 2, 3, 4, 5, 6];
+        let memory = FixedMemory::<RO>::from_bytes(0x3000, &bytes);
+        assert_eq!(memory.vec, vec![0x04030201, 0x0605]);
+        assert_eq!(memory.segment_bytes(0x3000, None), [1, 2, 3, 4, 5, 6, 0, 0]);
+        assert_eq!(memory.segment_bytes(0x3000, Some(0x3004)), [1, 2, 3, 4]);
+    }
 }
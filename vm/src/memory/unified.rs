@@ -27,10 +27,10 @@
 //! let mut memory = UnifiedMemory::default();
 //!
 //! // Add a read-only fixed memory region
-//! memory.add_fixed_ro(&FixedMemory::<RO>::new(0x1000, 0x1000)).unwrap();
+//! memory.add_fixed_ro(FixedMemory::<RO>::new(0x1000, 0x1000)).unwrap();
 //!
 //! // Add a read-write fixed memory region
-//! memory.add_fixed_rw(&FixedMemory::<RW>::new(0x2000, 0x1000)).unwrap();
+//! memory.add_fixed_rw(FixedMemory::<RW>::new(0x2000, 0x1000)).unwrap();
 //!
 //! // Add a fallback variable memory
 //! memory.add_variable(VariableMemory::<RW>::default()).unwrap();
@@ -68,21 +68,33 @@
 //! The use of `RangeMap` for memory layout allows for efficient lookup of the correct memory
 //! region for a given address. However, the performance may vary depending on the number and
 //! size of fixed memory regions.
-use nexus_common::error::MemoryError;
+use nexus_common::error::{MemoryError, MemorySegment};
 use num_derive::FromPrimitive;
 use num_traits::FromPrimitive;
 use rangemap::RangeMap;
+use serde::{Deserialize, Serialize};
 use std::{
     collections::BTreeMap,
     fmt::{Debug, Display, Formatter, Result as FmtResult},
 };
 
-use nexus_common::words_to_bytes;
+use nexus_common::{constants::WORD_SIZE, words_to_bytes};
 
 use super::{
     FixedMemory, LoadOp, MemAccessSize, MemoryProcessor, StoreOp, VariableMemory, NA, RO, RW, WO,
 };
 
+/// Contents of a writable memory segment, see [`UnifiedMemory::rw_segments`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MemorySegmentImage {
+    pub start: u32,
+    /// One byte past the end of the segment.
+    pub end: u32,
+    /// Words of the segment by their address. Words that were never written may be missing, they
+    /// read as zero.
+    pub words: BTreeMap<u32, u32>,
+}
+
 #[derive(Debug, Clone, Eq, PartialEq, FromPrimitive)]
 pub enum Modes {
     NA = 0,
@@ -110,27 +122,42 @@ pub struct UnifiedMemory {
     fna_store: Vec<FixedMemory<NA>>,
     // fallback variable read-write memory for all other addresses
     vrw: Option<VariableMemory<RW>>,
+    // names of fixed memories, if labeled
+    labels: RangeMap<u32, &'static str>,
 }
 
 impl Display for UnifiedMemory {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
         // Display RangeMap table
         writeln!(f, "\nMemory Layout:")?;
-        writeln!(f, "┌───────────────┬───────────────┬──────────┐")?;
-        writeln!(f, "│  Start Addr   │   End Addr    │  Mode    │")?;
-        writeln!(f, "├───────────────┼───────────────┼──────────┤")?;
+        writeln!(
+            f,
+            "┌───────────────┬───────────────┬──────────┬──────────────────┐"
+        )?;
+        writeln!(
+            f,
+            "│  Start Addr   │   End Addr    │  Mode    │  Label           │"
+        )?;
+        writeln!(
+            f,
+            "├───────────────┼───────────────┼──────────┼──────────────────┤"
+        )?;
 
         for (range, mode) in self.meta.iter() {
             writeln!(
                 f,
-                "│ 0x{:08x}    │ 0x{:08x}    │ {:<8} │",
+                "│ 0x{:08x}    │ 0x{:08x}    │ {:<8} │ {:<16} │",
                 range.start,
                 range.end,
-                format!("{:?}", mode)
+                format!("{:?}", mode),
+                self.label(range.start).unwrap_or_default()
             )?;
         }
 
-        writeln!(f, "└───────────────┴───────────────┴──────────┘")?;
+        writeln!(
+            f,
+            "└───────────────┴───────────────┴──────────┴──────────────────┘"
+        )?;
 
         Ok(())
     }
@@ -199,13 +226,14 @@ impl From<VariableMemory<RW>> for UnifiedMemory {
             fna: RangeMap::new(),
             fna_store: Vec::new(),
             vrw: Some(vrw),
+            labels: RangeMap::new(),
         }
     }
 }
 
 macro_rules! add_fixed {
     ( $func: ident, $map: ident, $store: ident, $mode: ident ) => {
-        pub fn $func(&mut self, mem: &FixedMemory<$mode>) -> Result<(usize, usize), MemoryError> {
+        pub fn $func(&mut self, mem: FixedMemory<$mode>) -> Result<(usize, usize), MemoryError> {
             let rng = std::ops::Range {
                 start: mem.base_address,
                 end: mem.base_address + mem.max_len as u32,
@@ -218,14 +246,92 @@ macro_rules! add_fixed {
 
             let idx = self.$store.len();
             self.$map.insert(rng, idx);
-            self.$store.push(mem.clone());
+            self.$store.push(mem);
 
             Ok((Modes::$mode as usize, idx))
         }
     };
 }
 
+impl Modes {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Modes::NA => "NA",
+            Modes::RO => "RO",
+            Modes::WO => "WO",
+            Modes::RW => "RW",
+        }
+    }
+}
+
 impl UnifiedMemory {
+    /// Error for an access missing every segment, naming the closest segments around it.
+    fn out_of_bounds(&self, address: u32, size: MemAccessSize) -> MemoryError {
+        let segment = |(range, mode): (&std::ops::Range<u32>, &Modes)| MemorySegment {
+            start: range.start,
+            end: range.end,
+            mode: mode.as_str(),
+            label: self.label(range.start),
+        };
+
+        MemoryError::OutOfBounds {
+            address,
+            size,
+            below: self
+                .meta
+                .iter()
+                .take_while(|(range, _)| range.end <= address)
+                .last()
+                .map(segment),
+            above: self
+                .meta
+                .iter()
+                .find(|(range, _)| range.start > address)
+                .map(segment),
+        }
+    }
+
+    /// Names the fixed memory at `uidx`, as returned when it was added, e.g. `heap`. The label is
+    /// used in errors and in the layout table instead of the mode and index of the memory.
+    pub fn set_label(
+        &mut self,
+        uidx: (usize, usize),
+        label: &'static str,
+    ) -> Result<(), MemoryError> {
+        let (store, idx) = uidx;
+        let range = match FromPrimitive::from_usize(store) {
+            Some(Modes::RW) => self
+                .frw_store
+                .get(idx)
+                .map(|mem| (mem.base_address, mem.max_len)),
+            Some(Modes::RO) => self
+                .fro_store
+                .get(idx)
+                .map(|mem| (mem.base_address, mem.max_len)),
+            Some(Modes::WO) => self
+                .fwo_store
+                .get(idx)
+                .map(|mem| (mem.base_address, mem.max_len)),
+            Some(Modes::NA) => self
+                .fna_store
+                .get(idx)
+                .map(|mem| (mem.base_address, mem.max_len)),
+            None => None,
+        }
+        .map(|(start, len)| start..start + len as u32)
+        .ok_or(MemoryError::UndefinedMemoryRegion)?;
+
+        if !range.is_empty() {
+            self.labels.insert(range, label);
+        }
+        Ok(())
+    }
+
+    /// Label of the fixed memory containing `address`, if it was labeled.
+    pub fn label(&self, address: u32) -> Option<&'static str> {
+        self.labels.get(&address).copied()
+    }
+
     pub fn add_variable(&mut self, vrw: VariableMemory<RW>) -> Result<(), MemoryError> {
         if self.vrw.is_some() {
             return Err(MemoryError::MemoryOverlap);
@@ -276,6 +382,25 @@ impl UnifiedMemory {
         }
     }
 
+    /// Writes to a read-only memory, bypassing its permissions. Only meant for patching the
+    /// program when self-modifying code is allowed.
+    pub(crate) fn patch_fixed_ro(
+        &mut self,
+        uidx: (usize, usize),
+        address: u32,
+        size: MemAccessSize,
+        value: u32,
+    ) -> Result<StoreOp, MemoryError> {
+        let (store, idx) = uidx;
+        match (
+            FromPrimitive::from_usize(store),
+            self.fro_store.get_mut(idx),
+        ) {
+            (Some(Modes::RO), Some(memory)) => memory.execute_write(address, size, value),
+            _ => Err(MemoryError::UndefinedMemoryRegion),
+        }
+    }
+
     pub fn segment(
         &self,
         uidx: (usize, usize),
@@ -317,6 +442,41 @@ impl UnifiedMemory {
         }
     }
 
+    /// Images of all read-write segments, sorted by address. The variable memory, if any, is
+    /// reported as a single segment spanning the words written to it.
+    pub fn rw_segments(&self) -> Vec<MemorySegmentImage> {
+        let mut segments: Vec<MemorySegmentImage> = self
+            .frw
+            .iter()
+            .map(|(range, &idx)| MemorySegmentImage {
+                start: range.start,
+                end: range.end,
+                words: self.frw_store[idx]
+                    .segment(range.start, None)
+                    .iter()
+                    .zip((range.start..).step_by(WORD_SIZE))
+                    .map(|(&word, address)| (address, word))
+                    .collect(),
+            })
+            .collect();
+
+        if let Some(vrw) = &self.vrw {
+            let words = vrw.words();
+            if let (Some((&start, _)), Some((&last, _))) =
+                (words.first_key_value(), words.last_key_value())
+            {
+                segments.push(MemorySegmentImage {
+                    start,
+                    end: last + WORD_SIZE as u32,
+                    words: words.clone(),
+                });
+            }
+        }
+
+        segments.sort_by_key(|segment| segment.start);
+        segments
+    }
+
     pub fn segment_bytes(
         &self,
         uidx: (usize, usize),
@@ -368,7 +528,7 @@ impl MemoryProcessor for UnifiedMemory {
 
             ret
         } else {
-            Err(MemoryError::InvalidMemoryAccess(address))
+            Err(self.out_of_bounds(address, size))
         }
     }
 
@@ -394,7 +554,7 @@ impl MemoryProcessor for UnifiedMemory {
         } else if let Some(vrw) = &self.vrw {
             vrw.read(address, size)
         } else {
-            Err(MemoryError::InvalidMemoryAccess(address))
+            Err(self.out_of_bounds(address, size))
         }
     }
 }
@@ -410,20 +570,20 @@ mod tests {
             .unwrap();
 
         memory
-            .add_fixed_ro(&FixedMemory::<RO>::from_vec(
+            .add_fixed_ro(FixedMemory::<RO>::from_vec(
                 0,
                 0x1000,
                 vec![0x12EFCDAB; 0x1000],
             ))
             .unwrap();
         memory
-            .add_fixed_rw(&FixedMemory::<RW>::new(0x1000, 0x1000))
+            .add_fixed_rw(FixedMemory::<RW>::new(0x1000, 0x1000))
             .unwrap();
         memory
-            .add_fixed_wo(&FixedMemory::<WO>::new(0x2000, 0x1000))
+            .add_fixed_wo(FixedMemory::<WO>::new(0x2000, 0x1000))
             .unwrap();
         memory
-            .add_fixed_na(&FixedMemory::<NA>::new(0x3000, 0x1000))
+            .add_fixed_na(FixedMemory::<NA>::new(0x3000, 0x1000))
             .unwrap();
 
         memory
@@ -1024,7 +1184,110 @@ mod tests {
         // Write non-existant unified memory
         assert_eq!(
             memory.write(0x4000, MemAccessSize::Word, 0xABCD1234),
-            Err(MemoryError::InvalidMemoryAccess(0x4000))
+            Err(MemoryError::OutOfBounds {
+                address: 0x4000,
+                size: MemAccessSize::Word,
+                below: None,
+                above: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_rw_segments() {
+        let mut memory = memory_setup();
+        memory.write(0x1004, MemAccessSize::Word, 0x11).unwrap();
+        memory.write(0x2000, MemAccessSize::Word, 0x22).unwrap();
+        memory.write(0x8000, MemAccessSize::Byte, 0x33).unwrap();
+        memory.write(0x9000, MemAccessSize::Word, 0x44).unwrap();
+
+        assert_eq!(
+            memory.rw_segments(),
+            vec![
+                MemorySegmentImage {
+                    start: 0x1000,
+                    end: 0x2000,
+                    words: BTreeMap::from([(0x1000, 0), (0x1004, 0x11)]),
+                },
+                MemorySegmentImage {
+                    start: 0x8000,
+                    end: 0x9004,
+                    words: BTreeMap::from([(0x8000, 0x33), (0x9000, 0x44)]),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_out_of_bounds_names_nearest_segments() {
+        let mut memory = UnifiedMemory::default();
+        memory
+            .add_fixed_ro(FixedMemory::<RO>::new(0x1000, 0x1000))
+            .unwrap();
+        memory
+            .add_fixed_rw(FixedMemory::<RW>::new(0x8000, 0x1000))
+            .unwrap();
+
+        let err = memory.read(0x4000, MemAccessSize::HalfWord).unwrap_err();
+        assert_eq!(
+            err,
+            MemoryError::OutOfBounds {
+                address: 0x4000,
+                size: MemAccessSize::HalfWord,
+                below: Some(MemorySegment {
+                    start: 0x1000,
+                    end: 0x2000,
+                    mode: "RO",
+                    label: None,
+                }),
+                above: Some(MemorySegment {
+                    start: 0x8000,
+                    end: 0x9000,
+                    mode: "RW",
+                    label: None,
+                }),
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "Out of bounds HalfWord access at 0x00004000, nearest segments are \
+             RO [0x00001000, 0x00002000) below and RW [0x00008000, 0x00009000) above"
+        );
+
+        assert!(matches!(
+            memory.write(0x10000, MemAccessSize::Byte, 0),
+            Err(MemoryError::OutOfBounds {
+                above: None,
+                below: Some(_),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_segment_labels() {
+        let mut memory = UnifiedMemory::default();
+        let rodata = memory
+            .add_fixed_ro(FixedMemory::<RO>::new(0x1000, 0x1000))
+            .unwrap();
+        memory
+            .add_fixed_rw(FixedMemory::<RW>::new(0x8000, 0x1000))
+            .unwrap();
+        memory.set_label(rodata, "rodata").unwrap();
+        assert_eq!(
+            memory.set_label((Modes::WO as usize, 0), "output"),
+            Err(MemoryError::UndefinedMemoryRegion)
+        );
+
+        assert_eq!(memory.label(0x1800), Some("rodata"));
+        assert_eq!(memory.label(0x8000), None);
+        assert!(memory.to_string().contains("rodata"));
+
+        let err = memory.read(0x4000, MemAccessSize::Word).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Out of bounds Word access at 0x00004000, nearest segments are \
+             rodata RO [0x00001000, 0x00002000) below and RW [0x00008000, 0x00009000) above"
         );
     }
 }
@@ -87,6 +87,11 @@ impl<M: Mode> Debug for VariableMemory<M> {
 }
 
 impl<M: Mode> VariableMemory<M> {
+    /// Words written so far, by their address.
+    pub fn words(&self) -> &BTreeMap<u32, u32> {
+        &self.0
+    }
+
     /// Writes data to memory.
     ///
     /// # Arguments
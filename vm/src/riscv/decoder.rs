@@ -58,7 +58,10 @@
 //! and instruction-level parallelism detection in RISC-V programs.
 
 use crate::riscv::instructions::{BasicBlock, BasicBlockProgram, Instruction, InstructionDecoder};
-use nexus_common::riscv::{instruction::InstructionType, register::Register, Opcode};
+use nexus_common::{
+    constants::WORD_SIZE,
+    riscv::{instruction::InstructionType, register::Register, Opcode},
+};
 use rrs_lib::process_instruction;
 
 #[inline(always)]
@@ -171,6 +174,137 @@ pub fn decode_instruction(u32_instruction: u32) -> Instruction {
     })
 }
 
+/// Standard RISC-V extensions that the VM recognizes but doesn't implement.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Extension {
+    /// Atomic instructions.
+    A,
+    /// Single-precision floating point.
+    F,
+    /// Double-precision floating point.
+    D,
+    /// Compressed instructions.
+    C,
+}
+
+impl std::fmt::Display for Extension {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            Extension::A => "A",
+            Extension::F => "F",
+            Extension::D => "D",
+            Extension::C => "C",
+        };
+        f.write_str(name)
+    }
+}
+
+const AMO_OPCODE: u8 = 0b0101111;
+const LOAD_FP_OPCODE: u8 = 0b0000111;
+const STORE_FP_OPCODE: u8 = 0b0100111;
+const OP_FP_OPCODE: u8 = 0b1010011;
+const FUSED_FP_OPCODES: [u8; 4] = [0b1000011, 0b1000111, 0b1001011, 0b1001111];
+
+/// Returns the unsupported extension `u32_instruction` belongs to, judging by its major opcode.
+pub fn required_extension(u32_instruction: u32) -> Option<Extension> {
+    // Zeroed memory is defined to be an illegal instruction, also in the compressed encoding.
+    if u32_instruction & 0xFFFF == 0 {
+        return None;
+    }
+    // 32-bit instructions have the two lowest bits set.
+    if u32_instruction & 0b11 != 0b11 {
+        return Some(Extension::C);
+    }
+
+    let double = match extract_opcode(u32_instruction) {
+        AMO_OPCODE => return Some(Extension::A),
+        // The width is encoded in fn3: 0b010 for single and 0b011 for double precision.
+        LOAD_FP_OPCODE | STORE_FP_OPCODE => extract_fn3(u32_instruction) == 0b011,
+        // The format is encoded in the lowest bits of fn7: 0b00 for single and 0b01 for double.
+        opcode if opcode == OP_FP_OPCODE || FUSED_FP_OPCODES.contains(&opcode) => {
+            extract_fn7(u32_instruction) & 0b11 == 0b01
+        }
+        _ => return None,
+    };
+    Some(if double { Extension::D } else { Extension::F })
+}
+
+/// An instruction the VM can't decode, with its raw encoding, found by
+/// [`scan_undecodable_instructions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UndecodableInstruction {
+    pub pc: u32,
+    pub instruction: u32,
+}
+
+impl UndecodableInstruction {
+    /// The unsupported extension the instruction belongs to, if any.
+    pub fn extension(&self) -> Option<Extension> {
+        required_extension(self.instruction)
+    }
+}
+
+impl std::fmt::Display for UndecodableInstruction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "0x{:08X} at pc=0x{:08X}", self.instruction, self.pc)?;
+        if let Some(extension) = self.extension() {
+            write!(f, " ({} extension)", extension)?;
+        }
+        Ok(())
+    }
+}
+
+/// An instruction of an unsupported extension found by [`scan_unsupported_extensions`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnsupportedInstruction {
+    pub pc: u32,
+    pub instruction: u32,
+    pub extension: Extension,
+}
+
+/// Scans a program loaded at `base` for words the VM can't decode as instructions, so that they
+/// can be reported before execution.
+///
+/// Data embedded in the instruction segment is reported as well, e.g. zero padding.
+pub fn scan_undecodable_instructions(
+    u32_instructions: &[u32],
+    base: u32,
+) -> Vec<UndecodableInstruction> {
+    u32_instructions
+        .iter()
+        .enumerate()
+        .filter(|(_, &u32_instruction)| {
+            decode_instruction(u32_instruction) == Instruction::unimpl()
+        })
+        .map(|(idx, &u32_instruction)| UndecodableInstruction {
+            pc: base + (idx * WORD_SIZE) as u32,
+            instruction: u32_instruction,
+        })
+        .collect()
+}
+
+/// Scans a program loaded at `base` for instructions of extensions the VM doesn't support, so that
+/// they can be reported before execution.
+///
+/// Data embedded in the instruction segment may be reported as well.
+pub fn scan_unsupported_extensions(
+    u32_instructions: &[u32],
+    base: u32,
+) -> Vec<UnsupportedInstruction> {
+    scan_undecodable_instructions(u32_instructions, base)
+        .into_iter()
+        .filter_map(|undecodable| {
+            undecodable
+                .extension()
+                .map(|extension| UnsupportedInstruction {
+                    pc: undecodable.pc,
+                    instruction: undecodable.instruction,
+                    extension,
+                })
+        })
+        .collect()
+}
+
 /// Decodes RISC-V instructions from an ELF file into basic blocks
 ///
 /// # Arguments
@@ -232,7 +366,6 @@ pub fn decode_until_end_of_a_block(u32_instructions: &[u32]) -> BasicBlock {
 mod tests {
     use super::*;
     use crate::elf::ElfFile;
-    use nexus_common::constants::WORD_SIZE;
 
     /// Tests the decoding of instructions from an ELF file
     ///
@@ -276,6 +409,53 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_scan_unsupported_extensions() {
+        let program = [
+            0x00150513, // addi a0, a0, 1
+            0x0805252f, // amoswap.w a0, zero, (a0)
+            0x00052007, // flw ft0, 0(a0)
+            0x02a57053, // fadd.d ft0, fa0, fa0
+            0x00010001, // c.nop, c.addi zero, 0
+            0x00000000, // illegal
+        ];
+        let found = scan_unsupported_extensions(&program, 0x1000);
+
+        let found: Vec<_> = found
+            .into_iter()
+            .map(|unsupported| (unsupported.pc, unsupported.extension))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (0x1004, Extension::A),
+                (0x1008, Extension::F),
+                (0x100c, Extension::D),
+                (0x1010, Extension::C),
+            ]
+        );
+        assert!(scan_unsupported_extensions(
+            &ElfFile::from_path("test/fib_10.elf").unwrap().instructions,
+            0
+        )
+        .is_empty());
+
+        let undecodable = scan_undecodable_instructions(&program, 0x1000);
+        assert_eq!(undecodable.len(), 5);
+        assert_eq!(
+            undecodable[4],
+            UndecodableInstruction {
+                pc: 0x1014,
+                instruction: 0
+            }
+        );
+        assert_eq!(undecodable[4].to_string(), "0x00000000 at pc=0x00001014");
+        assert_eq!(
+            undecodable[0].to_string(),
+            "0x0805252F at pc=0x00001004 (A extension)"
+        );
+    }
+
     #[test]
     fn test_decode_instruction_from_elf_until_end_of_block() {
         let test_cases = [("test/fib_10.elf", 4096)];
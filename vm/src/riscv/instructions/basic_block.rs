@@ -1,4 +1,4 @@
-use std::{fmt::Display, ops::Index};
+use std::{fmt::Display, io::Write, ops::Index};
 
 use super::Instruction;
 
@@ -25,12 +25,25 @@ impl BasicBlock {
         self.0.is_empty()
     }
 
-    pub fn print_with_offset(&self, offset: usize) {
-        println!("┌─────────────────────────────────────────────────");
+    /// Emits a `trace` event for every instruction of the block, labelled by its address.
+    pub fn trace_with_offset(&self, offset: usize) {
         for (j, instruction) in self.0.iter().enumerate() {
-            println!("│ {:3x}: {}", j * 4 + offset, instruction);
+            tracing::trace!(
+                target: "nexus_vm::emulator",
+                "{:3x}: {}",
+                j * 4 + offset,
+                instruction
+            );
+        }
+    }
+
+    /// Writes the disassembly of the block to `w`, labelling every instruction by its address.
+    pub fn write_with_offset(&self, w: &mut impl Write, offset: usize) -> std::io::Result<()> {
+        writeln!(w, "┌─────────────────────────────────────────────────")?;
+        for (j, instruction) in self.0.iter().enumerate() {
+            writeln!(w, "│ {:3x}: {}", j * 4 + offset, instruction)?;
         }
-        println!("└─────────────────────────────────────────────────");
+        writeln!(w, "└─────────────────────────────────────────────────")
     }
 
     pub fn len(&self) -> usize {
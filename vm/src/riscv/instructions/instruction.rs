@@ -10,9 +10,9 @@
 //! - <https://github.com/riscv/riscv-opcodes/blob/master/rv_m>
 
 use crate::riscv::instructions::macros::{
-    impl_b_type_instructions, impl_i_type_instructions, impl_i_type_shamt_instructions,
-    impl_r_type_instructions, impl_s_type_instructions, impl_systemcall_instructions,
-    impl_u_type_instructions, unimplemented_instructions,
+    impl_b_type_instructions, impl_csr_instructions, impl_i_type_instructions,
+    impl_i_type_shamt_instructions, impl_r_type_instructions, impl_s_type_instructions,
+    impl_systemcall_instructions, impl_u_type_instructions, unimplemented_instructions,
 };
 use nexus_common::riscv::instruction::{Instruction, InstructionType};
 use nexus_common::riscv::opcode::BuiltinOpcode;
@@ -124,15 +124,38 @@ impl InstructionProcessor for InstructionDecoder {
         )
     }
 
+    // Implementations for CSR instructions, only executed in machine mode
+    impl_csr_instructions! {
+        process_csrrw => (0b001, "csrrw"),
+        process_csrrs => (0b010, "csrrs"),
+        process_csrrc => (0b011, "csrrc"),
+        process_csrrwi => (0b101, "csrrwi"),
+        process_csrrsi => (0b110, "csrrsi"),
+        process_csrrci => (0b111, "csrrci"),
+    }
+
+    // mret and wfi are only executed in machine mode, they are told apart by their funct12
+    fn process_mret(&mut self) -> Self::InstructionResult {
+        Instruction::new(
+            Opcode::new(0b1110011, Some(0b000), None, "mret"),
+            Register::X0,
+            Register::X0,
+            0x302,
+            InstructionType::IType,
+        )
+    }
+
+    fn process_wfi(&mut self) -> Self::InstructionResult {
+        Instruction::new(
+            Opcode::new(0b1110011, Some(0b000), None, "wfi"),
+            Register::X0,
+            Register::X0,
+            0x105,
+            InstructionType::IType,
+        )
+    }
+
     unimplemented_instructions! {
-        process_csrrc(dec_insn: ITypeCSR),
-        process_csrrci(dec_insn: ITypeCSR),
-        process_csrrs(dec_insn: ITypeCSR),
-        process_csrrsi(dec_insn: ITypeCSR),
-        process_csrrw(dec_insn: ITypeCSR),
-        process_csrrwi(dec_insn: ITypeCSR),
         process_fence(dec_insn: IType),
-        process_mret(),
-        process_wfi()
     }
 }
@@ -34,6 +34,24 @@ macro_rules! impl_systemcall_instructions {
     };
 }
 
+/// CSR instructions aren't part of the provable ISA, they decode to custom opcodes executed by the
+/// machine mode of the emulator. The immediate variants keep their 5-bit immediate in `op_b`.
+macro_rules! impl_csr_instructions {
+    ($($name:ident => ($fn3:expr, $mnemonic:expr)),+ $(,)?) => {
+        $(
+            fn $name(&mut self, dec_insn: ITypeCSR) -> Self::InstructionResult {
+                Instruction::new(
+                    Opcode::new(0b1110011, Some($fn3), None, $mnemonic),
+                    Register::from(dec_insn.rd as u8),
+                    Register::from(dec_insn.rs1 as u8),
+                    dec_insn.csr,
+                    InstructionType::IType,
+                )
+            }
+        )+
+    };
+}
+
 macro_rules! impl_i_type_shamt_instructions {
     ($($name:ident => $opcode:expr),+ $(,)?) => {
         $(
@@ -91,6 +109,7 @@ macro_rules! unimplemented_instructions {
 }
 
 pub(crate) use impl_b_type_instructions;
+pub(crate) use impl_csr_instructions;
 pub(crate) use impl_i_type_instructions;
 pub(crate) use impl_i_type_shamt_instructions;
 pub(crate) use impl_r_type_instructions;
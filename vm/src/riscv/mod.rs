@@ -1,7 +1,11 @@
 pub(crate) mod decoder;
 pub(crate) mod instructions;
 
-pub use decoder::{decode_instruction, decode_instructions, decode_until_end_of_a_block};
+pub use decoder::{
+    decode_instruction, decode_instructions, decode_until_end_of_a_block, required_extension,
+    scan_undecodable_instructions, scan_unsupported_extensions, Extension, UndecodableInstruction,
+    UnsupportedInstruction,
+};
 pub use instructions::{
     BasicBlock, BasicBlockProgram, BuiltinOpcode, Instruction, InstructionType, Opcode,
 };
@@ -0,0 +1,196 @@
+//! 256-bit modular arithmetic, executed by the `AddMod`, `MulMod` and `InvMod` syscalls.
+//!
+//! Integers are eight u32 limbs, least significant first, i.e. little-endian in memory. Operands
+//! may exceed the modulus, results are always reduced.
+
+/// A 256-bit integer, least significant limb first.
+pub(crate) type U256 = [u32; 8];
+
+const ZERO: U256 = [0; 8];
+const ONE: U256 = [1, 0, 0, 0, 0, 0, 0, 0];
+
+/// Shifts `x` left by one bit, shifting `bit` in.
+fn shl1(x: &mut [u32], bit: bool) {
+    let mut carry = bit as u32;
+    for limb in x.iter_mut() {
+        let next = *limb >> 31;
+        *limb = (*limb << 1) | carry;
+        carry = next;
+    }
+}
+
+/// Whether `x >= y`, for limbs of the same length.
+fn geq(x: &[u32], y: &[u32]) -> bool {
+    x.iter().rev().cmp(y.iter().rev()) != std::cmp::Ordering::Less
+}
+
+/// `x - y` modulo 2^(32・len), for limbs of the same length.
+fn wrapping_sub(x: &mut [u32], y: &[u32]) {
+    let mut borrow = false;
+    for (limb, &other) in x.iter_mut().zip(y) {
+        let (diff, b1) = limb.overflowing_sub(other);
+        let (diff, b2) = diff.overflowing_sub(borrow as u32);
+        *limb = diff;
+        borrow = b1 || b2;
+    }
+}
+
+/// `x + y` modulo 2^256.
+fn wrapping_add(x: &U256, y: &U256) -> U256 {
+    let mut sum = ZERO;
+    let mut carry = 0u64;
+    for i in 0..8 {
+        let limb = x[i] as u64 + y[i] as u64 + carry;
+        sum[i] = limb as u32;
+        carry = limb >> 32;
+    }
+    sum
+}
+
+/// Quotient and remainder of `n` divided by `m`, which must not be zero.
+fn div_rem(n: &[u32], m: &U256) -> (Vec<u32>, U256) {
+    // The remainder is below 2m before subtracting, it needs one more limb than m.
+    let mut divisor = [0u32; 9];
+    divisor[..8].copy_from_slice(m);
+    let mut rem = [0u32; 9];
+    let mut quotient = vec![0u32; n.len()];
+    for i in (0..n.len() * 32).rev() {
+        shl1(&mut rem, (n[i / 32] >> (i % 32)) & 1 == 1);
+        if geq(&rem, &divisor) {
+            wrapping_sub(&mut rem, &divisor);
+            quotient[i / 32] |= 1 << (i % 32);
+        }
+    }
+    (quotient, rem[..8].try_into().unwrap())
+}
+
+fn reduce(n: &[u32], m: &U256) -> U256 {
+    div_rem(n, m).1
+}
+
+/// `(x + y) mod m`, or `None` if `m` is zero.
+pub(crate) fn add_mod(x: &U256, y: &U256, m: &U256) -> Option<U256> {
+    if *m == ZERO {
+        return None;
+    }
+    let (x, y) = (reduce(x, m), reduce(y, m));
+    // Both are below m, so the sum fits in 257 bits.
+    let mut sum = [0u32; 9];
+    sum[..8].copy_from_slice(&wrapping_add(&x, &y));
+    sum[8] = !geq(&sum[..8], &x) as u32;
+    Some(reduce(&sum, m))
+}
+
+/// `(x・y) mod m`, or `None` if `m` is zero.
+pub(crate) fn mul_mod(x: &U256, y: &U256, m: &U256) -> Option<U256> {
+    if *m == ZERO {
+        return None;
+    }
+    let mut product = [0u32; 16];
+    for i in 0..8 {
+        let mut carry = 0u64;
+        for j in 0..8 {
+            let limb = product[i + j] as u64 + x[i] as u64 * y[j] as u64 + carry;
+            product[i + j] = limb as u32;
+            carry = limb >> 32;
+        }
+        product[i + 8] = carry as u32;
+    }
+    Some(reduce(&product, m))
+}
+
+/// `(x - y) mod m`, for `x` and `y` below `m`.
+fn sub_mod(x: &U256, y: &U256, m: &U256) -> U256 {
+    let mut diff = *x;
+    if geq(x, y) {
+        wrapping_sub(&mut diff, y);
+    } else {
+        // x + m - y is below m, the sum may wrap around 2^256 on the way.
+        diff = wrapping_add(&diff, m);
+        wrapping_sub(&mut diff, y);
+    }
+    diff
+}
+
+/// The inverse of `x` modulo `m`, or `None` if `m` is zero or `x` isn't invertible.
+pub(crate) fn inv_mod(x: &U256, m: &U256) -> Option<U256> {
+    if *m == ZERO {
+        return None;
+    }
+    if *m == ONE {
+        return Some(ZERO);
+    }
+
+    // Extended Euclidean algorithm, keeping the Bézout coefficients of x reduced modulo m.
+    let (mut r0, mut r1) = (*m, reduce(x, m));
+    let (mut t0, mut t1) = (ZERO, ONE);
+    while r1 != ZERO {
+        let (quotient, rem) = div_rem(&r0, &r1);
+        let quotient: U256 = quotient.try_into().unwrap();
+        (r0, r1) = (r1, rem);
+
+        let t = sub_mod(&t0, &mul_mod(&quotient, &t1, m)?, m);
+        (t0, t1) = (t1, t);
+    }
+    (r0 == ONE).then_some(t0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn from_u128(value: u128) -> U256 {
+        let mut limbs = ZERO;
+        for (i, limb) in limbs.iter_mut().take(4).enumerate() {
+            *limb = (value >> (32 * i)) as u32;
+        }
+        limbs
+    }
+
+    #[test]
+    fn test_small_operands() {
+        let m = from_u128(1_000_000_007);
+        let (x, y) = (from_u128(123_456_789_012), from_u128(987_654_321));
+
+        let sum = (123_456_789_012u128 + 987_654_321) % 1_000_000_007;
+        assert_eq!(add_mod(&x, &y, &m), Some(from_u128(sum)));
+        let product = 123_456_789_012u128 * 987_654_321 % 1_000_000_007;
+        assert_eq!(mul_mod(&x, &y, &m), Some(from_u128(product)));
+
+        let inverse = inv_mod(&y, &m).unwrap();
+        assert_eq!(mul_mod(&inverse, &y, &m), Some(ONE));
+        assert_eq!(inv_mod(&from_u128(6), &from_u128(9)), None);
+
+        assert_eq!(add_mod(&x, &y, &ZERO), None);
+        assert_eq!(inv_mod(&x, &ZERO), None);
+    }
+
+    #[test]
+    fn test_wide_operands() {
+        // The prime of secp256k1, 2^256 - 2^32 - 977.
+        let p: U256 = [
+            0xFFFFFC2F, 0xFFFFFFFE, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF, 0xFFFFFFFF,
+            0xFFFFFFFF,
+        ];
+        let max = [u32::MAX; 8];
+        // 2^256 - 1 = p + 2^32 + 976
+        let max_mod_p = from_u128((1 << 32) + 976);
+
+        assert_eq!(add_mod(&max, &ZERO, &p), Some(max_mod_p));
+        assert_eq!(
+            add_mod(&max, &max, &p),
+            Some(from_u128(2 * ((1 << 32) + 976)))
+        );
+        assert_eq!(
+            mul_mod(&max, &max, &p),
+            Some(from_u128(((1 << 32) + 976) * ((1 << 32) + 976)))
+        );
+
+        let inverse = inv_mod(&max, &p).unwrap();
+        assert_eq!(mul_mod(&inverse, &max, &p), Some(ONE));
+        // p - 1 is its own inverse.
+        let mut minus_one = p;
+        minus_one[0] -= 1;
+        assert_eq!(inv_mod(&minus_one, &p), Some(minus_one));
+    }
+}
@@ -1,3 +1,8 @@
+mod bigint;
+mod keccak;
+mod sha256;
 mod syscall;
+mod tohost;
 
-pub use syscall::{SyscallCode, SyscallInstruction};
+pub use syscall::{LogLevel, MemoryLayoutField, SyscallCode, SyscallInstruction};
+pub use tohost::ToHost;
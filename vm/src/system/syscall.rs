@@ -19,6 +19,19 @@
 //!    - ReadFromPrivateInput: Read data from a private input tape.
 //!    - OverwriteStackPointer: Modify the stack pointer based on memory layout.
 //!    - OverwriteHeapPointer: Modify the heap pointer based on memory layout.
+//!    - QueryMemoryLayout: Read an address of the memory layout, see [`MemoryLayoutField`].
+//!    - HeapStats: Read the number of heap bytes used (a0 = 0) or remaining (a0 = 1).
+//!    - ReadClock: Read the number of instructions executed so far.
+//!    - Log: Forward a message to the host `tracing` subscriber, at a [`LogLevel`].
+//!    - Alloc: Allocate a0 bytes aligned to a1 from the heap, returning the address or 0.
+//!    - Dealloc: Free the block allocated at a0, returning 0 or u32::MAX if there is none.
+//!    - Yield: Suspend execution and return control to the host, which can resume it later.
+//!    - Sha256Compress: Update the SHA-256 state of 8 words at a0 with the block of 16 words at a1.
+//!    - KeccakPermute: Apply Keccak-f[1600] to the state of 25 little-endian u64 lanes at a0.
+//!    - AddMod, MulMod: Replace the 256-bit integer at a0 with its sum or product with the one at
+//!      a1, modulo the one at a2. Returns 0, or u32::MAX if the modulus is zero.
+//!    - InvMod: Replace the 256-bit integer at a0 with its inverse modulo the one at a1. Returns 0,
+//!      or u32::MAX if there is no inverse.
 //! 3. Handling memory interactions for syscalls.
 //! 4. Writing back results to CPU registers.
 //!
@@ -28,70 +41,35 @@
 //! system.
 use std::collections::{hash_map, HashSet, VecDeque};
 
+pub use nexus_common::abi::{LogLevel, MemoryLayoutField, SyscallCode};
 use nexus_common::cpu::Registers;
 
+use super::{bigint, keccak, sha256};
 use crate::{
     cpu::Cpu,
-    emulator::{Executor, LinearMemoryLayout},
+    emulator::{Executor, HeapStats, LayoutOffsets, LinearMemoryLayout, StdoutHandler},
     error::{Result, VMError},
-    memory::{LoadOp, MemoryProcessor, StoreOp},
+    memory::{LoadOp, MemAccessSize, MemoryProcessor, StoreOp},
     riscv::{BuiltinOpcode, Instruction, Register},
+    WORD_SIZE,
 };
 
-pub enum SyscallCode {
-    // Syscall code defines opcodes start from 0x200
-    Write = 0x200, // Is converted to NOP for tracing
-    Exit = 0x201,
-    // zkVM specific syscall opcodes start from 0x400
-    ReadFromPrivateInput = 0x400,
-    CycleCount = 0x401, // Is converted to NOP for tracing
-    OverwriteStackPointer = 0x402,
-    OverwriteHeapPointer = 0x403,
-    ReadFromAuxiliaryInput = 0x404,
-}
-
-impl SyscallCode {
-    fn try_from(value: u32, pc: u32) -> Result<Self> {
-        let code = match value {
-            0x200 => SyscallCode::Write,
-            0x201 => SyscallCode::Exit,
-            0x400 => SyscallCode::ReadFromPrivateInput,
-            0x401 => SyscallCode::CycleCount,
-            0x402 => SyscallCode::OverwriteStackPointer,
-            0x403 => SyscallCode::OverwriteHeapPointer,
-            //0x404 => SyscallCode::ReadFromAuxiliaryInput,
-            _ => return Err(VMError::UnimplementedSyscall(value, pc)),
-        };
-        Ok(code)
-    }
-}
-
-impl From<u32> for SyscallCode {
-    fn from(value: u32) -> Self {
-        match value {
-            0x200 => SyscallCode::Write,
-            0x201 => SyscallCode::Exit,
-            0x400 => SyscallCode::ReadFromPrivateInput,
-            0x401 => SyscallCode::CycleCount,
-            0x402 => SyscallCode::OverwriteStackPointer,
-            0x403 => SyscallCode::OverwriteHeapPointer,
-            0x404 => SyscallCode::ReadFromAuxiliaryInput,
-            _ => panic!("Invalid syscall code"),
-        }
-    }
-}
-
-impl From<SyscallCode> for u32 {
-    fn from(val: SyscallCode) -> Self {
-        match val {
-            SyscallCode::Write => 0x200,
-            SyscallCode::Exit => 0x201,
-            SyscallCode::ReadFromPrivateInput => 0x400,
-            SyscallCode::CycleCount => 0x401,
-            SyscallCode::OverwriteStackPointer => 0x402,
-            SyscallCode::OverwriteHeapPointer => 0x403,
-            SyscallCode::ReadFromAuxiliaryInput => 0x404,
+/// Number of words of the SHA-256 state.
+const SHA256_STATE_WORDS: usize = 8;
+/// Number of words of a SHA-256 block.
+const SHA256_BLOCK_WORDS: usize = 16;
+/// Number of words of the Keccak-f[1600] state.
+const KECCAK_STATE_WORDS: usize = 50;
+/// Number of words of an operand of the modular arithmetic syscalls.
+const BIGINT_WORDS: usize = 8;
+
+/// Decodes the syscall code in a7 of the `ecall` at `pc`.
+fn decode_syscall_code(value: u32, pc: u32) -> Result<SyscallCode> {
+    match SyscallCode::from_code(value) {
+        Some(SyscallCode::ReadFromAuxiliaryInput) | None => {
+            Err(VMError::UnimplementedSyscall(value, pc))
         }
+        Some(code) => Ok(code),
     }
 }
 
@@ -117,6 +95,13 @@ pub struct SyscallInstruction {
     /// These correspond to registers X10 through X16 ("a0" through "a6").
     /// The number and meaning of arguments depend on the specific system call.
     args: Vec<u32>,
+
+    /// Words read from memory by `memory_read`, and written to memory by `memory_write` once the
+    /// syscall is executed.
+    ///
+    /// Only used by syscalls accessing memory in a provable way, i.e. the hash and modular
+    /// arithmetic syscalls. The state or operand they update in place comes first.
+    words: Vec<u32>,
 }
 
 impl SyscallInstruction {
@@ -128,7 +113,7 @@ impl SyscallInstruction {
             ));
         }
         Ok(Self {
-            code: SyscallCode::try_from(cpu.registers[Register::X17], cpu.pc.value)?,
+            code: decode_syscall_code(cpu.registers[Register::X17], cpu.pc.value)?,
             result: Some((Register::X10, u32::MAX)),
             args: vec![
                 cpu.registers[Register::X10],
@@ -139,16 +124,19 @@ impl SyscallInstruction {
                 cpu.registers[Register::X15],
                 cpu.registers[Register::X16],
             ],
+            words: Vec::new(),
         })
     }
 
     /// Executes the write syscall to output data to a file descriptor.
     ///
     /// This function currently only supports writing to standard output (stdout).
-    /// It reads data from memory and prints it to the console.
+    /// It reads data from memory and passes it to the stdout handler, if any, and captures it in
+    /// `logs` if enabled. Otherwise it is printed to the console.
     fn execute_write(
         &mut self,
         logs: &mut Option<Vec<Vec<u8>>>,
+        handler: Option<&StdoutHandler>,
         memory: &impl MemoryProcessor,
         fd: u32,
         buf_addr: u32,
@@ -158,9 +146,12 @@ impl SyscallInstruction {
         if fd == 1 {
             let buffer = memory.read_bytes(buf_addr, count as _)?;
 
+            if let Some(handler) = handler {
+                handler.write(&buffer);
+            }
             if let Some(logger) = logs {
                 logger.push(buffer.clone());
-            } else {
+            } else if handler.is_none() {
                 print!("{}", String::from_utf8_lossy(&buffer));
             }
 
@@ -172,6 +163,43 @@ impl SyscallInstruction {
         Ok(())
     }
 
+    /// Executes the log syscall, forwarding a message to the host `tracing` subscriber under the
+    /// `nexus_vm::guest` target, on the first pass only.
+    ///
+    /// Unlike writes to stdout, messages are neither collected in the logs of the executor nor
+    /// returned to the guest. Only the last error is kept, on both passes, as it's how the guest
+    /// reports its panic message.
+    fn execute_log(
+        &mut self,
+        executor: &mut Executor,
+        memory: &impl MemoryProcessor,
+        level: u32,
+        buf_addr: u32,
+        count: u32,
+        second_pass: bool,
+    ) -> Result<()> {
+        let buffer = memory.read_bytes(buf_addr, count as _)?;
+        let message = String::from_utf8_lossy(&buffer);
+        self.result = None;
+        let level = LogLevel::from_u32(level);
+        if level == LogLevel::Error {
+            executor.set_last_error_log(message.to_string());
+        }
+        if second_pass {
+            return Ok(());
+        }
+
+        match level {
+            LogLevel::Error => tracing::error!(target: "nexus_vm::guest", "{message}"),
+            LogLevel::Warn => tracing::warn!(target: "nexus_vm::guest", "{message}"),
+            LogLevel::Info => tracing::info!(target: "nexus_vm::guest", "{message}"),
+            LogLevel::Debug => tracing::debug!(target: "nexus_vm::guest", "{message}"),
+            LogLevel::Trace => tracing::trace!(target: "nexus_vm::guest", "{message}"),
+        }
+
+        Ok(())
+    }
+
     /// Executes the exit syscall to terminate the program.
     ///
     /// This function sets the exit code and signals the VM to terminate execution.
@@ -256,9 +284,10 @@ impl SyscallInstruction {
     fn execute_overwrite_stack_pointer(
         &mut self,
         memory_layout: Option<LinearMemoryLayout>,
+        offsets: LayoutOffsets,
     ) -> Result<()> {
         if let Some(layout) = memory_layout {
-            self.result = Some((Register::X2, layout.stack_top()));
+            self.result = Some((Register::X2, layout.stack_top() - offsets.stack));
         }
 
         Ok(())
@@ -267,9 +296,10 @@ impl SyscallInstruction {
     fn execute_overwrite_heap_pointer(
         &mut self,
         memory_layout: Option<LinearMemoryLayout>,
+        offsets: LayoutOffsets,
     ) -> Result<()> {
         if let Some(layout) = memory_layout {
-            self.result = Some((Register::X10, layout.heap_start()));
+            self.result = Some((Register::X10, layout.heap_start() + offsets.heap));
         } else {
             self.result = Some((Register::X10, 0)); // 0 indicates no overwrite is necessary
         }
@@ -277,9 +307,178 @@ impl SyscallInstruction {
         Ok(())
     }
 
+    /// Returns the address of the requested layout field in a0.
+    ///
+    /// The layout is only reported if it was fixed before the first pass, 0 is returned otherwise,
+    /// in both passes. Unknown fields return u32::MAX.
+    fn execute_query_memory_layout(
+        &mut self,
+        memory_layout: Option<LinearMemoryLayout>,
+        field: u32,
+    ) -> Result<()> {
+        let value = match (memory_layout, MemoryLayoutField::from_u32(field)) {
+            (_, None) => u32::MAX,
+            (None, Some(_)) => 0,
+            (Some(layout), Some(field)) => layout.field_address(field),
+        };
+        self.result = Some((Register::X10, value));
+
+        Ok(())
+    }
+
+    /// Returns the number of heap bytes used (`field` 0) or left (`field` 1) in a0.
+    ///
+    /// Unknown fields return u32::MAX.
+    fn execute_heap_stats(
+        &mut self,
+        heap_stats: &HeapStats,
+        stack_pointer: u32,
+        field: u32,
+    ) -> Result<()> {
+        let value = match field {
+            0 => heap_stats.used(),
+            1 => heap_stats.remaining(stack_pointer),
+            _ => u32::MAX,
+        };
+        self.result = Some((Register::X10, value));
+
+        Ok(())
+    }
+
+    /// Returns the global clock in a0, saturating at u32::MAX.
+    ///
+    /// The clock counts executed instructions, so it is the same for every execution of the
+    /// program on the same inputs.
+    fn execute_read_clock(&mut self, global_clock: usize) -> Result<()> {
+        let value = u32::try_from(global_clock).unwrap_or(u32::MAX);
+        self.result = Some((Register::X10, value));
+
+        Ok(())
+    }
+
+    /// Allocates `size` bytes aligned to `align` from the heap, see [`crate::emulator::AllocatorStats`].
+    ///
+    /// Returns the address in a0, or 0 if the heap is exhausted or `align` isn't a power of two.
+    fn execute_alloc(&mut self, executor: &mut Executor, size: u32, align: u32) -> Result<()> {
+        let stack_pointer = executor.cpu.registers[Register::X2];
+        let address = executor
+            .heap_allocator()
+            .alloc(size, align, stack_pointer)
+            .unwrap_or(0);
+        self.result = Some((Register::X10, address));
+
+        Ok(())
+    }
+
+    /// Frees the heap block allocated at `address`.
+    ///
+    /// Returns 0 in a0, or u32::MAX if no block is allocated at `address`.
+    fn execute_dealloc(&mut self, executor: &mut Executor, address: u32) -> Result<()> {
+        let value = if executor.heap_allocator().dealloc(address) {
+            0
+        } else {
+            u32::MAX
+        };
+        self.result = Some((Register::X10, value));
+
+        Ok(())
+    }
+
+    /// Compresses the block read by `memory_read` into the state, which `memory_write` stores back.
+    ///
+    /// Words of the state and the block are native u32 values, the guest reads the words of its
+    /// message big-endian. Nothing is returned in a0.
+    fn execute_sha256_compress(&mut self) -> Result<()> {
+        let (state, block) = self.words.split_at(SHA256_STATE_WORDS);
+        let mut state: [u32; SHA256_STATE_WORDS] = state.try_into().expect("state words are read");
+        let block: &[u32; SHA256_BLOCK_WORDS] = block.try_into().expect("block words are read");
+        sha256::compress(&mut state, block);
+
+        self.words = state.to_vec();
+        self.result = None;
+        Ok(())
+    }
+
+    /// Permutes the state read by `memory_read`, which `memory_write` stores back. Lanes are
+    /// little-endian, the low word first. Nothing is returned in a0.
+    fn execute_keccak_permute(&mut self) -> Result<()> {
+        let mut state: [u64; 25] = std::array::from_fn(|i| {
+            self.words[2 * i] as u64 | (self.words[2 * i + 1] as u64) << 32
+        });
+        keccak::keccak_f1600(&mut state);
+
+        self.words = state
+            .iter()
+            .flat_map(|&lane| [lane as u32, (lane >> 32) as u32])
+            .collect();
+        self.result = None;
+        Ok(())
+    }
+
+    /// Computes the modular sum, product or inverse of the operands read by `memory_read`, which
+    /// `memory_write` stores back in place of the first operand. The modulus comes last.
+    ///
+    /// Returns 0 in a0, or u32::MAX if the modulus is zero or the inverse doesn't exist, leaving
+    /// the first operand unchanged.
+    fn execute_modular_arithmetic(&mut self) -> Result<()> {
+        let operands: Vec<bigint::U256> = self
+            .words
+            .chunks_exact(BIGINT_WORDS)
+            .map(|chunk| chunk.try_into().expect("operand words are read"))
+            .collect();
+        let modulus = operands.last().expect("modulus words are read");
+        let value = match self.code {
+            SyscallCode::AddMod => bigint::add_mod(&operands[0], &operands[1], modulus),
+            SyscallCode::MulMod => bigint::mul_mod(&operands[0], &operands[1], modulus),
+            SyscallCode::InvMod => bigint::inv_mod(&operands[0], modulus),
+            _ => unreachable!(),
+        };
+
+        self.result = Some((Register::X10, if value.is_some() { 0 } else { u32::MAX }));
+        self.words = value.unwrap_or(operands[0]).to_vec();
+        Ok(())
+    }
+
+    /// Suspends execution after the yield syscall, see [`VMError::Yielded`].
+    ///
+    /// Nothing is returned to the guest.
+    fn execute_yield(&mut self, executor: &mut Executor) -> Result<()> {
+        executor.request_yield();
+        self.result = None;
+
+        Ok(())
+    }
+
     // Reads from memory for syscall instruction.
-    pub fn memory_read(&mut self, _memory: &impl MemoryProcessor) -> Result<HashSet<LoadOp>> {
-        Ok(HashSet::<LoadOp>::new())
+    pub fn memory_read(&mut self, memory: &impl MemoryProcessor) -> Result<HashSet<LoadOp>> {
+        let words_at = |address: u32, len: usize| {
+            (0..len).map(move |i| address.wrapping_add((i * WORD_SIZE) as u32))
+        };
+        let addresses: Vec<u32> = match self.code {
+            // The state, followed by the block.
+            SyscallCode::Sha256Compress => words_at(self.args[0], SHA256_STATE_WORDS)
+                .chain(words_at(self.args[1], SHA256_BLOCK_WORDS))
+                .collect(),
+            SyscallCode::KeccakPermute => words_at(self.args[0], KECCAK_STATE_WORDS).collect(),
+            // The operands, followed by the modulus.
+            SyscallCode::AddMod | SyscallCode::MulMod => words_at(self.args[0], BIGINT_WORDS)
+                .chain(words_at(self.args[1], BIGINT_WORDS))
+                .chain(words_at(self.args[2], BIGINT_WORDS))
+                .collect(),
+            SyscallCode::InvMod => words_at(self.args[0], BIGINT_WORDS)
+                .chain(words_at(self.args[1], BIGINT_WORDS))
+                .collect(),
+            _ => Vec::new(),
+        };
+
+        let mut load_ops = HashSet::<LoadOp>::new();
+        for address in addresses {
+            let op = memory.read(address, MemAccessSize::Word)?;
+            let LoadOp::Op(_, _, value) = op;
+            self.words.push(value);
+            load_ops.insert(op);
+        }
+        Ok(load_ops)
     }
 
     /// Executes the syscall instruction.
@@ -311,7 +510,8 @@ impl SyscallInstruction {
                 let fd = self.args[0];
                 let buf = self.args[1];
                 let count = self.args[2];
-                self.execute_write(&mut executor.logs, memory, fd, buf, count)
+                let handler = executor.stdout_handler().cloned();
+                self.execute_write(&mut executor.logs, handler.as_ref(), memory, fd, buf, count)
             }
 
             SyscallCode::CycleCount => {
@@ -343,18 +543,86 @@ impl SyscallInstruction {
             }
 
             SyscallCode::OverwriteStackPointer => {
-                self.execute_overwrite_stack_pointer(memory_layout)
+                let offsets = memory_layout.map(|layout| executor.layout_offsets(&layout));
+                self.execute_overwrite_stack_pointer(memory_layout, offsets.unwrap_or_default())
             }
 
-            SyscallCode::OverwriteHeapPointer => self.execute_overwrite_heap_pointer(memory_layout),
+            SyscallCode::OverwriteHeapPointer => {
+                let offsets = memory_layout.map(|layout| executor.layout_offsets(&layout));
+                self.execute_overwrite_heap_pointer(memory_layout, offsets.unwrap_or_default())
+            }
+
+            SyscallCode::QueryMemoryLayout => {
+                let field = self.args[0];
+                self.execute_query_memory_layout(executor.reported_layout(), field)
+            }
+
+            SyscallCode::HeapStats => {
+                let field = self.args[0];
+                let stack_pointer = executor.cpu.registers[Register::X2];
+                self.execute_heap_stats(executor.heap_stats(), stack_pointer, field)
+            }
+
+            SyscallCode::ReadClock => self.execute_read_clock(executor.global_clock),
+
+            SyscallCode::Log => {
+                let level = self.args[0];
+                let buf = self.args[1];
+                let count = self.args[2];
+                self.execute_log(executor, memory, level, buf, count, second_pass)
+            }
+
+            SyscallCode::Alloc => {
+                let size = self.args[0];
+                let align = self.args[1];
+                self.execute_alloc(executor, size, align)
+            }
+
+            SyscallCode::Dealloc => {
+                let address = self.args[0];
+                self.execute_dealloc(executor, address)
+            }
+
+            SyscallCode::Yield => {
+                // Execution isn't suspended on second pass, the host has already scheduled it.
+                if second_pass {
+                    self.result = None;
+                    return Ok(());
+                }
+
+                self.execute_yield(executor)
+            }
+
+            SyscallCode::Sha256Compress => self.execute_sha256_compress(),
+
+            SyscallCode::KeccakPermute => self.execute_keccak_permute(),
+
+            SyscallCode::AddMod | SyscallCode::MulMod | SyscallCode::InvMod => {
+                self.execute_modular_arithmetic()
+            }
 
             SyscallCode::ReadFromAuxiliaryInput => unreachable!(), // unreachable since parsing of the code will fail
         }
     }
 
     // Writes to memory for syscall instructions.
-    pub fn memory_write(&self, _memory: &mut impl MemoryProcessor) -> Result<HashSet<StoreOp>> {
-        Ok(HashSet::<StoreOp>::new())
+    pub fn memory_write(&self, memory: &mut impl MemoryProcessor) -> Result<HashSet<StoreOp>> {
+        let mut store_ops = HashSet::<StoreOp>::new();
+        if matches!(
+            self.code,
+            SyscallCode::Sha256Compress
+                | SyscallCode::KeccakPermute
+                | SyscallCode::AddMod
+                | SyscallCode::MulMod
+                | SyscallCode::InvMod
+        ) {
+            let state = self.args[0];
+            for (i, &word) in self.words.iter().enumerate() {
+                let address = state.wrapping_add((i * WORD_SIZE) as u32);
+                store_ops.insert(memory.write(address, MemAccessSize::Word, word)?);
+            }
+        }
+        Ok(store_ops)
     }
 
     // All the write back to registers is done in the write_back function
@@ -373,9 +641,10 @@ impl SyscallInstruction {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::emulator::HarvardEmulator;
-    use crate::memory::{VariableMemory, RW};
+    use crate::emulator::{EmulatorOptions, HarvardEmulator, HeapAllocator};
+    use crate::memory::{MemAccessSize, VariableMemory, RW};
     use crate::riscv::{BuiltinOpcode, Opcode};
+    use std::sync::{Arc, Mutex};
 
     fn setup_emulator() -> HarvardEmulator {
         let mut emul = HarvardEmulator::default();
@@ -397,6 +666,7 @@ mod tests {
             code: SyscallCode::Write,
             result: Some((Register::X10, 0)),
             args: vec![fd, buf_addr, buf_len as _, 0, 0, 0, 0],
+            words: Vec::new(),
         };
 
         emulator
@@ -404,7 +674,14 @@ mod tests {
             .write_bytes(buf_addr, buf)
             .expect("Failed to write to memory");
         syscall_instruction
-            .execute_write(&mut None, &emulator.data_memory, fd, buf_addr, buf_len as _)
+            .execute_write(
+                &mut None,
+                None,
+                &emulator.data_memory,
+                fd,
+                buf_addr,
+                buf_len as _,
+            )
             .expect("Failed to execute write syscall");
         syscall_instruction.write_back(&mut emulator.executor.cpu);
 
@@ -414,6 +691,44 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_execute_write_stdout_handler() {
+        let buf = b"Hello";
+        let mut emulator = setup_emulator();
+        emulator
+            .data_memory
+            .write_bytes(0, buf)
+            .expect("Failed to write to memory");
+
+        let stdout = Arc::new(Mutex::new(Vec::new()));
+        let handler = {
+            let stdout = stdout.clone();
+            StdoutHandler::new(move |bytes| stdout.lock().unwrap().extend_from_slice(bytes))
+        };
+        let mut logs = Some(Vec::new());
+        let mut syscall_instruction = SyscallInstruction {
+            code: SyscallCode::Write,
+            result: Some((Register::X10, 0)),
+            args: vec![1, 0, buf.len() as _, 0, 0, 0, 0],
+            words: Vec::new(),
+        };
+        for _ in 0..2 {
+            syscall_instruction
+                .execute_write(
+                    &mut logs,
+                    Some(&handler),
+                    &emulator.data_memory,
+                    1,
+                    0,
+                    buf.len() as _,
+                )
+                .expect("Failed to execute write syscall");
+        }
+
+        assert_eq!(*stdout.lock().unwrap(), b"HelloHello");
+        assert_eq!(logs, Some(vec![buf.to_vec(), buf.to_vec()]));
+    }
+
     #[test]
     fn test_execute_write_invalid_fd() {
         let fd = 2; // Invalid fd
@@ -425,6 +740,7 @@ mod tests {
             code: SyscallCode::Write,
             result: Some((Register::X10, 0)),
             args: vec![fd, buf_addr, buf_len as _, 0, 0, 0, 0],
+            words: Vec::new(),
         };
 
         emulator
@@ -432,7 +748,14 @@ mod tests {
             .write_bytes(buf_addr, buf)
             .expect("Failed to write to memory");
         syscall_instruction
-            .execute_write(&mut None, &emulator.data_memory, fd, buf_addr, buf_len as _)
+            .execute_write(
+                &mut None,
+                None,
+                &emulator.data_memory,
+                fd,
+                buf_addr,
+                buf_len as _,
+            )
             .expect("Failed to execute write syscall");
         syscall_instruction.write_back(&mut emulator.executor.cpu);
 
@@ -450,6 +773,7 @@ mod tests {
             code: SyscallCode::Exit,
             result: Some((Register::X10, 0)),
             args: vec![error_code, 0, 0, 0, 0, 0, 0],
+            words: Vec::new(),
         };
 
         let result = syscall_instruction.execute_exit(error_code);
@@ -466,9 +790,11 @@ mod tests {
             code: SyscallCode::OverwriteStackPointer,
             result: Some((Register::X10, 0)),
             args: vec![0, 0, 0, 0, 0, 0, 0],
+            words: Vec::new(),
         };
 
-        let _ = syscall_instruction.execute_overwrite_stack_pointer(Some(memory_layout));
+        let _ = syscall_instruction
+            .execute_overwrite_stack_pointer(Some(memory_layout), LayoutOffsets::default());
         syscall_instruction.write_back(&mut emulator.executor.cpu);
 
         assert_eq!(
@@ -485,9 +811,11 @@ mod tests {
             code: SyscallCode::OverwriteStackPointer,
             result: Some((Register::X10, 0)),
             args: vec![0, 0, 0, 0, 0, 0, 0],
+            words: Vec::new(),
         };
 
-        let _ = syscall_instruction.execute_overwrite_heap_pointer(Some(memory_layout));
+        let _ = syscall_instruction
+            .execute_overwrite_heap_pointer(Some(memory_layout), LayoutOffsets::default());
         syscall_instruction.write_back(&mut emulator.executor.cpu);
 
         assert_eq!(
@@ -496,6 +824,354 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_randomized_layout_pointers() {
+        let memory_layout = LinearMemoryLayout::default();
+        let mut emulator = setup_emulator();
+        emulator
+            .executor
+            .set_options(&EmulatorOptions {
+                randomize_layout: Some(7),
+                ..Default::default()
+            })
+            .unwrap();
+        let offsets = emulator.executor.layout_offsets(&memory_layout);
+        assert_eq!(offsets, memory_layout.random_offsets(7));
+
+        let mut syscall_instruction = SyscallInstruction {
+            code: SyscallCode::OverwriteStackPointer,
+            result: None,
+            args: vec![0, 0, 0, 0, 0, 0, 0],
+            words: Vec::new(),
+        };
+        let _ = syscall_instruction.execute_overwrite_stack_pointer(Some(memory_layout), offsets);
+        syscall_instruction.write_back(&mut emulator.executor.cpu);
+        let _ = syscall_instruction.execute_overwrite_heap_pointer(Some(memory_layout), offsets);
+        syscall_instruction.write_back(&mut emulator.executor.cpu);
+
+        let stack_pointer = emulator.executor.cpu.registers.read(Register::X2);
+        let heap_pointer = emulator.executor.cpu.registers.read(Register::X10);
+        assert_eq!(stack_pointer, memory_layout.stack_top() - offsets.stack);
+        assert_eq!(heap_pointer, memory_layout.heap_start() + offsets.heap);
+        assert!(stack_pointer > memory_layout.stack_bottom());
+        assert!(heap_pointer < memory_layout.heap_end());
+    }
+
+    #[test]
+    fn test_execute_query_memory_layout() {
+        let memory_layout = LinearMemoryLayout::default();
+        let mut emulator = setup_emulator();
+        let mut syscall_instruction = SyscallInstruction {
+            code: SyscallCode::QueryMemoryLayout,
+            result: Some((Register::X10, 0)),
+            args: vec![0, 0, 0, 0, 0, 0, 0],
+            words: Vec::new(),
+        };
+
+        for field in MemoryLayoutField::ALL {
+            let _ =
+                syscall_instruction.execute_query_memory_layout(Some(memory_layout), field as u32);
+            syscall_instruction.write_back(&mut emulator.executor.cpu);
+            assert_eq!(
+                emulator.executor.cpu.registers.read(Register::X10),
+                memory_layout.field_address(field)
+            );
+        }
+        assert_eq!(
+            memory_layout.field_address(MemoryLayoutField::StackTop),
+            memory_layout.stack_top()
+        );
+
+        // Unknown unless the layout is fixed.
+        let _ = syscall_instruction
+            .execute_query_memory_layout(None, MemoryLayoutField::HeapStart as u32);
+        syscall_instruction.write_back(&mut emulator.executor.cpu);
+        assert_eq!(emulator.executor.cpu.registers.read(Register::X10), 0);
+
+        let _ = syscall_instruction.execute_query_memory_layout(Some(memory_layout), 11);
+        syscall_instruction.write_back(&mut emulator.executor.cpu);
+        assert_eq!(
+            emulator.executor.cpu.registers.read(Register::X10),
+            u32::MAX
+        );
+    }
+
+    #[test]
+    fn test_execute_heap_stats() {
+        let mut heap_stats = HeapStats::new(0x1000, 0x2000);
+        let store_ops = HashSet::from([StoreOp::Op(MemAccessSize::Word, 0x1100, 0, 0)]);
+        heap_stats.update(&store_ops, 0x1800);
+
+        let mut emulator = setup_emulator();
+        let mut syscall_instruction = SyscallInstruction {
+            code: SyscallCode::HeapStats,
+            result: Some((Register::X10, 0)),
+            args: vec![0, 0, 0, 0, 0, 0, 0],
+            words: Vec::new(),
+        };
+
+        for (field, expected) in [(0, 0x104), (1, 0x6FC), (2, u32::MAX)] {
+            let _ = syscall_instruction.execute_heap_stats(&heap_stats, 0x1800, field);
+            syscall_instruction.write_back(&mut emulator.executor.cpu);
+            assert_eq!(
+                emulator.executor.cpu.registers.read(Register::X10),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_execute_read_clock() {
+        let mut emulator = setup_emulator();
+        let mut syscall_instruction = SyscallInstruction {
+            code: SyscallCode::ReadClock,
+            result: Some((Register::X10, 0)),
+            args: vec![0, 0, 0, 0, 0, 0, 0],
+            words: Vec::new(),
+        };
+
+        for (clock, expected) in [(42, 42), (u32::MAX as usize + 1, u32::MAX)] {
+            let _ = syscall_instruction.execute_read_clock(clock);
+            syscall_instruction.write_back(&mut emulator.executor.cpu);
+            assert_eq!(
+                emulator.executor.cpu.registers.read(Register::X10),
+                expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_execute_alloc() {
+        let mut emulator = setup_emulator();
+        *emulator.executor.heap_allocator() = HeapAllocator::new(0x1000, 0x2000);
+        emulator.executor.cpu.registers.write(Register::X2, 0x1800);
+        let mut syscall = |code, args: [u32; 2]| {
+            let mut syscall_instruction = SyscallInstruction {
+                code,
+                result: None,
+                args: vec![args[0], args[1], 0, 0, 0, 0, 0],
+                words: Vec::new(),
+            };
+            syscall_instruction
+                .execute(&mut emulator.executor, &emulator.data_memory, None, false)
+                .unwrap();
+            syscall_instruction.get_result()
+        };
+
+        let allocated = Some((Register::X10, 0x1000));
+        assert_eq!(syscall(SyscallCode::Alloc, [0x20, 16]), allocated);
+        assert_eq!(
+            syscall(SyscallCode::Alloc, [0x20, 3]),
+            Some((Register::X10, 0))
+        );
+        // The heap ends at the stack pointer.
+        assert_eq!(
+            syscall(SyscallCode::Alloc, [0x800, 4]),
+            Some((Register::X10, 0))
+        );
+        assert_eq!(
+            syscall(SyscallCode::Dealloc, [0x1000, 0]),
+            Some((Register::X10, 0))
+        );
+        assert_eq!(
+            syscall(SyscallCode::Dealloc, [0x1000, 0]),
+            Some((Register::X10, u32::MAX))
+        );
+        assert_eq!(syscall(SyscallCode::Alloc, [0x20, 16]), allocated);
+
+        let stats = emulator.executor.allocator_stats();
+        assert_eq!((stats.allocations, stats.deallocations), (2, 1));
+        assert_eq!((stats.in_use, stats.out_of_memory), (0x20, 1));
+    }
+
+    #[test]
+    fn test_execute_sha256_compress() {
+        let (state_addr, block_addr) = (0x100, 0x200);
+        let h0: [u32; 8] = [
+            0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab,
+            0x5be0cd19,
+        ];
+        // "abc", padded to a single block.
+        let mut block = [0u32; 16];
+        block[0] = 0x61626380;
+        block[15] = 24;
+
+        let mut emulator = setup_emulator();
+        for (base, words) in [(state_addr, &h0[..]), (block_addr, &block[..])] {
+            for (i, &word) in words.iter().enumerate() {
+                let address = base + (i * WORD_SIZE) as u32;
+                emulator
+                    .data_memory
+                    .write(address, MemAccessSize::Word, word)
+                    .unwrap();
+            }
+        }
+
+        let mut syscall_instruction = SyscallInstruction {
+            code: SyscallCode::Sha256Compress,
+            result: None,
+            args: vec![state_addr, block_addr, 0, 0, 0, 0, 0],
+            words: Vec::new(),
+        };
+        let load_ops = syscall_instruction
+            .memory_read(&emulator.data_memory)
+            .unwrap();
+        assert_eq!(load_ops.len(), 24);
+        syscall_instruction
+            .execute(&mut emulator.executor, &emulator.data_memory, None, false)
+            .unwrap();
+        let store_ops = syscall_instruction
+            .memory_write(&mut emulator.data_memory)
+            .unwrap();
+        assert_eq!(store_ops.len(), 8);
+        assert_eq!(syscall_instruction.get_result(), None);
+
+        let digest: Vec<u32> = (0..8)
+            .map(|i| {
+                let address = state_addr + (i * WORD_SIZE) as u32;
+                let LoadOp::Op(_, _, word) = emulator
+                    .data_memory
+                    .read(address, MemAccessSize::Word)
+                    .unwrap();
+                word
+            })
+            .collect();
+        assert_eq!(
+            digest,
+            [
+                0xba7816bf, 0x8f01cfea, 0x414140de, 0x5dae2223, 0xb00361a3, 0x96177a9c, 0xb410ff61,
+                0xf20015ad
+            ]
+        );
+    }
+
+    #[test]
+    fn test_execute_keccak_permute() {
+        // Keccak-256 of the empty message, padded to a single block of 136 bytes.
+        let state_addr = 0x100;
+        let mut emulator = setup_emulator();
+        emulator
+            .data_memory
+            .write(state_addr, MemAccessSize::Word, 0x01)
+            .unwrap();
+        emulator
+            .data_memory
+            .write(state_addr + 132, MemAccessSize::Word, 0x80 << 24)
+            .unwrap();
+
+        let mut syscall_instruction = SyscallInstruction {
+            code: SyscallCode::KeccakPermute,
+            result: None,
+            args: vec![state_addr, 0, 0, 0, 0, 0, 0],
+            words: Vec::new(),
+        };
+        let load_ops = syscall_instruction
+            .memory_read(&emulator.data_memory)
+            .unwrap();
+        assert_eq!(load_ops.len(), 50);
+        syscall_instruction
+            .execute(&mut emulator.executor, &emulator.data_memory, None, false)
+            .unwrap();
+        let store_ops = syscall_instruction
+            .memory_write(&mut emulator.data_memory)
+            .unwrap();
+        assert_eq!(store_ops.len(), 50);
+
+        let digest = emulator.data_memory.read_bytes(state_addr, 32).unwrap();
+        assert_eq!(
+            digest,
+            [
+                0xc5, 0xd2, 0x46, 0x01, 0x86, 0xf7, 0x23, 0x3c, 0x92, 0x7e, 0x7d, 0xb2, 0xdc, 0xc7,
+                0x03, 0xc0, 0xe5, 0x00, 0xb6, 0x53, 0xca, 0x82, 0x27, 0x3b, 0x7b, 0xfa, 0xd8, 0x04,
+                0x5d, 0x85, 0xa4, 0x70
+            ]
+        );
+    }
+
+    #[test]
+    fn test_execute_modular_arithmetic() {
+        let (x_addr, y_addr, m_addr) = (0x100, 0x200, 0x300);
+        let mut emulator = setup_emulator();
+        let mut run = |code: SyscallCode, x: u32, y: u32, m: u32| {
+            for (address, value) in [(x_addr, x), (y_addr, y), (m_addr, m)] {
+                // Only the low word of the 256-bit operands is set.
+                emulator.data_memory.write_bytes(address, &[0; 32]).unwrap();
+                emulator
+                    .data_memory
+                    .write(address, MemAccessSize::Word, value)
+                    .unwrap();
+            }
+            let args = match code {
+                SyscallCode::InvMod => vec![x_addr, m_addr, 0, 0, 0, 0, 0],
+                _ => vec![x_addr, y_addr, m_addr, 0, 0, 0, 0],
+            };
+            let mut syscall_instruction = SyscallInstruction {
+                code,
+                result: None,
+                args,
+                words: Vec::new(),
+            };
+            syscall_instruction
+                .memory_read(&emulator.data_memory)
+                .unwrap();
+            syscall_instruction
+                .execute(&mut emulator.executor, &emulator.data_memory, None, false)
+                .unwrap();
+            let store_ops = syscall_instruction
+                .memory_write(&mut emulator.data_memory)
+                .unwrap();
+            assert_eq!(store_ops.len(), 8);
+
+            let LoadOp::Op(_, _, value) = emulator
+                .data_memory
+                .read(x_addr, MemAccessSize::Word)
+                .unwrap();
+            let (_, status) = syscall_instruction.get_result().unwrap();
+            (status, value)
+        };
+
+        assert_eq!(run(SyscallCode::AddMod, 90, 20, 97), (0, 13));
+        assert_eq!(run(SyscallCode::MulMod, 90, 20, 97), (0, 54));
+        assert_eq!(run(SyscallCode::InvMod, 90, 0, 97), (0, 83));
+        // No inverse, the operand is left unchanged.
+        assert_eq!(run(SyscallCode::InvMod, 6, 0, 9), (u32::MAX, 6));
+        assert_eq!(run(SyscallCode::AddMod, 90, 20, 0), (u32::MAX, 90));
+    }
+
+    #[test]
+    fn test_execute_log() {
+        let buf = b"checkpoint";
+        let mut emulator = setup_emulator();
+        emulator
+            .data_memory
+            .write_bytes(0, buf)
+            .expect("Failed to write to memory");
+        emulator.executor.logs = Some(Vec::new());
+
+        let mut syscall_instruction = SyscallInstruction {
+            code: SyscallCode::Log,
+            result: Some((Register::X10, u32::MAX)),
+            args: vec![LogLevel::Info as u32, 0, buf.len() as u32, 0, 0, 0, 0],
+            words: Vec::new(),
+        };
+        syscall_instruction
+            .execute(&mut emulator.executor, &emulator.data_memory, None, false)
+            .unwrap();
+
+        // Nothing is returned to the guest, or collected with the output of the program.
+        assert_eq!(syscall_instruction.get_result(), None);
+        assert_eq!(emulator.executor.logs, Some(Vec::new()));
+        assert_eq!(emulator.executor.last_error_log(), None);
+        assert_eq!(LogLevel::from_u32(7), LogLevel::Error);
+
+        // Errors are kept, on the second pass too.
+        syscall_instruction.args[0] = LogLevel::Error as u32;
+        syscall_instruction
+            .execute(&mut emulator.executor, &emulator.data_memory, None, true)
+            .unwrap();
+        assert_eq!(emulator.executor.last_error_log(), Some("checkpoint"));
+    }
+
     #[test]
     fn test_execute_cyclecount() {
         let buf = b"^#fib";
@@ -506,6 +1182,7 @@ mod tests {
             code: SyscallCode::CycleCount,
             result: Some((Register::X10, 0)),
             args: vec![buf_addr, buf_len as _, 0, 0, 0, 0, 0],
+            words: Vec::new(),
         };
 
         emulator
@@ -573,6 +1250,7 @@ mod tests {
             code: SyscallCode::ReadFromPrivateInput,
             result: Some((Register::X10, 0)),
             args: vec![],
+            words: Vec::new(),
         };
 
         // Test reading values
@@ -0,0 +1,155 @@
+//! Support for the `tohost`/`fromhost` protocol of riscv-tests and bare-metal test kernels.
+//!
+//! Programs following it don't use Nexus syscalls, they communicate with the host by storing to
+//! the 64-bit `tohost` symbol:
+//!
+//! - An odd value ends the execution, with exit code `value >> 1`. riscv-tests store `1` on
+//!   success and `test_number << 1 | 1` on failure.
+//! - An even value is the address of a `magic_mem` buffer of 64-bit words `[which, arg0, arg1,
+//!   arg2]` requesting a syscall. Only `write` (64) to stdout and `exit` (93) are supported, the
+//!   result is stored to `magic_mem[0]` and acknowledged by storing `1` to `fromhost`.
+//!
+//! The host accesses memory outside of any instruction, so programs using the protocol can't be
+//! proven.
+
+use std::collections::HashSet;
+
+use crate::{
+    elf::Symbol,
+    error::{Result, VMError},
+    memory::{MemAccessSize, MemoryProcessor, StoreOp},
+};
+
+const SYS_WRITE: u32 = 64;
+const SYS_EXIT: u32 = 93;
+const ENOSYS: u32 = 38;
+
+/// Addresses of the `tohost` and `fromhost` symbols of a program.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ToHost {
+    tohost: u32,
+    fromhost: Option<u32>,
+}
+
+impl ToHost {
+    /// Finds the `tohost` and `fromhost` symbols, returns `None` if the program has no `tohost`.
+    pub fn from_symbols(symbols: &[Symbol]) -> Option<Self> {
+        let find = |name: &str| symbols.iter().find(|s| s.name == name).map(|s| s.address);
+        Some(Self {
+            tohost: find("tohost")?,
+            fromhost: find("fromhost"),
+        })
+    }
+
+    pub fn tohost(&self) -> u32 {
+        self.tohost
+    }
+
+    /// Handles the request of the program, if it stored to `tohost`.
+    pub(crate) fn handle(
+        &self,
+        store_ops: &HashSet<StoreOp>,
+        memory: &mut impl MemoryProcessor,
+        logs: &mut Option<Vec<Vec<u8>>>,
+    ) -> Result<()> {
+        if !store_ops.iter().any(|op| op.get_address() == self.tohost) {
+            return Ok(());
+        }
+
+        let value = read_word(memory, self.tohost)?;
+        if value == 0 {
+            return Ok(());
+        }
+        memory.write(self.tohost, MemAccessSize::Word, 0)?;
+
+        if value & 1 == 1 {
+            return Err(VMError::VMExited(value >> 1));
+        }
+
+        // Only the low words of the 64-bit arguments are used on RV32.
+        let magic_mem = value;
+        let which = read_word(memory, magic_mem)?;
+        let args = [
+            read_word(memory, magic_mem + 8)?,
+            read_word(memory, magic_mem + 16)?,
+            read_word(memory, magic_mem + 24)?,
+        ];
+
+        let result = match which {
+            SYS_WRITE if args[0] == 1 => {
+                let buffer = memory.read_bytes(args[1], args[2] as usize)?;
+                match logs {
+                    Some(logger) => logger.push(buffer),
+                    None => print!("{}", String::from_utf8_lossy(&buffer)),
+                }
+                args[2]
+            }
+            SYS_WRITE => u32::MAX,
+            SYS_EXIT => return Err(VMError::VMExited(args[0])),
+            _ => ENOSYS.wrapping_neg(),
+        };
+
+        memory.write(magic_mem, MemAccessSize::Word, result)?;
+        memory.write(magic_mem + 4, MemAccessSize::Word, 0)?;
+        if let Some(fromhost) = self.fromhost {
+            memory.write(fromhost, MemAccessSize::Word, 1)?;
+        }
+        Ok(())
+    }
+}
+
+fn read_word(memory: &impl MemoryProcessor, address: u32) -> Result<u32> {
+    Ok(memory.read(address, MemAccessSize::Word)?.get_value())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{VariableMemory, RW};
+
+    fn symbol(name: &str, address: u32) -> Symbol {
+        Symbol {
+            name: name.to_string(),
+            address,
+            size: 8,
+        }
+    }
+
+    #[test]
+    fn test_tohost() {
+        let tohost =
+            ToHost::from_symbols(&[symbol("tohost", 0x1000), symbol("fromhost", 0x1008)]).unwrap();
+        assert_eq!(ToHost::from_symbols(&[symbol("fromhost", 0x1008)]), None);
+
+        let mut memory = VariableMemory::<RW>::default();
+        let mut logs = Some(Vec::new());
+        let store = |memory: &mut VariableMemory<RW>, value| {
+            HashSet::from([memory.write(0x1000, MemAccessSize::Word, value).unwrap()])
+        };
+
+        // Write "hi" to stdout.
+        memory.write_bytes(0x3000, b"hi").unwrap();
+        for (i, word) in [SYS_WRITE, 1, 0x3000, 2].into_iter().enumerate() {
+            memory
+                .write(0x2000 + 8 * i as u32, MemAccessSize::Word, word)
+                .unwrap();
+        }
+        let store_ops = store(&mut memory, 0x2000);
+        tohost.handle(&store_ops, &mut memory, &mut logs).unwrap();
+        assert_eq!(logs, Some(vec![b"hi".to_vec()]));
+        assert_eq!(read_word(&memory, 0x2000).unwrap(), 2);
+        assert_eq!(read_word(&memory, 0x1000).unwrap(), 0);
+        assert_eq!(read_word(&memory, 0x1008).unwrap(), 1);
+
+        // Failure of test 3.
+        let store_ops = store(&mut memory, 3 << 1 | 1);
+        assert_eq!(
+            tohost.handle(&store_ops, &mut memory, &mut logs),
+            Err(VMError::VMExited(3))
+        );
+
+        // Stores elsewhere are ignored.
+        let store_ops = HashSet::from([memory.write(0x1004, MemAccessSize::Word, 1).unwrap()]);
+        assert_eq!(tohost.handle(&store_ops, &mut memory, &mut logs), Ok(()));
+    }
+}
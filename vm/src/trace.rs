@@ -1,10 +1,15 @@
+use std::{ops::Range, path::Path};
+
 use serde::{Deserialize, Serialize};
 
 use crate::{
     cpu::{instructions::InstructionResult, RegisterFile},
     elf::ElfFile,
-    emulator::{Emulator, HarvardEmulator, InternalView, LinearEmulator, LinearMemoryLayout, View},
-    error::{Result, VMError},
+    emulator::{
+        Emulator, EmulatorBuilder, HarvardEmulator, InternalView, LinearEmulator,
+        LinearMemoryLayout, View,
+    },
+    error::{Result, TraceFileError, VMError},
     memory::MemoryRecords,
     riscv::{BasicBlock, Instruction},
     WORD_SIZE,
@@ -53,6 +58,41 @@ pub trait Trace {
     fn get_num_steps(&self) -> usize {
         self.get_blocks_iter().map(|b| b.steps.len()).sum()
     }
+
+    /// Return the step executed at global clock `clock`, if it is contained in this (sub)trace.
+    fn step_at(&self, clock: u32) -> Option<&Step> {
+        self.get_blocks_iter()
+            .flat_map(|b| b.steps.iter())
+            .find(|step| step.timestamp == clock)
+    }
+
+    /// Iterate over the steps executed within the range of global clocks `clocks`.
+    fn steps_in(&self, clocks: Range<u32>) -> impl Iterator<Item = &Step> + '_ {
+        self.get_blocks_iter()
+            .flat_map(|b| b.steps.iter())
+            .skip_while(move |step| step.timestamp < clocks.start)
+            .take_while(move |step| step.timestamp < clocks.end)
+    }
+}
+
+/// Iterate over the steps of `blocks` starting from the first one executed at or after `clock`.
+///
+/// Steps are ordered by timestamp, which allows to locate the first step by binary search.
+fn steps_from(blocks: &[Block], clock: u32) -> impl Iterator<Item = &Step> + '_ {
+    let block_idx = blocks.partition_point(|block| {
+        block
+            .steps
+            .last()
+            .is_some_and(|step| step.timestamp < clock)
+    });
+    let step_idx = blocks.get(block_idx).map_or(0, |block| {
+        block.steps.partition_point(|step| step.timestamp < clock)
+    });
+
+    blocks[block_idx..]
+        .iter()
+        .flat_map(|block| block.steps.iter())
+        .skip(step_idx)
 }
 
 /// Represents a program trace over uniform blocks.
@@ -81,6 +121,16 @@ impl Trace for UniformTrace {
         self.start
     }
 
+    fn step_at(&self, clock: u32) -> Option<&Step> {
+        steps_from(&self.blocks, clock)
+            .next()
+            .filter(|step| step.timestamp == clock)
+    }
+
+    fn steps_in(&self, clocks: Range<u32>) -> impl Iterator<Item = &Step> + '_ {
+        steps_from(&self.blocks, clocks.start).take_while(move |step| step.timestamp < clocks.end)
+    }
+
     fn get_num_steps(&self) -> usize {
         self.k * self.blocks.len()
     }
@@ -146,6 +196,16 @@ impl Trace for BBTrace {
     fn get_start(&self) -> usize {
         self.start
     }
+
+    fn step_at(&self, clock: u32) -> Option<&Step> {
+        steps_from(&self.blocks, clock)
+            .next()
+            .filter(|step| step.timestamp == clock)
+    }
+
+    fn steps_in(&self, clocks: Range<u32>) -> impl Iterator<Item = &Step> + '_ {
+        steps_from(&self.blocks, clocks.start).take_while(move |step| step.timestamp < clocks.end)
+    }
 }
 
 impl BBTrace {
@@ -266,6 +326,20 @@ fn k_step(
 
                             return (Some(block), Err(VMError::VMExited(n)));
                         }
+                        Err(VMError::Breakpoint(_)) => {
+                            // The first pass was resumed after the breakpoint, the trace steps
+                            // over it like over any other instruction.
+                            vm.get_executor_mut().global_clock += 1;
+                            block.steps.push(Step {
+                                timestamp,
+                                pc,
+                                next_pc: vm.get_executor().cpu.pc.value,
+                                raw_instruction: instruction.encode(),
+                                instruction: instruction.clone(),
+                                result: None,
+                                memory_records: MemoryRecords::default(),
+                            });
+                        }
                         Err(e) => return (None, Err(e)),
                     }
                 }
@@ -293,46 +367,49 @@ pub fn k_trace(
     private_input: &[u8],
     k: usize,
 ) -> Result<(View, UniformTrace)> {
+    let builder = EmulatorBuilder::new(elf)
+        .ad(ad)
+        .public_input(public_input)
+        .private_input(private_input);
+    k_trace_with(&builder, k)
+}
+
+/// Like [`k_trace`], with the emulators of both passes configured by `builder`, e.g. with a
+/// fixed memory layout or a cycle limit.
+pub fn k_trace_with(builder: &EmulatorBuilder, k: usize) -> Result<(View, UniformTrace)> {
     assert!(k > 0);
-    let mut harvard = HarvardEmulator::from_elf(&elf, public_input, private_input);
-    harvard.get_executor_mut().capture_logs(true);
+    let harvard = builder.run_first_pass()?;
 
-    match harvard.execute(false) {
-        Err(VMError::VMExited(_)) => {
-            // todo: consistency check i/o between harvard and linear?
-            let mut linear = LinearEmulator::from_harvard(&harvard, elf, ad, private_input)?;
+    // todo: consistency check i/o between harvard and linear?
+    let mut linear = builder.build_linear_after(&harvard)?;
 
-            let mut trace = UniformTrace {
-                memory_layout: linear.memory_layout,
-                k,
-                start: 0,
-                blocks: Vec::new(),
-            };
+    let mut trace = UniformTrace {
+        memory_layout: linear.memory_layout,
+        k,
+        start: 0,
+        blocks: Vec::new(),
+    };
 
-            loop {
-                match k_step(&mut linear, k, false) {
-                    (Some(block), Ok(())) => trace.blocks.push(block),
-                    (Some(block), Err(e)) => {
-                        if !block.steps.is_empty() {
-                            trace.blocks.push(block);
-                        }
+    loop {
+        match k_step(&mut linear, k, false) {
+            (Some(block), Ok(())) => trace.blocks.push(block),
+            (Some(block), Err(e)) => {
+                if !block.steps.is_empty() {
+                    trace.blocks.push(block);
+                }
 
-                        match e {
-                            VMError::VMExited(_) => {
-                                let mut view = linear.finalize();
-                                view.add_logs(&harvard);
-                                return Ok((view, trace));
-                            }
-                            _ => return Err(e),
-                        }
+                match e {
+                    VMError::VMExited(_) => {
+                        let mut view = linear.finalize();
+                        view.add_logs(&harvard);
+                        return Ok((view, trace));
                     }
-                    (None, Err(e)) => return Err(e),
-                    (None, Ok(())) => unreachable!(),
+                    _ => return Err(e),
                 }
             }
+            (None, Err(e)) => return Err(e),
+            (None, Ok(())) => unreachable!(),
         }
-        Err(e) => Err(e),
-        Ok(_) => unreachable!(),
     }
 }
 
@@ -487,6 +564,26 @@ pub fn bb_trace_direct(basic_blocks: &Vec<BasicBlock>) -> Result<(View, BBTrace)
     }
 }
 
+/// Write the view and the trace of an execution to `path` in a binary encoding, so that they can be
+/// proven later or on a different machine, see [`read_trace_file`].
+pub fn write_trace_file<P: AsRef<Path> + ?Sized>(
+    path: &P,
+    view: &View,
+    trace: &UniformTrace,
+) -> std::result::Result<(), TraceFileError> {
+    let bytes = postcard::to_allocvec(&(view, trace))?;
+    std::fs::write(path, bytes)?;
+    Ok(())
+}
+
+/// Read the view and the trace of an execution written by [`write_trace_file`].
+pub fn read_trace_file<P: AsRef<Path> + ?Sized>(
+    path: &P,
+) -> std::result::Result<(View, UniformTrace), TraceFileError> {
+    let bytes = std::fs::read(path)?;
+    Ok(postcard::from_bytes(&bytes)?)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -555,6 +652,58 @@ mod tests {
         assert!(step.memory_records.is_empty());
     }
 
+    #[test]
+    #[serial]
+    fn test_k_trace_with_builder() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let layout = LinearMemoryLayout::default();
+        let builder = EmulatorBuilder::new(elf_file).layout(layout);
+        let (_, trace) = k_trace_with(&builder, 1).unwrap();
+        assert_eq!(trace.memory_layout.program_start(), layout.program_start());
+        assert_eq!(trace.memory_layout.heap_end(), layout.heap_end());
+
+        assert!(matches!(
+            k_trace_with(&builder.max_cycles(10), 1),
+            Err(VMError::OutOfFuel { cycles: 10, .. })
+        ));
+    }
+
+    #[test]
+    fn test_k_trace_resumes_first_pass() {
+        use nexus_common::constants::ELF_TEXT_START;
+        use std::collections::BTreeMap;
+
+        let yield_code = u32::from(crate::system::SyscallCode::Yield);
+        let exit_code = u32::from(crate::system::SyscallCode::Exit);
+        let instructions = [
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, yield_code),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::EBREAK), 0, 0, 1),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 17, 0, exit_code),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ADDI), 10, 0, 0),
+            Instruction::new_ir(Opcode::from(BuiltinOpcode::ECALL), 0, 0, 0),
+        ];
+        let elf = ElfFile::new(
+            instructions.iter().map(Instruction::encode).collect(),
+            ELF_TEXT_START,
+            ELF_TEXT_START,
+            BTreeMap::new(),
+            BTreeMap::new(),
+            vec![],
+        );
+
+        let builder = EmulatorBuilder::new(elf).layout(LinearMemoryLayout::default());
+        let (_, trace) = k_trace_with(&builder, 1).unwrap();
+
+        // Every instruction has a step, the breakpoint included.
+        let steps: Vec<_> = trace.get_blocks_iter().flat_map(|b| &b.steps).collect();
+        assert_eq!(steps.len(), instructions.len());
+        for (i, step) in steps.iter().enumerate() {
+            assert_eq!(step.timestamp, i as u32 + 1);
+            assert_eq!(step.pc, ELF_TEXT_START + 4 * i as u32);
+        }
+    }
+
     #[test]
     #[serial]
     fn test_k8_trace_nexus_rt_binary() {
@@ -754,6 +903,33 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_step_at_clock() {
+        let basic_block = setup_basic_block_ir();
+        let (_, k1_trace) = k_trace_direct(&basic_block, 1).expect("Failed to create trace");
+        let (_, k4_trace) = k_trace_direct(&basic_block, 4).expect("Failed to create trace");
+        let (_, bb_trace) = bb_trace_direct(&basic_block).expect("Failed to create trace");
+
+        fn check(trace: &impl Trace) {
+            let steps: Vec<&Step> = trace.get_blocks_iter().flat_map(|b| &b.steps).collect();
+            for step in &steps {
+                let found = trace.step_at(step.timestamp).expect("step not found");
+                assert_eq!(found.timestamp, step.timestamp);
+                assert_eq!(found.pc, step.pc);
+            }
+            assert!(trace.step_at(0).is_none());
+            assert!(trace.step_at(steps.last().unwrap().timestamp + 1).is_none());
+
+            let (start, end) = (steps[3].timestamp, steps[10].timestamp);
+            let range: Vec<u32> = trace.steps_in(start..end).map(|s| s.timestamp).collect();
+            assert_eq!(range, (start..end).collect::<Vec<_>>());
+        }
+
+        check(&k1_trace);
+        check(&k4_trace);
+        check(&bb_trace);
+    }
+
     #[test]
     fn test_k4_trace_direct_from_basic_block_ir() {
         let basic_block = setup_basic_block_ir();
@@ -785,6 +961,33 @@ mod tests {
         assert_eq!(last_step.result, None, "Unexpected Fibonacci result");
     }
 
+    #[test]
+    fn test_trace_file_roundtrip() {
+        let path = std::env::temp_dir().join(format!("nexus-trace-{}.bin", std::process::id()));
+        let (view, trace) =
+            k_trace_direct(&setup_basic_block_ir(), 4).expect("Failed to create trace");
+
+        write_trace_file(&path, &view, &trace).unwrap();
+        let (read_view, read_trace) = read_trace_file(&path).unwrap();
+        std::fs::write(&path, b"not a trace").unwrap();
+        let err = read_trace_file(&path)
+            .err()
+            .expect("garbage must not decode");
+        std::fs::remove_file(&path).unwrap();
+
+        assert!(matches!(err, TraceFileError::Encoding(_)));
+        assert_eq!(read_trace.k, trace.k);
+        assert_eq!(read_trace.get_num_steps(), trace.get_num_steps());
+        assert_eq!(
+            read_trace.blocks.last().unwrap().steps[2].result,
+            Some(1346269)
+        );
+        assert_eq!(
+            read_view.get_program_memory().program.len(),
+            view.get_program_memory().program.len()
+        );
+    }
+
     #[test]
     fn test_k8_trace_direct_timestamp_tick_after_instruction_ended() {
         let basic_block = vec![BasicBlock::new(vec![
@@ -0,0 +1,271 @@
+//! Host-side consistency checks of execution traces.
+//!
+//! Proving a broken trace only fails deep inside the prover, after all traces are committed. The
+//! checks here walk the trace once and report the first inconsistency with the step it occurs at.
+
+use std::collections::BTreeMap;
+
+use nexus_common::cpu::Registers;
+
+use crate::{
+    emulator::LinearMemoryLayout,
+    error::TraceValidationError,
+    memory::{MemoryRecord, MemoryRecords},
+    riscv::{BuiltinOpcode, InstructionType, Register},
+    trace::{Step, Trace},
+};
+
+/// Check that `trace` is consistent before proving it:
+///
+/// - Step timestamps strictly increase, and memory records carry the timestamp of their step.
+/// - Every load returns the bytes last written to its address, and every store reports them as the
+///   previous value. Bytes are unknown until first accessed, since the initial memory isn't part
+///   of the trace.
+/// - The register file at the start of each block follows from the writes of the preceding block.
+///   System calls may write registers without reporting it, the check resumes from the next block
+///   after one.
+/// - Memory accesses stay within segments of the memory layout that permit them.
+///
+/// The layout checks assume a trace of the linear emulator, e.g. from [`crate::trace::k_trace`].
+pub fn validate_trace(trace: &impl Trace) -> Result<(), TraceValidationError> {
+    let layout = trace.get_memory_layout();
+    let mut memory = BTreeMap::<u32, u8>::new();
+    let mut prev_timestamp = None;
+    let mut expected_regs = None;
+
+    for block in trace.get_blocks_iter() {
+        let mut regs = block.regs;
+        let mut syscall = false;
+        for (idx, step) in block.steps.iter().enumerate() {
+            if let Some(prev) = prev_timestamp.filter(|&prev| step.timestamp <= prev) {
+                return Err(TraceValidationError::NonMonotonicTimestamp {
+                    timestamp: step.timestamp,
+                    pc: step.pc,
+                    prev,
+                });
+            }
+            prev_timestamp = Some(step.timestamp);
+
+            if let Some(expected) = expected_regs.filter(|_| idx == 0) {
+                check_registers(step, &expected, &regs)?;
+            }
+
+            check_memory(step, &step.memory_records, layout, &mut memory)?;
+
+            // Branches and stores report a result without writing a register.
+            match (step.instruction.opcode.builtin(), step.instruction.ins_type) {
+                (Some(BuiltinOpcode::ECALL | BuiltinOpcode::EBREAK), _) => syscall = true,
+                (_, InstructionType::BType | InstructionType::SType) => {}
+                _ => {
+                    if let Some(result) = step.result {
+                        regs.write(step.instruction.op_a, result);
+                    }
+                }
+            }
+        }
+        expected_regs = (!syscall).then_some(regs);
+    }
+    Ok(())
+}
+
+fn check_registers(
+    step: &Step,
+    expected: &impl Registers,
+    actual: &impl Registers,
+) -> Result<(), TraceValidationError> {
+    for register in (0..32).map(Register::from) {
+        let (expected, actual) = (expected.read(register), actual.read(register));
+        if expected != actual {
+            return Err(TraceValidationError::RegisterMismatch {
+                timestamp: step.timestamp,
+                register,
+                expected,
+                actual,
+            });
+        }
+    }
+    Ok(())
+}
+
+fn check_memory(
+    step: &Step,
+    records: &MemoryRecords,
+    layout: &LinearMemoryLayout,
+    memory: &mut BTreeMap<u32, u8>,
+) -> Result<(), TraceValidationError> {
+    // Within an instruction, loads happen before stores.
+    let (loads, stores): (Vec<_>, Vec<_>) = records
+        .iter()
+        .partition(|record| matches!(record, MemoryRecord::LoadRecord(..)));
+
+    for record in loads.into_iter().chain(stores) {
+        let store = matches!(record, MemoryRecord::StoreRecord(..));
+        let address = record.get_address();
+        if record.get_timestamp() != step.timestamp {
+            return Err(TraceValidationError::RecordTimestamp {
+                timestamp: step.timestamp,
+                pc: step.pc,
+                record: record.get_timestamp(),
+            });
+        }
+        if !is_permitted(layout, address, store) {
+            return Err(TraceValidationError::SegmentPermission {
+                timestamp: step.timestamp,
+                pc: step.pc,
+                address,
+                store,
+            });
+        }
+
+        // Loads must read, and stores must overwrite, the bytes last written.
+        let read = record.get_prev_value().unwrap_or(record.get_value());
+        let size = record.get_size() as u32;
+        for (offset, &actual) in read.to_le_bytes()[..size as usize].iter().enumerate() {
+            let byte_address = address.wrapping_add(offset as u32);
+            let expected = *memory.entry(byte_address).or_insert(actual);
+            if expected != actual {
+                return Err(TraceValidationError::StaleMemory {
+                    timestamp: step.timestamp,
+                    pc: step.pc,
+                    address: byte_address,
+                    expected,
+                    actual,
+                });
+            }
+        }
+        if store {
+            for (offset, &byte) in record.get_value().to_le_bytes()[..size as usize]
+                .iter()
+                .enumerate()
+            {
+                memory.insert(address.wrapping_add(offset as u32), byte);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Whether `layout` permits a load, or a store if `store` is set, at `address`.
+///
+/// Static data isn't split by permissions in the layout, accesses below the end of the program
+/// segment are always permitted.
+fn is_permitted(layout: &LinearMemoryLayout, address: u32, store: bool) -> bool {
+    let within = |start: u32, end: u32| (start..end).contains(&address);
+
+    if address < layout.program_end() {
+        true
+    } else if within(layout.public_input_start(), layout.public_input_end()) {
+        !store
+    } else if within(layout.exit_code(), layout.public_output_end()) {
+        store
+    } else {
+        within(layout.heap_start(), layout.heap_end())
+            || within(layout.stack_bottom(), layout.stack_top())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        elf::ElfFile,
+        memory::MemAccessSize,
+        riscv::{BasicBlock, Instruction, Opcode},
+        trace::{k_trace, k_trace_direct},
+    };
+    use serial_test::serial;
+
+    #[test]
+    #[serial]
+    fn test_validate_elf_traces() {
+        for k in [1, 8] {
+            let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+            let (_, trace) = k_trace(elf_file, &[], &[], &[], k).unwrap();
+            validate_trace(&trace).unwrap();
+        }
+    }
+
+    #[test]
+    #[serial]
+    fn test_validate_rejects_broken_traces() {
+        let elf_file = ElfFile::from_path("test/fib_10.elf").expect("Unable to load ELF file");
+        let (_, trace) = k_trace(elf_file, &[], &[], &[], 1).unwrap();
+
+        // Repeat a timestamp.
+        let mut broken = trace.clone();
+        broken.blocks[4].steps[0].timestamp = trace.blocks[3].steps[0].timestamp;
+        assert!(matches!(
+            validate_trace(&broken),
+            Err(TraceValidationError::NonMonotonicTimestamp { .. })
+        ));
+
+        // Change the result of the first instruction writing a register.
+        let mut broken = trace.clone();
+        let (idx, block) = broken
+            .blocks
+            .iter_mut()
+            .enumerate()
+            .find(|(_, block)| {
+                let step = &block.steps[0];
+                step.result.is_some() && step.instruction.op_a != Register::X0
+            })
+            .unwrap();
+        *block.steps[0].result.as_mut().unwrap() ^= 1;
+        assert!(matches!(
+            validate_trace(&broken),
+            Err(TraceValidationError::RegisterMismatch { timestamp, .. })
+                if timestamp == trace.blocks[idx + 1].steps[0].timestamp
+        ));
+
+        // Load a value that was never stored.
+        let mut broken = trace.clone();
+        let step = &mut broken
+            .blocks
+            .iter_mut()
+            .find(|block| {
+                block.steps[0]
+                    .memory_records
+                    .iter()
+                    .any(|record| matches!(record, MemoryRecord::StoreRecord(..)))
+            })
+            .unwrap()
+            .steps[0];
+        let store = step.memory_records.iter().next().cloned().unwrap();
+        let load = MemoryRecord::LoadRecord(
+            (
+                store.get_size(),
+                store.get_address(),
+                store.get_prev_value().unwrap() ^ 1,
+            ),
+            step.timestamp,
+        );
+        step.memory_records.insert(load);
+        assert!(matches!(
+            validate_trace(&broken),
+            Err(TraceValidationError::StaleMemory { .. })
+        ));
+    }
+
+    #[test]
+    fn test_validate_rejects_store_to_public_input() {
+        let basic_block = vec![BasicBlock::new(vec![Instruction::new_ir(
+            Opcode::from(BuiltinOpcode::ADDI),
+            1,
+            0,
+            1,
+        )])];
+        let (_, mut trace) = k_trace_direct(&basic_block, 1).unwrap();
+        trace.memory_layout = LinearMemoryLayout::default();
+
+        let address = trace.memory_layout.public_input_start();
+        let step = &mut trace.blocks[0].steps[0];
+        step.memory_records.insert(MemoryRecord::StoreRecord(
+            (MemAccessSize::Word, address, 1, 0),
+            step.timestamp,
+        ));
+        assert!(matches!(
+            validate_trace(&trace),
+            Err(TraceValidationError::SegmentPermission { store: true, .. })
+        ));
+    }
+}